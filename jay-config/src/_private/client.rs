@@ -4,20 +4,23 @@ use {
     crate::{
         _private::{
             bincode_ops,
-            ipc::{ClientMessage, InitMessage, Response, ServerMessage},
+            ipc::{ClientMessage, InitMessage, Response, ServerMessage, WireMode},
             logging, Config, ConfigEntry, ConfigEntryGen, VERSION,
         },
         exec::Command,
-        input::{acceleration::AccelProfile, capability::Capability, InputDevice, Seat},
+        input::{
+            acceleration::AccelProfile, capability::Capability, InputDevice, Seat, SwitchEvent,
+        },
         keyboard::Keymap,
         logging::LogLevel,
         theme::{colors::Colorable, sized::Resizable, Color},
         timer::Timer,
         video::{
             connector_type::{ConnectorType, CON_UNKNOWN},
-            Connector, DrmDevice, Mode,
+            Connector, DrmDevice, Mode, Transform, VrrMode,
         },
-        Axis, Direction, ModifiedKeySym, PciId, Workspace,
+        window::{Window, WindowRule},
+        ActivationBehavior, Axis, Direction, ModifiedKeySym, PciId, Workspace,
     },
     std::{
         cell::{Cell, RefCell},
@@ -36,6 +39,9 @@ pub(crate) struct Client {
     srv_unref: unsafe extern "C" fn(data: *const u8),
     srv_handler: unsafe extern "C" fn(data: *const u8, msg: *const u8, size: usize),
     key_handlers: RefCell<HashMap<(Seat, ModifiedKeySym), Rc<dyn Fn()>>>,
+    swipe_handlers: RefCell<HashMap<(Seat, u32, Direction), Rc<dyn Fn()>>>,
+    pad_button_handlers: RefCell<HashMap<(InputDevice, u32), Rc<dyn Fn()>>>,
+    switch_handlers: RefCell<HashMap<InputDevice, Rc<dyn Fn(SwitchEvent)>>>,
     timer_handlers: RefCell<HashMap<Timer, Rc<dyn Fn()>>>,
     response: RefCell<Vec<Response>>,
     on_new_seat: RefCell<Option<Rc<dyn Fn(Seat)>>>,
@@ -47,6 +53,8 @@ pub(crate) struct Client {
     on_new_drm_device: RefCell<Option<Rc<dyn Fn(DrmDevice)>>>,
     on_del_drm_device: RefCell<Option<Rc<dyn Fn(DrmDevice)>>>,
     on_idle: RefCell<Option<Rc<dyn Fn()>>>,
+    on_new_window: RefCell<Option<Rc<dyn Fn(Window)>>>,
+    on_shortcuts_inhibit_request: RefCell<Option<Rc<dyn Fn(Seat, &str) -> bool>>>,
     bufs: RefCell<Vec<Vec<u8>>>,
     reload: Cell<bool>,
 }
@@ -124,6 +132,9 @@ pub unsafe extern "C" fn init(
         srv_unref,
         srv_handler,
         key_handlers: Default::default(),
+        swipe_handlers: Default::default(),
+        pad_button_handlers: Default::default(),
+        switch_handlers: Default::default(),
         timer_handlers: Default::default(),
         response: Default::default(),
         on_new_seat: Default::default(),
@@ -135,6 +146,8 @@ pub unsafe extern "C" fn init(
         on_new_drm_device: Default::default(),
         on_del_drm_device: Default::default(),
         on_idle: Default::default(),
+        on_new_window: Default::default(),
+        on_shortcuts_inhibit_request: Default::default(),
         bufs: Default::default(),
         reload: Cell::new(false),
     });
@@ -386,6 +399,10 @@ impl Client {
         self.send(&ClientMessage::SetMono { seat, mono });
     }
 
+    pub fn set_activation_behavior(&self, behavior: ActivationBehavior) {
+        self.send(&ClientMessage::SetActivationBehavior { behavior });
+    }
+
     pub fn set_env(&self, key: &str, val: &str) {
         self.send(&ClientMessage::SetEnv { key, val });
     }
@@ -402,6 +419,104 @@ impl Client {
         self.send(&ClientMessage::CreateSplit { seat, axis });
     }
 
+    pub fn focused_window(&self, seat: Seat) -> Window {
+        let res = self.send_with_response(&ClientMessage::GetFocusedWindow { seat });
+        get_response!(res, Window::INVALID, GetFocusedWindow { window });
+        window
+    }
+
+    pub fn windows(&self) -> Vec<Window> {
+        let res = self.send_with_response(&ClientMessage::GetWindows);
+        get_response!(res, vec![], GetWindows { windows });
+        windows
+    }
+
+    pub fn window_exists(&self, window: Window) -> bool {
+        let res = self.send_with_response(&ClientMessage::WindowExists { window });
+        get_response!(res, false, WindowExists { exists });
+        exists
+    }
+
+    pub fn window_title(&self, window: Window) -> String {
+        let res = self.send_with_response(&ClientMessage::WindowTitle { window });
+        get_response!(res, String::new(), WindowTitle { title });
+        title
+    }
+
+    pub fn window_app_id(&self, window: Window) -> String {
+        let res = self.send_with_response(&ClientMessage::WindowAppId { window });
+        get_response!(res, String::new(), WindowAppId { app_id });
+        app_id
+    }
+
+    pub fn window_is_x(&self, window: Window) -> bool {
+        let res = self.send_with_response(&ClientMessage::WindowIsX { window });
+        get_response!(res, false, WindowIsX { is_x });
+        is_x
+    }
+
+    pub fn window_x_class(&self, window: Window) -> String {
+        let res = self.send_with_response(&ClientMessage::WindowXClass { window });
+        get_response!(res, String::new(), WindowXClass { class });
+        class
+    }
+
+    pub fn window_x_instance(&self, window: Window) -> String {
+        let res = self.send_with_response(&ClientMessage::WindowXInstance { window });
+        get_response!(res, String::new(), WindowXInstance { instance });
+        instance
+    }
+
+    pub fn window_x_role(&self, window: Window) -> String {
+        let res = self.send_with_response(&ClientMessage::WindowXRole { window });
+        get_response!(res, String::new(), WindowXRole { role });
+        role
+    }
+
+    pub fn window_pid(&self, window: Window) -> Option<u32> {
+        let res = self.send_with_response(&ClientMessage::WindowPid { window });
+        get_response!(res, None, WindowPid { pid });
+        pid
+    }
+
+    pub fn window_workspace(&self, window: Window) -> Option<Workspace> {
+        let res = self.send_with_response(&ClientMessage::WindowWorkspace { window });
+        get_response!(res, None, WindowWorkspace { workspace });
+        workspace
+    }
+
+    pub fn window_set_workspace(&self, window: Window, workspace: Workspace) {
+        self.send(&ClientMessage::WindowSetWorkspace { window, workspace });
+    }
+
+    pub fn window_floating(&self, window: Window) -> bool {
+        let res = self.send_with_response(&ClientMessage::WindowFloating { window });
+        get_response!(res, false, WindowFloating { floating });
+        floating
+    }
+
+    pub fn window_set_floating(&self, window: Window, floating: bool) {
+        self.send(&ClientMessage::WindowSetFloating { window, floating });
+    }
+
+    pub fn window_fullscreen(&self, window: Window) -> bool {
+        let res = self.send_with_response(&ClientMessage::WindowFullscreen { window });
+        get_response!(res, false, WindowFullscreen { fullscreen });
+        fullscreen
+    }
+
+    pub fn window_set_fullscreen(&self, window: Window, fullscreen: bool) {
+        self.send(&ClientMessage::WindowSetFullscreen { window, fullscreen });
+    }
+
+    pub fn window_focus(&self, window: Window, seat: Seat) {
+        self.send(&ClientMessage::WindowFocus { window, seat });
+    }
+
+    pub fn window_close(&self, window: Window) {
+        self.send(&ClientMessage::WindowClose { window });
+    }
+
     pub fn close(&self, seat: Seat) {
         self.send(&ClientMessage::Close { seat });
     }
@@ -446,6 +561,25 @@ impl Client {
         self.send(&ClientMessage::ConnectorSetEnabled { connector, enabled });
     }
 
+    pub fn connector_set_transform(&self, connector: Connector, transform: Transform) {
+        self.send(&ClientMessage::ConnectorSetTransform {
+            connector,
+            transform,
+        });
+    }
+
+    pub fn connector_set_vrr(&self, connector: Connector, mode: VrrMode) {
+        self.send(&ClientMessage::ConnectorSetVrr { connector, mode });
+    }
+
+    pub fn set_tearing_allowed(&self, allowed: bool) {
+        self.send(&ClientMessage::SetTearingAllowed { allowed });
+    }
+
+    pub fn connector_set_color_temperature(&self, connector: Connector, kelvin: u32) {
+        self.send(&ClientMessage::ConnectorSetColorTemperature { connector, kelvin });
+    }
+
     pub fn device_connectors(&self, device: DrmDevice) -> Vec<Connector> {
         let res = self.send_with_response(&ClientMessage::GetDeviceConnectors { device });
         get_response!(res, vec![], GetDeviceConnectors { connectors });
@@ -520,6 +654,30 @@ impl Client {
         }
     }
 
+    pub fn connector_modes(&self, connector: Connector) -> Vec<Mode> {
+        let res = self.send_with_response(&ClientMessage::ConnectorModes { connector });
+        get_response!(res, vec![], ConnectorModes { modes });
+        modes
+            .into_iter()
+            .map(|m| Mode {
+                width: m.width,
+                height: m.height,
+                refresh_millihz: m.refresh_millihz,
+            })
+            .collect()
+    }
+
+    pub fn connector_set_mode(&self, connector: Connector, mode: Mode) {
+        self.send(&ClientMessage::ConnectorSetMode {
+            connector,
+            mode: WireMode {
+                width: mode.width,
+                height: mode.height,
+                refresh_millihz: mode.refresh_millihz,
+            },
+        });
+    }
+
     pub fn connector_size(&self, connector: Connector) -> (i32, i32) {
         let res = self.send_with_response(&ClientMessage::ConnectorSize { connector });
         get_response!(res, (0, 0), ConnectorSize { width, height });
@@ -548,6 +706,22 @@ impl Client {
         *self.on_idle.borrow_mut() = Some(Rc::new(f));
     }
 
+    pub fn on_new_window<F: Fn(Window) + 'static>(&self, f: F) {
+        *self.on_new_window.borrow_mut() = Some(Rc::new(f));
+    }
+
+    pub fn add_window_rule(&self, rule: WindowRule) {
+        self.send(&ClientMessage::AddWindowRule { rule });
+    }
+
+    pub fn clear_window_rules(&self) {
+        self.send(&ClientMessage::ClearWindowRules);
+    }
+
+    pub fn on_shortcuts_inhibit_request<F: Fn(Seat, &str) -> bool + 'static>(&self, f: F) {
+        *self.on_shortcuts_inhibit_request.borrow_mut() = Some(Rc::new(f));
+    }
+
     pub fn on_connector_connected<F: Fn(Connector) + 'static>(&self, f: F) {
         *self.on_connector_connected.borrow_mut() = Some(Rc::new(f));
     }
@@ -583,6 +757,40 @@ impl Client {
         self.send(&ClientMessage::SetTransformMatrix { device, matrix })
     }
 
+    pub fn set_input_device_connector(&self, device: InputDevice, connector: Connector) {
+        self.send(&ClientMessage::SetInputDeviceConnector { device, connector })
+    }
+
+    pub fn map_input_device_to_layout(&self, device: InputDevice) {
+        self.send(&ClientMessage::MapInputDeviceToLayout { device })
+    }
+
+    pub fn remove_input_mapping(&self, device: InputDevice) {
+        self.send(&ClientMessage::RemoveInputMapping { device })
+    }
+
+    pub fn bind_pad_button<F: Fn() + 'static>(&self, device: InputDevice, button: u32, f: F) {
+        let register = self
+            .pad_button_handlers
+            .borrow_mut()
+            .insert((device, button), Rc::new(f))
+            .is_none();
+        if register {
+            self.send(&ClientMessage::AddPadButtonBinding { device, button });
+        }
+    }
+
+    pub fn unbind_pad_button(&self, device: InputDevice, button: u32) {
+        let deregister = self
+            .pad_button_handlers
+            .borrow_mut()
+            .remove(&(device, button))
+            .is_some();
+        if deregister {
+            self.send(&ClientMessage::RemovePadButtonBinding { device, button });
+        }
+    }
+
     pub fn set_px_per_wheel_scroll(&self, device: InputDevice, px: f64) {
         self.send(&ClientMessage::SetPxPerWheelScroll { device, px })
     }
@@ -599,6 +807,16 @@ impl Client {
         self.send(&ClientMessage::SetDragLockEnabled { device, enabled })
     }
 
+    pub fn on_switch_event<F: Fn(SwitchEvent) + 'static>(&self, device: InputDevice, f: F) {
+        self.switch_handlers.borrow_mut().insert(device, Rc::new(f));
+    }
+
+    pub fn is_lid_closed(&self) -> bool {
+        let res = self.send_with_response(&ClientMessage::GetLidClosed);
+        get_response!(res, false, GetLidClosed { closed });
+        closed
+    }
+
     pub fn device_name(&self, device: InputDevice) -> String {
         let res = self.send_with_response(&ClientMessage::GetDeviceName { device });
         get_response!(res, String::new(), GetDeviceName { name });
@@ -656,6 +874,51 @@ impl Client {
         }
     }
 
+    pub fn bind_swipe<F: Fn() + 'static>(
+        &self,
+        seat: Seat,
+        fingers: u32,
+        direction: Direction,
+        f: F,
+    ) {
+        let register = self
+            .swipe_handlers
+            .borrow_mut()
+            .insert((seat, fingers, direction), Rc::new(f))
+            .is_none();
+        if register {
+            self.send(&ClientMessage::AddSwipeBinding {
+                seat,
+                fingers,
+                direction,
+            });
+        }
+    }
+
+    pub fn unbind_swipe(&self, seat: Seat, fingers: u32, direction: Direction) {
+        let deregister = self
+            .swipe_handlers
+            .borrow_mut()
+            .remove(&(seat, fingers, direction))
+            .is_some();
+        if deregister {
+            self.send(&ClientMessage::RemoveSwipeBinding {
+                seat,
+                fingers,
+                direction,
+            });
+        }
+    }
+
+    pub fn set_shortcuts_inhibit_escape<T: Into<ModifiedKeySym>>(&self, seat: Seat, mod_sym: T) {
+        let mod_sym = mod_sym.into();
+        self.send(&ClientMessage::SetShortcutsInhibitEscape {
+            seat,
+            mods: mod_sym.mods,
+            sym: mod_sym.sym,
+        });
+    }
+
     pub fn log(&self, level: LogLevel, msg: &str, file: Option<&str>, line: Option<u32>) {
         self.send(&ClientMessage::Log {
             level,
@@ -697,7 +960,9 @@ impl Client {
                     handler(device);
                 }
             }
-            ServerMessage::DelInputDevice { .. } => {}
+            ServerMessage::DelInputDevice { device } => {
+                self.switch_handlers.borrow_mut().remove(&device);
+            }
             ServerMessage::ConnectorConnect { device } => {
                 let handler = self.on_connector_connected.borrow_mut().clone();
                 if let Some(handler) = handler {
@@ -712,6 +977,12 @@ impl Client {
                 }
             }
             ServerMessage::DelConnector { .. } => {}
+            ServerMessage::NewWindow { window } => {
+                let handler = self.on_new_window.borrow_mut().clone();
+                if let Some(handler) = handler {
+                    handler(window);
+                }
+            }
             ServerMessage::TimerExpired { timer } => {
                 let handler = self.timer_handlers.borrow_mut().get(&timer).cloned();
                 if let Some(handler) = handler {
@@ -749,6 +1020,44 @@ impl Client {
                     handler();
                 }
             }
+            ServerMessage::InvokeSwipe {
+                seat,
+                fingers,
+                direction,
+            } => {
+                let handler = self
+                    .swipe_handlers
+                    .borrow_mut()
+                    .get(&(seat, fingers, direction))
+                    .cloned();
+                if let Some(handler) = handler {
+                    handler();
+                }
+            }
+            ServerMessage::InvokePadButton { device, button } => {
+                let handler = self
+                    .pad_button_handlers
+                    .borrow_mut()
+                    .get(&(device, button))
+                    .cloned();
+                if let Some(handler) = handler {
+                    handler();
+                }
+            }
+            ServerMessage::SwitchEvent { device, event } => {
+                let handler = self.switch_handlers.borrow_mut().get(&device).cloned();
+                if let Some(handler) = handler {
+                    handler(event);
+                }
+            }
+            ServerMessage::ShortcutsInhibitRequest { seat, app_id } => {
+                let handler = self.on_shortcuts_inhibit_request.borrow_mut().clone();
+                let allow = match handler {
+                    Some(handler) => handler(seat, &app_id),
+                    _ => true,
+                };
+                self.send(&ClientMessage::ShortcutsInhibitResponse { allow });
+            }
         }
     }
 
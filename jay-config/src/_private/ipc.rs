@@ -1,12 +1,15 @@
 use {
     crate::{
-        input::{acceleration::AccelProfile, capability::Capability, InputDevice, Seat},
+        input::{
+            acceleration::AccelProfile, capability::Capability, InputDevice, Seat, SwitchEvent,
+        },
         keyboard::{mods::Modifiers, syms::KeySym, Keymap},
         logging::LogLevel,
         theme::{colors::Colorable, sized::Resizable, Color},
         timer::Timer,
-        video::{connector_type::ConnectorType, Connector, DrmDevice},
-        Axis, Direction, PciId, Workspace,
+        video::{connector_type::ConnectorType, Connector, DrmDevice, Transform, VrrMode},
+        window::{Window, WindowRule},
+        ActivationBehavior, Axis, Direction, PciId, Workspace,
     },
     bincode::{BorrowDecode, Decode, Encode},
     std::time::Duration,
@@ -56,6 +59,26 @@ pub enum ServerMessage {
     },
     Idle,
     DevicesEnumerated,
+    ShortcutsInhibitRequest {
+        seat: Seat,
+        app_id: String,
+    },
+    InvokeSwipe {
+        seat: Seat,
+        fingers: u32,
+        direction: Direction,
+    },
+    InvokePadButton {
+        device: InputDevice,
+        button: u32,
+    },
+    SwitchEvent {
+        device: InputDevice,
+        event: SwitchEvent,
+    },
+    NewWindow {
+        window: Window,
+    },
 }
 
 #[derive(Encode, BorrowDecode, Debug)]
@@ -316,6 +339,128 @@ pub enum ClientMessage<'a> {
     MakeRenderDevice {
         device: DrmDevice,
     },
+    ConnectorModes {
+        connector: Connector,
+    },
+    ConnectorSetMode {
+        connector: Connector,
+        mode: WireMode,
+    },
+    ConnectorSetTransform {
+        connector: Connector,
+        transform: Transform,
+    },
+    ShortcutsInhibitResponse {
+        allow: bool,
+    },
+    SetShortcutsInhibitEscape {
+        seat: Seat,
+        mods: Modifiers,
+        sym: KeySym,
+    },
+    SetActivationBehavior {
+        behavior: ActivationBehavior,
+    },
+    AddSwipeBinding {
+        seat: Seat,
+        fingers: u32,
+        direction: Direction,
+    },
+    RemoveSwipeBinding {
+        seat: Seat,
+        fingers: u32,
+        direction: Direction,
+    },
+    SetInputDeviceConnector {
+        device: InputDevice,
+        connector: Connector,
+    },
+    RemoveInputMapping {
+        device: InputDevice,
+    },
+    AddPadButtonBinding {
+        device: InputDevice,
+        button: u32,
+    },
+    RemovePadButtonBinding {
+        device: InputDevice,
+        button: u32,
+    },
+    MapInputDeviceToLayout {
+        device: InputDevice,
+    },
+    GetLidClosed,
+    ConnectorSetVrr {
+        connector: Connector,
+        mode: VrrMode,
+    },
+    ConnectorSetColorTemperature {
+        connector: Connector,
+        kelvin: u32,
+    },
+    SetTearingAllowed {
+        allowed: bool,
+    },
+    GetWindows,
+    GetFocusedWindow {
+        seat: Seat,
+    },
+    WindowExists {
+        window: Window,
+    },
+    WindowTitle {
+        window: Window,
+    },
+    WindowAppId {
+        window: Window,
+    },
+    WindowIsX {
+        window: Window,
+    },
+    WindowXClass {
+        window: Window,
+    },
+    WindowXInstance {
+        window: Window,
+    },
+    WindowXRole {
+        window: Window,
+    },
+    WindowPid {
+        window: Window,
+    },
+    WindowWorkspace {
+        window: Window,
+    },
+    WindowSetWorkspace {
+        window: Window,
+        workspace: Workspace,
+    },
+    WindowFloating {
+        window: Window,
+    },
+    WindowSetFloating {
+        window: Window,
+        floating: bool,
+    },
+    WindowFullscreen {
+        window: Window,
+    },
+    WindowSetFullscreen {
+        window: Window,
+        fullscreen: bool,
+    },
+    WindowFocus {
+        window: Window,
+        seat: Seat,
+    },
+    WindowClose {
+        window: Window,
+    },
+    AddWindowRule {
+        rule: WindowRule,
+    },
+    ClearWindowRules,
 }
 
 #[derive(Encode, Decode, Debug)]
@@ -409,6 +554,58 @@ pub enum Response {
         width: i32,
         height: i32,
     },
+    ConnectorModes {
+        modes: Vec<WireMode>,
+    },
+    GetLidClosed {
+        closed: bool,
+    },
+    GetWindows {
+        windows: Vec<Window>,
+    },
+    GetFocusedWindow {
+        window: Window,
+    },
+    WindowExists {
+        exists: bool,
+    },
+    WindowTitle {
+        title: String,
+    },
+    WindowAppId {
+        app_id: String,
+    },
+    WindowIsX {
+        is_x: bool,
+    },
+    WindowXClass {
+        class: String,
+    },
+    WindowXInstance {
+        instance: String,
+    },
+    WindowXRole {
+        role: String,
+    },
+    WindowPid {
+        pid: Option<u32>,
+    },
+    WindowWorkspace {
+        workspace: Option<Workspace>,
+    },
+    WindowFloating {
+        floating: bool,
+    },
+    WindowFullscreen {
+        fullscreen: bool,
+    },
+}
+
+#[derive(Encode, Decode, Copy, Clone, Debug)]
+pub struct WireMode {
+    pub width: i32,
+    pub height: i32,
+    pub refresh_millihz: u32,
 }
 
 #[derive(Encode, Decode, Debug)]
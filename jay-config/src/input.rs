@@ -7,6 +7,8 @@ use {
     crate::{
         input::{acceleration::AccelProfile, capability::Capability},
         keyboard::Keymap,
+        video::Connector,
+        window::Window,
         Axis, Direction, ModifiedKeySym, Workspace,
     },
     bincode::{Decode, Encode},
@@ -67,6 +69,52 @@ impl InputDevice {
         get!().set_transform_matrix(self, matrix);
     }
 
+    /// Maps the absolute positions reported by this device to the connector.
+    ///
+    /// This affects devices that report absolute positions such as touchscreens, drawing
+    /// tablets, and the absolute pointers of virtual machines. If the connector is not
+    /// connected, the default mapping is used.
+    ///
+    /// By default, absolute pointers are mapped to the whole layout and all other devices
+    /// are mapped to the output containing the cursor of the seat.
+    pub fn set_connector(self, connector: Connector) {
+        get!().set_input_device_connector(self, connector)
+    }
+
+    /// Maps the absolute positions reported by this device to the whole layout.
+    ///
+    /// The layout is the smallest rectangle containing all outputs.
+    pub fn map_to_layout(self) {
+        get!().map_input_device_to_layout(self)
+    }
+
+    /// Removes the mapping set with [`InputDevice::set_connector`] or
+    /// [`InputDevice::map_to_layout`].
+    pub fn remove_mapping(self) {
+        get!().remove_input_mapping(self)
+    }
+
+    /// Creates a binding for a button of a tablet pad.
+    ///
+    /// The closure is invoked when the button is pressed. Bound buttons are no longer
+    /// forwarded to applications. Buttons are numbered starting at 0.
+    pub fn bind_pad_button<F: Fn() + 'static>(self, button: u32, f: F) {
+        get!().bind_pad_button(self, button, f)
+    }
+
+    /// Unbinds a button of a tablet pad.
+    pub fn unbind_pad_button(self, button: u32) {
+        get!().unbind_pad_button(self, button)
+    }
+
+    /// Sets a closure to run when a switch of this device changes its state.
+    ///
+    /// This is used by lid switches and by the tablet-mode switches of convertible laptops.
+    /// Setting a new closure replaces the previous one.
+    pub fn on_switch_event<F: Fn(SwitchEvent) + 'static>(self, f: F) {
+        get!().on_switch_event(self, f)
+    }
+
     /// Returns the name of the device.
     pub fn name(self) -> String {
         get!(String::new()).device_name(self)
@@ -106,6 +154,19 @@ impl InputDevice {
     }
 }
 
+/// An event emitted by a switch of an input device.
+#[derive(Encode, Decode, Copy, Clone, Debug, Hash, Eq, PartialEq)]
+pub enum SwitchEvent {
+    /// The lid of the device has been opened.
+    LidOpened,
+    /// The lid of the device has been closed.
+    LidClosed,
+    /// The device has been converted from tablet mode to laptop mode.
+    ConvertedToLaptop,
+    /// The device has been converted from laptop mode to tablet mode.
+    ConvertedToTablet,
+}
+
 /// A seat.
 #[derive(Encode, Decode, Copy, Clone, Debug, Hash, Eq, PartialEq)]
 pub struct Seat(pub u64);
@@ -162,6 +223,26 @@ impl Seat {
         get!().unbind(self, mod_sym)
     }
 
+    /// Creates a binding for a touchpad swipe.
+    ///
+    /// The closure is invoked when the user finishes a swipe with the specified number of
+    /// fingers in the specified direction. While any swipe binding exists for a finger
+    /// count, swipes with that number of fingers are no longer forwarded to applications.
+    ///
+    /// Example:
+    ///
+    /// ```rust,ignore
+    /// seat.bind_swipe(3, Direction::Left, move || seat.show_workspace(prev));
+    /// ```
+    pub fn bind_swipe<F: Fn() + 'static>(self, fingers: u32, direction: Direction, f: F) {
+        get!().bind_swipe(self, fingers, direction, f)
+    }
+
+    /// Unbinds a touchpad swipe.
+    pub fn unbind_swipe(self, fingers: u32, direction: Direction) {
+        get!().unbind_swipe(self, fingers, direction)
+    }
+
     /// Moves the keyboard focus of the seat in the specified direction.
     pub fn focus(self, direction: Direction) {
         get!().focus(self, direction)
@@ -235,6 +316,13 @@ impl Seat {
         get!().focus_parent(self);
     }
 
+    /// Returns the currently focused window.
+    ///
+    /// Returns [`Window::INVALID`] if no window is focused.
+    pub fn focused_window(self) -> Window {
+        get!(Window::INVALID).focused_window(self)
+    }
+
     /// Requests the currently focused window to be closed.
     pub fn close(self) {
         get!().close(self);
@@ -286,6 +374,18 @@ impl Seat {
     pub fn disable_pointer_constraint(self) {
         get!().disable_pointer_constraint(self)
     }
+
+    /// Sets the shortcut that ends an active keyboard shortcuts inhibitor.
+    ///
+    /// Clients such as virtual machines and remote desktop viewers can ask the compositor
+    /// to forward all key presses to them instead of invoking hotkeys. While such an
+    /// inhibitor is active, only this shortcut is processed by the compositor. Pressing it
+    /// deactivates the inhibitor until the window loses and regains the keyboard focus.
+    ///
+    /// Default: `LOGO | SYM_Escape`.
+    pub fn set_shortcuts_inhibit_escape<T: Into<ModifiedKeySym>>(self, mod_sym: T) {
+        get!().set_shortcuts_inhibit_escape(self, mod_sym)
+    }
 }
 
 /// Returns all seats.
@@ -305,6 +405,13 @@ pub fn get_seat(name: &str) -> Seat {
     get!(Seat(0)).get_seat(name)
 }
 
+/// Returns whether the lid of any device is currently closed.
+///
+/// See also [`InputDevice::on_switch_event`].
+pub fn is_lid_closed() -> bool {
+    get!(false).is_lid_closed()
+}
+
 /// Sets a closure to run when a new seat has been created.
 pub fn on_new_seat<F: Fn(Seat) + 'static>(f: F) {
     get!().on_new_seat(f)
@@ -314,3 +421,15 @@ pub fn on_new_seat<F: Fn(Seat) + 'static>(f: F) {
 pub fn on_new_input_device<F: Fn(InputDevice) + 'static>(f: F) {
     get!().on_new_input_device(f)
 }
+
+/// Sets a closure that decides whether a window may inhibit the keyboard shortcuts.
+///
+/// The closure is invoked with the seat and the app-id of the window the first time the
+/// inhibitor would become active. The app-id is empty if it is not known. If the closure
+/// returns `false`, the hotkeys of the seat continue to work while the window is
+/// focused.
+///
+/// By default, all requests are granted.
+pub fn on_shortcuts_inhibit_request<F: Fn(Seat, &str) -> bool + 'static>(f: F) {
+    get!().on_shortcuts_inhibit_request(f)
+}
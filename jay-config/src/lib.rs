@@ -55,9 +55,10 @@ pub mod status;
 pub mod theme;
 pub mod timer;
 pub mod video;
+pub mod window;
 
 /// A planar direction.
-#[derive(Encode, Decode, Copy, Clone, Debug, Eq, PartialEq)]
+#[derive(Encode, Decode, Copy, Clone, Debug, Hash, Eq, PartialEq)]
 pub enum Direction {
     Left,
     Down,
@@ -147,3 +148,24 @@ pub fn on_idle<F: Fn() + 'static>(f: F) {
 pub fn on_devices_enumerated<F: FnOnce() + 'static>(f: F) {
     get!().on_devices_enumerated(f)
 }
+
+/// How the compositor reacts when a window asks to be activated.
+///
+/// Applications ask to be activated via xdg-activation or, for X applications, via their
+/// startup ID. This happens for example when a program started with
+/// [`Command::spawn`](exec::Command::spawn) opens its window or when a terminal asks the
+/// browser to open a link.
+#[derive(Encode, Decode, Copy, Clone, Debug, Eq, PartialEq)]
+pub enum ActivationBehavior {
+    /// The window is focused and its workspace is shown.
+    Focus,
+    /// The title of the window is highlighted until it is focused.
+    RequestAttention,
+}
+
+/// Sets how the compositor reacts when a window asks to be activated.
+///
+/// Default: [`ActivationBehavior::Focus`].
+pub fn set_activation_behavior(behavior: ActivationBehavior) {
+    get!().set_activation_behavior(behavior)
+}
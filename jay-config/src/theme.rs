@@ -245,6 +245,10 @@ pub mod colors {
         ///
         /// Default: `#ffffff`.
         const 11 => BAR_STATUS_TEXT_COLOR,
+        /// The title background color of an unfocused window that requested attention.
+        ///
+        /// Default: `#23092c`.
+        const 12 => ATTENTION_REQUESTED_BACKGROUND_COLOR,
     }
 }
 
@@ -55,6 +55,44 @@ impl Mode {
     }
 }
 
+/// A transformation of the contents of an output.
+///
+/// This can be used to compensate for monitors that have been rotated or flipped. Rotations
+/// turn the image counter-clockwise, which undoes turning the monitor clockwise by the same
+/// angle. For example, use `Rotate90` for a monitor whose top edge now points to the right.
+#[derive(Encode, Decode, Copy, Clone, Debug, Hash, Eq, PartialEq)]
+pub enum Transform {
+    /// No transformation.
+    None,
+    /// Rotate by 90 degrees.
+    Rotate90,
+    /// Rotate by 180 degrees.
+    Rotate180,
+    /// Rotate by 270 degrees.
+    Rotate270,
+    /// Flip around the vertical axis.
+    Flip,
+    /// Flip around the vertical axis, then rotate by 90 degrees.
+    FlipRotate90,
+    /// Flip around the vertical axis, then rotate by 180 degrees.
+    FlipRotate180,
+    /// Flip around the vertical axis, then rotate by 270 degrees.
+    FlipRotate270,
+}
+
+/// When variable refresh rate (VRR) is used on a connector.
+///
+/// VRR is only used if both the monitor and the graphics card support it.
+#[derive(Encode, Decode, Copy, Clone, Debug, Hash, Eq, PartialEq)]
+pub enum VrrMode {
+    /// Never use VRR.
+    Never,
+    /// Always use VRR.
+    Always,
+    /// Use VRR only while a window is fullscreen on the output.
+    Fullscreen,
+}
+
 /// A connector that is potentially connected to an output device.
 ///
 /// A connector is the part that sticks out of your graphics card. A graphics card usually
@@ -112,6 +150,28 @@ impl Connector {
         get!(Mode::zeroed()).connector_mode(self)
     }
 
+    /// Returns the modes supported by the connected monitor.
+    ///
+    /// Returns an empty list if the connector is not connected.
+    pub fn modes(self) -> Vec<Mode> {
+        if !self.exists() {
+            return vec![];
+        }
+        get!(vec![]).connector_modes(self)
+    }
+
+    /// Sets the mode of the connector.
+    ///
+    /// The mode should be one of the modes returned by `modes()`. The mode is remembered
+    /// and re-applied when the monitor is reconnected.
+    pub fn set_mode(self, mode: Mode) {
+        if !self.exists() {
+            log::warn!("set_mode called on a connector that does not exist");
+            return;
+        }
+        get!().connector_set_mode(self, mode);
+    }
+
     /// Returns the logical width of the connector.
     ///
     /// The returned value will be different from `mode().width()` if the scale is not 1.
@@ -158,6 +218,56 @@ impl Connector {
         }
         get!().connector_set_enabled(self, enabled);
     }
+
+    /// Sets the transformation to apply to the content of this connector.
+    ///
+    /// See [`Transform`] for the direction of the rotations.
+    pub fn set_transform(self, transform: Transform) {
+        if !self.exists() {
+            log::warn!("set_transform called on a connector that does not exist");
+            return;
+        }
+        get!().connector_set_transform(self, transform);
+    }
+
+    /// Sets when variable refresh rate is used on this connector.
+    ///
+    /// While VRR is enabled, the display refreshes whenever a client presents a new frame
+    /// instead of at a fixed rate.
+    ///
+    /// The default is `VrrMode::Never`.
+    pub fn set_vrr(self, mode: VrrMode) {
+        if !self.exists() {
+            log::warn!("set_vrr called on a connector that does not exist");
+            return;
+        }
+        get!().connector_set_vrr(self, mode);
+    }
+
+    /// Sets the color temperature of this connector in Kelvin.
+    ///
+    /// Lower temperatures make the colors warmer. The temperature must be in the range
+    /// 1000 to 10000. The default is 6500 which leaves the colors unchanged.
+    ///
+    /// While a client controls the gamma of the connector, for example via
+    /// wlr-gamma-control, the color temperature is not applied.
+    pub fn set_color_temperature(self, kelvin: u32) {
+        if !self.exists() {
+            log::warn!("set_color_temperature called on a connector that does not exist");
+            return;
+        }
+        get!().connector_set_color_temperature(self, kelvin);
+    }
+}
+
+/// Sets whether fullscreen applications may use tearing page flips.
+///
+/// Applications can request tearing via the tearing-control protocol to reduce latency.
+/// Tearing is only used while the application is displayed via direct scanout.
+///
+/// By default, tearing is allowed.
+pub fn set_tearing_allowed(allowed: bool) {
+    get!().set_tearing_allowed(allowed);
 }
 
 /// Returns all available DRM devices.
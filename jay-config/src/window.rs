@@ -0,0 +1,249 @@
+//! Tools for inspecting and manipulating windows.
+
+use {
+    crate::{input::Seat, Workspace},
+    bincode::{Decode, Encode},
+    std::ops::BitOr,
+};
+
+/// A toplevel window.
+///
+/// A window handle remains valid until the window is destroyed. Afterwards, queries return
+/// default values and actions have no effect.
+#[derive(Encode, Decode, Copy, Clone, Debug, Hash, Eq, PartialEq)]
+pub struct Window(pub u64);
+
+impl Window {
+    pub const INVALID: Self = Self(0);
+
+    /// Returns whether the window is invalid.
+    pub fn is_invalid(self) -> bool {
+        self == Self::INVALID
+    }
+
+    /// Returns whether the window still exists.
+    pub fn exists(self) -> bool {
+        !self.is_invalid() && get!(false).window_exists(self)
+    }
+
+    /// Returns the title of the window.
+    pub fn title(self) -> String {
+        get!().window_title(self)
+    }
+
+    /// Returns the app-id of the window.
+    ///
+    /// The app-id is empty if it is not known. For X windows, this is the class of the
+    /// window.
+    pub fn app_id(self) -> String {
+        get!().window_app_id(self)
+    }
+
+    /// Returns whether this is an X window.
+    pub fn is_x(self) -> bool {
+        get!(false).window_is_x(self)
+    }
+
+    /// Returns the `WM_CLASS` class of an X window.
+    ///
+    /// Returns an empty string for Wayland windows or if the property is not set.
+    pub fn x_class(self) -> String {
+        get!().window_x_class(self)
+    }
+
+    /// Returns the `WM_CLASS` instance of an X window.
+    ///
+    /// Returns an empty string for Wayland windows or if the property is not set.
+    pub fn x_instance(self) -> String {
+        get!().window_x_instance(self)
+    }
+
+    /// Returns the `WM_WINDOW_ROLE` of an X window.
+    ///
+    /// Returns an empty string for Wayland windows or if the property is not set.
+    pub fn x_role(self) -> String {
+        get!().window_x_role(self)
+    }
+
+    /// Returns the process ID of the client that owns the window.
+    ///
+    /// For X windows, this is the `_NET_WM_PID` property of the window.
+    pub fn pid(self) -> Option<u32> {
+        get!().window_pid(self)
+    }
+
+    /// Returns the workspace that contains the window.
+    pub fn workspace(self) -> Option<Workspace> {
+        get!().window_workspace(self)
+    }
+
+    /// Moves the window to the workspace.
+    ///
+    /// If the workspace doesn't currently exist, it is created on the output that
+    /// contains the window.
+    pub fn set_workspace(self, workspace: Workspace) {
+        get!().window_set_workspace(self, workspace)
+    }
+
+    /// Returns whether the window is floating.
+    pub fn floating(self) -> bool {
+        get!(false).window_floating(self)
+    }
+
+    /// Sets whether the window is floating.
+    pub fn set_floating(self, floating: bool) {
+        get!().window_set_floating(self, floating)
+    }
+
+    /// Toggles whether the window is floating.
+    pub fn toggle_floating(self) {
+        self.set_floating(!self.floating());
+    }
+
+    /// Returns whether the window is fullscreen.
+    pub fn fullscreen(self) -> bool {
+        get!(false).window_fullscreen(self)
+    }
+
+    /// Sets whether the window is fullscreen.
+    pub fn set_fullscreen(self, fullscreen: bool) {
+        get!().window_set_fullscreen(self, fullscreen)
+    }
+
+    /// Toggles whether the window is fullscreen.
+    pub fn toggle_fullscreen(self) {
+        self.set_fullscreen(!self.fullscreen());
+    }
+
+    /// Shows the workspace of the window and moves the keyboard focus of the seat to the
+    /// window.
+    pub fn focus(self, seat: Seat) {
+        get!().window_focus(self, seat)
+    }
+
+    /// Requests the window to be closed.
+    pub fn close(self) {
+        get!().window_close(self)
+    }
+}
+
+/// Returns all windows.
+pub fn windows() -> Vec<Window> {
+    get!().windows()
+}
+
+/// Sets a closure to run when a new window has been mapped.
+///
+/// The closure runs after the [window rules](add_window_rule) have been applied.
+pub fn on_new_window<F: Fn(Window) + 'static>(f: F) {
+    get!().on_new_window(f)
+}
+
+/// Zero or more `_NET_WM_WINDOW_TYPE`s of an X window.
+#[derive(Encode, Decode, Copy, Clone, Eq, PartialEq, Default, Hash, Debug)]
+pub struct XWindowTypes(pub u32);
+
+pub const X_WINDOW_TYPE_NORMAL: XWindowTypes = XWindowTypes(1 << 0);
+pub const X_WINDOW_TYPE_DIALOG: XWindowTypes = XWindowTypes(1 << 1);
+pub const X_WINDOW_TYPE_UTILITY: XWindowTypes = XWindowTypes(1 << 2);
+pub const X_WINDOW_TYPE_TOOLBAR: XWindowTypes = XWindowTypes(1 << 3);
+pub const X_WINDOW_TYPE_SPLASH: XWindowTypes = XWindowTypes(1 << 4);
+pub const X_WINDOW_TYPE_MENU: XWindowTypes = XWindowTypes(1 << 5);
+pub const X_WINDOW_TYPE_DROPDOWN_MENU: XWindowTypes = XWindowTypes(1 << 6);
+pub const X_WINDOW_TYPE_POPUP_MENU: XWindowTypes = XWindowTypes(1 << 7);
+pub const X_WINDOW_TYPE_TOOLTIP: XWindowTypes = XWindowTypes(1 << 8);
+pub const X_WINDOW_TYPE_NOTIFICATION: XWindowTypes = XWindowTypes(1 << 9);
+pub const X_WINDOW_TYPE_COMBO: XWindowTypes = XWindowTypes(1 << 10);
+pub const X_WINDOW_TYPE_DND: XWindowTypes = XWindowTypes(1 << 11);
+
+impl BitOr for XWindowTypes {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self::Output {
+        Self(self.0 | rhs.0)
+    }
+}
+
+/// The criteria that select the windows a [`WindowRule`] applies to.
+///
+/// A window matches if it matches all criteria that are set. X11 criteria never match
+/// Wayland windows.
+#[derive(Encode, Decode, Clone, Debug, Default)]
+pub struct WindowMatcher {
+    /// The app-id of the window.
+    pub app_id: Option<String>,
+    /// A regular expression that must match somewhere in the title of the window.
+    pub title: Option<String>,
+    /// The `WM_CLASS` class of an X window.
+    pub x_class: Option<String>,
+    /// The `WM_CLASS` instance of an X window.
+    pub x_instance: Option<String>,
+    /// The `WM_WINDOW_ROLE` of an X window.
+    pub x_role: Option<String>,
+    /// The `_NET_WM_WINDOW_TYPE`s of an X window. Matches if the window has any of them.
+    pub x_window_types: Option<XWindowTypes>,
+    /// The process ID of the client. See [`Window::pid`].
+    pub pid: Option<u32>,
+    /// The name of the process of the client as shown in `/proc/<pid>/comm`.
+    pub comm: Option<String>,
+}
+
+/// A rule that is applied to windows when they are mapped for the first time.
+///
+/// The rules of Wayland windows are evaluated when the window first commits its state,
+/// before it has attached a buffer. The app-id and title must have been set at that
+/// point to be matched. Rules are not applied again if a window is unmapped and mapped
+/// again.
+///
+/// Properties that are not set keep their default behavior. If multiple rules match a
+/// window, all of them are applied in the order in which they were added, i.e., later
+/// rules override properties set by earlier rules.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// add_window_rule(WindowRule {
+///     matcher: WindowMatcher {
+///         app_id: Some("firefox".to_string()),
+///         title: Some("^Picture-in-Picture$".to_string()),
+///         ..Default::default()
+///     },
+///     floating: Some(true),
+///     ..Default::default()
+/// });
+/// ```
+#[derive(Encode, Decode, Clone, Debug, Default)]
+pub struct WindowRule {
+    pub matcher: WindowMatcher,
+    /// The workspace to place the window on.
+    ///
+    /// If the workspace doesn't currently exist, it is created on the output that contains
+    /// the cursor of the most recently used seat.
+    pub workspace: Option<Workspace>,
+    /// Whether the window is floating.
+    pub floating: Option<bool>,
+    /// The size of the window if it is floating.
+    pub size: Option<(i32, i32)>,
+    /// The position of the window relative to its output if it is floating.
+    pub position: Option<(i32, i32)>,
+    /// Whether the window is fullscreen.
+    pub fullscreen: Option<bool>,
+    /// Whether the window receives the keyboard focus.
+    ///
+    /// If this is `true`, the workspace of the window is shown. By default, windows are
+    /// focused if they are tiled on a visible workspace.
+    pub focus: Option<bool>,
+}
+
+/// Adds a window rule.
+///
+/// Rules only affect windows that are mapped after the rule has been added. Reloading the
+/// configuration removes all rules.
+pub fn add_window_rule(rule: WindowRule) {
+    get!().add_window_rule(rule)
+}
+
+/// Removes all window rules.
+pub fn clear_window_rules() {
+    get!().clear_window_rules()
+}
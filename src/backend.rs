@@ -4,7 +4,7 @@ use {
         fixed::Fixed,
         ifs::wl_seat::wl_pointer::{CONTINUOUS, FINGER, HORIZONTAL_SCROLL, VERTICAL_SCROLL, WHEEL},
         render::Framebuffer,
-        video::drm::{ConnectorType, DrmError, DrmVersion},
+        video::drm::{ConnectorType, DrmConnector, DrmError, DrmVersion},
     },
     std::{
         any::Any,
@@ -12,12 +12,13 @@ use {
         fmt::{Debug, Display, Formatter},
         rc::Rc,
     },
-    uapi::c,
+    uapi::{c, OwnedFd},
 };
 
 linear_ids!(ConnectorIds, ConnectorId);
 linear_ids!(InputDeviceIds, InputDeviceId);
 linear_ids!(DrmDeviceIds, DrmDeviceId);
+linear_ids!(TabletToolIds, TabletToolId, usize);
 
 pub trait Backend {
     fn run(self: Rc<Self>) -> SpawnedFuture<Result<(), Box<dyn Error>>>;
@@ -58,8 +59,12 @@ pub struct MonitorInfo {
     pub product: String,
     pub serial_number: String,
     pub initial_mode: Mode,
+    /// The mode that the display prefers, usually its native resolution.
+    pub preferred_mode: Option<Mode>,
     pub width_mm: i32,
     pub height_mm: i32,
+    /// Whether the display is not meant to be part of the desktop, e.g. a VR headset.
+    pub non_desktop: bool,
 }
 
 #[derive(Copy, Clone, Debug)]
@@ -82,6 +87,40 @@ pub trait Connector {
     fn damage(&self);
     fn drm_dev(&self) -> Option<DrmDeviceId>;
     fn set_enabled(&self, enabled: bool);
+    fn set_mode(&self, mode: Mode);
+    /// Returns whether a client buffer is currently displayed without compositing.
+    fn direct_scanout_active(&self) -> bool {
+        false
+    }
+    fn set_vrr_mode(&self, mode: VrrMode) {
+        let _ = mode;
+    }
+    /// Returns whether variable refresh rate is currently enabled.
+    fn vrr_active(&self) -> bool {
+        false
+    }
+    /// Returns the number of entries in each gamma ramp or 0 if gamma cannot be changed.
+    fn gamma_size(&self) -> u32 {
+        0
+    }
+    /// Sets the red, green, and blue gamma ramps, in that order.
+    ///
+    /// `None` restores the default gamma.
+    fn set_gamma(&self, _ramps: Option<Rc<[u16]>>) {}
+    /// Returns the id of the DRM connector object.
+    fn drm_object_id(&self) -> Option<DrmConnector> {
+        None
+    }
+}
+
+/// When variable refresh rate is used on a connector.
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq)]
+pub enum VrrMode {
+    #[default]
+    Never,
+    Always,
+    /// Only while a window is fullscreen on the output.
+    Fullscreen,
 }
 
 #[derive(Debug)]
@@ -119,6 +158,17 @@ pub trait InputDevice {
     fn set_tap_enabled(&self, enabled: bool);
     fn set_drag_enabled(&self, enabled: bool);
     fn set_drag_lock_enabled(&self, enabled: bool);
+
+    fn tablet_pad_init(&self) -> TabletPadInit {
+        Default::default()
+    }
+
+    /// Returns an identifier of the physical device that this device belongs to.
+    ///
+    /// For example, a tablet and its pad have the same device group.
+    fn device_group(&self) -> Option<usize> {
+        None
+    }
 }
 
 #[derive(Debug, Copy, Clone, Hash, Eq, PartialEq)]
@@ -151,6 +201,14 @@ pub enum KeyState {
     Pressed,
 }
 
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum SwitchEvent {
+    LidOpened,
+    LidClosed,
+    ConvertedToLaptop,
+    ConvertedToTablet,
+}
+
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
 pub enum ScrollAxis {
     Horizontal = HORIZONTAL_SCROLL as _,
@@ -166,6 +224,70 @@ pub enum AxisSource {
 
 pub const AXIS_120: i32 = 120;
 
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum TabletToolType {
+    Pen,
+    Eraser,
+    Brush,
+    Pencil,
+    Airbrush,
+    Mouse,
+    Lens,
+}
+
+#[derive(Debug, Copy, Clone, Default)]
+pub struct TabletToolCapabilities {
+    pub pressure: bool,
+    pub distance: bool,
+    pub tilt: bool,
+    pub rotation: bool,
+    pub slider: bool,
+    pub wheel: bool,
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct TabletToolInit {
+    pub id: TabletToolId,
+    pub ty: TabletToolType,
+    pub serial: u64,
+    pub hardware_id: u64,
+    pub caps: TabletToolCapabilities,
+}
+
+/// The buttons, rings, and strips of a tablet pad.
+#[derive(Debug, Clone, Default)]
+pub struct TabletPadInit {
+    pub buttons: u32,
+    pub rings: u32,
+    pub strips: u32,
+    pub groups: Vec<TabletPadGroupInit>,
+}
+
+/// A mode group of a tablet pad.
+#[derive(Debug, Clone, Default)]
+pub struct TabletPadGroupInit {
+    pub buttons: Vec<u32>,
+    pub rings: Vec<u32>,
+    pub strips: Vec<u32>,
+    pub modes: u32,
+    pub mode: u32,
+}
+
+/// The axes of a tablet tool that changed.
+///
+/// The position is normalized to `[0, 1]` within the tablet area.
+#[derive(Debug, Clone, Default)]
+pub struct TabletToolChanges {
+    pub x_normed: f64,
+    pub y_normed: f64,
+    pub pressure: Option<f64>,
+    pub distance: Option<f64>,
+    pub tilt: Option<(f64, f64)>,
+    pub rotation: Option<f64>,
+    pub slider: Option<f64>,
+    pub wheel: Option<(f64, i32)>,
+}
+
 #[derive(Debug)]
 pub enum InputEvent {
     Key {
@@ -179,6 +301,12 @@ pub enum InputEvent {
         x: Fixed,
         y: Fixed,
     },
+    /// The position is normalized to `[0, 1]` within the device area.
+    AbsoluteMotion {
+        time_usec: u64,
+        x_normed: f64,
+        y_normed: f64,
+    },
     Motion {
         time_usec: u64,
         dx: Fixed,
@@ -209,11 +337,131 @@ pub enum InputEvent {
     AxisFrame {
         time_usec: u64,
     },
+
+    SwipeBegin {
+        time_usec: u64,
+        finger_count: u32,
+    },
+    SwipeUpdate {
+        time_usec: u64,
+        dx: Fixed,
+        dy: Fixed,
+        dx_unaccelerated: Fixed,
+        dy_unaccelerated: Fixed,
+    },
+    SwipeEnd {
+        time_usec: u64,
+        cancelled: bool,
+    },
+    PinchBegin {
+        time_usec: u64,
+        finger_count: u32,
+    },
+    PinchUpdate {
+        time_usec: u64,
+        dx: Fixed,
+        dy: Fixed,
+        scale: Fixed,
+        rotation: Fixed,
+    },
+    PinchEnd {
+        time_usec: u64,
+        cancelled: bool,
+    },
+    HoldBegin {
+        time_usec: u64,
+        finger_count: u32,
+    },
+    HoldEnd {
+        time_usec: u64,
+        cancelled: bool,
+    },
+
+    /// Touch coordinates are normalized to `[0, 1]` within the device area.
+    TouchDown {
+        time_usec: u64,
+        id: i32,
+        x_normed: f64,
+        y_normed: f64,
+    },
+    TouchMotion {
+        time_usec: u64,
+        id: i32,
+        x_normed: f64,
+        y_normed: f64,
+    },
+    TouchUp {
+        time_usec: u64,
+        id: i32,
+    },
+    TouchFrame,
+    TouchCancel {
+        time_usec: u64,
+    },
+
+    TabletToolProximityIn {
+        tool: TabletToolInit,
+    },
+    TabletToolProximityOut {
+        time_usec: u64,
+        tool: TabletToolId,
+    },
+    TabletToolAxes {
+        time_usec: u64,
+        tool: TabletToolId,
+        changes: Box<TabletToolChanges>,
+    },
+    TabletToolTip {
+        time_usec: u64,
+        tool: TabletToolId,
+        down: bool,
+    },
+    TabletToolButton {
+        time_usec: u64,
+        tool: TabletToolId,
+        button: u32,
+        state: KeyState,
+    },
+    TabletPadButton {
+        time_usec: u64,
+        button: u32,
+        state: KeyState,
+    },
+    /// The current mode of a mode group. This is emitted before every pad event and does
+    /// not indicate that the mode changed.
+    TabletPadModeSwitch {
+        time_usec: u64,
+        group: u32,
+        mode: u32,
+    },
+    /// `degrees` is `None` if the finger was lifted.
+    TabletPadRing {
+        time_usec: u64,
+        ring: u32,
+        finger: bool,
+        degrees: Option<f64>,
+    },
+    /// `position` is normalized to `[0, 1]` and `None` if the finger was lifted.
+    TabletPadStrip {
+        time_usec: u64,
+        strip: u32,
+        finger: bool,
+        position: Option<f64>,
+    },
+
+    Switch {
+        event: SwitchEvent,
+    },
 }
 
+#[derive(Debug)]
 pub enum DrmEvent {
     #[allow(dead_code)]
     Removed,
+    /// The device has been paused, e.g. because another session is active. Connectors
+    /// cannot be leased until the device is resumed.
+    Paused,
+    Resumed,
 }
 
 pub trait BackendDrmDevice {
@@ -223,4 +471,37 @@ pub trait BackendDrmDevice {
     fn dev_t(&self) -> c::dev_t;
     fn make_render_device(self: Rc<Self>);
     fn version(&self) -> Result<DrmVersion, DrmError>;
+    /// Returns whether connectors of this device can be leased to clients.
+    fn supports_leases(&self) -> bool {
+        false
+    }
+    /// Opens a file descriptor for the device that is not DRM master.
+    fn non_master_fd(&self) -> Option<Rc<OwnedFd>> {
+        None
+    }
+    /// Returns whether the device is paused. See [`DrmEvent::Paused`].
+    fn paused(&self) -> bool {
+        false
+    }
+    /// Leases the connectors, together with a crtc and a primary plane for each, to a
+    /// client.
+    ///
+    /// The lease is revoked when the returned object is dropped.
+    fn create_lease(
+        self: Rc<Self>,
+        lessee: Rc<dyn BackendDrmLessee>,
+        connectors: &[ConnectorId],
+    ) -> Option<Rc<dyn BackendDrmLease>> {
+        let _ = (lessee, connectors);
+        None
+    }
+}
+
+pub trait BackendDrmLease {
+    fn fd(&self) -> &Rc<OwnedFd>;
+}
+
+pub trait BackendDrmLessee {
+    /// Called when the backend revokes the lease, e.g. because the device was paused.
+    fn revoked(&self);
 }
@@ -7,7 +7,7 @@ use {
         async_engine::SpawnedFuture,
         backend::{
             Backend, InputDevice, InputDeviceAccelProfile, InputDeviceCapability, InputDeviceId,
-            InputEvent, KeyState, TransformMatrix,
+            InputEvent, KeyState, TabletPadGroupInit, TabletPadInit, TransformMatrix,
         },
         backends::metal::video::{MetalDrmDeviceData, MetalRenderContext, PendingDrmDevice},
         dbus::{DbusError, SignalHandler},
@@ -84,6 +84,10 @@ pub enum MetalError {
     NoModeForConnector,
     #[error("Could not allocate scanout buffer")]
     ScanoutBuffer(#[source] GbmError),
+    #[error("The render and scanout devices do not share a modifier")]
+    NoSharedModifier,
+    #[error("The plane cannot display the scanout buffer")]
+    TestScanoutBuffer(#[source] DrmError),
     #[error("addfb2 failed")]
     Framebuffer(#[source] DrmError),
     #[error("Could not import a framebuffer into EGL")]
@@ -465,6 +469,36 @@ impl InputDevice for MetalInputDevice {
             dev.device().set_drag_lock_enabled(enabled);
         }
     }
+
+    fn tablet_pad_init(&self) -> TabletPadInit {
+        let dev = match self.inputdev.get() {
+            Some(dev) => dev,
+            _ => return Default::default(),
+        };
+        let dev = dev.device();
+        let mut init = TabletPadInit {
+            buttons: dev.tablet_pad_num_buttons(),
+            rings: dev.tablet_pad_num_rings(),
+            strips: dev.tablet_pad_num_strips(),
+            groups: vec![],
+        };
+        for idx in 0..dev.tablet_pad_num_mode_groups() {
+            if let Some(group) = dev.tablet_pad_mode_group(idx) {
+                init.groups.push(TabletPadGroupInit {
+                    buttons: (0..init.buttons).filter(|b| group.has_button(*b)).collect(),
+                    rings: (0..init.rings).filter(|r| group.has_ring(*r)).collect(),
+                    strips: (0..init.strips).filter(|s| group.has_strip(*s)).collect(),
+                    modes: group.num_modes(),
+                    mode: group.mode(),
+                });
+            }
+        }
+        init
+    }
+
+    fn device_group(&self) -> Option<usize> {
+        self.inputdev.get().map(|dev| dev.device().device_group())
+    }
 }
 
 impl MetalInputDevice {
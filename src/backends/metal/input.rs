@@ -1,14 +1,20 @@
 use {
     crate::{
-        backend::{AxisSource, InputEvent, KeyState, ScrollAxis},
-        backends::metal::MetalBackend,
+        backend::{
+            AxisSource, InputEvent, KeyState, ScrollAxis, SwitchEvent, TabletToolCapabilities,
+            TabletToolChanges, TabletToolId, TabletToolInit, TabletToolType,
+        },
+        backends::metal::{MetalBackend, MetalInputDevice},
         fixed::Fixed,
         libinput::{
             consts::{
                 LIBINPUT_BUTTON_STATE_PRESSED, LIBINPUT_KEY_STATE_PRESSED,
                 LIBINPUT_POINTER_AXIS_SCROLL_HORIZONTAL, LIBINPUT_POINTER_AXIS_SCROLL_VERTICAL,
+                LIBINPUT_SWITCH_LID, LIBINPUT_SWITCH_STATE_ON, LIBINPUT_SWITCH_TABLET_MODE,
+                LIBINPUT_TABLET_PAD_RING_SOURCE_FINGER, LIBINPUT_TABLET_PAD_STRIP_SOURCE_FINGER,
+                LIBINPUT_TABLET_TOOL_PROXIMITY_STATE_IN, LIBINPUT_TABLET_TOOL_TIP_DOWN,
             },
-            event::LibInputEvent,
+            event::{LibInputEvent, LibInputEventTabletPad, LibInputEventTabletTool},
         },
         utils::{bitflags::BitflagsExt, errorfmt::ErrorFmt},
     },
@@ -81,6 +87,7 @@ impl MetalBackend {
             c::LIBINPUT_EVENT_DEVICE_REMOVED => self.handle_li_device_removed(event),
             c::LIBINPUT_EVENT_KEYBOARD_KEY => self.handle_keyboard_key(event),
             c::LIBINPUT_EVENT_POINTER_MOTION => self.handle_pointer_motion(event),
+            c::LIBINPUT_EVENT_POINTER_MOTION_ABSOLUTE => self.handle_pointer_motion_absolute(event),
             c::LIBINPUT_EVENT_POINTER_BUTTON => self.handle_pointer_button(event),
             c::LIBINPUT_EVENT_POINTER_SCROLL_WHEEL => {
                 self.handle_pointer_axis(event, AxisSource::Wheel)
@@ -91,6 +98,27 @@ impl MetalBackend {
             c::LIBINPUT_EVENT_POINTER_SCROLL_CONTINUOUS => {
                 self.handle_pointer_axis(event, AxisSource::Continuous)
             }
+            c::LIBINPUT_EVENT_GESTURE_SWIPE_BEGIN => self.handle_gesture_swipe_begin(event),
+            c::LIBINPUT_EVENT_GESTURE_SWIPE_UPDATE => self.handle_gesture_swipe_update(event),
+            c::LIBINPUT_EVENT_GESTURE_SWIPE_END => self.handle_gesture_swipe_end(event),
+            c::LIBINPUT_EVENT_GESTURE_PINCH_BEGIN => self.handle_gesture_pinch_begin(event),
+            c::LIBINPUT_EVENT_GESTURE_PINCH_UPDATE => self.handle_gesture_pinch_update(event),
+            c::LIBINPUT_EVENT_GESTURE_PINCH_END => self.handle_gesture_pinch_end(event),
+            c::LIBINPUT_EVENT_GESTURE_HOLD_BEGIN => self.handle_gesture_hold_begin(event),
+            c::LIBINPUT_EVENT_GESTURE_HOLD_END => self.handle_gesture_hold_end(event),
+            c::LIBINPUT_EVENT_TOUCH_DOWN => self.handle_touch_down(event),
+            c::LIBINPUT_EVENT_TOUCH_MOTION => self.handle_touch_motion(event),
+            c::LIBINPUT_EVENT_TOUCH_UP => self.handle_touch_up(event),
+            c::LIBINPUT_EVENT_TOUCH_FRAME => self.handle_touch_frame(event),
+            c::LIBINPUT_EVENT_TOUCH_CANCEL => self.handle_touch_cancel(event),
+            c::LIBINPUT_EVENT_TABLET_TOOL_AXIS => self.handle_tablet_tool_axis(event),
+            c::LIBINPUT_EVENT_TABLET_TOOL_PROXIMITY => self.handle_tablet_tool_proximity(event),
+            c::LIBINPUT_EVENT_TABLET_TOOL_TIP => self.handle_tablet_tool_tip(event),
+            c::LIBINPUT_EVENT_TABLET_TOOL_BUTTON => self.handle_tablet_tool_button(event),
+            c::LIBINPUT_EVENT_TABLET_PAD_BUTTON => self.handle_tablet_pad_button(event),
+            c::LIBINPUT_EVENT_TABLET_PAD_RING => self.handle_tablet_pad_ring(event),
+            c::LIBINPUT_EVENT_TABLET_PAD_STRIP => self.handle_tablet_pad_strip(event),
+            c::LIBINPUT_EVENT_SWITCH_TOGGLE => self.handle_switch_toggle(event),
             _ => {}
         }
     }
@@ -203,4 +231,306 @@ impl MetalBackend {
             dy_unaccelerated: Fixed::from_f64(dy_unaccelerated),
         });
     }
+
+    fn handle_pointer_motion_absolute(self: &Rc<Self>, event: LibInputEvent) {
+        let (event, dev) = unpack!(self, event, pointer_event);
+        dev.event(InputEvent::AbsoluteMotion {
+            time_usec: event.time_usec(),
+            x_normed: event.absolute_x_normed(),
+            y_normed: event.absolute_y_normed(),
+        });
+    }
+
+    fn handle_gesture_swipe_begin(self: &Rc<Self>, event: LibInputEvent) {
+        let (event, dev) = unpack!(self, event, gesture_event);
+        dev.event(InputEvent::SwipeBegin {
+            time_usec: event.time_usec(),
+            finger_count: event.finger_count(),
+        });
+    }
+
+    fn handle_gesture_swipe_update(self: &Rc<Self>, event: LibInputEvent) {
+        let (event, dev) = unpack!(self, event, gesture_event);
+        dev.event(InputEvent::SwipeUpdate {
+            time_usec: event.time_usec(),
+            dx: Fixed::from_f64(event.dx()),
+            dy: Fixed::from_f64(event.dy()),
+            dx_unaccelerated: Fixed::from_f64(event.dx_unaccelerated()),
+            dy_unaccelerated: Fixed::from_f64(event.dy_unaccelerated()),
+        });
+    }
+
+    fn handle_gesture_swipe_end(self: &Rc<Self>, event: LibInputEvent) {
+        let (event, dev) = unpack!(self, event, gesture_event);
+        dev.event(InputEvent::SwipeEnd {
+            time_usec: event.time_usec(),
+            cancelled: event.cancelled(),
+        });
+    }
+
+    fn handle_gesture_pinch_begin(self: &Rc<Self>, event: LibInputEvent) {
+        let (event, dev) = unpack!(self, event, gesture_event);
+        dev.event(InputEvent::PinchBegin {
+            time_usec: event.time_usec(),
+            finger_count: event.finger_count(),
+        });
+    }
+
+    fn handle_gesture_pinch_update(self: &Rc<Self>, event: LibInputEvent) {
+        let (event, dev) = unpack!(self, event, gesture_event);
+        dev.event(InputEvent::PinchUpdate {
+            time_usec: event.time_usec(),
+            dx: Fixed::from_f64(event.dx()),
+            dy: Fixed::from_f64(event.dy()),
+            scale: Fixed::from_f64(event.scale()),
+            rotation: Fixed::from_f64(event.angle_delta()),
+        });
+    }
+
+    fn handle_gesture_pinch_end(self: &Rc<Self>, event: LibInputEvent) {
+        let (event, dev) = unpack!(self, event, gesture_event);
+        dev.event(InputEvent::PinchEnd {
+            time_usec: event.time_usec(),
+            cancelled: event.cancelled(),
+        });
+    }
+
+    fn handle_gesture_hold_begin(self: &Rc<Self>, event: LibInputEvent) {
+        let (event, dev) = unpack!(self, event, gesture_event);
+        dev.event(InputEvent::HoldBegin {
+            time_usec: event.time_usec(),
+            finger_count: event.finger_count(),
+        });
+    }
+
+    fn handle_gesture_hold_end(self: &Rc<Self>, event: LibInputEvent) {
+        let (event, dev) = unpack!(self, event, gesture_event);
+        dev.event(InputEvent::HoldEnd {
+            time_usec: event.time_usec(),
+            cancelled: event.cancelled(),
+        });
+    }
+
+    fn handle_touch_down(self: &Rc<Self>, event: LibInputEvent) {
+        let (event, dev) = unpack!(self, event, touch_event);
+        dev.event(InputEvent::TouchDown {
+            time_usec: event.time_usec(),
+            id: event.seat_slot(),
+            x_normed: event.x_normed(),
+            y_normed: event.y_normed(),
+        });
+    }
+
+    fn handle_touch_motion(self: &Rc<Self>, event: LibInputEvent) {
+        let (event, dev) = unpack!(self, event, touch_event);
+        dev.event(InputEvent::TouchMotion {
+            time_usec: event.time_usec(),
+            id: event.seat_slot(),
+            x_normed: event.x_normed(),
+            y_normed: event.y_normed(),
+        });
+    }
+
+    fn handle_touch_up(self: &Rc<Self>, event: LibInputEvent) {
+        let (event, dev) = unpack!(self, event, touch_event);
+        dev.event(InputEvent::TouchUp {
+            time_usec: event.time_usec(),
+            id: event.seat_slot(),
+        });
+    }
+
+    fn handle_touch_frame(self: &Rc<Self>, event: LibInputEvent) {
+        let (_, dev) = unpack!(self, event, touch_event);
+        dev.event(InputEvent::TouchFrame);
+    }
+
+    fn handle_touch_cancel(self: &Rc<Self>, event: LibInputEvent) {
+        let (event, dev) = unpack!(self, event, touch_event);
+        dev.event(InputEvent::TouchCancel {
+            time_usec: event.time_usec(),
+        });
+    }
+
+    fn tablet_tool_id(&self, event: &LibInputEventTabletTool) -> TabletToolId {
+        let tool = event.tool();
+        match tool.id() {
+            Some(id) => TabletToolId::from_raw(id),
+            _ => {
+                let id = self.state.tablet_tool_ids.next();
+                tool.set_id(id.raw());
+                id
+            }
+        }
+    }
+
+    fn tablet_tool_init(&self, event: &LibInputEventTabletTool) -> Option<TabletToolInit> {
+        use crate::libinput::consts as c;
+
+        let tool = event.tool();
+        let ty = match tool.ty() {
+            c::LIBINPUT_TABLET_TOOL_TYPE_PEN => TabletToolType::Pen,
+            c::LIBINPUT_TABLET_TOOL_TYPE_ERASER => TabletToolType::Eraser,
+            c::LIBINPUT_TABLET_TOOL_TYPE_BRUSH => TabletToolType::Brush,
+            c::LIBINPUT_TABLET_TOOL_TYPE_PENCIL => TabletToolType::Pencil,
+            c::LIBINPUT_TABLET_TOOL_TYPE_AIRBRUSH => TabletToolType::Airbrush,
+            c::LIBINPUT_TABLET_TOOL_TYPE_MOUSE => TabletToolType::Mouse,
+            c::LIBINPUT_TABLET_TOOL_TYPE_LENS => TabletToolType::Lens,
+            _ => return None,
+        };
+        Some(TabletToolInit {
+            id: self.tablet_tool_id(event),
+            ty,
+            serial: tool.serial(),
+            hardware_id: tool.tool_id(),
+            caps: TabletToolCapabilities {
+                pressure: tool.has_pressure(),
+                distance: tool.has_distance(),
+                tilt: tool.has_tilt(),
+                rotation: tool.has_rotation(),
+                slider: tool.has_slider(),
+                wheel: tool.has_wheel(),
+            },
+        })
+    }
+
+    fn tablet_tool_changes(event: &LibInputEventTabletTool, all: bool) -> TabletToolChanges {
+        let tool = event.tool();
+        let has = |has_axis: bool, changed: bool| has_axis && (all || changed);
+        TabletToolChanges {
+            x_normed: event.x_normed(),
+            y_normed: event.y_normed(),
+            pressure: has(tool.has_pressure(), event.pressure_has_changed())
+                .then(|| event.pressure()),
+            distance: has(tool.has_distance(), event.distance_has_changed())
+                .then(|| event.distance()),
+            tilt: has(tool.has_tilt(), event.tilt_has_changed())
+                .then(|| (event.tilt_x(), event.tilt_y())),
+            rotation: has(tool.has_rotation(), event.rotation_has_changed())
+                .then(|| event.rotation()),
+            slider: has(tool.has_slider(), event.slider_has_changed())
+                .then(|| event.slider_position()),
+            wheel: (tool.has_wheel() && event.wheel_has_changed())
+                .then(|| (event.wheel_delta(), event.wheel_delta_discrete())),
+        }
+    }
+
+    fn handle_tablet_tool_proximity(self: &Rc<Self>, event: LibInputEvent) {
+        let (event, dev) = unpack!(self, event, tablet_tool_event);
+        let tool = match self.tablet_tool_init(&event) {
+            Some(t) => t,
+            _ => return,
+        };
+        if event.proximity_state() == LIBINPUT_TABLET_TOOL_PROXIMITY_STATE_IN {
+            dev.event(InputEvent::TabletToolProximityIn { tool });
+            dev.event(InputEvent::TabletToolAxes {
+                time_usec: event.time_usec(),
+                tool: tool.id,
+                changes: Box::new(Self::tablet_tool_changes(&event, true)),
+            });
+        } else {
+            dev.event(InputEvent::TabletToolProximityOut {
+                time_usec: event.time_usec(),
+                tool: tool.id,
+            });
+        }
+    }
+
+    fn handle_tablet_tool_axis(self: &Rc<Self>, event: LibInputEvent) {
+        let (event, dev) = unpack!(self, event, tablet_tool_event);
+        dev.event(InputEvent::TabletToolAxes {
+            time_usec: event.time_usec(),
+            tool: self.tablet_tool_id(&event),
+            changes: Box::new(Self::tablet_tool_changes(&event, false)),
+        });
+    }
+
+    fn handle_tablet_tool_tip(self: &Rc<Self>, event: LibInputEvent) {
+        let (event, dev) = unpack!(self, event, tablet_tool_event);
+        let tool = self.tablet_tool_id(&event);
+        dev.event(InputEvent::TabletToolAxes {
+            time_usec: event.time_usec(),
+            tool,
+            changes: Box::new(Self::tablet_tool_changes(&event, false)),
+        });
+        dev.event(InputEvent::TabletToolTip {
+            time_usec: event.time_usec(),
+            tool,
+            down: event.tip_state() == LIBINPUT_TABLET_TOOL_TIP_DOWN,
+        });
+    }
+
+    fn handle_tablet_tool_button(self: &Rc<Self>, event: LibInputEvent) {
+        let (event, dev) = unpack!(self, event, tablet_tool_event);
+        let state = match event.button_state() == LIBINPUT_BUTTON_STATE_PRESSED {
+            true => KeyState::Pressed,
+            false => KeyState::Released,
+        };
+        dev.event(InputEvent::TabletToolButton {
+            time_usec: event.time_usec(),
+            tool: self.tablet_tool_id(&event),
+            button: event.button(),
+            state,
+        });
+    }
+
+    fn tablet_pad_mode(&self, dev: &MetalInputDevice, event: &LibInputEventTabletPad) {
+        dev.event(InputEvent::TabletPadModeSwitch {
+            time_usec: event.time_usec(),
+            group: event.mode_group().index(),
+            mode: event.mode(),
+        });
+    }
+
+    fn handle_tablet_pad_button(self: &Rc<Self>, event: LibInputEvent) {
+        let (event, dev) = unpack!(self, event, tablet_pad_event);
+        self.tablet_pad_mode(&dev, &event);
+        let state = match event.button_state() == LIBINPUT_BUTTON_STATE_PRESSED {
+            true => KeyState::Pressed,
+            false => KeyState::Released,
+        };
+        dev.event(InputEvent::TabletPadButton {
+            time_usec: event.time_usec(),
+            button: event.button_number(),
+            state,
+        });
+    }
+
+    fn handle_tablet_pad_ring(self: &Rc<Self>, event: LibInputEvent) {
+        let (event, dev) = unpack!(self, event, tablet_pad_event);
+        self.tablet_pad_mode(&dev, &event);
+        let degrees = event.ring_position();
+        dev.event(InputEvent::TabletPadRing {
+            time_usec: event.time_usec(),
+            ring: event.ring_number(),
+            finger: event.ring_source() == LIBINPUT_TABLET_PAD_RING_SOURCE_FINGER,
+            degrees: (degrees >= 0.0).then_some(degrees),
+        });
+    }
+
+    fn handle_tablet_pad_strip(self: &Rc<Self>, event: LibInputEvent) {
+        let (event, dev) = unpack!(self, event, tablet_pad_event);
+        self.tablet_pad_mode(&dev, &event);
+        let position = event.strip_position();
+        dev.event(InputEvent::TabletPadStrip {
+            time_usec: event.time_usec(),
+            strip: event.strip_number(),
+            finger: event.strip_source() == LIBINPUT_TABLET_PAD_STRIP_SOURCE_FINGER,
+            position: (position >= 0.0).then_some(position),
+        });
+    }
+
+    fn handle_switch_toggle(self: &Rc<Self>, event: LibInputEvent) {
+        let (event, dev) = unpack!(self, event, switch_event);
+        let on = event.switch_state() == LIBINPUT_SWITCH_STATE_ON;
+        let switch_event = match event.switch() {
+            LIBINPUT_SWITCH_LID if on => SwitchEvent::LidClosed,
+            LIBINPUT_SWITCH_LID => SwitchEvent::LidOpened,
+            LIBINPUT_SWITCH_TABLET_MODE if on => SwitchEvent::ConvertedToTablet,
+            LIBINPUT_SWITCH_TABLET_MODE => SwitchEvent::ConvertedToLaptop,
+            _ => return,
+        };
+        dev.event(InputEvent::Switch {
+            event: switch_event,
+        });
+    }
 }
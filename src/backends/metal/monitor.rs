@@ -92,6 +92,7 @@ impl MetalBackend {
         if let Err(e) = self.resume_drm_device(dev) {
             log::error!("Could not resume drm device: {}", ErrorFmt(e));
         }
+        dev.dev.resume();
     }
 
     fn handle_input_device_resume(self: &Rc<Self>, dev: &Rc<MetalInputDevice>, fd: Rc<OwnedFd>) {
@@ -121,6 +122,8 @@ impl MetalBackend {
 
     fn handle_drm_device_removed(self: &Rc<Self>, dev: &Rc<MetalDrmDeviceData>) {
         log::info!("Device removed: {}", dev.dev.devnode.to_bytes().as_bstr());
+        dev.dev.removed.set(true);
+        dev.dev.revoke_leases();
     }
 
     fn handle_input_device_removed(self: &Rc<Self>, dev: &Rc<MetalInputDevice>) {
@@ -150,6 +153,7 @@ impl MetalBackend {
 
     fn handle_drm_device_paused(self: &Rc<Self>, dev: &Rc<MetalDrmDeviceData>) {
         log::info!("Device paused: {}", dev.dev.devnode.to_bytes().as_bstr());
+        dev.dev.pause();
     }
 
     fn handle_input_device_paused(self: &Rc<Self>, dev: &Rc<MetalInputDevice>) {
@@ -2,15 +2,25 @@ use {
     crate::{
         async_engine::{Phase, SpawnedFuture},
         backend::{
-            BackendDrmDevice, BackendEvent, Connector, ConnectorEvent, ConnectorId,
-            ConnectorKernelId, DrmDeviceId, HardwareCursor, MonitorInfo,
+            BackendDrmDevice, BackendDrmLease, BackendDrmLessee, BackendEvent, Connector,
+            ConnectorEvent, ConnectorId, ConnectorKernelId, DrmDeviceId, HardwareCursor, Mode,
+            MonitorInfo, VrrMode,
         },
         backends::metal::{MetalBackend, MetalError},
         edid::Descriptor,
         format::{Format, ARGB8888, XRGB8888},
-        ifs::wp_presentation_feedback::{KIND_HW_COMPLETION, KIND_VSYNC},
+        ifs::{
+            wl_buffer::WlBuffer,
+            wl_surface::{SurfaceNodeId, WlSurface},
+            wp_presentation_feedback::{KIND_HW_COMPLETION, KIND_VSYNC},
+            zwp_linux_dmabuf_feedback_v1::ScanoutFeedback,
+        },
+        rect::{Rect, Region},
         render::{Framebuffer, RenderContext, RenderResult, Texture},
         state::State,
+        time::now_usec,
+        transform::Transform,
+        tree::OutputNode,
         udev::UdevDevice,
         utils::{
             asyncevent::AsyncEvent, bitflags::BitflagsExt, clonecell::CloneCell,
@@ -18,28 +28,32 @@ use {
             oserror::OsError, syncqueue::SyncQueue,
         },
         video::{
+            dmabuf::DmaBuf,
             drm::{
-                drm_mode_modeinfo, Change, ConnectorStatus, ConnectorType, DrmBlob, DrmConnector,
-                DrmCrtc, DrmEncoder, DrmError, DrmEvent, DrmFramebuffer, DrmMaster, DrmModeInfo,
-                DrmObject, DrmPlane, DrmProperty, DrmPropertyDefinition, DrmPropertyType,
-                DrmVersion, PropBlob, DRM_CLIENT_CAP_ATOMIC, DRM_MODE_ATOMIC_ALLOW_MODESET,
-                DRM_MODE_ATOMIC_NONBLOCK, DRM_MODE_PAGE_FLIP_EVENT,
+                drm_color_lut, drm_mode_modeinfo, Change, ConnectorStatus, ConnectorType, DrmBlob,
+                DrmConnector, DrmCrtc, DrmEncoder, DrmError, DrmEvent, DrmFb, DrmFramebuffer,
+                DrmMaster, DrmModeInfo, DrmObject, DrmPlane, DrmProperty, DrmPropertyDefinition,
+                DrmPropertyType, DrmVersion, PropBlob, DRM_CLIENT_CAP_ATOMIC,
+                DRM_MODE_ATOMIC_ALLOW_MODESET, DRM_MODE_ATOMIC_NONBLOCK, DRM_MODE_PAGE_FLIP_ASYNC,
+                DRM_MODE_PAGE_FLIP_EVENT,
             },
-            gbm::{GbmDevice, GBM_BO_USE_LINEAR, GBM_BO_USE_RENDERING, GBM_BO_USE_SCANOUT},
-            ModifiedFormat, INVALID_MODIFIER,
+            gbm::{GbmBo, GbmDevice, GBM_BO_USE_LINEAR, GBM_BO_USE_RENDERING, GBM_BO_USE_SCANOUT},
+            ModifiedFormat, INVALID_MODIFIER, LINEAR_MODIFIER,
         },
     },
     ahash::{AHashMap, AHashSet},
     bstr::{BString, ByteSlice},
+    futures_util::{select, FutureExt},
     std::{
         cell::{Cell, RefCell},
+        collections::VecDeque,
         ffi::CString,
         fmt::{Debug, Formatter},
         mem,
         ops::DerefMut,
-        rc::Rc,
+        rc::{Rc, Weak},
     },
-    uapi::{c, c::dev_t},
+    uapi::{c, c::dev_t, OwnedFd},
 };
 
 pub struct PendingDrmDevice {
@@ -73,6 +87,11 @@ pub struct MetalDrmDevice {
     pub gbm: GbmDevice,
     pub handle_events: HandleEvents,
     pub ctx: Rc<MetalRenderContext>,
+    pub leases: CopyHashMap<u32, Weak<MetalLease>>,
+    pub paused: Cell<bool>,
+    pub removed: Cell<bool>,
+    pub events: SyncQueue<crate::backend::DrmEvent>,
+    pub on_change: OnChange,
 }
 
 impl BackendDrmDevice for MetalDrmDevice {
@@ -81,11 +100,11 @@ impl BackendDrmDevice for MetalDrmDevice {
     }
 
     fn event(&self) -> Option<crate::backend::DrmEvent> {
-        None
+        self.events.pop()
     }
 
-    fn on_change(&self, _cb: Rc<dyn Fn()>) {
-        // nothing
+    fn on_change(&self, cb: Rc<dyn Fn()>) {
+        self.on_change.on_change.set(Some(cb));
     }
 
     fn dev_t(&self) -> dev_t {
@@ -99,6 +118,223 @@ impl BackendDrmDevice for MetalDrmDevice {
     fn version(&self) -> Result<DrmVersion, DrmError> {
         self.gbm.drm.version()
     }
+
+    fn supports_leases(&self) -> bool {
+        true
+    }
+
+    fn non_master_fd(&self) -> Option<Rc<OwnedFd>> {
+        match self.master.open_non_master() {
+            Ok(fd) => Some(fd),
+            Err(e) => {
+                log::error!("Could not open a non-master fd: {}", ErrorFmt(e));
+                None
+            }
+        }
+    }
+
+    fn paused(&self) -> bool {
+        self.paused.get()
+    }
+
+    fn create_lease(
+        self: Rc<Self>,
+        lessee: Rc<dyn BackendDrmLessee>,
+        connector_ids: &[ConnectorId],
+    ) -> Option<Rc<dyn BackendDrmLease>> {
+        let dev = self.backend.device_holder.drm_devices.get(&self.devnum)?;
+        let mut connectors = vec![];
+        let mut crtcs = vec![];
+        let mut planes = vec![];
+        let mut objects = vec![];
+        for id in connector_ids {
+            let connector = dev
+                .connectors
+                .lock()
+                .values()
+                .find(|c| c.connector_id == *id)
+                .cloned();
+            let connector = match connector {
+                Some(c) => c,
+                _ => {
+                    log::error!("Cannot lease a connector that no longer exists");
+                    return None;
+                }
+            };
+            let dd = connector.display.borrow_mut();
+            if !dd.non_desktop || dd.connection != ConnectorStatus::Connected {
+                log::error!("Can only lease connected non-desktop connectors");
+                return None;
+            }
+            if connector.leased.get() {
+                log::error!("Connector is already leased");
+                return None;
+            }
+            let crtc = dd.crtcs.values().find(|c| {
+                c.connector.get().is_none()
+                    && !c.leased.get()
+                    && !crtcs.iter().any(|o: &Rc<MetalCrtc>| o.id == c.id)
+            });
+            let crtc = match crtc {
+                Some(c) => c.clone(),
+                _ => {
+                    log::error!("There is no free crtc for the lease");
+                    return None;
+                }
+            };
+            let plane = crtc.possible_planes.values().find(|p| {
+                p.ty == PlaneType::Primary
+                    && !p.assigned.get()
+                    && !planes.iter().any(|o: &Rc<MetalPlane>| o.id == p.id)
+            });
+            let plane = match plane {
+                Some(p) => p.clone(),
+                _ => {
+                    log::error!("There is no free primary plane for the lease");
+                    return None;
+                }
+            };
+            objects.extend([connector.id.0, crtc.id.0, plane.id.0]);
+            drop(dd);
+            connectors.push(connector);
+            crtcs.push(crtc);
+            planes.push(plane);
+        }
+        let (fd, lessee_id) = match self.master.create_lease(&objects) {
+            Ok(l) => l,
+            Err(e) => {
+                log::error!("Could not create a lease: {}", ErrorFmt(e));
+                return None;
+            }
+        };
+        for connector in &connectors {
+            connector.leased.set(true);
+        }
+        for crtc in &crtcs {
+            crtc.leased.set(true);
+        }
+        for plane in &planes {
+            plane.leased.set(true);
+            plane.assigned.set(true);
+        }
+        let lease = Rc::new(MetalLease {
+            dev: self.clone(),
+            lessee_id,
+            fd: Rc::new(fd),
+            lessee: CloneCell::new(Some(lessee)),
+            connectors,
+            crtcs,
+            planes,
+        });
+        self.leases.set(lessee_id, Rc::downgrade(&lease));
+        Some(lease)
+    }
+}
+
+impl MetalDrmDevice {
+    fn send_event(&self, event: crate::backend::DrmEvent) {
+        self.events.push(event);
+        if let Some(cb) = self.on_change.on_change.get() {
+            cb();
+        }
+    }
+
+    /// Revokes all leases and stops leasing connectors until the device is resumed.
+    pub fn pause(&self) {
+        if !self.paused.replace(true) {
+            self.revoke_leases();
+            self.send_event(crate::backend::DrmEvent::Paused);
+        }
+    }
+
+    pub fn resume(&self) {
+        if self.paused.replace(false) {
+            self.send_event(crate::backend::DrmEvent::Resumed);
+        }
+    }
+
+    /// Revokes all leases of this device.
+    pub fn revoke_leases(&self) {
+        let leases: Vec<_> = self.leases.lock().values().cloned().collect();
+        for lease in leases {
+            if let Some(lease) = lease.upgrade() {
+                if let Some(lessee) = lease.lessee.take() {
+                    lessee.revoked();
+                }
+            }
+        }
+    }
+}
+
+pub struct MetalLease {
+    pub dev: Rc<MetalDrmDevice>,
+    pub lessee_id: u32,
+    pub fd: Rc<OwnedFd>,
+    pub lessee: CloneCell<Option<Rc<dyn BackendDrmLessee>>>,
+    pub connectors: Vec<Rc<MetalConnector>>,
+    pub crtcs: Vec<Rc<MetalCrtc>>,
+    pub planes: Vec<Rc<MetalPlane>>,
+}
+
+impl Debug for MetalLease {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MetalLease")
+            .field("lessee_id", &self.lessee_id)
+            .finish_non_exhaustive()
+    }
+}
+
+impl BackendDrmLease for MetalLease {
+    fn fd(&self) -> &Rc<OwnedFd> {
+        &self.fd
+    }
+}
+
+impl Drop for MetalLease {
+    fn drop(&mut self) {
+        self.dev.leases.remove(&self.lessee_id);
+        let inactive = self.dev.paused.get() || self.dev.removed.get();
+        if !inactive {
+            if let Err(e) = self.dev.master.revoke_lease(self.lessee_id) {
+                log::warn!("Could not revoke lease: {}", ErrorFmt(e));
+            }
+        }
+        // The lessee might have left the objects enabled. If the device is inactive, the
+        // objects are reset when it is resumed.
+        let mut changes = self.dev.master.change();
+        for connector in &self.connectors {
+            connector.leased.set(false);
+            let dd = connector.display.borrow_mut();
+            dd.crtc_id.value.set(DrmCrtc::NONE);
+            changes.change_object(connector.id, |c| {
+                c.change(dd.crtc_id.id, 0);
+            });
+        }
+        for crtc in &self.crtcs {
+            crtc.leased.set(false);
+            crtc.active.value.set(false);
+            crtc.mode_id.value.set(DrmBlob::NONE);
+            changes.change_object(crtc.id, |c| {
+                c.change(crtc.active.id, 0);
+                c.change(crtc.mode_id.id, 0);
+            });
+        }
+        for plane in &self.planes {
+            plane.leased.set(false);
+            plane.assigned.set(false);
+            plane.crtc_id.value.set(DrmCrtc::NONE);
+            changes.change_object(plane.id, |c| {
+                c.change(plane.crtc_id.id, 0);
+                c.change(plane.fb_id, 0);
+            });
+        }
+        if inactive {
+            return;
+        }
+        if let Err(e) = changes.commit(DRM_MODE_ATOMIC_ALLOW_MODESET, 0) {
+            log::warn!("Could not disable the objects of a lease: {}", ErrorFmt(e));
+        }
+    }
 }
 
 pub struct HandleEvents {
@@ -135,17 +371,50 @@ pub struct ConnectorDisplayData {
     pub mm_width: u32,
     pub mm_height: u32,
     pub subpixel: u32,
+    pub vrr_capable: bool,
+    /// Whether the display should not be used by the compositor, e.g. a VR headset.
+    pub non_desktop: bool,
 
     pub connector_type: ConnectorType,
     pub connector_type_id: u32,
 }
 
+/// Identifies a monitor across reconnects.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct MonitorIdentity {
+    pub manufacturer: String,
+    pub name: String,
+    pub serial_number: String,
+}
+
 impl ConnectorDisplayData {
     fn is_same_monitor(&self, other: &Self) -> bool {
         self.monitor_manufacturer == other.monitor_manufacturer
             && self.monitor_name == other.monitor_name
             && self.monitor_serial_number == other.monitor_serial_number
     }
+
+    fn monitor_identity(&self) -> MonitorIdentity {
+        MonitorIdentity {
+            manufacturer: self.monitor_manufacturer.clone(),
+            name: self.monitor_name.clone(),
+            serial_number: self.monitor_serial_number.clone(),
+        }
+    }
+
+    /// Uses the desired mode if the display supports it and the first mode otherwise.
+    fn select_mode(&mut self, desired: Option<Mode>) {
+        self.mode = desired
+            .and_then(|d| self.modes.iter().find(|m| m.to_backend() == d))
+            .or(self.modes.first())
+            .cloned()
+            .map(Rc::new);
+        self.refresh = self
+            .mode
+            .as_ref()
+            .map(|m| 1_000_000_000_000u64 / (m.refresh_rate_millihz() as u64))
+            .unwrap_or(0) as u32;
+    }
 }
 
 #[derive(Debug)]
@@ -163,19 +432,38 @@ pub struct MetalConnector {
 
     pub buffers: CloneCell<Option<Rc<[RenderBuffer; 2]>>>,
     pub next_buffer: NumCell<usize>,
+    /// The damage of the most recent frames rendered into `buffers`, oldest first.
+    pub damage_history: RefCell<VecDeque<Rc<Region>>>,
 
     pub enabled: Cell<bool>,
+    pub desired_mode: Cell<Option<Mode>>,
+    /// The monitor that `desired_mode` was chosen for.
+    pub desired_mode_monitor: RefCell<MonitorIdentity>,
 
     pub can_present: Cell<bool>,
     pub has_damage: Cell<bool>,
     pub cursor_changed: Cell<bool>,
+    /// Whether the pending page flip is an async (tearing) flip.
+    pub async_flip: Cell<bool>,
+    /// The time of the most recent successful commit in microseconds.
+    pub last_present: Cell<u64>,
+
+    pub vrr_mode: Cell<VrrMode>,
+
+    /// The gamma ramps requested for this connector. `None` means the default gamma.
+    pub gamma: CloneCell<Option<Rc<[u16]>>>,
+    /// Whether `gamma` has to be applied in the next commit.
+    pub gamma_changed: Cell<bool>,
 
     pub display: RefCell<ConnectorDisplayData>,
 
     pub connect_sent: Cell<bool>,
+    /// Whether the connector is leased to a client.
+    pub leased: Cell<bool>,
 
     pub primary_plane: CloneCell<Option<Rc<MetalPlane>>>,
     pub cursor_plane: CloneCell<Option<Rc<MetalPlane>>>,
+    pub overlay_planes: RefCell<Vec<Rc<MetalPlane>>>,
 
     pub crtc: CloneCell<Option<Rc<MetalCrtc>>>,
 
@@ -185,6 +473,10 @@ pub struct MetalConnector {
 
     pub render_result: RefCell<RenderResult>,
 
+    pub direct_scanout: DirectScanoutData,
+    /// The surface that was last sent a scanout tranche for this connector.
+    pub scanout_feedback_surface: CloneCell<Option<Rc<WlSurface>>>,
+
     pub cursor_generation: NumCell<u64>,
     pub cursor_x: Cell<i32>,
     pub cursor_y: Cell<i32>,
@@ -258,6 +550,76 @@ impl HardwareCursor for MetalHardwareCursor {
     }
 }
 
+/// The minimum interval between commits that only update the cursor while VRR is enabled.
+///
+/// Without this limit, moving the cursor would drive the display to its maximum refresh
+/// rate. 20 ms is close to the lower end of the refresh range of most VRR monitors.
+const VRR_CURSOR_INTERVAL_USEC: u64 = 20_000;
+
+/// The maximum number of client buffers for which framebuffers are cached.
+const MAX_CLIENT_FBS: usize = 8;
+
+/// The maximum number of overlay planes used by a connector.
+const MAX_OVERLAY_PLANES: usize = 3;
+
+/// A client buffer and the framebuffer through which it is displayed on a plane.
+type ClientFb = (Rc<WlBuffer>, Rc<DrmFramebuffer>);
+
+#[derive(Default)]
+pub struct DirectScanoutData {
+    /// Whether the primary plane displays a client buffer instead of a rendered frame.
+    pub active: Cell<bool>,
+    /// The client buffers currently displayed on the primary or overlay planes.
+    pub buffers: RefCell<Vec<ClientFb>>,
+    /// The client buffers that will be displayed after the next page flip.
+    pub pending_buffers: RefCell<Vec<ClientFb>>,
+    /// Whether the next page flip changes the primary or overlay planes.
+    pub flip_pending: Cell<bool>,
+    /// The surfaces displayed on overlay planes and their positions.
+    pub overlays: RefCell<Vec<(SurfaceNodeId, Rect)>>,
+    /// The overlay plane candidates of the last test commit and which of them were
+    /// accepted.
+    pub overlay_tests: RefCell<Option<(Vec<OverlayCandidate>, Vec<bool>)>>,
+    /// The framebuffers of recently displayed client buffers. `None` if the buffer could
+    /// not be imported.
+    pub fbs: RefCell<VecDeque<(Weak<WlBuffer>, Option<Rc<DrmFramebuffer>>)>>,
+}
+
+impl Debug for DirectScanoutData {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DirectScanoutData")
+            .field("active", &self.active.get())
+            .finish_non_exhaustive()
+    }
+}
+
+struct DirectScanout {
+    surface: Rc<WlSurface>,
+    buffer: Rc<WlBuffer>,
+    fb: Rc<DrmFramebuffer>,
+}
+
+/// The properties of an overlay plane candidate that determine whether a test commit
+/// succeeds.
+#[derive(Copy, Clone, Eq, PartialEq)]
+pub struct OverlayCandidate {
+    surface: SurfaceNodeId,
+    format: u32,
+    size: (i32, i32),
+    src: [u64; 4],
+    dst: Rect,
+}
+
+struct OverlayPlacement {
+    surface: Rc<WlSurface>,
+    buffer: Rc<WlBuffer>,
+    fb: Rc<DrmFramebuffer>,
+    /// The source rectangle in 16.16 fixed-point buffer coordinates.
+    src: [u64; 4],
+    /// The destination rectangle in physical output coordinates.
+    dst: Rect,
+}
+
 pub struct ConnectorFutures {
     pub present: SpawnedFuture<()>,
 }
@@ -286,10 +648,97 @@ impl MetalConnector {
     async fn present_loop(self: Rc<Self>) {
         loop {
             self.present_trigger.triggered().await;
+            while let Some(delay) = self.vrr_cursor_delay() {
+                select! {
+                    _ = self.present_trigger.triggered().fuse() => { },
+                    res = self.state.wheel.timeout(delay).fuse() => {
+                        if let Err(e) = res {
+                            log::error!("Could not wait for timeout: {}", ErrorFmt(e));
+                            break;
+                        }
+                    },
+                }
+            }
             self.present();
         }
     }
 
+    /// Returns how many milliseconds a commit that only updates the cursor should be
+    /// delayed.
+    fn vrr_cursor_delay(&self) -> Option<u64> {
+        if !self.vrr_active() || self.has_damage.get() || !self.cursor_changed.get() {
+            return None;
+        }
+        let elapsed = now_usec().saturating_sub(self.last_present.get());
+        if elapsed >= VRR_CURSOR_INTERVAL_USEC {
+            return None;
+        }
+        Some((VRR_CURSOR_INTERVAL_USEC - elapsed).div_ceil(1000))
+    }
+
+    /// Adds the requested gamma ramps to the commit.
+    ///
+    /// Returns the blob that has to be kept alive while it is attached to the crtc.
+    fn change_gamma(&self, changes: &mut Change, crtc: &MetalCrtc) -> Option<Option<Rc<PropBlob>>> {
+        if !self.gamma_changed.get() {
+            return None;
+        }
+        let gamma_lut = crtc.gamma_lut.as_ref()?;
+        let mut blob = None;
+        if let Some(ramps) = self.gamma.get() {
+            let size = crtc.gamma_lut_size as usize;
+            if ramps.len() != 3 * size {
+                log::warn!(
+                    "Gamma ramps have {} entries but crtc expects {}",
+                    ramps.len(),
+                    3 * size
+                );
+                self.gamma_changed.set(false);
+                return None;
+            }
+            let lut: Vec<_> = (0..size)
+                .map(|i| drm_color_lut {
+                    red: ramps[i],
+                    green: ramps[size + i],
+                    blue: ramps[2 * size + i],
+                    reserved: 0,
+                })
+                .collect();
+            match self.master.create_blob(&lut[..]) {
+                Ok(b) => blob = Some(Rc::new(b)),
+                Err(e) => {
+                    log::error!("Could not create gamma blob: {}", ErrorFmt(e));
+                    self.gamma_changed.set(false);
+                    return None;
+                }
+            }
+        }
+        let id = blob.as_ref().map(|b| b.id()).unwrap_or(DrmBlob::NONE);
+        changes.change_object(crtc.id, |c| {
+            c.change(gamma_lut.id, id.0 as _);
+        });
+        Some(blob)
+    }
+
+    /// Returns whether VRR should be enabled for the next commit.
+    fn vrr_wanted(&self, crtc: &MetalCrtc) -> bool {
+        if crtc.vrr_enabled.is_none() || !self.display.borrow().vrr_capable {
+            return false;
+        }
+        match self.vrr_mode.get() {
+            VrrMode::Never => false,
+            VrrMode::Always => true,
+            VrrMode::Fullscreen => self
+                .state
+                .root
+                .outputs
+                .get(&self.connector_id)
+                .and_then(|node| node.workspace.get())
+                .map(|ws| ws.fullscreen.get().is_some())
+                .unwrap_or(false),
+        }
+    }
+
     fn send_hardware_cursor(self: &Rc<Self>) {
         if !self.connect_sent.get() {
             return;
@@ -318,6 +767,17 @@ impl MetalConnector {
             && self.primary_plane.get().is_some()
     }
 
+    /// Returns whether a non-desktop display is connected that can be offered for leasing.
+    fn non_desktop_connected(&self) -> bool {
+        let dd = self.display.borrow_mut();
+        self.enabled.get() && dd.connection == ConnectorStatus::Connected && dd.non_desktop
+    }
+
+    /// Returns whether the connector should be driven by the compositor.
+    fn needs_crtc(&self, dd: &ConnectorDisplayData) -> bool {
+        self.enabled.get() && dd.connection == ConnectorStatus::Connected && !dd.non_desktop
+    }
+
     fn send_event(&self, event: ConnectorEvent) {
         self.events.push(event);
         if let Some(oc) = self.on_change.on_change.get() {
@@ -329,6 +789,335 @@ impl MetalConnector {
         self.present_trigger.trigger();
     }
 
+    /// Returns the area of the next buffer that has to be re-rendered.
+    ///
+    /// The buffers are used round-robin, so the next buffer last contained the frame that
+    /// was rendered `num_buffers` frames ago. `None` means that the contents of the buffer
+    /// are undefined and that it has to be re-rendered completely.
+    fn buffer_damage(&self, damage: Rc<Region>, num_buffers: usize) -> Option<Rc<Region>> {
+        let mut history = self.damage_history.borrow_mut();
+        let res = if history.len() < num_buffers {
+            None
+        } else {
+            let mut res = damage.clone();
+            for old in history.iter().skip(1) {
+                res = res.union(old);
+            }
+            Some(res)
+        };
+        history.push_back(damage);
+        if history.len() > num_buffers {
+            history.pop_front();
+        }
+        res
+    }
+
+    fn client_planes_supported(&self) -> bool {
+        match self.backend.ctx.get() {
+            Some(ctx) => ctx.dev_id == self.dev.id,
+            _ => false,
+        }
+    }
+
+    fn client_fb(&self, buffer: &Rc<WlBuffer>) -> Option<Rc<DrmFramebuffer>> {
+        let mut fbs = self.direct_scanout.fbs.borrow_mut();
+        fbs.retain(|(b, _)| b.strong_count() > 0);
+        if let Some((_, fb)) = fbs.iter().find(|(b, _)| b.as_ptr() == Rc::as_ptr(buffer)) {
+            return fb.clone();
+        }
+        let fb = match self.master.add_fb(buffer.dmabuf()?) {
+            Ok(fb) => Some(Rc::new(fb)),
+            Err(e) => {
+                log::debug!("Could not import client buffer: {}", ErrorFmt(e));
+                None
+            }
+        };
+        fbs.push_back((Rc::downgrade(buffer), fb.clone()));
+        if fbs.len() > MAX_CLIENT_FBS {
+            fbs.pop_front();
+        }
+        fb
+    }
+
+    fn prepare_direct_scanout(
+        &self,
+        node: &OutputNode,
+        crtc: &MetalCrtc,
+        plane: &MetalPlane,
+    ) -> Option<DirectScanout> {
+        let mut candidate = None;
+        if self.client_planes_supported() {
+            candidate = node.direct_scanout_surface();
+        }
+        // The feedback does not depend on cursors and popups. Otherwise moving the cursor
+        // over the output would make the client reallocate its buffers.
+        self.update_scanout_feedback(candidate.as_ref().map(|c| &c.0), plane);
+        let (surface, buffer) = candidate?;
+        if node.direct_scanout_occluded(!self.cursor_enabled.get()) {
+            return None;
+        }
+        let modifier = buffer.dmabuf()?.modifier;
+        if !plane.supports_modifier(buffer.format.drm, modifier) {
+            return None;
+        }
+        let fb = self.client_fb(&buffer)?;
+        let mut changes = self.master.change();
+        changes.change_object(plane.id, |c| {
+            c.change(plane.fb_id, fb.id().0 as _);
+        });
+        self.set_overlay_planes(&mut changes, crtc, &[]);
+        if let Err(e) = changes.test(DRM_MODE_ATOMIC_NONBLOCK) {
+            log::debug!("Client buffer cannot be scanned out: {}", ErrorFmt(e));
+            return None;
+        }
+        Some(DirectScanout {
+            surface,
+            buffer,
+            fb,
+        })
+    }
+
+    /// Tells the direct scanout candidate which formats and modifiers the primary plane
+    /// supports so that the client can allocate buffers that can be scanned out.
+    fn update_scanout_feedback(&self, surface: Option<&Rc<WlSurface>>, plane: &MetalPlane) {
+        let old = self.scanout_feedback_surface.get();
+        if old.as_ref().map(Rc::as_ptr) == surface.map(Rc::as_ptr) {
+            return;
+        }
+        if let Some(old) = old {
+            old.set_scanout_feedback(None);
+        }
+        if let Some(surface) = surface {
+            surface.set_scanout_feedback(Some(Rc::new(ScanoutFeedback {
+                device: self.dev.devnum,
+                formats: plane.format_modifiers(),
+            })));
+        }
+        self.scanout_feedback_surface.set(surface.cloned());
+    }
+
+    /// Assigns client buffers to the overlay planes of this connector.
+    ///
+    /// Every assignment is validated with a test-only commit together with the previous
+    /// assignments and the framebuffer `primary_fb` on the primary plane. The results are
+    /// reused until the surfaces, formats, sizes, or positions of the candidates change.
+    fn assign_overlay_planes(
+        &self,
+        node: &OutputNode,
+        crtc: &MetalCrtc,
+        plane: &MetalPlane,
+        primary_fb: DrmFb,
+    ) -> Vec<OverlayPlacement> {
+        let mut res = vec![];
+        let overlay_planes = self.overlay_planes.borrow();
+        if overlay_planes.is_empty() || !self.client_planes_supported() {
+            self.direct_scanout.overlay_tests.take();
+            return res;
+        }
+        let opos = node.global.pos.get();
+        let scale = node.preferred_scale.get().to_f64();
+        let scale = |v: i32| (v as f64 * scale).round() as i32;
+        let mut candidates = vec![];
+        for (surface, buffer) in node.overlay_plane_candidates(!self.cursor_enabled.get()) {
+            let fb = match self.client_fb(&buffer) {
+                Some(fb) => fb,
+                _ => continue,
+            };
+            let rect = surface.buffer_abs_pos.get().move_(-opos.x1(), -opos.y1());
+            let dst = match Rect::new(
+                scale(rect.x1()),
+                scale(rect.y1()),
+                scale(rect.x2()),
+                scale(rect.y2()),
+            ) {
+                Some(dst) if !dst.is_empty() => dst,
+                _ => continue,
+            };
+            let src = surface.plane_src_rect(&buffer);
+            candidates.push(OverlayPlacement {
+                surface,
+                buffer,
+                fb,
+                src,
+                dst,
+            });
+        }
+        let keys: Vec<_> = candidates
+            .iter()
+            .map(|p| OverlayCandidate {
+                surface: p.surface.node_id,
+                format: p.buffer.format.drm,
+                size: (p.buffer.rect.width(), p.buffer.rect.height()),
+                src: p.src,
+                dst: p.dst,
+            })
+            .collect();
+        let mut tests = self.direct_scanout.overlay_tests.borrow_mut();
+        if let Some((prev, accepted)) = &*tests {
+            if *prev == keys {
+                for (placement, accepted) in candidates.into_iter().zip(accepted.iter()) {
+                    if *accepted {
+                        res.push(placement);
+                    }
+                }
+                return res;
+            }
+        }
+        let mut accepted = vec![];
+        for placement in candidates {
+            let overlay = match overlay_planes.get(res.len()) {
+                Some(p) => p,
+                _ => {
+                    accepted.push(false);
+                    continue;
+                }
+            };
+            if !overlay.formats.contains_key(&placement.buffer.format.drm) {
+                accepted.push(false);
+                continue;
+            }
+            res.push(placement);
+            let mut changes = self.master.change();
+            changes.change_object(plane.id, |c| {
+                c.change(plane.fb_id, primary_fb.0 as _);
+            });
+            self.set_overlay_planes(&mut changes, crtc, &res);
+            if let Err(e) = changes.test(DRM_MODE_ATOMIC_NONBLOCK) {
+                log::debug!(
+                    "Client buffer cannot be displayed on an overlay plane: {}",
+                    ErrorFmt(e)
+                );
+                res.pop();
+            }
+            accepted.push(res.len() > accepted.iter().filter(|a| **a).count());
+        }
+        *tests = Some((keys, accepted));
+        res
+    }
+
+    /// Displays the placements on the overlay planes and disables the remaining planes.
+    fn set_overlay_planes(
+        &self,
+        changes: &mut Change,
+        crtc: &MetalCrtc,
+        placements: &[OverlayPlacement],
+    ) {
+        for (idx, plane) in self.overlay_planes.borrow().iter().enumerate() {
+            match placements.get(idx) {
+                Some(p) => changes.change_object(plane.id, |c| {
+                    c.change(plane.fb_id, p.fb.id().0 as _);
+                    c.change(plane.crtc_id.id, crtc.id.0 as _);
+                    c.change(plane.crtc_x.id, p.dst.x1() as _);
+                    c.change(plane.crtc_y.id, p.dst.y1() as _);
+                    c.change(plane.crtc_w.id, p.dst.width() as _);
+                    c.change(plane.crtc_h.id, p.dst.height() as _);
+                    c.change(plane.src_x.id, p.src[0]);
+                    c.change(plane.src_y.id, p.src[1]);
+                    c.change(plane.src_w.id, p.src[2]);
+                    c.change(plane.src_h.id, p.src[3]);
+                }),
+                _ => changes.change_object(plane.id, |c| {
+                    c.change(plane.fb_id, 0);
+                    c.change(plane.crtc_id.id, 0);
+                }),
+            }
+        }
+    }
+
+    /// Damages the areas of surfaces that move onto or off of overlay planes.
+    fn update_overlays(&self, node: &OutputNode, placements: &[OverlayPlacement]) {
+        let new: Vec<_> = placements
+            .iter()
+            .map(|p| (p.surface.node_id, p.surface.buffer_abs_pos.get()))
+            .collect();
+        let mut overlays = self.direct_scanout.overlays.borrow_mut();
+        if *overlays != new {
+            for (_, rect) in overlays.iter().chain(new.iter()) {
+                node.add_damage(*rect);
+            }
+            *overlays = new;
+        }
+    }
+
+    fn update_direct_scanout(&self, active: bool, buffers: Vec<ClientFb>) {
+        for (buffer, _) in &buffers {
+            buffer.scanout_start();
+        }
+        let old = mem::replace(
+            self.direct_scanout.pending_buffers.borrow_mut().deref_mut(),
+            buffers,
+        );
+        for (buffer, _) in old {
+            buffer.scanout_end();
+        }
+        self.direct_scanout.flip_pending.set(true);
+        if self.direct_scanout.active.replace(active) != active {
+            let status = match active {
+                true => "enabled",
+                false => "disabled",
+            };
+            log::info!("{}: Direct scanout {}", self.kernel_id(), status);
+        }
+    }
+
+    fn handle_direct_scanout_flip(&self) {
+        let ds = &self.direct_scanout;
+        if ds.flip_pending.take() {
+            let pending = mem::take(ds.pending_buffers.borrow_mut().deref_mut());
+            let old = mem::replace(ds.buffers.borrow_mut().deref_mut(), pending);
+            for (buffer, _) in old {
+                buffer.scanout_end();
+            }
+        }
+    }
+
+    fn reset_direct_scanout(&self) {
+        let ds = &self.direct_scanout;
+        ds.flip_pending.set(false);
+        ds.active.set(false);
+        ds.overlays.borrow_mut().clear();
+        ds.overlay_tests.take();
+        if let Some(surface) = self.scanout_feedback_surface.take() {
+            surface.set_scanout_feedback(None);
+        }
+        let mut buffers = mem::take(ds.buffers.borrow_mut().deref_mut());
+        buffers.append(ds.pending_buffers.borrow_mut().deref_mut());
+        for (buffer, _) in buffers {
+            buffer.scanout_end();
+        }
+    }
+
+    /// Renders the output into `buffer`.
+    fn render(
+        &self,
+        node: &OutputNode,
+        buffer: &RenderBuffer,
+        num_buffers: usize,
+        overlays: &[SurfaceNodeId],
+    ) {
+        let damage = self.buffer_damage(node.take_damage(), num_buffers);
+        let mut rr = self.render_result.borrow_mut();
+        let render_fb = buffer.render_fb();
+        render_fb.render(
+            node,
+            &self.state,
+            Some(node.global.pos.get()),
+            true,
+            &mut rr,
+            node.preferred_scale.get(),
+            !self.cursor_enabled.get(),
+            node.global.transform.get(),
+            damage.as_deref(),
+            overlays,
+        );
+        buffer.copy_to_dev(&self.state, false);
+        for fr in rr.frame_requests.drain(..) {
+            fr.send_done();
+            let _ = fr.client.remove_obj(&*fr);
+        }
+        node.perform_screencopies(&render_fb, &buffer.render_tex);
+    }
+
     pub fn present(&self) {
         let crtc = match self.crtc.get() {
             Some(crtc) => crtc,
@@ -350,35 +1139,78 @@ impl MetalConnector {
         };
         let cursor = self.cursor_plane.get();
         let mut changes = self.master.change();
-        if self.has_damage.get() {
+        let vrr = self.vrr_wanted(&crtc);
+        if let Some(prop) = &crtc.vrr_enabled {
+            if prop.value.get() != vrr {
+                changes.change_object(crtc.id, |c| {
+                    c.change(prop.id, vrr as _);
+                });
+            }
+        }
+        let gamma_blob = self.change_gamma(&mut changes, &crtc);
+        let vrr_changed = match &crtc.vrr_enabled {
+            Some(prop) => prop.value.get() != vrr,
+            _ => false,
+        };
+        let primary_changed = self.has_damage.get();
+        let mut direct_scanout_active = false;
+        let mut async_fb = None;
+        let mut client_fbs = vec![];
+        if primary_changed {
             if !self.backend.check_render_context(&self.dev) {
                 return;
             }
-            let buffer = &buffers[self.next_buffer.fetch_add(1) % buffers.len()];
-            if let Some(node) = self.state.root.outputs.get(&self.connector_id) {
-                let mut rr = self.render_result.borrow_mut();
-                let render_fb = buffer.render_fb();
-                render_fb.render(
-                    &*node,
-                    &self.state,
-                    Some(node.global.pos.get()),
-                    true,
-                    &mut rr,
-                    node.preferred_scale.get(),
-                    !self.cursor_enabled.get(),
-                );
-                if let Some(tex) = &buffer.dev_tex {
-                    buffer.dev_fb.copy_texture(&self.state, tex, 0, 0, false);
+            let node = self.state.root.outputs.get(&self.connector_id);
+            let mut direct_scanout = None;
+            if let Some(node) = &node {
+                direct_scanout = self.prepare_direct_scanout(node, &crtc, &plane);
+            }
+            let mut overlays = vec![];
+            let fb = match direct_scanout {
+                Some(ds) => {
+                    if let Some(node) = &node {
+                        node.take_damage();
+                    }
+                    self.damage_history.borrow_mut().clear();
+                    self.direct_scanout.overlays.borrow_mut().clear();
+                    for fr in ds.surface.frame_requests.borrow_mut().drain(..) {
+                        fr.send_done();
+                        let _ = fr.client.remove_obj(&*fr);
+                    }
+                    let mut fbs = ds.surface.presentation_feedback.borrow_mut();
+                    let mut rr = self.render_result.borrow_mut();
+                    rr.presentation_feedbacks.extend(fbs.drain(..));
+                    direct_scanout_active = true;
+                    let fb = ds.fb.id();
+                    // Async flips can only change the framebuffer of the primary plane.
+                    let tearing = self.state.tearing_allowed.get()
+                        && ds.surface.tearing.get()
+                        && self.direct_scanout.active.get()
+                        && !self.cursor_changed.get()
+                        && gamma_blob.is_none()
+                        && !vrr_changed;
+                    if tearing {
+                        async_fb = Some(fb);
+                    }
+                    client_fbs.push((ds.buffer, ds.fb));
+                    fb
                 }
-                for fr in rr.frame_requests.drain(..) {
-                    fr.send_done();
-                    let _ = fr.client.remove_obj(&*fr);
+                _ => {
+                    let buffer = &buffers[self.next_buffer.fetch_add(1) % buffers.len()];
+                    if let Some(node) = &node {
+                        overlays = self.assign_overlay_planes(node, &crtc, &plane, buffer.drm.id());
+                        self.update_overlays(node, &overlays);
+                        let ids: Vec<_> = overlays.iter().map(|o| o.surface.node_id).collect();
+                        self.render(node, buffer, buffers.len(), &ids);
+                    }
+                    buffer.drm.id()
                 }
-                node.perform_screencopies(&render_fb, &buffer.render_tex);
-            }
+            };
             changes.change_object(plane.id, |c| {
-                c.change(plane.fb_id, buffer.drm.id().0 as _);
+                c.change(plane.fb_id, fb.0 as _);
             });
+            self.set_overlay_planes(&mut changes, &crtc, &overlays);
+            client_fbs.extend(overlays.into_iter().map(|o| (o.buffer, o.fb)));
         }
         if self.cursor_changed.get() && cursor.is_some() {
             let plane = cursor.unwrap();
@@ -390,9 +1222,7 @@ impl MetalConnector {
                 let buffers = self.cursor_buffers.get().unwrap();
                 let buffer = &buffers[self.cursor_front_buffer.get() % buffers.len()];
                 if swap_buffer {
-                    if let Some(tex) = &buffer.dev_tex {
-                        buffer.dev_fb.copy_texture(&self.state, tex, 0, 0, true);
-                    }
+                    buffer.copy_to_dev(&self.state, true);
                 }
                 changes.change_object(plane.id, |c| {
                     c.change(plane.fb_id, buffer.drm.id().0 as _);
@@ -413,7 +1243,24 @@ impl MetalConnector {
                 });
             }
         }
-        if let Err(e) = changes.commit(DRM_MODE_ATOMIC_NONBLOCK | DRM_MODE_PAGE_FLIP_EVENT, 0) {
+        let mut async_flip = false;
+        if let Some(fb) = async_fb {
+            let mut changes = self.master.change();
+            changes.change_object(plane.id, |c| {
+                c.change(plane.fb_id, fb.0 as _);
+            });
+            let flags =
+                DRM_MODE_ATOMIC_NONBLOCK | DRM_MODE_PAGE_FLIP_EVENT | DRM_MODE_PAGE_FLIP_ASYNC;
+            match changes.commit(flags, 0) {
+                Ok(()) => async_flip = true,
+                Err(e) => log::debug!("Could not perform async page flip: {}", ErrorFmt(e)),
+            }
+        }
+        let res = match async_flip {
+            true => Ok(()),
+            false => changes.commit(DRM_MODE_ATOMIC_NONBLOCK | DRM_MODE_PAGE_FLIP_EVENT, 0),
+        };
+        if let Err(e) = res {
             match e {
                 DrmError::Atomic(OsError(c::EACCES)) => {
                     log::debug!("Could not perform atomic commit, likely because we're no longer the DRM master");
@@ -421,6 +1268,34 @@ impl MetalConnector {
                 _ => log::error!("Could not set plane framebuffer: {}", ErrorFmt(e)),
             }
         } else {
+            if primary_changed {
+                self.update_direct_scanout(direct_scanout_active, client_fbs);
+            }
+            if let Some(prop) = &crtc.vrr_enabled {
+                if prop.value.replace(vrr) != vrr {
+                    let dd = self.display.borrow();
+                    let state = match vrr {
+                        true => "enabled",
+                        false => "disabled",
+                    };
+                    log::info!(
+                        "{}-{}: VRR {}",
+                        dd.connector_type,
+                        dd.connector_type_id,
+                        state
+                    );
+                }
+            }
+            if let Some(blob) = gamma_blob {
+                if let Some(gamma_lut) = &crtc.gamma_lut {
+                    let id = blob.as_ref().map(|b| b.id()).unwrap_or(DrmBlob::NONE);
+                    gamma_lut.value.set(id);
+                }
+                crtc.gamma_blob.set(blob);
+                self.gamma_changed.set(false);
+            }
+            self.async_flip.set(async_flip);
+            self.last_present.set(now_usec());
             self.can_present.set(false);
             self.has_damage.set(false);
             self.cursor_changed.set(false);
@@ -460,6 +1335,10 @@ impl Connector for MetalConnector {
         Some(self.dev.id)
     }
 
+    fn drm_object_id(&self) -> Option<DrmConnector> {
+        Some(self.id)
+    }
+
     fn set_enabled(&self, enabled: bool) {
         if self.enabled.replace(enabled) != enabled {
             if self.display.borrow_mut().connection == ConnectorStatus::Connected {
@@ -472,6 +1351,90 @@ impl Connector for MetalConnector {
             }
         }
     }
+
+    fn set_mode(&self, mode: Mode) {
+        let mut dd = self.display.borrow_mut();
+        if !dd.modes.iter().any(|m| m.to_backend() == mode) {
+            log::warn!(
+                "Connector {}-{} does not support mode {:?}",
+                dd.connector_type,
+                dd.connector_type_id,
+                mode
+            );
+            return;
+        }
+        let prev = self.desired_mode.replace(Some(mode));
+        *self.desired_mode_monitor.borrow_mut() = dd.monitor_identity();
+        if dd.mode.as_ref().map(|m| m.to_backend()) == Some(mode) {
+            return;
+        }
+        if dd.connection != ConnectorStatus::Connected {
+            return;
+        }
+        let prev_dd_mode = dd.mode.clone();
+        dd.select_mode(Some(mode));
+        drop(dd);
+        if let Some(dev) = self.backend.device_holder.drm_devices.get(&self.dev.devnum) {
+            let connector = dev.connectors.get(&self.id);
+            let res = match connector.map(|c| self.backend.change_connector_mode(&c)) {
+                Some(Ok(())) => Ok(()),
+                Some(Err(e)) => {
+                    log::warn!(
+                        "Could not change the mode of the connector, reinitializing the device: {}",
+                        ErrorFmt(e)
+                    );
+                    // The planes of the connector are in an unknown state.
+                    self.backend.handle_drm_change_(&dev, false)
+                }
+                _ => self.backend.handle_drm_change_(&dev, true),
+            };
+            if let Err(e) = res {
+                self.desired_mode.set(prev);
+                let mut dd = self.display.borrow_mut();
+                let prev_dd_mode = prev_dd_mode.map(|m| m.to_backend());
+                dd.select_mode(prev_dd_mode);
+                dev.unprocessed_change.set(true);
+                log::error!("Could not change mode: {}", ErrorFmt(e));
+                return;
+            }
+        }
+        if self.connect_sent.get() {
+            self.send_event(ConnectorEvent::ModeChanged(mode));
+        }
+    }
+
+    fn direct_scanout_active(&self) -> bool {
+        self.direct_scanout.active.get()
+    }
+
+    fn set_vrr_mode(&self, mode: VrrMode) {
+        if self.vrr_mode.replace(mode) != mode {
+            self.damage();
+        }
+    }
+
+    fn gamma_size(&self) -> u32 {
+        match self.crtc.get() {
+            Some(crtc) if crtc.gamma_lut.is_some() => crtc.gamma_lut_size,
+            _ => 0,
+        }
+    }
+
+    fn set_gamma(&self, ramps: Option<Rc<[u16]>>) {
+        self.gamma.set(ramps);
+        self.gamma_changed.set(true);
+        self.damage();
+    }
+
+    fn vrr_active(&self) -> bool {
+        match self.crtc.get() {
+            Some(crtc) => match &crtc.vrr_enabled {
+                Some(prop) => prop.value.get(),
+                _ => false,
+            },
+            _ => false,
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -487,8 +1450,14 @@ pub struct MetalCrtc {
     pub active: MutableProperty<bool>,
     pub mode_id: MutableProperty<DrmBlob>,
     pub out_fence_ptr: DrmProperty,
+    pub vrr_enabled: Option<MutableProperty<bool>>,
+    pub gamma_lut: Option<MutableProperty<DrmBlob>>,
+    pub gamma_lut_size: u32,
+    pub gamma_blob: CloneCell<Option<Rc<PropBlob>>>,
 
     pub mode_blob: CloneCell<Option<Rc<PropBlob>>>,
+
+    pub leased: Cell<bool>,
 }
 
 #[derive(Debug)]
@@ -513,8 +1482,12 @@ pub struct MetalPlane {
 
     pub possible_crtcs: u32,
     pub formats: AHashMap<u32, &'static Format>,
+    /// The modifiers supported for each format. `None` if the plane does not have an
+    /// `IN_FORMATS` property.
+    pub in_formats: Option<AHashMap<u32, AHashSet<u64>>>,
 
     pub assigned: Cell<bool>,
+    pub leased: Cell<bool>,
 
     pub crtc_id: MutableProperty<DrmCrtc>,
     pub crtc_x: MutableProperty<i32>,
@@ -529,6 +1502,34 @@ pub struct MetalPlane {
     pub fb_id: DrmProperty,
 }
 
+impl MetalPlane {
+    /// Returns whether the plane can display buffers with this format and modifier.
+    pub fn supports_modifier(&self, format: u32, modifier: u64) -> bool {
+        match &self.in_formats {
+            Some(f) => f.get(&format).is_some_and(|m| m.contains(&modifier)),
+            _ => {
+                self.formats.contains_key(&format)
+                    && matches!(modifier, LINEAR_MODIFIER | INVALID_MODIFIER)
+            }
+        }
+    }
+
+    /// Returns all format and modifier pairs accepted by [`Self::supports_modifier`].
+    pub fn format_modifiers(&self) -> AHashSet<(u32, u64)> {
+        match &self.in_formats {
+            Some(f) => f
+                .iter()
+                .flat_map(|(format, mods)| mods.iter().map(|m| (*format, *m)))
+                .collect(),
+            _ => self
+                .formats
+                .keys()
+                .flat_map(|f| [(*f, LINEAR_MODIFIER), (*f, INVALID_MODIFIER)])
+                .collect(),
+        }
+    }
+}
+
 fn get_connectors(
     backend: &Rc<MetalBackend>,
     dev: &Rc<MetalDrmDevice>,
@@ -560,7 +1561,7 @@ fn create_connector(
     connector: DrmConnector,
     dev: &Rc<MetalDrmDevice>,
 ) -> Result<(Rc<MetalConnector>, ConnectorFutures), DrmError> {
-    let display = create_connector_display_data(connector, dev)?;
+    let display = create_connector_display_data(connector, dev, None)?;
     let slf = Rc::new(MetalConnector {
         id: connector,
         master: dev.master.clone(),
@@ -571,15 +1572,26 @@ fn create_connector(
         events: Default::default(),
         buffers: Default::default(),
         next_buffer: Default::default(),
+        damage_history: Default::default(),
         enabled: Cell::new(true),
+        desired_mode: Cell::new(None),
+        desired_mode_monitor: Default::default(),
         can_present: Cell::new(true),
         has_damage: Cell::new(true),
+        async_flip: Cell::new(false),
+        last_present: Cell::new(0),
+        vrr_mode: Cell::new(VrrMode::Never),
+        gamma: Default::default(),
+        gamma_changed: Cell::new(false),
         primary_plane: Default::default(),
         cursor_plane: Default::default(),
+        overlay_planes: Default::default(),
         crtc: Default::default(),
         on_change: Default::default(),
         present_trigger: Default::default(),
         render_result: RefCell::new(Default::default()),
+        direct_scanout: Default::default(),
+        scanout_feedback_surface: Default::default(),
         cursor_generation: Default::default(),
         cursor_x: Cell::new(0),
         cursor_y: Cell::new(0),
@@ -587,6 +1599,7 @@ fn create_connector(
         cursor_buffers: Default::default(),
         display: RefCell::new(display),
         connect_sent: Cell::new(false),
+        leased: Cell::new(false),
         cursor_changed: Cell::new(false),
         cursor_front_buffer: Default::default(),
         cursor_swap_buffer: Cell::new(false),
@@ -603,6 +1616,7 @@ fn create_connector(
 fn create_connector_display_data(
     connector: DrmConnector,
     dev: &Rc<MetalDrmDevice>,
+    desired_mode: Option<Mode>,
 ) -> Result<ConnectorDisplayData, DrmError> {
     let info = dev.master.get_connector_info(connector, true)?;
     let mut crtcs = AHashMap::new();
@@ -618,11 +1632,6 @@ fn create_connector_display_data(
     let mut name = String::new();
     let mut manufacturer = String::new();
     let mut serial_number = String::new();
-    let mode = info.modes.first().cloned().map(Rc::new);
-    let refresh = mode
-        .as_ref()
-        .map(|m| 1_000_000_000_000u64 / (m.refresh_rate_millihz() as u64))
-        .unwrap_or(0) as u32;
     let connector_type = ConnectorType::from_drm(info.connector_type);
     let connector_name = debug_fn(|f| write!(f, "{}-{}", connector_type, info.connector_type_id));
     'fetch_edid: {
@@ -689,12 +1698,12 @@ fn create_connector_display_data(
     }
     let props = collect_properties(&dev.master, connector)?;
     let connector_type = ConnectorType::from_drm(info.connector_type);
-    Ok(ConnectorDisplayData {
+    let mut dd = ConnectorDisplayData {
         crtc_id: props.get("CRTC_ID")?.map(|v| DrmCrtc(v as _)),
         crtcs,
         modes: info.modes,
-        mode,
-        refresh,
+        mode: None,
+        refresh: 0,
         monitor_manufacturer: manufacturer,
         monitor_name: name,
         monitor_serial_number: serial_number,
@@ -702,9 +1711,19 @@ fn create_connector_display_data(
         mm_width: info.mm_width,
         mm_height: info.mm_height,
         subpixel: info.subpixel,
+        vrr_capable: props
+            .get("vrr_capable")
+            .map(|p| p.value.get() == 1)
+            .unwrap_or(false),
+        non_desktop: props
+            .get("non-desktop")
+            .map(|p| p.value.get() == 1)
+            .unwrap_or(false),
         connector_type,
         connector_type_id: info.connector_type_id,
-    })
+    };
+    dd.select_mode(desired_mode);
+    Ok(dd)
 }
 
 fn create_encoder(
@@ -748,7 +1767,18 @@ fn create_crtc(
         active: props.get("ACTIVE")?.map(|v| v == 1),
         mode_id: props.get("MODE_ID")?.map(|v| DrmBlob(v as u32)),
         out_fence_ptr: props.get("OUT_FENCE_PTR")?.id,
+        vrr_enabled: props.get("VRR_ENABLED").ok().map(|p| p.map(|v| v == 1)),
+        gamma_lut: props
+            .get("GAMMA_LUT")
+            .ok()
+            .map(|p| p.map(|v| DrmBlob(v as _))),
+        gamma_lut_size: props
+            .get("GAMMA_LUT_SIZE")
+            .map(|p| p.value.get() as u32)
+            .unwrap_or(0),
+        gamma_blob: Default::default(),
         mode_blob: Default::default(),
+        leased: Cell::new(false),
     })
 }
 
@@ -767,6 +1797,13 @@ fn create_plane(plane: DrmPlane, master: &Rc<DrmMaster>) -> Result<MetalPlane, D
         }
     }
     let props = collect_properties(master, plane)?;
+    let mut in_formats = None;
+    if let Ok(prop) = props.get("IN_FORMATS") {
+        match master.getblob_vec::<u8>(DrmBlob(prop.value.get() as _)) {
+            Ok(blob) => in_formats = parse_in_formats(&blob),
+            Err(e) => log::warn!("Could not read the IN_FORMATS blob: {}", ErrorFmt(e)),
+        }
+    }
     let ty = match props.props.get(b"type".as_bstr()) {
         Some((def, val)) => match &def.ty {
             DrmPropertyType::Enum { values, .. } => 'ty: {
@@ -796,6 +1833,7 @@ fn create_plane(plane: DrmPlane, master: &Rc<DrmMaster>) -> Result<MetalPlane, D
         ty,
         possible_crtcs: info.possible_crtcs,
         formats,
+        in_formats,
         fb_id: props.get("FB_ID")?.id,
         crtc_id: props.get("CRTC_ID")?.map(|v| DrmCrtc(v as _)),
         crtc_x: props.get("CRTC_X")?.map(|v| v as i32),
@@ -808,9 +1846,46 @@ fn create_plane(plane: DrmPlane, master: &Rc<DrmMaster>) -> Result<MetalPlane, D
         src_h: props.get("SRC_H")?.map(|v| v as u32),
         in_fence_fd: props.get("IN_FENCE_FD")?.id,
         assigned: Cell::new(false),
+        leased: Cell::new(false),
     })
 }
 
+/// Parses a `drm_format_modifier_blob`.
+fn parse_in_formats(blob: &[u8]) -> Option<AHashMap<u32, AHashSet<u64>>> {
+    let u32_at = |off: usize| {
+        blob.get(off..off + 4)
+            .map(|b| u32::from_ne_bytes(b.try_into().unwrap()))
+    };
+    let u64_at = |off: usize| {
+        blob.get(off..off + 8)
+            .map(|b| u64::from_ne_bytes(b.try_into().unwrap()))
+    };
+    let count_formats = u32_at(8)? as usize;
+    let formats_offset = u32_at(12)? as usize;
+    let count_modifiers = u32_at(16)? as usize;
+    let modifiers_offset = u32_at(20)? as usize;
+    let mut formats = Vec::with_capacity(count_formats);
+    for i in 0..count_formats {
+        formats.push(u32_at(formats_offset + 4 * i)?);
+    }
+    let mut res = AHashMap::<u32, AHashSet<u64>>::new();
+    for i in 0..count_modifiers {
+        // struct drm_format_modifier { __u64 formats; __u32 offset; __u32 pad; __u64 modifier; }
+        let off = modifiers_offset + 24 * i;
+        let mask = u64_at(off)?;
+        let offset = u32_at(off + 8)? as usize;
+        let modifier = u64_at(off + 16)?;
+        for bit in 0..64 {
+            if mask & (1 << bit) != 0 {
+                if let Some(format) = formats.get(offset + bit) {
+                    res.entry(*format).or_default().insert(modifier);
+                }
+            }
+        }
+    }
+    Some(res)
+}
+
 fn collect_properties<T: DrmObject>(
     master: &Rc<DrmMaster>,
     t: T,
@@ -969,7 +2044,7 @@ impl MetalBackend {
         }
         let mut preserve = Preserve::default();
         for c in dev.connectors.lock().values() {
-            let mut dd = match create_connector_display_data(c.id, &dev.dev) {
+            let mut dd = match create_connector_display_data(c.id, &dev.dev, c.desired_mode.get()) {
                 Ok(d) => d,
                 Err(e) => {
                     log::error!(
@@ -980,6 +2055,14 @@ impl MetalBackend {
                 }
             };
             let mut old = c.display.borrow_mut();
+            if c.desired_mode.get().is_some()
+                && dd.connection == ConnectorStatus::Connected
+                && *c.desired_mode_monitor.borrow() != dd.monitor_identity()
+            {
+                // The mode was chosen for a different display.
+                c.desired_mode.take();
+                dd.select_mode(None);
+            }
             mem::swap(old.deref_mut(), &mut dd);
             if c.connect_sent.get() {
                 if !c.enabled.get()
@@ -1014,6 +2097,8 @@ impl MetalBackend {
                     connector.can_present.set(true);
                 }
                 self.start_connector(connector, true);
+            } else if connector.non_desktop_connected() && !connector.connect_sent.get() {
+                self.send_connected(connector, &connector.display.borrow_mut());
             }
         }
         dev.unprocessed_change.set(false);
@@ -1033,9 +2118,15 @@ impl MetalBackend {
             manufacturer: dd.monitor_manufacturer.clone(),
             product: dd.monitor_name.clone(),
             serial_number: dd.monitor_serial_number.clone(),
-            initial_mode: dd.mode.clone().unwrap().to_backend(),
+            initial_mode: dd.mode.as_ref().map(|m| m.to_backend()).unwrap_or_default(),
+            preferred_mode: dd
+                .modes
+                .iter()
+                .find(|m| m.is_preferred())
+                .map(|m| m.to_backend()),
             width_mm: dd.mm_width as _,
             height_mm: dd.mm_height as _,
+            non_desktop: dd.non_desktop,
         }));
         connector.connect_sent.set(true);
         connector.send_hardware_cursor();
@@ -1123,6 +2214,11 @@ impl MetalBackend {
                 handle_events: Cell::new(None),
             },
             ctx,
+            leases: Default::default(),
+            paused: Cell::new(false),
+            removed: Cell::new(false),
+            events: Default::default(),
+            on_change: Default::default(),
         });
 
         let (connectors, futures) = get_connectors(self, &dev, &resources.connectors)?;
@@ -1146,6 +2242,8 @@ impl MetalBackend {
                 .push(BackendEvent::NewConnector(connector.clone()));
             if connector.connected() {
                 self.start_connector(connector, true);
+            } else if connector.non_desktop_connected() {
+                self.send_connected(connector, &connector.display.borrow_mut());
             }
         }
 
@@ -1177,6 +2275,14 @@ impl MetalBackend {
             c.mode_id
                 .value
                 .set(DrmBlob(get(&props, c.mode_id.id)? as _));
+            if let Some(vrr_enabled) = &c.vrr_enabled {
+                vrr_enabled.value.set(get(&props, vrr_enabled.id)? != 0);
+            }
+            if let Some(gamma_lut) = &c.gamma_lut {
+                gamma_lut
+                    .value
+                    .set(DrmBlob(get(&props, gamma_lut.id)? as _));
+            }
         }
         for c in dev.dev.planes.values() {
             let props = collect_untyped_properties(master, c.id)?;
@@ -1259,6 +2365,11 @@ impl MetalBackend {
             _ => return,
         };
         connector.can_present.set(true);
+        connector.handle_direct_scanout_flip();
+        let kind = match connector.async_flip.take() {
+            true => KIND_HW_COMPLETION,
+            false => KIND_VSYNC | KIND_HW_COMPLETION,
+        };
         if connector.has_damage.get() || connector.cursor_changed.get() {
             connector.schedule_present();
         }
@@ -1275,13 +2386,7 @@ impl MetalBackend {
                             fb.send_sync_output(binding);
                         }
                     }
-                    fb.send_presented(
-                        tv_sec as _,
-                        tv_usec * 1000,
-                        refresh,
-                        sequence as _,
-                        KIND_VSYNC | KIND_HW_COMPLETION,
-                    );
+                    fb.send_presented(tv_sec as _, tv_usec * 1000, refresh, sequence as _, kind);
                     let _ = fb.client.remove_obj(&*fb);
                 }
             } else {
@@ -1295,7 +2400,7 @@ impl MetalBackend {
 
     fn reset_planes(&self, dev: &MetalDrmDeviceData, changes: &mut Change, preserve: &Preserve) {
         for plane in dev.dev.planes.values() {
-            if preserve.planes.contains(&plane.id) {
+            if preserve.planes.contains(&plane.id) || plane.leased.get() {
                 continue;
             }
             plane.crtc_id.value.set(DrmCrtc::NONE);
@@ -1315,11 +2420,14 @@ impl MetalBackend {
         preserve: &Preserve,
     ) {
         for connector in dev.connectors.lock().values() {
-            if preserve.connectors.contains(&connector.id) {
+            if preserve.connectors.contains(&connector.id) || connector.leased.get() {
                 continue;
             }
+            connector.reset_direct_scanout();
+            connector.gamma_changed.set(connector.gamma.get().is_some());
             connector.primary_plane.set(None);
             connector.cursor_plane.set(None);
+            connector.overlay_planes.borrow_mut().clear();
             connector.cursor_enabled.set(false);
             connector.crtc.set(None);
             let dd = connector.display.borrow_mut();
@@ -1329,16 +2437,29 @@ impl MetalBackend {
             })
         }
         for crtc in dev.dev.crtcs.values() {
-            if preserve.crtcs.contains(&crtc.id) {
+            if preserve.crtcs.contains(&crtc.id) || crtc.leased.get() {
                 continue;
             }
             crtc.connector.set(None);
             crtc.active.value.set(false);
             crtc.mode_id.value.set(DrmBlob::NONE);
+            if let Some(vrr_enabled) = &crtc.vrr_enabled {
+                vrr_enabled.value.set(false);
+            }
+            if let Some(gamma_lut) = &crtc.gamma_lut {
+                gamma_lut.value.set(DrmBlob::NONE);
+            }
+            crtc.gamma_blob.take();
             changes.change_object(crtc.id, |c| {
                 c.change(crtc.active.id, 0);
                 c.change(crtc.mode_id.id, 0);
                 c.change(crtc.out_fence_ptr, 0);
+                if let Some(vrr_enabled) = &crtc.vrr_enabled {
+                    c.change(vrr_enabled.id, 0);
+                }
+                if let Some(gamma_lut) = &crtc.gamma_lut {
+                    c.change(gamma_lut.id, 0);
+                }
             })
         }
     }
@@ -1413,6 +2534,9 @@ impl MetalBackend {
                 if let Some(pp) = connector.cursor_plane.get() {
                     preserve.planes.insert(pp.id);
                 }
+                for op in connector.overlay_planes.borrow().iter() {
+                    preserve.planes.insert(op.id);
+                }
                 if let Some(crtc) = connector.crtc.get() {
                     preserve.crtcs.insert(crtc.id);
                 }
@@ -1496,8 +2620,11 @@ impl MetalBackend {
         let mut used_planes = AHashSet::new();
 
         for connector in dev.connectors.lock().values() {
+            if connector.leased.get() {
+                continue;
+            }
             let dd = connector.display.borrow_mut();
-            if !connector.enabled.get() || dd.connection != ConnectorStatus::Connected {
+            if !connector.needs_crtc(&dd) {
                 if dd.crtc_id.value.get().is_some() {
                     log::debug!("Connector is not connected but has an assigned crtc");
                     return false;
@@ -1555,6 +2682,9 @@ impl MetalBackend {
         let mut changes = dev.dev.master.change();
         let mut flags = 0;
         for crtc in dev.dev.crtcs.values() {
+            if crtc.leased.get() {
+                continue;
+            }
             changes.change_object(crtc.id, |c| {
                 if !used_crtcs.contains(&crtc.id) && crtc.active.value.take() {
                     flags |= DRM_MODE_ATOMIC_ALLOW_MODESET;
@@ -1578,9 +2708,9 @@ impl MetalBackend {
         width: i32,
         height: i32,
         ctx: &MetalRenderContext,
-        cursor: bool,
+        target: &ScanoutPlane,
     ) -> Result<[RenderBuffer; 2], MetalError> {
-        let create = || self.create_scanout_buffer(dev, format, width, height, ctx, cursor);
+        let create = || self.create_scanout_buffer(dev, format, width, height, ctx, target);
         Ok([create()?, create()?])
     }
 
@@ -1591,14 +2721,37 @@ impl MetalBackend {
         width: i32,
         height: i32,
         render_ctx: &MetalRenderContext,
+        target: &ScanoutPlane,
+    ) -> Result<RenderBuffer, MetalError> {
+        let cursor = target.plane.ty == PlaneType::Cursor;
+        if dev.id == render_ctx.dev_id {
+            return self.create_local_scanout_buffer(dev, format, width, height, cursor);
+        }
+        match self.create_shared_scanout_buffer(dev, format, width, height, render_ctx, target) {
+            Ok(b) => return Ok(b),
+            Err(e) => log::debug!("Could not create a shared scanout buffer: {}", ErrorFmt(e)),
+        }
+        match self.create_bridged_scanout_buffer(dev, format, width, height, render_ctx, cursor) {
+            Ok(b) => return Ok(b),
+            Err(e) => log::debug!("Could not create a bridged scanout buffer: {}", ErrorFmt(e)),
+        }
+        self.create_cpu_scanout_buffer(dev, format, width, height, render_ctx)
+    }
+
+    /// Creates a buffer that is rendered to and scanned out by the same device.
+    fn create_local_scanout_buffer(
+        &self,
+        dev: &Rc<MetalDrmDevice>,
+        format: &ModifiedFormat,
+        width: i32,
+        height: i32,
         cursor: bool,
     ) -> Result<RenderBuffer, MetalError> {
         let mut usage = GBM_BO_USE_RENDERING | GBM_BO_USE_SCANOUT;
         if cursor {
             usage |= GBM_BO_USE_LINEAR;
         };
-        let dev_bo = dev.gbm.create_bo(width, height, format, usage);
-        let dev_bo = match dev_bo {
+        let dev_bo = match dev.gbm.create_bo(width, height, format, usage) {
             Ok(b) => b,
             Err(e) => return Err(MetalError::ScanoutBuffer(e)),
         };
@@ -1606,61 +2759,152 @@ impl MetalBackend {
             Ok(fb) => Rc::new(fb),
             Err(e) => return Err(MetalError::Framebuffer(e)),
         };
-        let dev_img = match dev.ctx.egl.dmabuf_img(dev_bo.dmabuf()) {
+        let (render_fb, render_tex) = import_render_buffer(&dev.ctx.egl, dev_bo.dmabuf())?;
+        Ok(RenderBuffer {
+            drm: drm_fb,
+            render_fb,
+            render_tex,
+            transfer: BufferTransfer::None,
+        })
+    }
+
+    /// Creates a buffer in the render device that the scanout device can scan out
+    /// directly.
+    ///
+    /// Only modifiers supported by the plane are used and every buffer is validated with
+    /// a test-only commit on the plane.
+    fn create_shared_scanout_buffer(
+        &self,
+        dev: &Rc<MetalDrmDevice>,
+        format: &ModifiedFormat,
+        width: i32,
+        height: i32,
+        render_ctx: &MetalRenderContext,
+        target: &ScanoutPlane,
+    ) -> Result<RenderBuffer, MetalError> {
+        let mut modifiers = vec![];
+        if target.plane.ty != PlaneType::Cursor {
+            modifiers = shared_modifiers(&render_ctx.egl, &dev.ctx.egl, format.format);
+        }
+        modifiers.push(LINEAR_MODIFIER);
+        modifiers.retain(|m| target.plane.supports_modifier(format.format.drm, *m));
+        let mut res = Err(MetalError::NoSharedModifier);
+        for modifier in modifiers {
+            let format = ModifiedFormat {
+                format: format.format,
+                modifier,
+            };
+            let mut usage = GBM_BO_USE_RENDERING | GBM_BO_USE_SCANOUT;
+            if modifier == LINEAR_MODIFIER {
+                usage |= GBM_BO_USE_LINEAR;
+            }
+            let render_bo = match render_ctx.egl.gbm.create_bo(width, height, &format, usage) {
+                Ok(b) => b,
+                Err(e) => {
+                    res = Err(MetalError::ScanoutBuffer(e));
+                    continue;
+                }
+            };
+            // Scanning out the buffer fails unless the scanout device supports the
+            // modifier.
+            let drm_fb = match dev.master.add_fb(render_bo.dmabuf()) {
+                Ok(fb) => Rc::new(fb),
+                Err(e) => {
+                    res = Err(MetalError::Framebuffer(e));
+                    continue;
+                }
+            };
+            if let Err(e) = target.test(&drm_fb, width, height) {
+                res = Err(MetalError::TestScanoutBuffer(e));
+                continue;
+            }
+            let (render_fb, render_tex) =
+                import_render_buffer(&render_ctx.egl, render_bo.dmabuf())?;
+            return Ok(RenderBuffer {
+                drm: drm_fb,
+                render_fb,
+                render_tex,
+                transfer: BufferTransfer::None,
+            });
+        }
+        res
+    }
+
+    /// Creates a buffer in the scanout device that is filled by copying a linear bridge
+    /// buffer in the render device with the GPU of the scanout device.
+    fn create_bridged_scanout_buffer(
+        &self,
+        dev: &Rc<MetalDrmDevice>,
+        format: &ModifiedFormat,
+        width: i32,
+        height: i32,
+        render_ctx: &MetalRenderContext,
+        cursor: bool,
+    ) -> Result<RenderBuffer, MetalError> {
+        let local = self.create_local_scanout_buffer(dev, format, width, height, cursor)?;
+        let usage = GBM_BO_USE_RENDERING | GBM_BO_USE_LINEAR;
+        let render_bo = match render_ctx.egl.gbm.create_bo(width, height, format, usage) {
+            Ok(b) => b,
+            Err(e) => return Err(MetalError::ScanoutBuffer(e)),
+        };
+        let (render_fb, render_tex) = import_render_buffer(&render_ctx.egl, render_bo.dmabuf())?;
+        let dev_img = match dev.ctx.egl.dmabuf_img(render_bo.dmabuf()) {
             Ok(img) => img,
             Err(e) => return Err(MetalError::ImportImage(e)),
         };
-        let dev_fb = match dev_img.to_framebuffer() {
+        let dev_tex = match dev_img.to_texture() {
             Ok(fb) => fb,
-            Err(e) => return Err(MetalError::ImportFb(e)),
+            Err(e) => return Err(MetalError::ImportTexture(e)),
         };
-        dev_fb.clear();
-        let (dev_tex, render_tex, render_fb) = if dev.id == render_ctx.dev_id {
-            let render_tex = match dev_img.to_texture() {
-                Ok(fb) => fb,
-                Err(e) => return Err(MetalError::ImportTexture(e)),
-            };
-            (None, render_tex, None)
-        } else {
-            // Create a _bridge_ BO in the render device
-            usage = GBM_BO_USE_RENDERING | GBM_BO_USE_LINEAR;
-            let render_bo = render_ctx.egl.gbm.create_bo(width, height, format, usage);
-            let render_bo = match render_bo {
+        Ok(RenderBuffer {
+            drm: local.drm,
+            render_fb,
+            render_tex,
+            transfer: BufferTransfer::Gpu {
+                dev_fb: local.render_fb,
+                dev_tex,
+            },
+        })
+    }
+
+    /// Creates a linear buffer in the scanout device that is filled by the CPU with the
+    /// contents of a buffer in the render device.
+    fn create_cpu_scanout_buffer(
+        &self,
+        dev: &Rc<MetalDrmDevice>,
+        format: &ModifiedFormat,
+        width: i32,
+        height: i32,
+        render_ctx: &MetalRenderContext,
+    ) -> Result<RenderBuffer, MetalError> {
+        let usage = GBM_BO_USE_SCANOUT | GBM_BO_USE_LINEAR;
+        let dev_bo = match dev.gbm.create_bo(width, height, format, usage) {
+            Ok(b) => Rc::new(b),
+            Err(e) => return Err(MetalError::ScanoutBuffer(e)),
+        };
+        let drm_fb = match dev.master.add_fb(dev_bo.dmabuf()) {
+            Ok(fb) => Rc::new(fb),
+            Err(e) => return Err(MetalError::Framebuffer(e)),
+        };
+        let render_bo =
+            match render_ctx
+                .egl
+                .gbm
+                .create_bo(width, height, format, GBM_BO_USE_RENDERING)
+            {
                 Ok(b) => b,
                 Err(e) => return Err(MetalError::ScanoutBuffer(e)),
             };
-            let render_img = match render_ctx.egl.dmabuf_img(render_bo.dmabuf()) {
-                Ok(img) => img,
-                Err(e) => return Err(MetalError::ImportImage(e)),
-            };
-            let render_fb = match render_img.to_framebuffer() {
-                Ok(fb) => fb,
-                Err(e) => return Err(MetalError::ImportFb(e)),
-            };
-            render_fb.clear();
-            let render_tex = match render_img.to_texture() {
-                Ok(fb) => fb,
-                Err(e) => return Err(MetalError::ImportTexture(e)),
-            };
-
-            // Import the bridge BO into the current device
-            let dev_img = match dev.ctx.egl.dmabuf_img(render_bo.dmabuf()) {
-                Ok(img) => img,
-                Err(e) => return Err(MetalError::ImportImage(e)),
-            };
-            let dev_tex = match dev_img.to_texture() {
-                Ok(fb) => fb,
-                Err(e) => return Err(MetalError::ImportTexture(e)),
-            };
-
-            (Some(dev_tex), render_tex, Some(render_fb))
-        };
+        let (render_fb, render_tex) = import_render_buffer(&render_ctx.egl, render_bo.dmabuf())?;
+        let size = width as usize * height as usize * format.format.bpp as usize;
         Ok(RenderBuffer {
             drm: drm_fb,
-            dev_fb,
-            dev_tex,
-            render_tex,
             render_fb,
+            render_tex,
+            transfer: BufferTransfer::Cpu {
+                dev_bo,
+                staging: (0..size).map(|_| Cell::new(0)).collect(),
+            },
         })
     }
 
@@ -1670,12 +2914,12 @@ impl MetalBackend {
         changes: &mut Change,
     ) -> Result<(), MetalError> {
         let dd = connector.display.borrow_mut();
-        if !connector.enabled.get() || dd.connection != ConnectorStatus::Connected {
+        if connector.leased.get() || !connector.needs_crtc(&dd) {
             return Ok(());
         }
         let crtc = 'crtc: {
             for crtc in dd.crtcs.values() {
-                if crtc.connector.get().is_none() {
+                if crtc.connector.get().is_none() && !crtc.leased.get() {
                     break 'crtc crtc.clone();
                 }
             }
@@ -1741,8 +2985,27 @@ impl MetalBackend {
             mode.hdisplay as _,
             mode.vdisplay as _,
             ctx,
-            false,
+            &ScanoutPlane {
+                connector,
+                connector_crtc_id: dd.crtc_id.id,
+                crtc: &crtc,
+                plane: &primary_plane,
+            },
         )?);
+        match buffers[0].transfer {
+            BufferTransfer::None => {}
+            BufferTransfer::Gpu { .. } => log::info!(
+                "{}-{}: Frames are copied to the scanout device by its GPU",
+                dd.connector_type,
+                dd.connector_type_id,
+            ),
+            BufferTransfer::Cpu { .. } => log::warn!(
+                "{}-{}: Frames are copied to the scanout device by the CPU. \
+                 This blocks the compositor for the duration of the copy on every frame.",
+                dd.connector_type,
+                dd.connector_type_id,
+            ),
+        }
         let mut cursor_plane = None;
         for plane in crtc.possible_planes.values() {
             if plane.ty == PlaneType::Cursor
@@ -1753,8 +3016,17 @@ impl MetalBackend {
                 break;
             }
         }
+        let mut overlay_planes = vec![];
+        for plane in crtc.possible_planes.values() {
+            if overlay_planes.len() == MAX_OVERLAY_PLANES {
+                break;
+            }
+            if plane.ty == PlaneType::Overlay && !plane.assigned.get() {
+                overlay_planes.push(plane.clone());
+            }
+        }
         let mut cursor_buffers = None;
-        if cursor_plane.is_some() {
+        if let Some(plane) = &cursor_plane {
             let res = self.create_scanout_buffers(
                 &connector.dev,
                 &ModifiedFormat {
@@ -1764,7 +3036,12 @@ impl MetalBackend {
                 connector.dev.cursor_width as _,
                 connector.dev.cursor_height as _,
                 ctx,
-                true,
+                &ScanoutPlane {
+                    connector,
+                    connector_crtc_id: dd.crtc_id.id,
+                    crtc: &crtc,
+                    plane,
+                },
             );
             match res {
                 Ok(r) => cursor_buffers = Some(Rc::new(r)),
@@ -1802,10 +3079,16 @@ impl MetalBackend {
         if let Some(old) = connector.buffers.set(Some(buffers)) {
             old_buffers.push(old);
         }
+        connector.damage_history.borrow_mut().clear();
+        connector.reset_direct_scanout();
         connector.primary_plane.set(Some(primary_plane.clone()));
         if let Some(cp) = &cursor_plane {
             cp.assigned.set(true);
         }
+        for op in &overlay_planes {
+            op.assigned.set(true);
+        }
+        *connector.overlay_planes.borrow_mut() = overlay_planes;
         if let Some(old) = connector.cursor_buffers.set(cursor_buffers) {
             old_buffers.push(old);
         }
@@ -1814,6 +3097,57 @@ impl MetalBackend {
         Ok(())
     }
 
+    /// Applies the mode in the display data of the connector with a modeset of only its
+    /// crtc and planes.
+    fn change_connector_mode(&self, connector: &Rc<MetalConnector>) -> Result<(), MetalError> {
+        let ctx = match self.ctx.get() {
+            Some(ctx) => ctx,
+            _ => return Ok(()),
+        };
+        let crtc = match connector.crtc.get() {
+            Some(c) => c,
+            _ => return Err(MetalError::NoCrtcForConnector),
+        };
+        let mut changes = connector.master.change();
+        let mut planes: Vec<_> = connector.overlay_planes.borrow().clone();
+        planes.extend(connector.primary_plane.get());
+        planes.extend(connector.cursor_plane.get());
+        for plane in planes {
+            plane.assigned.set(false);
+            plane.crtc_id.value.set(DrmCrtc::NONE);
+            changes.change_object(plane.id, |c| {
+                c.change(plane.crtc_id.id, 0);
+                c.change(plane.fb_id, 0);
+            });
+        }
+        connector.primary_plane.set(None);
+        connector.cursor_plane.set(None);
+        connector.overlay_planes.borrow_mut().clear();
+        {
+            let dd = connector.display.borrow_mut();
+            let mode = match &dd.mode {
+                Some(m) => m,
+                _ => return Err(MetalError::NoModeForConnector),
+            };
+            let mode_blob = mode.create_blob(&connector.master)?;
+            changes.change_object(crtc.id, |c| {
+                c.change(crtc.active.id, 1);
+                c.change(crtc.mode_id.id, mode_blob.id().0 as _);
+            });
+            crtc.mode_id.value.set(mode_blob.id());
+            crtc.mode_blob.set(Some(Rc::new(mode_blob)));
+        }
+        let mut old_buffers = vec![];
+        self.assign_connector_planes(connector, &mut changes, &ctx, &mut old_buffers)?;
+        if let Err(e) = changes.commit(DRM_MODE_ATOMIC_ALLOW_MODESET, 0) {
+            return Err(MetalError::Modeset(e));
+        }
+        connector.send_hardware_cursor();
+        connector.can_present.set(true);
+        self.start_connector(connector, true);
+        Ok(())
+    }
+
     fn start_connector(&self, connector: &Rc<MetalConnector>, log_mode: bool) {
         let dd = connector.display.borrow_mut();
         if !connector.connect_sent.get() {
@@ -1833,29 +3167,166 @@ impl MetalBackend {
     }
 }
 
+/// The plane that displays a scanout buffer.
+struct ScanoutPlane<'a> {
+    connector: &'a MetalConnector,
+    connector_crtc_id: DrmProperty,
+    crtc: &'a MetalCrtc,
+    plane: &'a MetalPlane,
+}
+
+impl ScanoutPlane<'_> {
+    /// Tests whether the framebuffer can be displayed on the plane with a test-only
+    /// commit.
+    fn test(&self, fb: &DrmFramebuffer, width: i32, height: i32) -> Result<(), DrmError> {
+        let (crtc, plane) = (self.crtc, self.plane);
+        let mut changes = self.connector.master.change();
+        changes.change_object(self.connector.id, |c| {
+            c.change(self.connector_crtc_id, crtc.id.0 as _);
+        });
+        changes.change_object(crtc.id, |c| {
+            c.change(crtc.active.id, 1);
+            c.change(crtc.mode_id.id, crtc.mode_id.value.get().0 as _);
+        });
+        changes.change_object(plane.id, |c| {
+            c.change(plane.fb_id, fb.id().0 as _);
+            c.change(plane.crtc_id.id, crtc.id.0 as _);
+            c.change(plane.crtc_x.id, 0);
+            c.change(plane.crtc_y.id, 0);
+            c.change(plane.crtc_w.id, width as _);
+            c.change(plane.crtc_h.id, height as _);
+            c.change(plane.src_x.id, 0);
+            c.change(plane.src_y.id, 0);
+            c.change(plane.src_w.id, (width as u64) << 16);
+            c.change(plane.src_h.id, (height as u64) << 16);
+        });
+        changes.test(DRM_MODE_ATOMIC_ALLOW_MODESET)
+    }
+}
+
 #[derive(Debug)]
 pub struct RenderBuffer {
     drm: Rc<DrmFramebuffer>,
-    // ctx = dev
-    // buffer location = dev
-    dev_fb: Rc<Framebuffer>,
-    // ctx = dev
-    // buffer location = render
-    dev_tex: Option<Rc<Texture>>,
     // ctx = render
-    // buffer location = render
     render_tex: Rc<Texture>,
     // ctx = render
-    // buffer location = render
-    render_fb: Option<Rc<Framebuffer>>,
+    render_fb: Rc<Framebuffer>,
+    transfer: BufferTransfer,
+}
+
+/// How the contents of `render_fb` reach the buffer that is scanned out.
+enum BufferTransfer {
+    /// `render_fb` is scanned out directly.
+    None,
+    /// The scanout device copies the bridge buffer with its GPU.
+    Gpu {
+        // ctx = dev
+        // buffer location = dev
+        dev_fb: Rc<Framebuffer>,
+        // ctx = dev
+        // buffer location = render
+        dev_tex: Rc<Texture>,
+    },
+    /// The contents are read back from the render device and written into the mapped
+    /// scanout buffer.
+    ///
+    /// The read-back is synchronous and happens on every frame. This is only used if the
+    /// devices neither share a modifier nor can import each other's buffers.
+    Cpu {
+        dev_bo: Rc<GbmBo>,
+        staging: Box<[Cell<u8>]>,
+    },
+}
+
+impl Debug for BufferTransfer {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BufferTransfer::None => f.write_str("None"),
+            BufferTransfer::Gpu { .. } => f.write_str("Gpu"),
+            BufferTransfer::Cpu { .. } => f.write_str("Cpu"),
+        }
+    }
 }
 
 impl RenderBuffer {
     fn render_fb(&self) -> Rc<Framebuffer> {
-        self.render_fb
-            .clone()
-            .unwrap_or_else(|| self.dev_fb.clone())
+        self.render_fb.clone()
     }
+
+    /// Copies the rendered contents to the scanout device if they are not already there.
+    fn copy_to_dev(&self, state: &State, alpha: bool) {
+        match &self.transfer {
+            BufferTransfer::None => {}
+            BufferTransfer::Gpu { dev_fb, dev_tex } => {
+                dev_fb.copy_texture(state, dev_tex, 0, 0, alpha, Transform::Normal);
+            }
+            BufferTransfer::Cpu { dev_bo, staging } => {
+                let dmabuf = dev_bo.dmabuf();
+                let (width, height) = (dmabuf.width, dmabuf.height);
+                self.render_fb
+                    .copy_to_shm(0, 0, width, height, dmabuf.format, staging);
+                let mut map = match dev_bo.map_write() {
+                    Ok(m) => m,
+                    Err(e) => {
+                        log::error!("Could not map the scanout buffer: {}", ErrorFmt(e));
+                        return;
+                    }
+                };
+                let stride = map.stride() as usize;
+                let row_len = width as usize * dmabuf.format.bpp as usize;
+                let data = unsafe { map.data_mut() };
+                for (dst, src) in data.chunks_mut(stride).zip(staging.chunks(row_len)) {
+                    for (d, s) in dst.iter_mut().zip(src) {
+                        *d = s.get();
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Imports a buffer of the render device as a framebuffer and texture and clears it.
+fn import_render_buffer(
+    ctx: &Rc<RenderContext>,
+    dmabuf: &DmaBuf,
+) -> Result<(Rc<Framebuffer>, Rc<Texture>), MetalError> {
+    let img = match ctx.dmabuf_img(dmabuf) {
+        Ok(img) => img,
+        Err(e) => return Err(MetalError::ImportImage(e)),
+    };
+    let fb = match img.to_framebuffer() {
+        Ok(fb) => fb,
+        Err(e) => return Err(MetalError::ImportFb(e)),
+    };
+    fb.clear();
+    let tex = match img.to_texture() {
+        Ok(tex) => tex,
+        Err(e) => return Err(MetalError::ImportTexture(e)),
+    };
+    Ok((fb, tex))
+}
+
+/// Returns the modifiers of `format` that the render device can render to and that the
+/// scanout device can import, excluding the linear modifier.
+fn shared_modifiers(render: &RenderContext, dev: &RenderContext, format: &Format) -> Vec<u64> {
+    let render_formats = render.formats();
+    let dev_formats = dev.formats();
+    let (rf, df) = match (
+        render_formats.get(&format.drm),
+        dev_formats.get(&format.drm),
+    ) {
+        (Some(rf), Some(df)) => (rf, df),
+        _ => return vec![],
+    };
+    let mut modifiers: Vec<_> = rf
+        .modifiers
+        .values()
+        .filter(|m| !m.external_only && m.modifier != LINEAR_MODIFIER)
+        .filter(|m| df.modifiers.contains_key(&m.modifier))
+        .map(|m| m.modifier)
+        .collect();
+    modifiers.sort_unstable();
+    modifiers
 }
 
 fn modes_equal(a: &DrmModeInfo, b: &DrmModeInfo) -> bool {
@@ -553,8 +553,10 @@ impl XBackend {
                 height: output.height.get(),
                 refresh_rate_millihz: 60_000, // TODO
             },
+            preferred_mode: None,
             width_mm: output.width.get(),
             height_mm: output.height.get(),
+            non_desktop: false,
         }));
         output.changed();
         self.present(output).await;
@@ -719,6 +721,8 @@ impl XBackend {
         image.last_serial.set(serial);
 
         if let Some(node) = self.state.root.outputs.get(&output.id) {
+            // The contents of the pixmaps are not tracked. Always render the whole output.
+            node.take_damage();
             let mut rr = self.render_result.borrow_mut();
             let fb = image.fb.get();
             fb.render(
@@ -729,6 +733,9 @@ impl XBackend {
                 rr.deref_mut(),
                 node.preferred_scale.get(),
                 true,
+                node.global.transform.get(),
+                None,
+                &[],
             );
             for fr in rr.frame_requests.drain(..) {
                 fr.send_done();
@@ -1033,6 +1040,10 @@ impl Connector for XOutput {
     fn set_enabled(&self, _enabled: bool) {
         // nothing
     }
+
+    fn set_mode(&self, _mode: Mode) {
+        // nothing
+    }
 }
 
 struct XSeat {
@@ -1,3 +1,4 @@
+mod connectors;
 mod generate;
 mod idle;
 mod log;
@@ -53,6 +54,8 @@ pub enum Cmd {
     RunPrivileged(RunPrivilegedArgs),
     /// Tests the events produced by a seat.
     SeatTest(SeatTestArgs),
+    /// Show the connected outputs and whether they use direct scanout.
+    Connectors,
     /// Run the desktop portal.
     Portal,
     #[cfg(feature = "it")]
@@ -216,6 +219,7 @@ pub fn main() {
         Cmd::Unlock => unlock::main(cli.global),
         Cmd::RunPrivileged(a) => run_privileged::main(cli.global, a),
         Cmd::SeatTest(a) => seat_test::main(cli.global, a),
+        Cmd::Connectors => connectors::main(cli.global),
         Cmd::Portal => portal::run(cli.global),
         #[cfg(feature = "it")]
         Cmd::RunTests => crate::it::run_tests(),
@@ -0,0 +1,35 @@
+use {
+    crate::{
+        cli::GlobalArgs,
+        tools::tool_client::{Handle, ToolClient},
+        wire::jay_compositor::{Connector, GetConnectors},
+    },
+    std::{cell::RefCell, rc::Rc},
+};
+
+pub fn main(global: GlobalArgs) {
+    let tc = ToolClient::new(global.log_level.into());
+    tc.run(run(tc.clone()));
+}
+
+async fn run(tc: Rc<ToolClient>) {
+    let comp = tc.jay_compositor().await;
+    tc.send(GetConnectors { self_id: comp });
+    let connectors = Rc::new(RefCell::new(vec![]));
+    Connector::handle(&tc, comp, connectors.clone(), |c, msg| {
+        c.borrow_mut()
+            .push((msg.name.to_string(), msg.direct_scanout != 0, msg.vrr != 0));
+    });
+    tc.round_trip().await;
+    let mut connectors = connectors.borrow_mut();
+    connectors.sort();
+    let state = |active: bool| match active {
+        true => "active",
+        false => "inactive",
+    };
+    for &(ref name, direct_scanout, vrr) in connectors.iter() {
+        println!("{}:", name);
+        println!("  direct scanout: {}", state(direct_scanout));
+        println!("  vrr: {}", state(vrr));
+    }
+}
@@ -463,12 +463,16 @@ pub trait WaylandObjectLookup: Copy + Into<ObjectId> {
 }
 
 fn get_pid_info(uid: c::uid_t, pid: c::pid_t) -> PidInfo {
-    let comm = match std::fs::read(format!("/proc/{}/comm", pid)) {
+    let comm = get_comm(pid);
+    PidInfo { uid, pid, comm }
+}
+
+pub fn get_comm(pid: c::pid_t) -> String {
+    match std::fs::read(format!("/proc/{}/comm", pid)) {
         Ok(name) => name.trim().as_bstr().to_string(),
         Err(e) => {
             log::warn!("Could not read `comm` of pid {}: {}", pid, ErrorFmt(e));
             "Unknown".to_string()
         }
-    };
-    PidInfo { uid, pid, comm }
+    }
 }
@@ -15,8 +15,12 @@ use {
         config::ConfigProxy,
         dbus::Dbus,
         forker,
+        gamma::NEUTRAL_TEMPERATURE,
         globals::Globals,
-        ifs::{wl_output::WlOutputGlobal, wl_surface::NoneSurfaceExt},
+        ifs::{
+            wl_output::WlOutputGlobal, wl_surface::NoneSurfaceExt,
+            zwlr_output_manager_v1::output_manager_updates,
+        },
         io_uring::{IoUring, IoUringError},
         leaks,
         logger::Logger,
@@ -40,6 +44,7 @@ use {
     },
     ahash::AHashSet,
     forker::ForkerProxy,
+    jay_config::ActivationBehavior,
     std::{cell::Cell, env, future::Future, ops::Deref, rc::Rc, sync::Arc, time::Duration},
     thiserror::Error,
     uapi::c,
@@ -150,6 +155,7 @@ fn start_compositor2(
         tree_changed_sent: Cell::new(false),
         config: Default::default(),
         input_device_ids: Default::default(),
+        tablet_tool_ids: Default::default(),
         input_device_handlers: Default::default(),
         theme: Default::default(),
         pending_container_layout: Default::default(),
@@ -197,7 +203,18 @@ fn start_compositor2(
         hardware_tick_cursor: Default::default(),
         testers: Default::default(),
         render_ctx_watchers: Default::default(),
+        window_rules: Default::default(),
+        dmabuf_feedback_table: Default::default(),
+        dmabuf_feedback: Default::default(),
         workspace_watchers: Default::default(),
+        output_managers: Default::default(),
+        output_managers_changed: Default::default(),
+        toplevels: Default::default(),
+        toplevel_managers: Default::default(),
+        toplevel_lists: Default::default(),
+        activation_tokens: Default::default(),
+        activation_behavior: Cell::new(ActivationBehavior::Focus),
+        tearing_allowed: Cell::new(true),
     });
     state.tracker.register(ClientId::from_raw(0));
     create_dummy_output(&state);
@@ -286,6 +303,7 @@ fn start_global_event_handlers(
         eng.spawn2(Phase::Layout, float_layout(state.clone())),
         eng.spawn2(Phase::PostLayout, float_titles(state.clone())),
         eng.spawn2(Phase::PostLayout, idle(state.clone(), backend.clone())),
+        eng.spawn2(Phase::PostLayout, output_manager_updates(state.clone())),
     ]
 }
 
@@ -364,6 +382,13 @@ fn create_dummy_output(state: &Rc<State>) {
                 name: "Dummy".to_string(),
                 drm_dev: None,
                 async_event: Default::default(),
+                enabled: Cell::new(true),
+                monitor_info: Default::default(),
+                pending_position: Default::default(),
+                pending_scale: Default::default(),
+                pending_transform: Default::default(),
+                gamma_control: Default::default(),
+                color_temperature: Cell::new(NEUTRAL_TEMPERATURE),
             }),
             0,
             &backend::Mode {
@@ -391,8 +416,10 @@ fn create_dummy_output(state: &Rc<State>) {
         lock_surface: Default::default(),
         preferred_scale: Cell::new(Scale::from_int(1)),
         hardware_cursor: Default::default(),
+        hardware_cursor_too_small: Cell::new(false),
         update_render_data_scheduled: Cell::new(false),
         screencasts: Default::default(),
+        damage: Default::default(),
     });
     let dummy_workspace = Rc::new(WorkspaceNode {
         id: state.node_ids.next(),
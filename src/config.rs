@@ -4,10 +4,11 @@ mod handler;
 use crate::it::test_config::TEST_CONFIG_ENTRY;
 use {
     crate::{
-        backend::{ConnectorId, DrmDeviceId, InputDeviceId},
+        backend::{self, ConnectorId, DrmDeviceId, InputDeviceId},
         config::handler::ConfigProxyHandler,
         ifs::wl_seat::SeatId,
         state::State,
+        tree::NodeId,
         utils::{
             clonecell::CloneCell, numcell::NumCell, oserror::OsError, ptr_ext::PtrExt,
             unlink_on_drop::UnlinkOnDrop, xrd::xrd,
@@ -19,9 +20,11 @@ use {
             ipc::{InitMessage, ServerMessage, V1InitMessage},
             ConfigEntry, VERSION,
         },
-        input::{InputDevice, Seat},
+        input::{InputDevice, Seat, SwitchEvent},
         keyboard::ModifiedKeySym,
         video::{Connector, DrmDevice},
+        window::Window,
+        Direction,
     },
     libloading::Library,
     std::{cell::Cell, mem, ptr, rc::Rc},
@@ -70,6 +73,34 @@ impl ConfigProxy {
         });
     }
 
+    pub fn invoke_swipe(&self, seat: SeatId, fingers: u32, direction: Direction) {
+        self.send(&ServerMessage::InvokeSwipe {
+            seat: Seat(seat.raw() as _),
+            fingers,
+            direction,
+        });
+    }
+
+    pub fn invoke_pad_button(&self, dev: InputDeviceId, button: u32) {
+        self.send(&ServerMessage::InvokePadButton {
+            device: InputDevice(dev.raw() as _),
+            button,
+        });
+    }
+
+    pub fn switch_event(&self, dev: InputDeviceId, event: backend::SwitchEvent) {
+        let event = match event {
+            backend::SwitchEvent::LidOpened => SwitchEvent::LidOpened,
+            backend::SwitchEvent::LidClosed => SwitchEvent::LidClosed,
+            backend::SwitchEvent::ConvertedToLaptop => SwitchEvent::ConvertedToLaptop,
+            backend::SwitchEvent::ConvertedToTablet => SwitchEvent::ConvertedToTablet,
+        };
+        self.send(&ServerMessage::SwitchEvent {
+            device: InputDevice(dev.raw() as _),
+            event,
+        });
+    }
+
     pub fn new_drm_dev(&self, dev: DrmDeviceId) {
         self.send(&ServerMessage::NewDrmDev {
             device: DrmDevice(dev.raw() as _),
@@ -82,6 +113,12 @@ impl ConfigProxy {
         });
     }
 
+    pub fn new_window(&self, window: NodeId) {
+        self.send(&ServerMessage::NewWindow {
+            window: Window(window.0 as _),
+        });
+    }
+
     pub fn new_connector(&self, connector: ConnectorId) {
         self.send(&ServerMessage::NewConnector {
             device: Connector(connector.raw() as _),
@@ -133,6 +170,19 @@ impl ConfigProxy {
     pub fn idle(&self) {
         self.send(&ServerMessage::Idle);
     }
+
+    pub fn shortcuts_inhibit_request(&self, seat: SeatId, app_id: String) -> bool {
+        let handler = match self.handler.get() {
+            Some(h) => h,
+            _ => return true,
+        };
+        handler.shortcuts_inhibit_allowed.set(true);
+        handler.send(&ServerMessage::ShortcutsInhibitRequest {
+            seat: Seat(seat.raw() as _),
+            app_id,
+        });
+        handler.shortcuts_inhibit_allowed.get()
+    }
 }
 
 impl Drop for ConfigProxy {
@@ -174,6 +224,7 @@ impl ConfigProxy {
             timer_ids: NumCell::new(1),
             timers_by_name: Default::default(),
             timers_by_id: Default::default(),
+            shortcuts_inhibit_allowed: Cell::new(true),
         });
         let init_msg =
             bincode::encode_to_vec(&InitMessage::V1(V1InitMessage {}), bincode_ops()).unwrap();
@@ -3,16 +3,25 @@ use {
         async_engine::SpawnedFuture,
         backend::{
             self, ConnectorId, DrmDeviceId, InputDeviceAccelProfile, InputDeviceCapability,
-            InputDeviceId,
+            InputDeviceId, VrrMode,
         },
         compositor::MAX_EXTENTS,
         config::ConfigProxy,
-        ifs::wl_seat::{SeatId, WlSeatGlobal},
+        gamma::{MAX_TEMPERATURE, MIN_TEMPERATURE},
+        ifs::{
+            wl_seat::{SeatId, WlSeatGlobal},
+            wl_surface::x_surface::xwindow::XwindowInfo,
+        },
         scale::Scale,
-        state::{ConnectorData, DeviceHandlerData, DrmDevData, OutputData, State},
+        state::{ConnectorData, DeviceHandlerData, DrmDevData, InputMapping, OutputData, State},
         theme::{Color, ThemeSized, DEFAULT_FONT},
-        tree::{ContainerNode, ContainerSplit, FloatNode, Node, NodeVisitorBase, OutputNode},
+        transform::Transform,
+        tree::{
+            ContainerNode, ContainerSplit, FloatNode, Node, NodeId, NodeVisitorBase, OutputNode,
+            ToplevelNode,
+        },
         utils::{
+            activation_token::ActivationToken,
             copyhashmap::CopyHashMap,
             debug_fn::debug_fn,
             errorfmt::ErrorFmt,
@@ -20,13 +29,15 @@ use {
             stack::Stack,
             timer::{TimerError, TimerFd},
         },
+        window_rules::{WindowRule as CompiledWindowRule, WindowRuleActions},
         xkbcommon::{XkbCommonError, XkbKeymap},
     },
     bincode::error::DecodeError,
+    bstr::BString,
     jay_config::{
         _private::{
             bincode_ops,
-            ipc::{ClientMessage, Response, ServerMessage},
+            ipc::{ClientMessage, Response, ServerMessage, WireMode},
         },
         input::{
             acceleration::{AccelProfile, ACCEL_PROFILE_ADAPTIVE, ACCEL_PROFILE_FLAT},
@@ -40,12 +51,18 @@ use {
         logging::LogLevel,
         theme::{colors::Colorable, sized::Resizable},
         timer::Timer as JayTimer,
-        video::{Connector, DrmDevice},
+        video::{Connector, DrmDevice, Transform as ConfigTransform, VrrMode as ConfigVrrMode},
+        window::{Window, WindowRule},
         Axis, Direction, Workspace,
     },
     libloading::Library,
     log::Level,
-    std::{cell::Cell, ops::Deref, rc::Rc, time::Duration},
+    std::{
+        cell::{Cell, RefCell},
+        ops::Deref,
+        rc::Rc,
+        time::Duration,
+    },
     thiserror::Error,
     uapi::c,
 };
@@ -69,6 +86,8 @@ pub(super) struct ConfigProxyHandler {
     pub timer_ids: NumCell<u64>,
     pub timers_by_name: CopyHashMap<Rc<String>, Rc<TimerData>>,
     pub timers_by_id: CopyHashMap<u64, Rc<TimerData>>,
+
+    pub shortcuts_inhibit_allowed: Cell<bool>,
 }
 
 pub(super) struct TimerData {
@@ -229,6 +248,10 @@ impl ConfigProxyHandler {
             for seat in self.state.globals.seats.lock().values() {
                 seat.clear_shortcuts();
             }
+            for dev in self.state.input_device_handlers.borrow().values() {
+                dev.data.pad_button_bindings.clear();
+            }
+            self.state.window_rules.clear();
         }
         config.configure(true);
         self.state.config.set(Some(Rc::new(config)));
@@ -437,6 +460,13 @@ impl ConfigProxyHandler {
         Err(CphError::SeatDoesNotExist(seat))
     }
 
+    fn get_window(&self, window: Window) -> Result<Rc<dyn ToplevelNode>, CphError> {
+        match self.state.toplevels.get(&NodeId(window.0 as _)) {
+            Some(tl) => Ok(tl),
+            _ => Err(CphError::WindowDoesNotExist(window)),
+        }
+    }
+
     fn get_kb(&self, kb: InputDevice) -> Result<Rc<dyn backend::InputDevice>, CphError> {
         let kbs = self.state.input_device_handlers.borrow_mut();
         match kbs.get(&(InputDeviceId::from_raw(kb.0 as _))) {
@@ -459,7 +489,7 @@ impl ConfigProxyHandler {
             Some(self.get_seat(seat)?)
         };
         let dev = self.get_device_handler_data(device)?;
-        dev.seat.set(seat);
+        dev.set_seat(seat);
         Ok(())
     }
 
@@ -532,7 +562,51 @@ impl ConfigProxyHandler {
         Ok(())
     }
 
-    fn handle_get_workspace(&self, name: &str) {
+    fn handle_set_input_device_connector(
+        &self,
+        device: InputDevice,
+        connector: Connector,
+    ) -> Result<(), CphError> {
+        let dev = self.get_device_handler_data(device)?;
+        let connector = self.get_connector(connector)?;
+        dev.mapping
+            .set(InputMapping::Connector(connector.connector.id()));
+        Ok(())
+    }
+
+    fn handle_map_input_device_to_layout(&self, device: InputDevice) -> Result<(), CphError> {
+        let dev = self.get_device_handler_data(device)?;
+        dev.mapping.set(InputMapping::Layout);
+        Ok(())
+    }
+
+    fn handle_remove_input_mapping(&self, device: InputDevice) -> Result<(), CphError> {
+        let dev = self.get_device_handler_data(device)?;
+        dev.mapping.set(InputMapping::Default);
+        Ok(())
+    }
+
+    fn handle_add_pad_button_binding(
+        &self,
+        device: InputDevice,
+        button: u32,
+    ) -> Result<(), CphError> {
+        let dev = self.get_device_handler_data(device)?;
+        dev.pad_button_bindings.set(button, ());
+        Ok(())
+    }
+
+    fn handle_remove_pad_button_binding(
+        &self,
+        device: InputDevice,
+        button: u32,
+    ) -> Result<(), CphError> {
+        let dev = self.get_device_handler_data(device)?;
+        dev.pad_button_bindings.remove(&button);
+        Ok(())
+    }
+
+    fn workspace_by_name(&self, name: &str) -> Workspace {
         let name = Rc::new(name.to_owned());
         let ws = match self.workspaces_by_name.get(&name) {
             Some(w) => w,
@@ -543,8 +617,12 @@ impl ConfigProxyHandler {
                 ws
             }
         };
+        Workspace(ws)
+    }
+
+    fn handle_get_workspace(&self, name: &str) {
         self.respond(Response::GetWorkspace {
-            workspace: Workspace(ws),
+            workspace: self.workspace_by_name(name),
         });
     }
 
@@ -566,6 +644,16 @@ impl ConfigProxyHandler {
         Ok(())
     }
 
+    fn handle_get_lid_closed(&self) {
+        let closed = self
+            .state
+            .input_device_handlers
+            .borrow()
+            .values()
+            .any(|d| d.data.lid_closed.get());
+        self.respond(Response::GetLidClosed { closed });
+    }
+
     fn handle_get_device_name(&self, device: InputDevice) -> Result<(), CphError> {
         let dev = self.get_device_handler_data(device)?;
         let name = dev.device.name();
@@ -602,6 +690,44 @@ impl ConfigProxyHandler {
         Ok(())
     }
 
+    fn handle_connector_modes(&self, connector: Connector) -> Result<(), CphError> {
+        let connector = self.get_connector(connector)?;
+        let modes = match connector.monitor_info.get() {
+            Some(info) => info
+                .modes
+                .iter()
+                .map(|m| WireMode {
+                    width: m.width,
+                    height: m.height,
+                    refresh_millihz: m.refresh_rate_millihz,
+                })
+                .collect(),
+            _ => vec![],
+        };
+        self.respond(Response::ConnectorModes { modes });
+        Ok(())
+    }
+
+    fn handle_connector_set_mode(
+        &self,
+        connector: Connector,
+        mode: WireMode,
+    ) -> Result<(), CphError> {
+        let data = self.get_connector(connector)?;
+        let mode = backend::Mode {
+            width: mode.width,
+            height: mode.height,
+            refresh_rate_millihz: mode.refresh_millihz,
+        };
+        if let Some(info) = data.monitor_info.get() {
+            if !info.modes.contains(&mode) {
+                return Err(CphError::UnsupportedMode(connector, mode));
+            }
+        }
+        data.connector.set_mode(mode);
+        Ok(())
+    }
+
     fn handle_set_cursor_size(&self, seat: Seat, size: i32) -> Result<(), CphError> {
         let seat = self.get_seat(seat)?;
         if size < 0 {
@@ -667,6 +793,58 @@ impl ConfigProxyHandler {
         Ok(())
     }
 
+    fn handle_connector_set_transform(
+        &self,
+        connector: Connector,
+        transform: ConfigTransform,
+    ) -> Result<(), CphError> {
+        let transform = match transform {
+            ConfigTransform::None => Transform::Normal,
+            ConfigTransform::Rotate90 => Transform::Rotate90,
+            ConfigTransform::Rotate180 => Transform::Rotate180,
+            ConfigTransform::Rotate270 => Transform::Rotate270,
+            ConfigTransform::Flip => Transform::Flipped,
+            ConfigTransform::FlipRotate90 => Transform::Flipped90,
+            ConfigTransform::FlipRotate180 => Transform::Flipped180,
+            ConfigTransform::FlipRotate270 => Transform::Flipped270,
+        };
+        let connector = self.get_connector(connector)?;
+        match self.state.outputs.get(&connector.connector.id()) {
+            Some(output) => output.node.set_transform(transform),
+            _ => connector.pending_transform.set(Some(transform)),
+        }
+        Ok(())
+    }
+
+    fn handle_connector_set_vrr(
+        &self,
+        connector: Connector,
+        mode: ConfigVrrMode,
+    ) -> Result<(), CphError> {
+        let mode = match mode {
+            ConfigVrrMode::Never => VrrMode::Never,
+            ConfigVrrMode::Always => VrrMode::Always,
+            ConfigVrrMode::Fullscreen => VrrMode::Fullscreen,
+        };
+        let connector = self.get_connector(connector)?;
+        connector.connector.set_vrr_mode(mode);
+        Ok(())
+    }
+
+    fn handle_connector_set_color_temperature(
+        &self,
+        connector: Connector,
+        kelvin: u32,
+    ) -> Result<(), CphError> {
+        let connector = self.get_connector(connector)?;
+        if !(MIN_TEMPERATURE..=MAX_TEMPERATURE).contains(&kelvin) {
+            return Err(CphError::InvalidColorTemperature(kelvin));
+        }
+        connector.color_temperature.set(kelvin);
+        connector.update_gamma();
+        Ok(())
+    }
+
     fn handle_connector_set_position(
         &self,
         connector: Connector,
@@ -677,18 +855,7 @@ impl ConfigProxyHandler {
         if x < 0 || y < 0 || x > MAX_EXTENTS || y > MAX_EXTENTS {
             return Err(CphError::InvalidConnectorPosition(x, y));
         }
-        let old_pos = connector.node.global.pos.get();
         connector.node.set_position(x, y);
-        let seats = self.state.globals.seats.lock();
-        for seat in seats.values() {
-            if seat.get_output().id == connector.node.id {
-                let seat_pos = seat.position();
-                seat.set_position(
-                    seat_pos.0.round_down() + x - old_pos.x1(),
-                    seat_pos.1.round_down() + y - old_pos.y1(),
-                );
-            }
-        }
         Ok(())
     }
 
@@ -790,6 +957,17 @@ impl ConfigProxyHandler {
         Ok(())
     }
 
+    fn handle_set_shortcuts_inhibit_escape(
+        &self,
+        seat: Seat,
+        mods: Modifiers,
+        sym: KeySym,
+    ) -> Result<(), CphError> {
+        let seat = self.get_seat(seat)?;
+        seat.set_shortcuts_inhibit_escape(mods, sym);
+        Ok(())
+    }
+
     fn handle_remove_shortcut(
         &self,
         seat: Seat,
@@ -801,6 +979,28 @@ impl ConfigProxyHandler {
         Ok(())
     }
 
+    fn handle_add_swipe_binding(
+        &self,
+        seat: Seat,
+        fingers: u32,
+        direction: Direction,
+    ) -> Result<(), CphError> {
+        let seat = self.get_seat(seat)?;
+        seat.add_swipe_binding(fingers, direction);
+        Ok(())
+    }
+
+    fn handle_remove_swipe_binding(
+        &self,
+        seat: Seat,
+        fingers: u32,
+        direction: Direction,
+    ) -> Result<(), CphError> {
+        let seat = self.get_seat(seat)?;
+        seat.remove_swipe_binding(fingers, direction);
+        Ok(())
+    }
+
     fn handle_get_input_devices(&self, seat: Option<Seat>) {
         let id = seat.map(|s| SeatId::from_raw(s.0 as _));
         let matches = |dhd: &DeviceHandlerData| {
@@ -846,7 +1046,15 @@ impl ConfigProxyHandler {
             Some(f) => f,
             _ => return Err(CphError::NoForker),
         };
-        forker.spawn(prog.to_string(), args, env, None);
+        let token = ActivationToken::new();
+        self.state.add_activation_token(token, None);
+        let token = token.to_string();
+        let mut env_with_token = vec![
+            ("XDG_ACTIVATION_TOKEN".to_string(), token.clone()),
+            ("DESKTOP_STARTUP_ID".to_string(), token),
+        ];
+        env_with_token.extend(env);
+        forker.spawn(prog.to_string(), args, env_with_token, None);
         Ok(())
     }
 
@@ -891,6 +1099,195 @@ impl ConfigProxyHandler {
         Ok(())
     }
 
+    fn handle_get_windows(&self) {
+        let windows = self
+            .state
+            .toplevels
+            .lock()
+            .keys()
+            .map(|id| Window(id.0 as _))
+            .collect();
+        self.respond(Response::GetWindows { windows });
+    }
+
+    fn handle_get_focused_window(&self, seat: Seat) -> Result<(), CphError> {
+        let seat = self.get_seat(seat)?;
+        let window = match seat.focused_toplevel() {
+            Some(tl) if self.state.toplevels.contains(&tl.node_id()) => Window(tl.node_id().0 as _),
+            _ => Window::INVALID,
+        };
+        self.respond(Response::GetFocusedWindow { window });
+        Ok(())
+    }
+
+    fn handle_window_exists(&self, window: Window) {
+        let exists = self.state.toplevels.contains(&NodeId(window.0 as _));
+        self.respond(Response::WindowExists { exists });
+    }
+
+    fn handle_window_title(&self, window: Window) -> Result<(), CphError> {
+        let tl = self.get_window(window)?;
+        let title = tl.tl_data().title.borrow().clone();
+        self.respond(Response::WindowTitle { title });
+        Ok(())
+    }
+
+    fn handle_window_app_id(&self, window: Window) -> Result<(), CphError> {
+        let tl = self.get_window(window)?;
+        let app_id = tl.tl_data().app_id.borrow().clone();
+        self.respond(Response::WindowAppId { app_id });
+        Ok(())
+    }
+
+    fn handle_window_is_x(&self, window: Window) -> Result<(), CphError> {
+        let tl = self.get_window(window)?;
+        self.respond(Response::WindowIsX {
+            is_x: tl.tl_x_data().is_some(),
+        });
+        Ok(())
+    }
+
+    fn window_x_property(
+        &self,
+        window: Window,
+        f: impl FnOnce(&XwindowInfo) -> &RefCell<Option<BString>>,
+    ) -> Result<String, CphError> {
+        let tl = self.get_window(window)?;
+        let res = match tl.tl_x_data() {
+            Some(data) => match &*f(&data.info).borrow() {
+                Some(s) => s.to_string(),
+                _ => String::new(),
+            },
+            _ => String::new(),
+        };
+        Ok(res)
+    }
+
+    fn handle_window_x_class(&self, window: Window) -> Result<(), CphError> {
+        let class = self.window_x_property(window, |i| &i.class)?;
+        self.respond(Response::WindowXClass { class });
+        Ok(())
+    }
+
+    fn handle_window_x_instance(&self, window: Window) -> Result<(), CphError> {
+        let instance = self.window_x_property(window, |i| &i.instance)?;
+        self.respond(Response::WindowXInstance { instance });
+        Ok(())
+    }
+
+    fn handle_window_x_role(&self, window: Window) -> Result<(), CphError> {
+        let role = self.window_x_property(window, |i| &i.role)?;
+        self.respond(Response::WindowXRole { role });
+        Ok(())
+    }
+
+    fn handle_window_pid(&self, window: Window) -> Result<(), CphError> {
+        let tl = self.get_window(window)?;
+        let pid = match tl.tl_x_data() {
+            Some(data) => data.info.pid.get(),
+            _ => tl
+                .tl_data()
+                .client
+                .as_ref()
+                .map(|c| c.pid_info.pid as u32)
+                .filter(|&pid| pid != 0),
+        };
+        self.respond(Response::WindowPid { pid });
+        Ok(())
+    }
+
+    fn handle_window_workspace(&self, window: Window) -> Result<(), CphError> {
+        let tl = self.get_window(window)?;
+        let workspace = tl
+            .tl_data()
+            .workspace
+            .get()
+            .map(|ws| self.workspace_by_name(&ws.name));
+        self.respond(Response::WindowWorkspace { workspace });
+        Ok(())
+    }
+
+    fn handle_window_set_workspace(&self, window: Window, ws: Workspace) -> Result<(), CphError> {
+        let tl = self.get_window(window)?;
+        let name = self.get_workspace(ws)?;
+        let workspace = match self.state.workspaces.get(name.deref()) {
+            Some(ws) => ws,
+            _ => match tl.tl_data().workspace.get() {
+                Some(old) => old.output.get().create_workspace(name.deref()),
+                _ => return Ok(()),
+            },
+        };
+        self.state.move_toplevel_to_workspace(tl, &workspace);
+        Ok(())
+    }
+
+    fn handle_window_floating(&self, window: Window) -> Result<(), CphError> {
+        let tl = self.get_window(window)?;
+        self.respond(Response::WindowFloating {
+            floating: tl.tl_data().is_floating.get(),
+        });
+        Ok(())
+    }
+
+    fn handle_window_set_floating(&self, window: Window, floating: bool) -> Result<(), CphError> {
+        let tl = self.get_window(window)?;
+        self.state.set_toplevel_floating(tl, floating);
+        Ok(())
+    }
+
+    fn handle_window_fullscreen(&self, window: Window) -> Result<(), CphError> {
+        let tl = self.get_window(window)?;
+        self.respond(Response::WindowFullscreen {
+            fullscreen: tl.tl_data().is_fullscreen.get(),
+        });
+        Ok(())
+    }
+
+    fn handle_window_set_fullscreen(
+        &self,
+        window: Window,
+        fullscreen: bool,
+    ) -> Result<(), CphError> {
+        let tl = self.get_window(window)?;
+        tl.tl_set_fullscreen(fullscreen);
+        Ok(())
+    }
+
+    fn handle_window_focus(&self, window: Window, seat: Seat) -> Result<(), CphError> {
+        let tl = self.get_window(window)?;
+        let seat = self.get_seat(seat)?;
+        seat.activate_toplevel(tl);
+        Ok(())
+    }
+
+    fn handle_window_close(&self, window: Window) -> Result<(), CphError> {
+        let tl = self.get_window(window)?;
+        tl.tl_close();
+        Ok(())
+    }
+
+    fn handle_add_window_rule(&self, rule: WindowRule) -> Result<(), CphError> {
+        let workspace = match rule.workspace {
+            Some(ws) => Some(self.get_workspace(ws)?),
+            _ => None,
+        };
+        let actions = WindowRuleActions {
+            workspace,
+            floating: rule.floating,
+            size: rule.size,
+            position: rule.position,
+            fullscreen: rule.fullscreen,
+            focus: rule.focus,
+        };
+        match CompiledWindowRule::new(rule.matcher, actions) {
+            Some(rule) => {
+                self.state.window_rules.add(rule);
+                Ok(())
+            }
+            _ => Err(CphError::InvalidWindowRule),
+        }
+    }
+
     fn spaces_change(&self) {
         struct V;
         impl NodeVisitorBase for V {
@@ -993,6 +1390,7 @@ impl ConfigProxyHandler {
             FOCUSED_TITLE_TEXT_COLOR => &colors.focused_title_text,
             FOCUSED_INACTIVE_TITLE_TEXT_COLOR => &colors.focused_inactive_title_text,
             BAR_STATUS_TEXT_COLOR => &colors.bar_text,
+            ATTENTION_REQUESTED_BACKGROUND_COLOR => &colors.attention_requested_background,
             _ => return Err(CphError::UnknownColor(colorable.0)),
         };
         Ok(colorable)
@@ -1065,6 +1463,20 @@ impl ConfigProxyHandler {
             ClientMessage::RemoveShortcut { seat, mods, sym } => self
                 .handle_remove_shortcut(seat, mods, sym)
                 .wrn("remove_shortcut")?,
+            ClientMessage::AddSwipeBinding {
+                seat,
+                fingers,
+                direction,
+            } => self
+                .handle_add_swipe_binding(seat, fingers, direction)
+                .wrn("add_swipe_binding")?,
+            ClientMessage::RemoveSwipeBinding {
+                seat,
+                fingers,
+                direction,
+            } => self
+                .handle_remove_swipe_binding(seat, fingers, direction)
+                .wrn("remove_swipe_binding")?,
             ClientMessage::Focus { seat, direction } => {
                 self.handle_focus(seat, direction).wrn("focus")?
             }
@@ -1116,6 +1528,21 @@ impl ConfigProxyHandler {
             ClientMessage::SetTransformMatrix { device, matrix } => self
                 .handle_set_transform_matrix(device, matrix)
                 .wrn("set_transform_matrix")?,
+            ClientMessage::SetInputDeviceConnector { device, connector } => self
+                .handle_set_input_device_connector(device, connector)
+                .wrn("set_input_device_connector")?,
+            ClientMessage::MapInputDeviceToLayout { device } => self
+                .handle_map_input_device_to_layout(device)
+                .wrn("map_input_device_to_layout")?,
+            ClientMessage::RemoveInputMapping { device } => self
+                .handle_remove_input_mapping(device)
+                .wrn("remove_input_mapping")?,
+            ClientMessage::AddPadButtonBinding { device, button } => self
+                .handle_add_pad_button_binding(device, button)
+                .wrn("add_pad_button_binding")?,
+            ClientMessage::RemovePadButtonBinding { device, button } => self
+                .handle_remove_pad_button_binding(device, button)
+                .wrn("remove_pad_button_binding")?,
             ClientMessage::GetDeviceName { device } => {
                 self.handle_get_device_name(device).wrn("get_device_name")?
             }
@@ -1226,6 +1653,89 @@ impl ConfigProxyHandler {
             ClientMessage::MakeRenderDevice { device } => self
                 .handle_make_render_device(device)
                 .wrn("make_render_device")?,
+            ClientMessage::ConnectorModes { connector } => self
+                .handle_connector_modes(connector)
+                .wrn("connector_modes")?,
+            ClientMessage::ConnectorSetMode { connector, mode } => self
+                .handle_connector_set_mode(connector, mode)
+                .wrn("connector_set_mode")?,
+            ClientMessage::ConnectorSetTransform {
+                connector,
+                transform,
+            } => self
+                .handle_connector_set_transform(connector, transform)
+                .wrn("connector_set_transform")?,
+            ClientMessage::ShortcutsInhibitResponse { allow } => {
+                self.shortcuts_inhibit_allowed.set(allow)
+            }
+            ClientMessage::SetShortcutsInhibitEscape { seat, mods, sym } => self
+                .handle_set_shortcuts_inhibit_escape(seat, mods, sym)
+                .wrn("set_shortcuts_inhibit_escape")?,
+            ClientMessage::SetActivationBehavior { behavior } => {
+                self.state.activation_behavior.set(behavior)
+            }
+            ClientMessage::GetLidClosed => self.handle_get_lid_closed(),
+            ClientMessage::ConnectorSetVrr { connector, mode } => self
+                .handle_connector_set_vrr(connector, mode)
+                .wrn("connector_set_vrr")?,
+            ClientMessage::ConnectorSetColorTemperature { connector, kelvin } => self
+                .handle_connector_set_color_temperature(connector, kelvin)
+                .wrn("connector_set_color_temperature")?,
+            ClientMessage::SetTearingAllowed { allowed } => self.state.tearing_allowed.set(allowed),
+            ClientMessage::GetWindows => self.handle_get_windows(),
+            ClientMessage::GetFocusedWindow { seat } => self
+                .handle_get_focused_window(seat)
+                .wrn("get_focused_window")?,
+            ClientMessage::WindowExists { window } => self.handle_window_exists(window),
+            ClientMessage::WindowTitle { window } => {
+                self.handle_window_title(window).wrn("window_title")?
+            }
+            ClientMessage::WindowAppId { window } => {
+                self.handle_window_app_id(window).wrn("window_app_id")?
+            }
+            ClientMessage::WindowIsX { window } => {
+                self.handle_window_is_x(window).wrn("window_is_x")?
+            }
+            ClientMessage::WindowXClass { window } => {
+                self.handle_window_x_class(window).wrn("window_x_class")?
+            }
+            ClientMessage::WindowXInstance { window } => self
+                .handle_window_x_instance(window)
+                .wrn("window_x_instance")?,
+            ClientMessage::WindowXRole { window } => {
+                self.handle_window_x_role(window).wrn("window_x_role")?
+            }
+            ClientMessage::WindowPid { window } => {
+                self.handle_window_pid(window).wrn("window_pid")?
+            }
+            ClientMessage::WindowWorkspace { window } => self
+                .handle_window_workspace(window)
+                .wrn("window_workspace")?,
+            ClientMessage::WindowSetWorkspace { window, workspace } => self
+                .handle_window_set_workspace(window, workspace)
+                .wrn("window_set_workspace")?,
+            ClientMessage::WindowFloating { window } => {
+                self.handle_window_floating(window).wrn("window_floating")?
+            }
+            ClientMessage::WindowSetFloating { window, floating } => self
+                .handle_window_set_floating(window, floating)
+                .wrn("window_set_floating")?,
+            ClientMessage::WindowFullscreen { window } => self
+                .handle_window_fullscreen(window)
+                .wrn("window_fullscreen")?,
+            ClientMessage::WindowSetFullscreen { window, fullscreen } => self
+                .handle_window_set_fullscreen(window, fullscreen)
+                .wrn("window_set_fullscreen")?,
+            ClientMessage::WindowFocus { window, seat } => {
+                self.handle_window_focus(window, seat).wrn("window_focus")?
+            }
+            ClientMessage::WindowClose { window } => {
+                self.handle_window_close(window).wrn("window_close")?
+            }
+            ClientMessage::AddWindowRule { rule } => {
+                self.handle_add_window_rule(rule).wrn("add_window_rule")?
+            }
+            ClientMessage::ClearWindowRules => self.state.window_rules.clear(),
         }
         Ok(())
     }
@@ -1255,8 +1765,12 @@ enum CphError {
     TimerDoesNotExist(JayTimer),
     #[error("Connector {0:?} does not exist or is not connected")]
     OutputDoesNotExist(Connector),
+    #[error("Connector {0:?} does not support mode {1:?}")]
+    UnsupportedMode(Connector, backend::Mode),
     #[error("{0}x{1} is not a valid connector position")]
     InvalidConnectorPosition(i32, i32),
+    #[error("{0}K is not a valid color temperature")]
+    InvalidColorTemperature(u32),
     #[error("Keymap {0:?} does not exist")]
     KeymapDoesNotExist(Keymap),
     #[error("Seat {0:?} does not exist")]
@@ -1265,6 +1779,10 @@ enum CphError {
     DrmDeviceDoesNotExist(DrmDevice),
     #[error("Workspace {0:?} does not exist")]
     WorkspaceDoesNotExist(Workspace),
+    #[error("Window {0:?} does not exist")]
+    WindowDoesNotExist(Window),
+    #[error("The window rule is invalid")]
+    InvalidWindowRule,
     #[error("Keyboard {0:?} does not exist")]
     KeyboardDoesNotExist(InputDevice),
     #[error("Colorable element {0} is not known")]
@@ -0,0 +1,53 @@
+use std::rc::Rc;
+
+#[cfg(test)]
+mod tests;
+
+/// The color temperature at which the colors of an output are not adjusted.
+pub const NEUTRAL_TEMPERATURE: u32 = 6500;
+pub const MIN_TEMPERATURE: u32 = 1000;
+pub const MAX_TEMPERATURE: u32 = 10000;
+
+/// Returns the approximate color of a black body at the given temperature.
+///
+/// This uses the approximation by Tanner Helland. The channels are in the range `0..=1`.
+fn black_body(kelvin: u32) -> [f64; 3] {
+    let t = kelvin as f64 / 100.0;
+    let red = if t <= 66.0 {
+        255.0
+    } else {
+        329.698727446 * (t - 60.0).powf(-0.1332047592)
+    };
+    let green = if t <= 66.0 {
+        99.4708025861 * t.ln() - 161.1195681661
+    } else {
+        288.1221695283 * (t - 60.0).powf(-0.0755148492)
+    };
+    let blue = if t >= 66.0 {
+        255.0
+    } else if t <= 19.0 {
+        0.0
+    } else {
+        138.5177312231 * (t - 10.0).ln() - 305.0447927307
+    };
+    [red, green, blue].map(|c| (c / 255.0).clamp(0.0, 1.0))
+}
+
+/// Returns gamma ramps that shift the white point of an output to the given color
+/// temperature.
+///
+/// The ramps contain `size` entries for the red, green, and blue channels, in that order.
+pub fn temperature_ramps(kelvin: u32, size: u32) -> Rc<[u16]> {
+    let kelvin = kelvin.clamp(MIN_TEMPERATURE, MAX_TEMPERATURE);
+    let neutral = black_body(NEUTRAL_TEMPERATURE);
+    let white = black_body(kelvin);
+    let mut ramps = Vec::with_capacity(3 * size as usize);
+    for channel in 0..3 {
+        let factor = (white[channel] / neutral[channel]).min(1.0);
+        for i in 0..size {
+            let value = i as f64 / (size.max(2) - 1) as f64;
+            ramps.push((value * factor * u16::MAX as f64).round() as u16);
+        }
+    }
+    ramps.into()
+}
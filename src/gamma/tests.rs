@@ -0,0 +1,62 @@
+use crate::gamma::{temperature_ramps, MAX_TEMPERATURE, MIN_TEMPERATURE, NEUTRAL_TEMPERATURE};
+
+fn channels(ramps: &[u16], size: u32) -> [&[u16]; 3] {
+    let size = size as usize;
+    [&ramps[..size], &ramps[size..2 * size], &ramps[2 * size..]]
+}
+
+#[test]
+fn size() {
+    for size in [1, 2, 256, 1024] {
+        assert_eq!(temperature_ramps(4000, size).len(), 3 * size as usize);
+    }
+}
+
+#[test]
+fn neutral_is_identity() {
+    let ramps = temperature_ramps(NEUTRAL_TEMPERATURE, 256);
+    for channel in channels(&ramps, 256) {
+        assert_eq!(channel[0], 0);
+        assert_eq!(channel[255], u16::MAX);
+        for (i, v) in channel.iter().enumerate() {
+            assert_eq!(*v, (i as f64 * u16::MAX as f64 / 255.0).round() as u16);
+        }
+    }
+}
+
+#[test]
+fn warm_reduces_blue() {
+    let ramps = temperature_ramps(3000, 256);
+    let [red, green, blue] = channels(&ramps, 256);
+    assert_eq!(red[255], u16::MAX);
+    assert!(green[255] < red[255]);
+    assert!(blue[255] < green[255]);
+}
+
+#[test]
+fn cold_reduces_red() {
+    let ramps = temperature_ramps(MAX_TEMPERATURE, 256);
+    let [red, _, blue] = channels(&ramps, 256);
+    assert!(red[255] < u16::MAX);
+    assert_eq!(blue[255], u16::MAX);
+}
+
+#[test]
+fn monotonic() {
+    let ramps = temperature_ramps(2500, 1024);
+    for channel in channels(&ramps, 1024) {
+        assert!(channel.windows(2).all(|w| w[0] <= w[1]));
+    }
+}
+
+#[test]
+fn clamped() {
+    assert_eq!(
+        temperature_ramps(0, 256),
+        temperature_ramps(MIN_TEMPERATURE, 256)
+    );
+    assert_eq!(
+        temperature_ramps(u32::MAX, 256),
+        temperature_ramps(MAX_TEMPERATURE, 256)
+    );
+}
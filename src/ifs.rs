@@ -1,3 +1,5 @@
+pub mod ext_foreign_toplevel_handle_v1;
+pub mod ext_foreign_toplevel_list_v1;
 pub mod ext_session_lock_manager_v1;
 pub mod ext_session_lock_v1;
 pub mod ipc;
@@ -27,17 +29,34 @@ pub mod wl_shm;
 pub mod wl_shm_pool;
 pub mod wl_subcompositor;
 pub mod wl_surface;
+pub mod wp_drm_lease_connector_v1;
+pub mod wp_drm_lease_device_v1;
+pub mod wp_drm_lease_request_v1;
+pub mod wp_drm_lease_v1;
 pub mod wp_fractional_scale_manager_v1;
 pub mod wp_presentation;
 pub mod wp_presentation_feedback;
+pub mod wp_tearing_control_manager_v1;
 pub mod wp_viewporter;
+pub mod xdg_activation_token_v1;
+pub mod xdg_activation_v1;
 pub mod xdg_positioner;
 pub mod xdg_wm_base;
+pub mod zwlr_foreign_toplevel_handle_v1;
+pub mod zwlr_foreign_toplevel_manager_v1;
+pub mod zwlr_gamma_control_manager_v1;
+pub mod zwlr_gamma_control_v1;
 pub mod zwlr_layer_shell_v1;
+pub mod zwlr_output_configuration_head_v1;
+pub mod zwlr_output_configuration_v1;
+pub mod zwlr_output_head_v1;
+pub mod zwlr_output_manager_v1;
+pub mod zwlr_output_mode_v1;
 pub mod zwlr_screencopy_frame_v1;
 pub mod zwlr_screencopy_manager_v1;
 pub mod zwp_idle_inhibit_manager_v1;
 pub mod zwp_linux_buffer_params_v1;
+pub mod zwp_linux_dmabuf_feedback_v1;
 pub mod zwp_linux_dmabuf_v1;
 pub mod zxdg_decoration_manager_v1;
 pub mod zxdg_output_manager_v1;
@@ -0,0 +1,99 @@
+use {
+    crate::{
+        client::{Client, ClientError},
+        leaks::Tracker,
+        object::Object,
+        tree::ToplevelNode,
+        utils::{
+            buffd::{MsgParser, MsgParserError},
+            clonecell::CloneCell,
+        },
+        wire::{ext_foreign_toplevel_handle_v1::*, ExtForeignToplevelHandleV1Id},
+    },
+    std::rc::Rc,
+    thiserror::Error,
+};
+
+pub struct ExtForeignToplevelHandleV1 {
+    pub id: ExtForeignToplevelHandleV1Id,
+    pub client: Rc<Client>,
+    pub tracker: Tracker<Self>,
+    pub version: u32,
+    pub toplevel: CloneCell<Option<Rc<dyn ToplevelNode>>>,
+}
+
+impl ExtForeignToplevelHandleV1 {
+    pub fn send_identifier(&self, identifier: &str) {
+        self.client.event(Identifier {
+            self_id: self.id,
+            identifier,
+        });
+    }
+
+    pub fn send_title(&self, title: &str) {
+        self.client.event(Title {
+            self_id: self.id,
+            title,
+        });
+    }
+
+    pub fn send_app_id(&self, app_id: &str) {
+        self.client.event(AppId {
+            self_id: self.id,
+            app_id,
+        });
+    }
+
+    pub fn send_done(&self) {
+        self.client.event(Done { self_id: self.id });
+    }
+
+    pub fn send_closed(&self) {
+        self.toplevel.take();
+        self.client.event(Closed { self_id: self.id });
+    }
+
+    fn destroy(&self, parser: MsgParser<'_, '_>) -> Result<(), ExtForeignToplevelHandleV1Error> {
+        let _req: Destroy = self.client.parse(self, parser)?;
+        self.detach();
+        self.client.remove_obj(self)?;
+        Ok(())
+    }
+
+    fn detach(&self) {
+        if let Some(toplevel) = self.toplevel.take() {
+            toplevel
+                .tl_data()
+                .ext_handles
+                .remove(&(self.client.id, self.id));
+        }
+    }
+}
+
+object_base! {
+    ExtForeignToplevelHandleV1;
+
+    DESTROY => destroy,
+}
+
+impl Object for ExtForeignToplevelHandleV1 {
+    fn num_requests(&self) -> u32 {
+        DESTROY + 1
+    }
+
+    fn break_loops(&self) {
+        self.detach();
+    }
+}
+
+simple_add_obj!(ExtForeignToplevelHandleV1);
+
+#[derive(Debug, Error)]
+pub enum ExtForeignToplevelHandleV1Error {
+    #[error(transparent)]
+    ClientError(Box<ClientError>),
+    #[error("Parsing failed")]
+    MsgParserError(#[source] Box<MsgParserError>),
+}
+efrom!(ExtForeignToplevelHandleV1Error, ClientError);
+efrom!(ExtForeignToplevelHandleV1Error, MsgParserError);
@@ -0,0 +1,159 @@
+use {
+    crate::{
+        client::{Client, ClientError},
+        globals::{Global, GlobalName},
+        ifs::ext_foreign_toplevel_handle_v1::ExtForeignToplevelHandleV1,
+        leaks::Tracker,
+        object::Object,
+        tree::ToplevelNode,
+        utils::buffd::{MsgParser, MsgParserError},
+        wire::{ext_foreign_toplevel_list_v1::*, ExtForeignToplevelListV1Id},
+    },
+    std::{cell::Cell, rc::Rc},
+    thiserror::Error,
+};
+
+pub struct ExtForeignToplevelListV1Global {
+    pub name: GlobalName,
+}
+
+impl ExtForeignToplevelListV1Global {
+    pub fn new(name: GlobalName) -> Self {
+        Self { name }
+    }
+
+    fn bind_(
+        self: Rc<Self>,
+        id: ExtForeignToplevelListV1Id,
+        client: &Rc<Client>,
+        version: u32,
+    ) -> Result<(), ExtForeignToplevelListV1Error> {
+        let list = Rc::new(ExtForeignToplevelListV1 {
+            id,
+            client: client.clone(),
+            tracker: Default::default(),
+            version,
+            stopped: Cell::new(false),
+        });
+        track!(client, list);
+        client.add_client_obj(&list)?;
+        client
+            .state
+            .toplevel_lists
+            .set((client.id, id), list.clone());
+        let toplevels: Vec<_> = client.state.toplevels.lock().values().cloned().collect();
+        for toplevel in toplevels {
+            list.announce(&toplevel)?;
+        }
+        Ok(())
+    }
+}
+
+global_base!(
+    ExtForeignToplevelListV1Global,
+    ExtForeignToplevelListV1,
+    ExtForeignToplevelListV1Error
+);
+
+simple_add_global!(ExtForeignToplevelListV1Global);
+
+impl Global for ExtForeignToplevelListV1Global {
+    fn singleton(&self) -> bool {
+        true
+    }
+
+    fn version(&self) -> u32 {
+        1
+    }
+
+    fn secure(&self) -> bool {
+        true
+    }
+}
+
+pub struct ExtForeignToplevelListV1 {
+    pub id: ExtForeignToplevelListV1Id,
+    pub client: Rc<Client>,
+    pub tracker: Tracker<Self>,
+    pub version: u32,
+    pub stopped: Cell<bool>,
+}
+
+impl ExtForeignToplevelListV1 {
+    /// Creates a handle for the toplevel and sends its current state.
+    pub fn announce(&self, toplevel: &Rc<dyn ToplevelNode>) -> Result<(), ClientError> {
+        let handle = Rc::new(ExtForeignToplevelHandleV1 {
+            id: self.client.new_id()?,
+            client: self.client.clone(),
+            tracker: Default::default(),
+            version: self.version,
+            toplevel: Default::default(),
+        });
+        track!(self.client, handle);
+        self.client.add_server_obj(&handle);
+        self.client.event(Toplevel {
+            self_id: self.id,
+            toplevel: handle.id,
+        });
+        let data = toplevel.tl_data();
+        handle.send_identifier(&format!("{:08x}", toplevel.node_id().0));
+        handle.send_title(&data.title.borrow_mut());
+        handle.send_app_id(&data.app_id.borrow_mut());
+        handle.send_done();
+        handle.toplevel.set(Some(toplevel.clone()));
+        data.ext_handles.set((self.client.id, handle.id), handle);
+        Ok(())
+    }
+
+    fn stop(&self, parser: MsgParser<'_, '_>) -> Result<(), ExtForeignToplevelListV1Error> {
+        let _req: Stop = self.client.parse(self, parser)?;
+        if !self.stopped.replace(true) {
+            self.remove_from_state();
+            self.client.event(Finished { self_id: self.id });
+        }
+        Ok(())
+    }
+
+    fn destroy(&self, parser: MsgParser<'_, '_>) -> Result<(), ExtForeignToplevelListV1Error> {
+        let _req: Destroy = self.client.parse(self, parser)?;
+        self.remove_from_state();
+        self.client.remove_obj(self)?;
+        Ok(())
+    }
+
+    fn remove_from_state(&self) {
+        self.client
+            .state
+            .toplevel_lists
+            .remove(&(self.client.id, self.id));
+    }
+}
+
+object_base! {
+    ExtForeignToplevelListV1;
+
+    STOP => stop,
+    DESTROY => destroy,
+}
+
+impl Object for ExtForeignToplevelListV1 {
+    fn num_requests(&self) -> u32 {
+        DESTROY + 1
+    }
+
+    fn break_loops(&self) {
+        self.remove_from_state();
+    }
+}
+
+simple_add_obj!(ExtForeignToplevelListV1);
+
+#[derive(Debug, Error)]
+pub enum ExtForeignToplevelListV1Error {
+    #[error(transparent)]
+    ClientError(Box<ClientError>),
+    #[error("Parsing failed")]
+    MsgParserError(#[source] Box<MsgParserError>),
+}
+efrom!(ExtForeignToplevelListV1Error, ClientError);
+efrom!(ExtForeignToplevelListV1Error, MsgParserError);
@@ -1,7 +1,10 @@
 use {
     crate::{
         client::{Client, ClientError, ClientId, WaylandObject},
-        ifs::wl_seat::{WlSeatError, WlSeatGlobal},
+        ifs::{
+            ipc::zwlr_data_control_source_v1::ZwlrDataControlSourceV1,
+            wl_seat::{WlSeatError, WlSeatGlobal},
+        },
         utils::{
             bitflags::BitflagsExt, clonecell::CloneCell, numcell::NumCell, smallmap::SmallMap,
         },
@@ -20,6 +23,10 @@ pub mod wl_data_device;
 pub mod wl_data_device_manager;
 pub mod wl_data_offer;
 pub mod wl_data_source;
+pub mod zwlr_data_control_device_v1;
+pub mod zwlr_data_control_manager_v1;
+pub mod zwlr_data_control_offer_v1;
+pub mod zwlr_data_control_source_v1;
 pub mod zwp_primary_selection_device_manager_v1;
 pub mod zwp_primary_selection_device_v1;
 pub mod zwp_primary_selection_offer_v1;
@@ -55,6 +62,7 @@ pub trait IpcVtable: Sized {
         data: OfferData<Self>,
     ) -> Result<Rc<Self::Offer>, ClientError>;
     fn send_selection(dd: &Self::Device, offer: Option<&Rc<Self::Offer>>);
+    fn send_data_control_selection(seat: &WlSeatGlobal, source: Option<&Rc<Self::Source>>);
     fn send_cancelled(source: &Rc<Self::Source>);
     fn get_offer_id(offer: &Self::Offer) -> u64;
     fn send_offer(dd: &Self::Device, offer: &Rc<Self::Offer>);
@@ -113,6 +121,7 @@ pub struct SourceData<T: IpcVtable> {
     role: Cell<Role>,
     shared: CloneCell<Rc<SharedState>>,
     pub is_xwm: bool,
+    pub data_control: CloneCell<Option<Rc<ZwlrDataControlSourceV1>>>,
 }
 
 struct SharedState {
@@ -148,6 +157,7 @@ impl<T: IpcVtable> SourceData<T> {
             role: Cell::new(Role::Selection),
             shared: Default::default(),
             is_xwm,
+            data_control: Default::default(),
         }
     }
 }
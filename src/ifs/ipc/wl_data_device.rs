@@ -246,6 +246,10 @@ impl IpcVtable for ClipboardIpc {
         dd.send_selection(offer);
     }
 
+    fn send_data_control_selection(seat: &WlSeatGlobal, source: Option<&Rc<Self::Source>>) {
+        seat.for_each_data_control_device(|dd| dd.send_selection(source));
+    }
+
     fn send_cancelled(source: &Rc<Self::Source>) {
         source.send_cancelled();
     }
@@ -0,0 +1,209 @@
+use {
+    crate::{
+        client::{Client, ClientError},
+        ifs::{
+            ipc::{
+                wl_data_source::WlDataSource,
+                zwlr_data_control_offer_v1::{OfferSource, ZwlrDataControlOfferV1},
+                zwp_primary_selection_source_v1::ZwpPrimarySelectionSourceV1,
+            },
+            wl_seat::{WlSeatError, WlSeatGlobal},
+        },
+        leaks::Tracker,
+        object::Object,
+        utils::{
+            buffd::{MsgParser, MsgParserError},
+            clonecell::CloneCell,
+        },
+        wire::{
+            zwlr_data_control_device_v1::*, ZwlrDataControlDeviceV1Id, ZwlrDataControlOfferV1Id,
+        },
+    },
+    std::{cell::RefCell, rc::Rc},
+    thiserror::Error,
+};
+
+pub const PRIMARY_SELECTION_SINCE: u32 = 2;
+
+pub struct ZwlrDataControlDeviceV1 {
+    pub id: ZwlrDataControlDeviceV1Id,
+    pub client: Rc<Client>,
+    pub version: u32,
+    pub seat: Rc<WlSeatGlobal>,
+    pub tracker: Tracker<Self>,
+    selection: CloneCell<Option<Rc<ZwlrDataControlOfferV1>>>,
+    primary_selection: CloneCell<Option<Rc<ZwlrDataControlOfferV1>>>,
+}
+
+impl ZwlrDataControlDeviceV1 {
+    pub fn new(
+        id: ZwlrDataControlDeviceV1Id,
+        client: &Rc<Client>,
+        version: u32,
+        seat: &Rc<WlSeatGlobal>,
+    ) -> Self {
+        Self {
+            id,
+            client: client.clone(),
+            version,
+            seat: seat.clone(),
+            tracker: Default::default(),
+            selection: Default::default(),
+            primary_selection: Default::default(),
+        }
+    }
+
+    pub fn send_selection(&self, source: Option<&Rc<WlDataSource>>) {
+        let source = source.map(|s| OfferSource::Selection(s.clone()));
+        if let Some(id) = self.replace_offer(&self.selection, source) {
+            self.client.event(Selection {
+                self_id: self.id,
+                id,
+            });
+        }
+    }
+
+    pub fn send_primary_selection(&self, source: Option<&Rc<ZwpPrimarySelectionSourceV1>>) {
+        if self.version < PRIMARY_SELECTION_SINCE {
+            return;
+        }
+        let source = source.map(|s| OfferSource::PrimarySelection(s.clone()));
+        if let Some(id) = self.replace_offer(&self.primary_selection, source) {
+            self.client.event(PrimarySelection {
+                self_id: self.id,
+                id,
+            });
+        }
+    }
+
+    /// Invalidates the previous offer in `field` and creates a new offer for `source`.
+    ///
+    /// Returns `None` if the offer could not be created.
+    fn replace_offer(
+        &self,
+        field: &CloneCell<Option<Rc<ZwlrDataControlOfferV1>>>,
+        source: Option<OfferSource>,
+    ) -> Option<ZwlrDataControlOfferV1Id> {
+        if let Some(old) = field.take() {
+            old.source.take();
+        }
+        let source = match source {
+            Some(s) => s,
+            _ => return Some(ZwlrDataControlOfferV1Id::NONE),
+        };
+        let id = match self.client.new_id() {
+            Ok(id) => id,
+            Err(e) => {
+                self.client.error(e);
+                return None;
+            }
+        };
+        let mime_types = source.mime_types();
+        let offer = Rc::new(ZwlrDataControlOfferV1 {
+            id,
+            client: self.client.clone(),
+            tracker: Default::default(),
+            source: RefCell::new(Some(source)),
+        });
+        track!(self.client, offer);
+        self.client.add_server_obj(&offer);
+        self.client.event(DataOffer {
+            self_id: self.id,
+            id,
+        });
+        for mime_type in &mime_types {
+            offer.send_offer(mime_type);
+        }
+        field.set(Some(offer));
+        Some(id)
+    }
+
+    fn set_selection(&self, parser: MsgParser<'_, '_>) -> Result<(), ZwlrDataControlDeviceV1Error> {
+        let req: SetSelection = self.client.parse(self, parser)?;
+        let src = if req.source.is_none() {
+            None
+        } else {
+            let src = self.client.lookup(req.source)?;
+            if src.used.replace(true) {
+                return Err(ZwlrDataControlDeviceV1Error::AlreadyUsed);
+            }
+            Some(src.create_selection_source())
+        };
+        self.seat.set_selection(src, None)?;
+        Ok(())
+    }
+
+    fn set_primary_selection(
+        &self,
+        parser: MsgParser<'_, '_>,
+    ) -> Result<(), ZwlrDataControlDeviceV1Error> {
+        let req: SetPrimarySelection = self.client.parse(self, parser)?;
+        let src = if req.source.is_none() {
+            None
+        } else {
+            let src = self.client.lookup(req.source)?;
+            if src.used.replace(true) {
+                return Err(ZwlrDataControlDeviceV1Error::AlreadyUsed);
+            }
+            Some(src.create_primary_selection_source())
+        };
+        self.seat.set_primary_selection(src, None)?;
+        Ok(())
+    }
+
+    fn destroy(&self, parser: MsgParser<'_, '_>) -> Result<(), ZwlrDataControlDeviceV1Error> {
+        let _req: Destroy = self.client.parse(self, parser)?;
+        self.detach();
+        self.client.remove_obj(self)?;
+        Ok(())
+    }
+
+    fn detach(&self) {
+        self.seat.remove_data_control_device(self);
+        for offer in [self.selection.take(), self.primary_selection.take()]
+            .into_iter()
+            .flatten()
+        {
+            offer.source.take();
+        }
+    }
+}
+
+object_base! {
+    ZwlrDataControlDeviceV1;
+
+    SET_SELECTION => set_selection,
+    DESTROY => destroy,
+    SET_PRIMARY_SELECTION => set_primary_selection,
+}
+
+impl Object for ZwlrDataControlDeviceV1 {
+    fn num_requests(&self) -> u32 {
+        if self.version < PRIMARY_SELECTION_SINCE {
+            DESTROY + 1
+        } else {
+            SET_PRIMARY_SELECTION + 1
+        }
+    }
+
+    fn break_loops(&self) {
+        self.detach();
+    }
+}
+
+simple_add_obj!(ZwlrDataControlDeviceV1);
+
+#[derive(Debug, Error)]
+pub enum ZwlrDataControlDeviceV1Error {
+    #[error("Parsing failed")]
+    MsgParserError(#[source] Box<MsgParserError>),
+    #[error(transparent)]
+    ClientError(Box<ClientError>),
+    #[error(transparent)]
+    WlSeatError(Box<WlSeatError>),
+    #[error("The source has already been used")]
+    AlreadyUsed,
+}
+efrom!(ZwlrDataControlDeviceV1Error, ClientError);
+efrom!(ZwlrDataControlDeviceV1Error, MsgParserError);
+efrom!(ZwlrDataControlDeviceV1Error, WlSeatError);
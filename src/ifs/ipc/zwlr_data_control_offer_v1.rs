@@ -0,0 +1,98 @@
+use {
+    crate::{
+        client::{Client, ClientError},
+        ifs::ipc::{
+            wl_data_source::WlDataSource,
+            zwp_primary_selection_source_v1::ZwpPrimarySelectionSourceV1,
+        },
+        leaks::Tracker,
+        object::Object,
+        utils::buffd::{MsgParser, MsgParserError},
+        wire::{zwlr_data_control_offer_v1::*, ZwlrDataControlOfferV1Id},
+    },
+    std::{cell::RefCell, rc::Rc},
+    thiserror::Error,
+    uapi::OwnedFd,
+};
+
+pub enum OfferSource {
+    Selection(Rc<WlDataSource>),
+    PrimarySelection(Rc<ZwpPrimarySelectionSourceV1>),
+}
+
+impl OfferSource {
+    pub fn mime_types(&self) -> Vec<String> {
+        let mime_types = match self {
+            OfferSource::Selection(src) => src.data.mime_types.borrow(),
+            OfferSource::PrimarySelection(src) => src.data.mime_types.borrow(),
+        };
+        mime_types.iter().cloned().collect()
+    }
+
+    fn send_send(&self, mime_type: &str, fd: Rc<OwnedFd>) {
+        match self {
+            OfferSource::Selection(src) => src.send_send(mime_type, fd),
+            OfferSource::PrimarySelection(src) => src.send_send(mime_type, fd),
+        }
+    }
+}
+
+pub struct ZwlrDataControlOfferV1 {
+    pub id: ZwlrDataControlOfferV1Id,
+    pub client: Rc<Client>,
+    pub tracker: Tracker<Self>,
+    pub source: RefCell<Option<OfferSource>>,
+}
+
+impl ZwlrDataControlOfferV1 {
+    pub fn send_offer(&self, mime_type: &str) {
+        self.client.event(Offer {
+            self_id: self.id,
+            mime_type,
+        })
+    }
+
+    fn receive(&self, parser: MsgParser<'_, '_>) -> Result<(), ZwlrDataControlOfferV1Error> {
+        let req: Receive = self.client.parse(self, parser)?;
+        if let Some(src) = self.source.borrow().as_ref() {
+            src.send_send(req.mime_type, req.fd);
+        }
+        Ok(())
+    }
+
+    fn destroy(&self, parser: MsgParser<'_, '_>) -> Result<(), ZwlrDataControlOfferV1Error> {
+        let _req: Destroy = self.client.parse(self, parser)?;
+        self.source.take();
+        self.client.remove_obj(self)?;
+        Ok(())
+    }
+}
+
+object_base! {
+    ZwlrDataControlOfferV1;
+
+    RECEIVE => receive,
+    DESTROY => destroy,
+}
+
+impl Object for ZwlrDataControlOfferV1 {
+    fn num_requests(&self) -> u32 {
+        DESTROY + 1
+    }
+
+    fn break_loops(&self) {
+        self.source.take();
+    }
+}
+
+simple_add_obj!(ZwlrDataControlOfferV1);
+
+#[derive(Debug, Error)]
+pub enum ZwlrDataControlOfferV1Error {
+    #[error("Parsing failed")]
+    MsgParserError(#[source] Box<MsgParserError>),
+    #[error(transparent)]
+    ClientError(Box<ClientError>),
+}
+efrom!(ZwlrDataControlOfferV1Error, ClientError);
+efrom!(ZwlrDataControlOfferV1Error, MsgParserError);
@@ -0,0 +1,152 @@
+use {
+    crate::{
+        client::{Client, ClientError},
+        ifs::ipc::{
+            add_data_source_mime_type, destroy_data_source, wl_data_device::ClipboardIpc,
+            wl_data_source::WlDataSource, zwp_primary_selection_device_v1::PrimarySelectionIpc,
+            zwp_primary_selection_source_v1::ZwpPrimarySelectionSourceV1,
+        },
+        leaks::Tracker,
+        object::Object,
+        utils::{
+            buffd::{MsgParser, MsgParserError},
+            clonecell::CloneCell,
+        },
+        wire::{
+            zwlr_data_control_source_v1::*, WlDataSourceId, ZwlrDataControlSourceV1Id,
+            ZwpPrimarySelectionSourceV1Id,
+        },
+    },
+    std::{
+        cell::{Cell, RefCell},
+        rc::Rc,
+    },
+    thiserror::Error,
+    uapi::OwnedFd,
+};
+
+/// A data source created by a data control client.
+///
+/// When the source is used, it is wrapped in a regular or primary selection source that
+/// forwards all requests from other clients to this object.
+pub struct ZwlrDataControlSourceV1 {
+    pub id: ZwlrDataControlSourceV1Id,
+    pub client: Rc<Client>,
+    pub tracker: Tracker<Self>,
+    pub used: Cell<bool>,
+    mime_types: RefCell<Vec<String>>,
+    selection: CloneCell<Option<Rc<WlDataSource>>>,
+    primary_selection: CloneCell<Option<Rc<ZwpPrimarySelectionSourceV1>>>,
+}
+
+impl ZwlrDataControlSourceV1 {
+    pub fn new(id: ZwlrDataControlSourceV1Id, client: &Rc<Client>) -> Self {
+        Self {
+            id,
+            client: client.clone(),
+            tracker: Default::default(),
+            used: Cell::new(false),
+            mime_types: Default::default(),
+            selection: Default::default(),
+            primary_selection: Default::default(),
+        }
+    }
+
+    pub fn create_selection_source(self: &Rc<Self>) -> Rc<WlDataSource> {
+        let src = Rc::new(WlDataSource::new(WlDataSourceId::NONE, &self.client, false));
+        for mime_type in self.mime_types.borrow().iter() {
+            add_data_source_mime_type::<ClipboardIpc>(&src, mime_type);
+        }
+        src.data.data_control.set(Some(self.clone()));
+        self.selection.set(Some(src.clone()));
+        src
+    }
+
+    pub fn create_primary_selection_source(self: &Rc<Self>) -> Rc<ZwpPrimarySelectionSourceV1> {
+        let src = Rc::new(ZwpPrimarySelectionSourceV1::new(
+            ZwpPrimarySelectionSourceV1Id::NONE,
+            &self.client,
+            false,
+        ));
+        for mime_type in self.mime_types.borrow().iter() {
+            add_data_source_mime_type::<PrimarySelectionIpc>(&src, mime_type);
+        }
+        src.data.data_control.set(Some(self.clone()));
+        self.primary_selection.set(Some(src.clone()));
+        src
+    }
+
+    pub fn send_send(&self, mime_type: &str, fd: Rc<OwnedFd>) {
+        self.client.event(Send {
+            self_id: self.id,
+            mime_type,
+            fd,
+        })
+    }
+
+    pub fn send_cancelled(&self) {
+        self.client.event(Cancelled { self_id: self.id })
+    }
+
+    fn offer(&self, parser: MsgParser<'_, '_>) -> Result<(), ZwlrDataControlSourceV1Error> {
+        let req: Offer = self.client.parse(self, parser)?;
+        if self.used.get() {
+            return Err(ZwlrDataControlSourceV1Error::AlreadyUsed);
+        }
+        self.mime_types.borrow_mut().push(req.mime_type.to_string());
+        Ok(())
+    }
+
+    fn destroy(&self, parser: MsgParser<'_, '_>) -> Result<(), ZwlrDataControlSourceV1Error> {
+        let _req: Destroy = self.client.parse(self, parser)?;
+        self.detach();
+        self.client.remove_obj(self)?;
+        Ok(())
+    }
+
+    fn detach(&self) {
+        if let Some(src) = self.selection.take() {
+            destroy_data_source::<ClipboardIpc>(&src);
+            src.data.data_control.take();
+        }
+        if let Some(src) = self.primary_selection.take() {
+            destroy_data_source::<PrimarySelectionIpc>(&src);
+            src.data.data_control.take();
+        }
+    }
+}
+
+object_base! {
+    ZwlrDataControlSourceV1;
+
+    OFFER => offer,
+    DESTROY => destroy,
+}
+
+impl Object for ZwlrDataControlSourceV1 {
+    fn num_requests(&self) -> u32 {
+        DESTROY + 1
+    }
+
+    fn break_loops(&self) {
+        self.detach();
+    }
+}
+
+dedicated_add_obj!(
+    ZwlrDataControlSourceV1,
+    ZwlrDataControlSourceV1Id,
+    zwlr_data_control_sources
+);
+
+#[derive(Debug, Error)]
+pub enum ZwlrDataControlSourceV1Error {
+    #[error("Parsing failed")]
+    MsgParserError(#[source] Box<MsgParserError>),
+    #[error(transparent)]
+    ClientError(Box<ClientError>),
+    #[error("The source has already been used")]
+    AlreadyUsed,
+}
+efrom!(ZwlrDataControlSourceV1Error, ClientError);
+efrom!(ZwlrDataControlSourceV1Error, MsgParserError);
@@ -212,6 +212,22 @@ impl JayCompositor {
         Ok(())
     }
 
+    fn get_connectors(&self, parser: MsgParser<'_, '_>) -> Result<(), JayCompositorError> {
+        let _req: GetConnectors = self.client.parse(self, parser)?;
+        for connector in self.client.state.connectors.lock().values() {
+            if !connector.connected.get() {
+                continue;
+            }
+            self.client.event(Connector {
+                self_id: self.id,
+                name: &connector.name,
+                direct_scanout: connector.connector.direct_scanout_active() as u32,
+                vrr: connector.connector.vrr_active() as u32,
+            })
+        }
+        Ok(())
+    }
+
     fn seat_events(&self, parser: MsgParser<'_, '_>) -> Result<(), JayCompositorError> {
         let req: SeatEvents = self.client.parse(self, parser)?;
         let se = Rc::new(JaySeatEvents {
@@ -328,11 +344,12 @@ object_base! {
     GET_RENDER_CTX => get_render_ctx,
     WATCH_WORKSPACES => watch_workspaces,
     CREATE_SCREENCAST => create_screencast,
+    GET_CONNECTORS => get_connectors,
 }
 
 impl Object for JayCompositor {
     fn num_requests(&self) -> u32 {
-        CREATE_SCREENCAST + 1
+        GET_CONNECTORS + 1
     }
 }
 
@@ -163,9 +163,14 @@ impl JayScreencast {
         let mut buffer = self.buffers.borrow_mut();
         for (idx, buffer) in buffer.deref_mut().iter_mut().enumerate() {
             if buffer.free {
-                buffer
-                    .fb
-                    .copy_texture(&self.client.state, texture, 0, 0, false);
+                buffer.fb.copy_texture(
+                    &self.client.state,
+                    texture,
+                    0,
+                    0,
+                    false,
+                    on.global.transform.get(),
+                );
                 self.client.event(Ready {
                     self_id: self.id,
                     idx: idx as _,
@@ -194,6 +199,11 @@ impl JayScreencast {
         let mut buffers = vec![];
         if let Some(output) = self.output.get() {
             let mode = output.global.mode.get();
+            let (width, height) = output
+                .global
+                .transform
+                .get()
+                .maybe_swap((mode.width, mode.height));
             let num = 3;
             for _ in 0..num {
                 let format = ModifiedFormat {
@@ -204,7 +214,7 @@ impl JayScreencast {
                 if self.linear.get() {
                     flags |= GBM_BO_USE_LINEAR;
                 }
-                let buffer = ctx.gbm.create_bo(mode.width, mode.height, &format, flags)?;
+                let buffer = ctx.gbm.create_bo(width, height, &format, flags)?;
                 let fb = ctx.dmabuf_img(buffer.dmabuf())?.to_framebuffer()?;
                 buffers.push(ScreencastBuffer {
                     dmabuf: buffer.dmabuf().clone(),
@@ -11,6 +11,7 @@ use {
             buffd::{MsgParser, MsgParserError},
             clonecell::CloneCell,
             errorfmt::ErrorFmt,
+            numcell::NumCell,
         },
         video::dmabuf::DmaBuf,
         wire::{wl_buffer::*, WlBufferId},
@@ -41,6 +42,8 @@ pub struct WlBuffer {
     pub famebuffer: CloneCell<Option<Rc<Framebuffer>>>,
     width: i32,
     height: i32,
+    scanout_refs: NumCell<u32>,
+    release_pending: Cell<bool>,
     pub tracker: Tracker<Self>,
 }
 
@@ -72,6 +75,8 @@ impl WlBuffer {
             dmabuf: Some(dmabuf),
             render_ctx_version: Cell::new(client.state.render_ctx_version.get()),
             storage: RefCell::new(Some(WlBufferStorage::Dmabuf(img.clone()))),
+            scanout_refs: Default::default(),
+            release_pending: Cell::new(false),
             tracker: Default::default(),
         }
     }
@@ -109,11 +114,36 @@ impl WlBuffer {
             width,
             height,
             texture: CloneCell::new(None),
+            scanout_refs: Default::default(),
+            release_pending: Cell::new(false),
             tracker: Default::default(),
             famebuffer: Default::default(),
         })
     }
 
+    pub fn dmabuf(&self) -> Option<&DmaBuf> {
+        self.dmabuf.as_ref()
+    }
+
+    /// Marks the buffer as being scanned out directly.
+    ///
+    /// While the buffer is being scanned out, release events are delayed until the last
+    /// matching `scanout_end` call.
+    pub fn scanout_start(&self) {
+        self.scanout_refs.fetch_add(1);
+    }
+
+    pub fn scanout_end(&self) {
+        if self.scanout_refs.fetch_sub(1) == 1 && self.release_pending.take() && !self.destroyed() {
+            self.client.event(Release { self_id: self.id })
+        }
+    }
+
+    /// Cancels a release that was delayed because the buffer was being scanned out.
+    pub fn cancel_release(&self) {
+        self.release_pending.set(false);
+    }
+
     pub fn handle_gfx_context_change(&self) {
         let ctx_version = self.client.state.render_ctx_version.get();
         if self.render_ctx_version.replace(ctx_version) == ctx_version {
@@ -199,6 +229,10 @@ impl WlBuffer {
     }
 
     pub fn send_release(&self) {
+        if self.scanout_refs.get() > 0 {
+            self.release_pending.set(true);
+            return;
+        }
         self.client.event(Release { self_id: self.id })
     }
 }
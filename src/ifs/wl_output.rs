@@ -14,6 +14,7 @@ use {
         render::{Framebuffer, Texture},
         state::{ConnectorData, State},
         time::Time,
+        transform::Transform,
         tree::OutputNode,
         utils::{
             buffd::{MsgParser, MsgParserError},
@@ -65,6 +66,7 @@ pub struct WlOutputGlobal {
     pub pos: Cell<Rect>,
     pub output_id: Rc<OutputId>,
     pub mode: Cell<backend::Mode>,
+    pub transform: Cell<Transform>,
     pub node: CloneCell<Option<Rc<OutputNode>>>,
     pub width_mm: i32,
     pub height_mm: i32,
@@ -111,6 +113,7 @@ impl WlOutputGlobal {
                 serial_number: serial_number.to_string(),
             }),
             mode: Cell::new(*mode),
+            transform: Cell::new(Transform::Normal),
             node: Default::default(),
             width_mm,
             height_mm,
@@ -247,6 +250,7 @@ impl WlOutputGlobal {
                     -capture.rect.x1(),
                     -capture.rect.y1(),
                     false,
+                    Transform::Normal,
                 );
             }
             if capture.with_damage.get() {
@@ -302,7 +306,7 @@ impl WlOutput {
             subpixel: SP_UNKNOWN,
             make: &self.global.output_id.manufacturer,
             model: &self.global.output_id.model,
-            transform: TF_NORMAL,
+            transform: self.global.transform.get().to_wl(),
         };
         self.client.event(event);
     }
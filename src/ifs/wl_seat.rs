@@ -1,17 +1,30 @@
 mod event_handling;
 mod kb_owner;
 mod pointer_owner;
+pub mod tablet;
+pub mod text_input;
 pub mod wl_keyboard;
 pub mod wl_pointer;
 pub mod wl_touch;
+pub mod zwlr_virtual_pointer_manager_v1;
+pub mod zwlr_virtual_pointer_v1;
+pub mod zwp_keyboard_shortcuts_inhibit_manager_v1;
+pub mod zwp_keyboard_shortcuts_inhibitor_v1;
 pub mod zwp_pointer_constraints_v1;
+pub mod zwp_pointer_gesture_hold_v1;
+pub mod zwp_pointer_gesture_pinch_v1;
+pub mod zwp_pointer_gesture_swipe_v1;
+pub mod zwp_pointer_gestures_v1;
 pub mod zwp_relative_pointer_manager_v1;
 pub mod zwp_relative_pointer_v1;
+pub mod zwp_virtual_keyboard_manager_v1;
+pub mod zwp_virtual_keyboard_v1;
 
 pub use event_handling::NodeSeatState;
 use {
     crate::{
         async_engine::SpawnedFuture,
+        backend::{InputDeviceId, TabletToolId},
         client::{Client, ClientError, ClientId},
         cursor::{Cursor, KnownCursor},
         fixed::Fixed,
@@ -21,6 +34,7 @@ use {
             ipc::{
                 wl_data_device::{ClipboardIpc, WlDataDevice},
                 wl_data_source::WlDataSource,
+                zwlr_data_control_device_v1::ZwlrDataControlDeviceV1,
                 zwp_primary_selection_device_v1::{
                     PrimarySelectionIpc, ZwpPrimarySelectionDeviceV1,
                 },
@@ -28,12 +42,22 @@ use {
                 IpcError,
             },
             wl_seat::{
+                event_handling::BoundSwipe,
                 kb_owner::KbOwnerHolder,
                 pointer_owner::PointerOwnerHolder,
+                tablet::{zwp_tablet_seat_v2::ZwpTabletSeatV2, Tablet, TabletPad, TabletTool},
+                text_input::{
+                    zwp_input_method_keyboard_grab_v2::ZwpInputMethodKeyboardGrabV2,
+                    zwp_input_method_v2::ZwpInputMethodV2, zwp_text_input_v3::ZwpTextInputV3,
+                },
                 wl_keyboard::{WlKeyboard, WlKeyboardError, REPEAT_INFO_SINCE},
                 wl_pointer::WlPointer,
                 wl_touch::WlTouch,
+                zwp_keyboard_shortcuts_inhibitor_v1::ZwpKeyboardShortcutsInhibitorV1,
                 zwp_pointer_constraints_v1::{SeatConstraint, SeatConstraintStatus},
+                zwp_pointer_gesture_hold_v1::ZwpPointerGestureHoldV1,
+                zwp_pointer_gesture_pinch_v1::ZwpPointerGesturePinchV1,
+                zwp_pointer_gesture_swipe_v1::ZwpPointerGestureSwipeV1,
                 zwp_relative_pointer_v1::ZwpRelativePointerV1,
             },
             wl_surface::WlSurface,
@@ -44,7 +68,7 @@ use {
         state::State,
         tree::{
             generic_node_visitor, ContainerNode, ContainerSplit, Direction, FloatNode, FoundNode,
-            Node, OutputNode, WorkspaceNode,
+            Node, OutputNode, ToplevelNode, WorkspaceNode,
         },
         utils::{
             asyncevent::AsyncEvent,
@@ -57,13 +81,22 @@ use {
             rc_eq::rc_eq,
         },
         wire::{
-            wl_seat::*, WlDataDeviceId, WlKeyboardId, WlPointerId, WlSeatId,
-            ZwpPrimarySelectionDeviceV1Id, ZwpRelativePointerV1Id,
+            wl_seat::*, WlDataDeviceId, WlKeyboardId, WlPointerId, WlSeatId, WlTouchId,
+            ZwlrDataControlDeviceV1Id, ZwpPointerGestureHoldV1Id, ZwpPointerGesturePinchV1Id,
+            ZwpPointerGestureSwipeV1Id, ZwpPrimarySelectionDeviceV1Id, ZwpRelativePointerV1Id,
+            ZwpTabletSeatV2Id, ZwpTextInputV3Id,
         },
         xkbcommon::{XkbKeymap, XkbState},
     },
     ahash::{AHashMap, AHashSet},
-    jay_config::keyboard::mods::Modifiers,
+    jay_config::{
+        keyboard::{
+            mods::{Modifiers, LOGO},
+            syms::SYM_Escape,
+            ModifiedKeySym,
+        },
+        Direction as JayDirection,
+    },
     smallvec::SmallVec,
     std::{
         cell::{Cell, RefCell},
@@ -78,7 +111,6 @@ use {
 
 pub const POINTER: u32 = 1;
 const KEYBOARD: u32 = 2;
-#[allow(dead_code)]
 const TOUCH: u32 = 4;
 
 #[allow(dead_code)]
@@ -134,10 +166,15 @@ pub struct WlSeatGlobal {
             AHashMap<ZwpPrimarySelectionDeviceV1Id, Rc<ZwpPrimarySelectionDeviceV1>>,
         >,
     >,
+    data_control_devices:
+        CopyHashMap<(ClientId, ZwlrDataControlDeviceV1Id), Rc<ZwlrDataControlDeviceV1>>,
     repeat_rate: Cell<(i32, i32)>,
     kb_map: CloneCell<Rc<XkbKeymap>>,
-    kb_state: RefCell<XkbState>,
+    kb_state: Rc<RefCell<XkbState>>,
+    effective_kb_state: CloneCell<Rc<RefCell<XkbState>>>,
     cursor: CloneCell<Option<Rc<dyn Cursor>>>,
+    /// The area covered by the cursor when it was last damaged.
+    cursor_damage: Cell<Option<Rect>>,
     tree_changed: Rc<AsyncEvent>,
     selection: CloneCell<Option<Rc<WlDataSource>>>,
     selection_serial: Cell<u32>,
@@ -155,6 +192,22 @@ pub struct WlSeatGlobal {
     cursor_size: Cell<u32>,
     hardware_cursor: Cell<bool>,
     constraint: CloneCell<Option<Rc<SeatConstraint>>>,
+    text_inputs: RefCell<AHashMap<ClientId, AHashMap<ZwpTextInputV3Id, Rc<ZwpTextInputV3>>>>,
+    text_input: CloneCell<Option<Rc<ZwpTextInputV3>>>,
+    input_method: CloneCell<Option<Rc<ZwpInputMethodV2>>>,
+    input_method_grab: CloneCell<Option<Rc<ZwpInputMethodKeyboardGrabV2>>>,
+    shortcuts_inhibitor: CloneCell<Option<Rc<ZwpKeyboardShortcutsInhibitorV1>>>,
+    shortcuts_inhibit_escape: Cell<ModifiedKeySym>,
+    touch_points: CopyHashMap<i32, Rc<WlSurface>>,
+    touch_frame: CopyHashMap<ClientId, Rc<WlSurface>>,
+    touch_emulated_pointer: Cell<Option<i32>>,
+    swipe_bindings: CopyHashMap<(u32, JayDirection), ()>,
+    bound_swipe: Cell<Option<BoundSwipe>>,
+    gesture_surface: CloneCell<Option<Rc<WlSurface>>>,
+    tablet_seats: CopyHashMap<(ClientId, ZwpTabletSeatV2Id), Rc<ZwpTabletSeatV2>>,
+    tablets: CopyHashMap<InputDeviceId, Rc<Tablet>>,
+    tablet_tools: CopyHashMap<TabletToolId, Rc<TabletTool>>,
+    tablet_pads: CopyHashMap<InputDeviceId, Rc<TabletPad>>,
 }
 
 const CHANGE_CURSOR_MOVED: u32 = 1 << 0;
@@ -162,6 +215,11 @@ const CHANGE_TREE: u32 = 1 << 1;
 
 const DEFAULT_CURSOR_SIZE: u32 = 16;
 
+const DEFAULT_SHORTCUTS_INHIBIT_ESCAPE: ModifiedKeySym = ModifiedKeySym {
+    mods: LOGO,
+    sym: SYM_Escape,
+};
+
 impl Drop for WlSeatGlobal {
     fn drop(&mut self) {
         self.state.remove_cursor_size(self.cursor_size.get());
@@ -170,6 +228,7 @@ impl Drop for WlSeatGlobal {
 
 impl WlSeatGlobal {
     pub fn new(name: GlobalName, seat_name: &str, state: &Rc<State>) -> Rc<Self> {
+        let kb_state = Rc::new(RefCell::new(state.default_keymap.state().unwrap()));
         let slf = Rc::new(Self {
             id: state.seat_ids.next(),
             name,
@@ -188,9 +247,11 @@ impl WlSeatGlobal {
             bindings: Default::default(),
             data_devices: RefCell::new(Default::default()),
             primary_selection_devices: RefCell::new(Default::default()),
+            data_control_devices: Default::default(),
             repeat_rate: Cell::new((25, 250)),
             kb_map: CloneCell::new(state.default_keymap.clone()),
-            kb_state: RefCell::new(state.default_keymap.state().unwrap()),
+            kb_state: kb_state.clone(),
+            effective_kb_state: CloneCell::new(kb_state),
             cursor: Default::default(),
             tree_changed: Default::default(),
             selection: Default::default(),
@@ -209,6 +270,23 @@ impl WlSeatGlobal {
             cursor_size: Cell::new(DEFAULT_CURSOR_SIZE),
             hardware_cursor: Cell::new(state.globals.seats.len() == 0),
             constraint: Default::default(),
+            text_inputs: Default::default(),
+            text_input: Default::default(),
+            input_method: Default::default(),
+            input_method_grab: Default::default(),
+            shortcuts_inhibitor: Default::default(),
+            shortcuts_inhibit_escape: Cell::new(DEFAULT_SHORTCUTS_INHIBIT_ESCAPE),
+            touch_points: Default::default(),
+            touch_frame: Default::default(),
+            touch_emulated_pointer: Cell::new(None),
+            swipe_bindings: Default::default(),
+            bound_swipe: Cell::new(None),
+            gesture_surface: Default::default(),
+            tablet_seats: Default::default(),
+            tablets: Default::default(),
+            tablet_tools: Default::default(),
+            tablet_pads: Default::default(),
+            cursor_damage: Cell::new(None),
         });
         state.add_cursor_size(DEFAULT_CURSOR_SIZE);
         let seat = slf.clone();
@@ -259,10 +337,13 @@ impl WlSeatGlobal {
         for output in self.state.root.outputs.lock().values() {
             if let Some(hc) = output.hardware_cursor.get() {
                 let scale = output.preferred_scale.get();
+                let transform = output.global.transform.get();
                 let extents = cursor.extents_at_scale(scale);
+                let (max_width, max_height) = transform.maybe_swap(hc.max_size());
                 if render {
-                    let (max_width, max_height) = hc.max_size();
-                    if extents.width() > max_width || extents.height() > max_height {
+                    let too_small = extents.width() > max_width || extents.height() > max_height;
+                    output.hardware_cursor_too_small.set(too_small);
+                    if too_small {
                         hc.set_enabled(false);
                         hc.commit();
                         continue;
@@ -279,16 +360,28 @@ impl WlSeatGlobal {
                     y_rel = ((y - Fixed::from_int(opos.y1())).to_f64() * scalef).round() as i32;
                 }
                 let mode = output.global.mode.get();
-                if extents
-                    .intersects(&Rect::new_sized(-x_rel, -y_rel, mode.width, mode.height).unwrap())
-                {
+                let (width, height) = transform.maybe_swap((mode.width, mode.height));
+                if extents.intersects(&Rect::new_sized(-x_rel, -y_rel, width, height).unwrap()) {
                     if render {
                         let buffer = hc.get_buffer();
-                        buffer.render_hardware_cursor(cursor.deref(), &self.state, scale);
+                        buffer.render_hardware_cursor(
+                            cursor.deref(),
+                            &self.state,
+                            scale,
+                            transform,
+                        );
                         hc.swap_buffer();
                     }
+                    let buffer_rect = Rect::new_sized(
+                        x_rel + extents.x1(),
+                        y_rel + extents.y1(),
+                        max_width,
+                        max_height,
+                    )
+                    .unwrap();
+                    let buffer_rect = transform.apply_rect(buffer_rect, width, height);
                     hc.set_enabled(true);
-                    hc.set_position(x_rel + extents.x1(), y_rel + extents.y1());
+                    hc.set_position(buffer_rect.x1(), buffer_rect.y1());
                 } else {
                     hc.set_enabled(false);
                 }
@@ -340,6 +433,34 @@ impl WlSeatGlobal {
         }
     }
 
+    pub fn add_data_control_device(&self, device: &Rc<ZwlrDataControlDeviceV1>) {
+        self.data_control_devices
+            .set((device.client.id, device.id), device.clone());
+    }
+
+    pub fn remove_data_control_device(&self, device: &ZwlrDataControlDeviceV1) {
+        self.data_control_devices
+            .remove(&(device.client.id, device.id));
+    }
+
+    pub fn for_each_data_control_device<C>(&self, mut f: C)
+    where
+        C: FnMut(&Rc<ZwlrDataControlDeviceV1>),
+    {
+        let devices: Vec<_> = self.data_control_devices.lock().values().cloned().collect();
+        for dd in &devices {
+            f(dd);
+        }
+    }
+
+    pub fn get_selection(&self) -> Option<Rc<WlDataSource>> {
+        self.selection.get()
+    }
+
+    pub fn get_primary_selection(&self) -> Option<Rc<ZwpPrimarySelectionSourceV1>> {
+        self.primary_selection.get()
+    }
+
     pub fn get_output(&self) -> Rc<OutputNode> {
         self.output.get()
     }
@@ -349,42 +470,7 @@ impl WlSeatGlobal {
             Some(tl) => tl,
             _ => return,
         };
-        if tl.tl_data().is_fullscreen.get() {
-            return;
-        }
-        let old_ws = match tl.tl_data().workspace.get() {
-            Some(ws) => ws,
-            _ => return,
-        };
-        if old_ws.id == ws.id {
-            return;
-        }
-        let cn = match tl
-            .tl_data()
-            .parent
-            .get()
-            .and_then(|p| p.node_into_containing_node())
-        {
-            Some(cn) => cn,
-            _ => return,
-        };
-        let kb_foci = collect_kb_foci(tl.clone().tl_into_node());
-        cn.cnode_remove_child2(tl.tl_as_node(), true);
-        if !ws.visible.get() {
-            for focus in kb_foci {
-                old_ws.clone().node_do_focus(&focus, Direction::Unspecified);
-            }
-        }
-        if tl.tl_data().is_floating.get() {
-            self.state.map_floating(
-                tl.clone(),
-                tl.tl_data().float_width.get(),
-                tl.tl_data().float_height.get(),
-                ws,
-            );
-        } else {
-            self.state.map_tiled_on(tl, ws);
-        }
+        self.state.move_toplevel_to_workspace(tl, ws);
     }
 
     pub fn mark_last_active(self: &Rc<Self>) {
@@ -439,6 +525,10 @@ impl WlSeatGlobal {
         }
     }
 
+    pub fn focused_toplevel(&self) -> Option<Rc<dyn ToplevelNode>> {
+        self.keyboard_node.get().node_toplevel()
+    }
+
     pub fn get_fullscreen(&self) -> bool {
         if let Some(tl) = self.keyboard_node.get().node_toplevel() {
             return tl.tl_data().is_fullscreen.get();
@@ -456,6 +546,12 @@ impl WlSeatGlobal {
         };
         self.kb_map.set(keymap.clone());
         *self.kb_state.borrow_mut() = state;
+        self.effective_kb_state.set(self.kb_state.clone());
+        self.send_keymap(keymap);
+    }
+
+    /// Sends the keymap to all keyboards and the keyboard grab of the input method.
+    fn send_keymap(&self, keymap: &Rc<XkbKeymap>) {
         let bindings = self.bindings.borrow_mut();
         for (id, client) in bindings.iter() {
             for seat in client.values() {
@@ -472,6 +568,14 @@ impl WlSeatGlobal {
                 }
             }
         }
+        if let Some(grab) = self.input_method_grab.get() {
+            if let Err(e) = grab.send_keymap(keymap) {
+                log::error!(
+                    "Could not send the keymap to the input method: {}",
+                    ErrorFmt(e)
+                );
+            }
+        }
     }
 
     pub fn prepare_for_lock(self: &Rc<Self>) {
@@ -594,27 +698,7 @@ impl WlSeatGlobal {
             Some(tl) => tl,
             _ => return,
         };
-        let data = tl.tl_data();
-        if data.is_fullscreen.get() {
-            return;
-        }
-        if data.is_floating.get() == floating {
-            return;
-        }
-        let parent = match data.parent.get() {
-            Some(p) => p,
-            _ => return,
-        };
-        if let Some(cn) = parent.node_into_containing_node() {
-            if !floating {
-                cn.cnode_remove_child2(tl.tl_as_node(), true);
-                self.state.map_tiled(tl);
-            } else if let Some(ws) = data.workspace.get() {
-                cn.cnode_remove_child2(tl.tl_as_node(), true);
-                let (width, height) = data.float_size(&ws);
-                self.state.map_floating(tl, width, height, &ws);
-            }
-        }
+        self.state.set_toplevel_floating(tl, floating);
     }
 
     pub fn get_rate(&self) -> (i32, i32) {
@@ -634,6 +718,9 @@ impl WlSeatGlobal {
                 }
             }
         }
+        if let Some(grab) = self.input_method_grab.get() {
+            grab.send_repeat_info(rate, delay);
+        }
     }
 
     pub fn close(self: &Rc<Self>) {
@@ -684,6 +771,7 @@ impl WlSeatGlobal {
         if let Some(old) = field.set(src.clone()) {
             ipc::detach_seat::<T>(&old);
         }
+        T::send_data_control_selection(self, src.as_ref());
         if let Some(client) = self.keyboard_node.get().node_client() {
             match src {
                 Some(src) => ipc::offer_source_to::<T>(&src, &client),
@@ -811,6 +899,47 @@ impl WlSeatGlobal {
         self.cursor.set(cursor.clone());
         self.state.hardware_tick_cursor.push(cursor);
         self.update_hardware_cursor();
+        self.damage_cursor();
+    }
+
+    /// Returns the area covered by the cursor in global coordinates.
+    pub fn cursor_extents(&self) -> Option<Rect> {
+        let cursor = self.cursor.get()?;
+        let (x, y) = self.pos.get();
+        let (x, y) = (x.round_down(), y.round_down());
+        let mut res = None::<Rect>;
+        for (scale, _) in self.state.scales.lock().iter() {
+            let scalef = scale.to_f64();
+            let extents = cursor.extents_at_scale(*scale);
+            // Add a margin of one pixel to account for rounding during rendering.
+            let rect = Rect::new(
+                (extents.x1() as f64 / scalef).floor() as i32 + x - 1,
+                (extents.y1() as f64 / scalef).floor() as i32 + y - 1,
+                (extents.x2() as f64 / scalef).ceil() as i32 + x + 1,
+                (extents.y2() as f64 / scalef).ceil() as i32 + y + 1,
+            )?;
+            res = Some(match res {
+                Some(res) => res.union(rect),
+                _ => rect,
+            });
+        }
+        res
+    }
+
+    /// Damages the area covered by the cursor and the area it covered previously.
+    ///
+    /// Outputs that display the cursor on a hardware cursor plane are not damaged.
+    pub fn damage_cursor(&self) {
+        let extents = self.cursor_extents();
+        let old = self.cursor_damage.replace(extents);
+        let hardware_cursor = self.hardware_cursor.get();
+        for rect in [old, extents].into_iter().flatten() {
+            if hardware_cursor {
+                self.state.damage_software_cursor_rect(rect);
+            } else {
+                self.state.damage_rect(rect);
+            }
+        }
     }
 
     pub fn dnd_icon(&self) -> Option<Rc<WlSurface>> {
@@ -842,6 +971,7 @@ impl WlSeatGlobal {
         self.bindings.borrow_mut().clear();
         self.data_devices.borrow_mut().clear();
         self.primary_selection_devices.borrow_mut().clear();
+        self.data_control_devices.clear();
         self.cursor.set(None);
         self.selection.set(None);
         self.primary_selection.set(None);
@@ -852,6 +982,16 @@ impl WlSeatGlobal {
         self.tree_changed_handler.set(None);
         self.output.set(self.state.dummy_output.get().unwrap());
         self.constraint.take();
+        self.effective_kb_state.set(self.kb_state.clone());
+        self.text_inputs.borrow_mut().clear();
+        self.text_input.take();
+        self.input_method.take();
+        self.input_method_grab.take();
+        self.shortcuts_inhibitor.take();
+        self.gesture_surface.take();
+        self.touch_points.clear();
+        self.touch_frame.clear();
+        self.clear_tablets();
     }
 
     pub fn id(&self) -> SeatId {
@@ -862,6 +1002,10 @@ impl WlSeatGlobal {
         &self.seat_name
     }
 
+    pub fn keyboard_focus_client(&self) -> Option<ClientId> {
+        self.keyboard_node.get().node_client_id()
+    }
+
     fn bind_(
         self: Rc<Self>,
         id: WlSeatId,
@@ -874,6 +1018,10 @@ impl WlSeatGlobal {
             client: client.clone(),
             pointers: Default::default(),
             relative_pointers: Default::default(),
+            touches: Default::default(),
+            swipe_gestures: Default::default(),
+            pinch_gestures: Default::default(),
+            hold_gestures: Default::default(),
             keyboards: Default::default(),
             version,
             tracker: Default::default(),
@@ -919,6 +1067,10 @@ pub struct WlSeat {
     pub client: Rc<Client>,
     pointers: CopyHashMap<WlPointerId, Rc<WlPointer>>,
     relative_pointers: CopyHashMap<ZwpRelativePointerV1Id, Rc<ZwpRelativePointerV1>>,
+    touches: CopyHashMap<WlTouchId, Rc<WlTouch>>,
+    swipe_gestures: CopyHashMap<ZwpPointerGestureSwipeV1Id, Rc<ZwpPointerGestureSwipeV1>>,
+    pinch_gestures: CopyHashMap<ZwpPointerGesturePinchV1Id, Rc<ZwpPointerGesturePinchV1>>,
+    hold_gestures: CopyHashMap<ZwpPointerGestureHoldV1Id, Rc<ZwpPointerGestureHoldV1>>,
     keyboards: CopyHashMap<WlKeyboardId, Rc<WlKeyboard>>,
     version: u32,
     tracker: Tracker<Self>,
@@ -930,7 +1082,7 @@ impl WlSeat {
     fn send_capabilities(self: &Rc<Self>) {
         self.client.event(Capabilities {
             self_id: self.id,
-            capabilities: POINTER | KEYBOARD,
+            capabilities: POINTER | KEYBOARD | TOUCH,
         })
     }
 
@@ -960,7 +1112,13 @@ impl WlSeat {
         track!(self.client, p);
         self.client.add_client_obj(&p)?;
         self.keyboards.set(req.id, p.clone());
-        let keymap = self.global.kb_map.get();
+        let keymap = self
+            .global
+            .effective_kb_state
+            .get()
+            .borrow()
+            .keymap()
+            .clone();
         p.send_keymap(
             wl_keyboard::XKB_V1,
             self.keymap_fd(&keymap)?,
@@ -977,21 +1135,7 @@ impl WlSeat {
         if self.version >= READ_ONLY_KEYMAP_SINCE {
             return Ok(keymap.map.clone());
         }
-        let fd = match uapi::memfd_create("shared-keymap", c::MFD_CLOEXEC) {
-            Ok(fd) => fd,
-            Err(e) => return Err(WlKeyboardError::KeymapMemfd(e.into())),
-        };
-        let target = keymap.map_len as c::off_t;
-        let mut pos = 0;
-        while pos < target {
-            let rem = target - pos;
-            let res = uapi::sendfile(fd.raw(), keymap.map.raw(), Some(&mut pos), rem as usize);
-            match res {
-                Ok(_) | Err(Errno(c::EINTR)) => {}
-                Err(e) => return Err(WlKeyboardError::KeymapCopy(e.into())),
-            }
-        }
-        Ok(Rc::new(fd))
+        copy_keymap_fd(keymap)
     }
 
     fn get_touch(self: &Rc<Self>, parser: MsgParser<'_, '_>) -> Result<(), WlSeatError> {
@@ -999,6 +1143,7 @@ impl WlSeat {
         let p = Rc::new(WlTouch::new(req.id, self));
         track!(self.client, p);
         self.client.add_client_obj(&p)?;
+        self.touches.set(req.id, p);
         Ok(())
     }
 
@@ -1048,6 +1193,10 @@ impl Object for WlSeat {
         }
         self.pointers.clear();
         self.relative_pointers.clear();
+        self.touches.clear();
+        self.swipe_gestures.clear();
+        self.pinch_gestures.clear();
+        self.hold_gestures.clear();
         self.keyboards.clear();
     }
 }
@@ -1069,6 +1218,25 @@ efrom!(WlSeatError, ClientError);
 efrom!(WlSeatError, MsgParserError);
 efrom!(WlSeatError, WlKeyboardError);
 
+/// Copies the keymap into a new memfd that the client may modify.
+pub fn copy_keymap_fd(keymap: &XkbKeymap) -> Result<Rc<OwnedFd>, WlKeyboardError> {
+    let fd = match uapi::memfd_create("shared-keymap", c::MFD_CLOEXEC) {
+        Ok(fd) => fd,
+        Err(e) => return Err(WlKeyboardError::KeymapMemfd(e.into())),
+    };
+    let target = keymap.map_len as c::off_t;
+    let mut pos = 0;
+    while pos < target {
+        let rem = target - pos;
+        let res = uapi::sendfile(fd.raw(), keymap.map.raw(), Some(&mut pos), rem as usize);
+        match res {
+            Ok(_) | Err(Errno(c::EINTR)) => {}
+            Err(e) => return Err(WlKeyboardError::KeymapCopy(e.into())),
+        }
+    }
+    Ok(Rc::new(fd))
+}
+
 pub fn collect_kb_foci2(node: Rc<dyn Node>, seats: &mut SmallVec<[Rc<WlSeatGlobal>; 3]>) {
     node.node_visit(&mut generic_node_visitor(|node| {
         node.node_seat_state().for_each_kb_focus(|s| seats.push(s));
@@ -19,33 +19,54 @@ use {
                     AXIS_VALUE120_SINCE_VERSION, POINTER_FRAME_SINCE_VERSION, WHEEL_TILT,
                     WHEEL_TILT_SINCE_VERSION,
                 },
+                wl_touch::WlTouch,
                 zwp_pointer_constraints_v1::{ConstraintType, SeatConstraintStatus},
+                zwp_pointer_gesture_hold_v1::ZwpPointerGestureHoldV1,
+                zwp_pointer_gesture_pinch_v1::ZwpPointerGesturePinchV1,
+                zwp_pointer_gesture_swipe_v1::ZwpPointerGestureSwipeV1,
                 zwp_relative_pointer_v1::ZwpRelativePointerV1,
-                Dnd, SeatId, WlSeat, WlSeatGlobal, CHANGE_CURSOR_MOVED,
+                Dnd, SeatId, WlSeat, WlSeatGlobal, BTN_LEFT, CHANGE_CURSOR_MOVED, CHANGE_TREE,
+                DEFAULT_SHORTCUTS_INHIBIT_ESCAPE, PX_PER_SCROLL,
             },
             wl_surface::{xdg_surface::xdg_popup::XdgPopup, WlSurface},
         },
-        state::DeviceHandlerData,
-        tree::{Direction, FloatNode, Node, ToplevelNode},
-        utils::{bitflags::BitflagsExt, clonecell::CloneCell, smallmap::SmallMap},
+        state::{DeviceHandlerData, InputMapping},
+        tree::{Direction, FloatNode, FoundNode, Node, NodeId, ToplevelNode},
+        utils::{bitflags::BitflagsExt, clonecell::CloneCell, rc_eq::rc_eq, smallmap::SmallMap},
         wire::WlDataOfferId,
-        xkbcommon::{ModifierState, XKB_KEY_DOWN, XKB_KEY_UP},
+        xkbcommon::{ModifierState, XkbState, XKB_KEY_DOWN, XKB_KEY_UP},
     },
-    jay_config::keyboard::{
-        mods::{Modifiers, CAPS, NUM},
-        syms::KeySym,
-        ModifiedKeySym,
+    ahash::AHashMap,
+    jay_config::{
+        keyboard::{
+            mods::{Modifiers, CAPS, NUM},
+            syms::KeySym,
+            ModifiedKeySym,
+        },
+        Direction as JayDirection,
     },
     smallvec::SmallVec,
-    std::rc::Rc,
+    std::{cell::RefCell, rc::Rc},
 };
 
+/// The minimum distance a swipe bound in the config has to travel to trigger the binding.
+const SWIPE_BINDING_THRESHOLD: f64 = 100.0;
+
+/// A swipe that is consumed by the compositor because the config has bound its finger count.
+#[derive(Copy, Clone, Debug)]
+pub struct BoundSwipe {
+    fingers: u32,
+    dx: f64,
+    dy: f64,
+}
+
 #[derive(Default)]
 pub struct NodeSeatState {
     pointer_foci: SmallMap<SeatId, Rc<WlSeatGlobal>, 1>,
     kb_foci: SmallMap<SeatId, Rc<WlSeatGlobal>, 1>,
     pointer_grabs: SmallMap<SeatId, Rc<WlSeatGlobal>, 1>,
     dnd_targets: SmallMap<SeatId, Rc<WlSeatGlobal>, 1>,
+    touch_foci: SmallMap<SeatId, Rc<WlSeatGlobal>, 1>,
 }
 
 impl NodeSeatState {
@@ -75,6 +96,14 @@ impl NodeSeatState {
         self.pointer_grabs.remove(&seat.id);
     }
 
+    pub(super) fn add_touch_focus(&self, seat: &Rc<WlSeatGlobal>) {
+        self.touch_foci.insert(seat.id, seat.clone());
+    }
+
+    pub(super) fn remove_touch_focus(&self, seat: &WlSeatGlobal) {
+        self.touch_foci.remove(&seat.id);
+    }
+
     pub(super) fn add_dnd_target(&self, seat: &Rc<WlSeatGlobal>) {
         self.dnd_targets.insert(seat.id, seat.clone());
     }
@@ -101,6 +130,7 @@ impl NodeSeatState {
         self.release_kb_grab();
         while let Some((_, seat)) = self.kb_foci.pop() {
             seat.keyboard_node.set(seat.state.root.clone());
+            seat.tablet_pad_focus_changed();
             // log::info!("keyboard_node = root");
             if focus_last {
                 seat.output
@@ -144,6 +174,9 @@ impl NodeSeatState {
             seat.pointer_stack_modified.set(true);
             seat.state.tree_changed();
         }
+        while let Some((_, seat)) = self.touch_foci.pop() {
+            seat.touch_surface_removed(node_id);
+        }
         self.release_kb_focus2(focus_last);
     }
 
@@ -161,6 +194,7 @@ impl NodeSeatState {
         self.pointer_foci.remove(&seat.id);
         self.dnd_targets.remove(&seat.id);
         self.pointer_grabs.remove(&seat.id);
+        self.touch_foci.remove(&seat.id);
     }
 
     pub fn clear(&self) {
@@ -168,11 +202,27 @@ impl NodeSeatState {
         self.pointer_foci.clear();
         self.dnd_targets.clear();
         self.pointer_grabs.clear();
+        self.touch_foci.clear();
     }
 }
 
 impl WlSeatGlobal {
     pub fn event(self: &Rc<Self>, dev: &DeviceHandlerData, event: InputEvent) {
+        self.event_(Some(dev), event);
+    }
+
+    /// Handles an event of a virtual input device created by a client.
+    pub fn virtual_event(self: &Rc<Self>, event: InputEvent) {
+        self.event_(None, event);
+        self.mark_last_active();
+        self.state.input_occurred();
+    }
+
+    fn event_(self: &Rc<Self>, dev: Option<&DeviceHandlerData>, event: InputEvent) {
+        let px_per_scroll_wheel = match dev {
+            Some(dev) => dev.px_per_scroll_wheel.get(),
+            _ => PX_PER_SCROLL,
+        };
         match event {
             InputEvent::Key {
                 time_usec,
@@ -185,6 +235,11 @@ impl WlSeatGlobal {
                 x,
                 y,
             } => self.connector_position_event(time_usec, connector, x, y),
+            InputEvent::AbsoluteMotion {
+                time_usec,
+                x_normed,
+                y_normed,
+            } => self.absolute_motion_event(dev, time_usec, x_normed, y_normed),
             InputEvent::Motion {
                 dx,
                 dy,
@@ -202,7 +257,452 @@ impl WlSeatGlobal {
             InputEvent::Axis120 { dist, axis } => self.pointer_owner.axis_120(dist, axis),
             InputEvent::AxisPx { dist, axis } => self.pointer_owner.axis_px(dist, axis),
             InputEvent::AxisStop { axis } => self.pointer_owner.axis_stop(axis),
-            InputEvent::AxisFrame { time_usec } => self.pointer_owner.frame(dev, self, time_usec),
+            InputEvent::AxisFrame { time_usec } => {
+                self.pointer_owner
+                    .frame(px_per_scroll_wheel, self, time_usec)
+            }
+            InputEvent::SwipeBegin {
+                time_usec,
+                finger_count,
+            } => self.swipe_begin(time_usec, finger_count),
+            InputEvent::SwipeUpdate {
+                time_usec,
+                dx,
+                dy,
+                dx_unaccelerated,
+                dy_unaccelerated,
+            } => self.swipe_update(time_usec, dx, dy, dx_unaccelerated, dy_unaccelerated),
+            InputEvent::SwipeEnd {
+                time_usec,
+                cancelled,
+            } => self.swipe_end(time_usec, cancelled),
+            InputEvent::PinchBegin {
+                time_usec,
+                finger_count,
+            } => self.pinch_begin(time_usec, finger_count),
+            InputEvent::PinchUpdate {
+                time_usec,
+                dx,
+                dy,
+                scale,
+                rotation,
+            } => self.pinch_update(time_usec, dx, dy, scale, rotation),
+            InputEvent::PinchEnd {
+                time_usec,
+                cancelled,
+            } => self.pinch_end(time_usec, cancelled),
+            InputEvent::HoldBegin {
+                time_usec,
+                finger_count,
+            } => self.hold_begin(time_usec, finger_count),
+            InputEvent::HoldEnd {
+                time_usec,
+                cancelled,
+            } => self.hold_end(time_usec, cancelled),
+            InputEvent::TouchDown {
+                time_usec,
+                id,
+                x_normed,
+                y_normed,
+            } => {
+                let (x, y) = self.mapped_position(dev, false, x_normed, y_normed);
+                self.touch_down(time_usec, id, x, y)
+            }
+            InputEvent::TouchMotion {
+                time_usec,
+                id,
+                x_normed,
+                y_normed,
+            } => {
+                let (x, y) = self.mapped_position(dev, false, x_normed, y_normed);
+                self.touch_motion(time_usec, id, x, y)
+            }
+            InputEvent::TouchUp { time_usec, id } => self.touch_up(time_usec, id),
+            InputEvent::TouchFrame => self.touch_frame(),
+            InputEvent::TouchCancel { time_usec } => self.touch_cancel(time_usec),
+            InputEvent::TabletToolProximityIn { tool } => {
+                if let Some(dev) = dev {
+                    self.tablet_tool_proximity_in(dev, tool)
+                }
+            }
+            InputEvent::TabletToolProximityOut { time_usec, tool } => {
+                self.tablet_tool_proximity_out_(time_usec, tool)
+            }
+            InputEvent::TabletToolAxes {
+                time_usec,
+                tool,
+                changes,
+            } => {
+                if let Some(dev) = dev {
+                    self.tablet_tool_axes(dev, time_usec, tool, &changes)
+                }
+            }
+            InputEvent::TabletToolTip {
+                time_usec,
+                tool,
+                down,
+            } => self.tablet_tool_tip(time_usec, tool, down),
+            InputEvent::TabletToolButton {
+                time_usec,
+                tool,
+                button,
+                state,
+            } => self.tablet_tool_button(time_usec, tool, button, state),
+            InputEvent::TabletPadButton {
+                time_usec,
+                button,
+                state,
+            } => {
+                if let Some(dev) = dev {
+                    self.tablet_pad_button(dev, time_usec, button, state)
+                }
+            }
+            InputEvent::TabletPadModeSwitch {
+                time_usec,
+                group,
+                mode,
+            } => {
+                if let Some(dev) = dev {
+                    self.tablet_pad_mode_switch(dev, time_usec, group, mode)
+                }
+            }
+            InputEvent::TabletPadRing {
+                time_usec,
+                ring,
+                finger,
+                degrees,
+            } => {
+                if let Some(dev) = dev {
+                    self.tablet_pad_ring(dev, time_usec, ring, finger, degrees)
+                }
+            }
+            InputEvent::TabletPadStrip {
+                time_usec,
+                strip,
+                finger,
+                position,
+            } => {
+                if let Some(dev) = dev {
+                    self.tablet_pad_strip(dev, time_usec, strip, finger, position)
+                }
+            }
+            InputEvent::Switch { .. } => {
+                // handled by the device handler
+            }
+        }
+    }
+
+    /// Maps normalized device coordinates to the area that the device is mapped to.
+    ///
+    /// If the device uses the default mapping, `default_to_layout` determines whether the
+    /// whole layout or the output containing the cursor is used.
+    ///
+    /// If the area is an output, the device coordinates are relative to the untransformed
+    /// output and the inverse of the output transform is applied.
+    pub(super) fn mapped_position(
+        &self,
+        dev: Option<&DeviceHandlerData>,
+        default_to_layout: bool,
+        mut x_normed: f64,
+        mut y_normed: f64,
+    ) -> (Fixed, Fixed) {
+        let mapping = dev
+            .map(|d| d.mapping.get())
+            .unwrap_or(InputMapping::Default);
+        let output = match mapping {
+            InputMapping::Connector(c) => self.state.outputs.get(&c),
+            _ => None,
+        };
+        let global = match (output, mapping) {
+            (Some(output), _) => Some(output.node.global.clone()),
+            (_, InputMapping::Layout) => None,
+            _ if default_to_layout => None,
+            _ => Some(self.output.get().global.clone()),
+        };
+        let pos = match &global {
+            Some(global) => {
+                let transform = global.transform.get().inverse();
+                (x_normed, y_normed) = transform.apply_normed(x_normed, y_normed);
+                global.pos.get()
+            }
+            _ => self.state.root.extents.get(),
+        };
+        let x = pos.x1() as f64 + x_normed * pos.width() as f64;
+        let y = pos.y1() as f64 + y_normed * pos.height() as f64;
+        (Fixed::from_f64(x), Fixed::from_f64(y))
+    }
+
+    fn touch_down(self: &Rc<Self>, time_usec: u64, id: i32, x: Fixed, y: Fixed) {
+        let (x_int, y_int) = (x.round_down(), y.round_down());
+        let mut tree = vec![FoundNode {
+            node: self.state.root.clone(),
+            x: x_int,
+            y: y_int,
+        }];
+        self.state.root.node_find_tree_at(x_int, y_int, &mut tree);
+        let found = tree.pop().unwrap();
+        if let Some(surface) = found.node.node_into_surface() {
+            let (x, y) = (x.apply_fract(found.x), y.apply_fract(found.y));
+            let serial = self.state.next_serial(Some(&surface.client));
+            self.surface_touch_event(&surface, |t| {
+                t.send_down(serial, time_usec, &surface, id, x, y)
+            });
+            self.touch_frame.set(surface.client.id, surface.clone());
+            if let Some(node) = surface.get_focus_node(self.id) {
+                self.focus_node(node);
+            }
+            surface.node_seat_state().add_touch_focus(self);
+            self.touch_points.set(id, surface);
+        } else if self.touch_emulated_pointer.get().is_none() {
+            // Jay's own UI such as title bars only understands pointer input.
+            self.touch_emulated_pointer.set(Some(id));
+            self.set_new_position(time_usec, x, y);
+            self.button_event(time_usec, BTN_LEFT, KeyState::Pressed);
+        }
+    }
+
+    fn touch_motion(self: &Rc<Self>, time_usec: u64, id: i32, x: Fixed, y: Fixed) {
+        if self.touch_emulated_pointer.get() == Some(id) {
+            self.set_new_position(time_usec, x, y);
+            return;
+        }
+        if let Some(surface) = self.touch_points.get(&id) {
+            let pos = surface.buffer_abs_pos.get();
+            let (x, y) = (x - pos.x1(), y - pos.y1());
+            self.surface_touch_event(&surface, |t| t.send_motion(time_usec, id, x, y));
+            self.touch_frame.set(surface.client.id, surface);
+        }
+    }
+
+    fn touch_up(self: &Rc<Self>, time_usec: u64, id: i32) {
+        if self.touch_emulated_pointer.get() == Some(id) {
+            self.touch_emulated_pointer.take();
+            self.button_event(time_usec, BTN_LEFT, KeyState::Released);
+            return;
+        }
+        if let Some(surface) = self.touch_points.remove(&id) {
+            let serial = self.state.next_serial(Some(&surface.client));
+            self.surface_touch_event(&surface, |t| t.send_up(serial, time_usec, id));
+            let has_points = self
+                .touch_points
+                .lock()
+                .values()
+                .any(|s| rc_eq(s, &surface));
+            if !has_points {
+                surface.node_seat_state().remove_touch_focus(self);
+            }
+            self.touch_frame.set(surface.client.id, surface);
+        }
+    }
+
+    fn touch_frame(&self) {
+        for (_, surface) in self.touch_frame.lock().drain() {
+            self.surface_touch_event(&surface, |t| t.send_frame());
+        }
+    }
+
+    fn touch_cancel(self: &Rc<Self>, time_usec: u64) {
+        let mut clients = AHashMap::new();
+        for (_, surface) in self.touch_points.lock().drain() {
+            surface.node_seat_state().remove_touch_focus(self);
+            clients.insert(surface.client.id, surface);
+        }
+        self.touch_frame.clear();
+        for surface in clients.values() {
+            self.surface_touch_event(surface, |t| t.send_cancel());
+        }
+        if self.touch_emulated_pointer.take().is_some() {
+            self.button_event(time_usec, BTN_LEFT, KeyState::Released);
+        }
+    }
+
+    /// Cancels the touch points of the client whose surface has been destroyed or hidden.
+    ///
+    /// `wl_touch.cancel` applies to all touch points of the client.
+    fn touch_surface_removed(&self, node_id: NodeId) {
+        let client = match self
+            .touch_points
+            .lock()
+            .values()
+            .find(|s| s.node_id() == node_id)
+        {
+            Some(s) => s.client.clone(),
+            _ => return,
+        };
+        let mut surfaces = vec![];
+        self.touch_points.lock().retain(|_, s| {
+            if s.client.id != client.id {
+                return true;
+            }
+            surfaces.push(s.clone());
+            false
+        });
+        for surface in &surfaces {
+            surface.node_seat_state().remove_touch_focus(self);
+        }
+        self.touch_frame.remove(&client.id);
+        self.surface_touch_event(&surfaces[0], |t| t.send_cancel());
+    }
+
+    fn swipe_begin(self: &Rc<Self>, time_usec: u64, fingers: u32) {
+        let bound = self
+            .swipe_bindings
+            .lock()
+            .keys()
+            .any(|(f, _)| *f == fingers);
+        if bound {
+            self.bound_swipe.set(Some(BoundSwipe {
+                fingers,
+                dx: 0.0,
+                dy: 0.0,
+            }));
+            return;
+        }
+        self.gesture_begin(|surface, serial| {
+            self.surface_swipe_gesture_event(surface, |g| {
+                g.send_begin(serial, time_usec, surface, fingers)
+            });
+        });
+    }
+
+    fn swipe_update(
+        self: &Rc<Self>,
+        time_usec: u64,
+        dx: Fixed,
+        dy: Fixed,
+        dx_unaccelerated: Fixed,
+        dy_unaccelerated: Fixed,
+    ) {
+        if let Some(mut swipe) = self.bound_swipe.get() {
+            swipe.dx += dx_unaccelerated.to_f64();
+            swipe.dy += dy_unaccelerated.to_f64();
+            self.bound_swipe.set(Some(swipe));
+            return;
+        }
+        if let Some(surface) = self.gesture_surface.get() {
+            self.surface_swipe_gesture_event(&surface, |g| g.send_update(time_usec, dx, dy));
+        }
+    }
+
+    fn swipe_end(self: &Rc<Self>, time_usec: u64, cancelled: bool) {
+        if let Some(swipe) = self.bound_swipe.take() {
+            if !cancelled {
+                self.invoke_swipe_binding(swipe);
+            }
+            return;
+        }
+        self.gesture_end(|surface, serial| {
+            self.surface_swipe_gesture_event(surface, |g| g.send_end(serial, time_usec, cancelled));
+        });
+    }
+
+    fn invoke_swipe_binding(&self, swipe: BoundSwipe) {
+        let direction = if swipe.dx.abs() >= swipe.dy.abs() {
+            if swipe.dx.abs() < SWIPE_BINDING_THRESHOLD {
+                return;
+            }
+            match swipe.dx < 0.0 {
+                true => JayDirection::Left,
+                false => JayDirection::Right,
+            }
+        } else {
+            if swipe.dy.abs() < SWIPE_BINDING_THRESHOLD {
+                return;
+            }
+            match swipe.dy < 0.0 {
+                true => JayDirection::Up,
+                false => JayDirection::Down,
+            }
+        };
+        if self
+            .swipe_bindings
+            .get(&(swipe.fingers, direction))
+            .is_none()
+        {
+            return;
+        }
+        if let Some(config) = self.state.config.get() {
+            config.invoke_swipe(self.id, swipe.fingers, direction);
+        }
+    }
+
+    fn pinch_begin(self: &Rc<Self>, time_usec: u64, fingers: u32) {
+        self.gesture_begin(|surface, serial| {
+            self.surface_pinch_gesture_event(surface, |g| {
+                g.send_begin(serial, time_usec, surface, fingers)
+            });
+        });
+    }
+
+    fn pinch_update(
+        self: &Rc<Self>,
+        time_usec: u64,
+        dx: Fixed,
+        dy: Fixed,
+        scale: Fixed,
+        rotation: Fixed,
+    ) {
+        if let Some(surface) = self.gesture_surface.get() {
+            self.surface_pinch_gesture_event(&surface, |g| {
+                g.send_update(time_usec, dx, dy, scale, rotation)
+            });
+        }
+    }
+
+    fn pinch_end(self: &Rc<Self>, time_usec: u64, cancelled: bool) {
+        self.gesture_end(|surface, serial| {
+            self.surface_pinch_gesture_event(surface, |g| g.send_end(serial, time_usec, cancelled));
+        });
+    }
+
+    fn hold_begin(self: &Rc<Self>, time_usec: u64, fingers: u32) {
+        self.gesture_begin(|surface, serial| {
+            self.surface_hold_gesture_event(surface, |g| {
+                g.send_begin(serial, time_usec, surface, fingers)
+            });
+        });
+    }
+
+    fn hold_end(self: &Rc<Self>, time_usec: u64, cancelled: bool) {
+        self.gesture_end(|surface, serial| {
+            self.surface_hold_gesture_event(surface, |g| g.send_end(serial, time_usec, cancelled));
+        });
+    }
+
+    fn gesture_begin<F: FnOnce(&Rc<WlSurface>, u32)>(self: &Rc<Self>, f: F) {
+        let surface = self.pointer_owner.gesture_surface(self);
+        if let Some(surface) = &surface {
+            let serial = self.state.next_serial(Some(&surface.client));
+            f(surface, serial);
+        }
+        self.gesture_surface.set(surface);
+    }
+
+    fn gesture_end<F: FnOnce(&Rc<WlSurface>, u32)>(self: &Rc<Self>, f: F) {
+        if let Some(surface) = self.gesture_surface.take() {
+            let serial = self.state.next_serial(Some(&surface.client));
+            f(&surface, serial);
+        }
+    }
+
+    fn absolute_motion_event(
+        self: &Rc<Self>,
+        dev: Option<&DeviceHandlerData>,
+        time_usec: u64,
+        x_normed: f64,
+        y_normed: f64,
+    ) {
+        let (x, y) = self.mapped_position(dev, true, x_normed, y_normed);
+        let (x_int, y_int) = (x.round_down(), y.round_down());
+        let output = self
+            .state
+            .outputs
+            .lock()
+            .iter()
+            .find(|(_, o)| o.node.global.pos.get().contains(x_int, y_int))
+            .map(|(c, o)| (*c, o.node.global.pos.get()));
+        if let Some((connector, pos)) = output {
+            self.connector_position_event(time_usec, connector, x - pos.x1(), y - pos.y1());
         }
     }
 
@@ -310,7 +810,7 @@ impl WlSeatGlobal {
         self.set_new_position(time_usec, x, y);
     }
 
-    fn button_event(self: &Rc<Self>, time_usec: u64, button: u32, state: KeyState) {
+    pub(super) fn button_event(self: &Rc<Self>, time_usec: u64, button: u32, state: KeyState) {
         self.state.for_each_seat_tester(|t| {
             t.send_button(self.id, time_usec, button, state);
         });
@@ -318,6 +818,36 @@ impl WlSeatGlobal {
     }
 
     fn key_event(&self, time_usec: u64, key: u32, key_state: KeyState) {
+        let kb_state = self.kb_state.clone();
+        self.key_event_with_state(time_usec, key, key_state, &kb_state, None);
+    }
+
+    /// Makes `kb_state` the keyboard state whose keymap and modifiers are sent to
+    /// clients.
+    ///
+    /// Returns whether the effective keyboard state changed.
+    fn set_effective_kb_state(&self, kb_state: &Rc<RefCell<XkbState>>) -> bool {
+        if rc_eq(&self.effective_kb_state.get(), kb_state) {
+            return false;
+        }
+        self.effective_kb_state.set(kb_state.clone());
+        let keymap = kb_state.borrow().keymap().clone();
+        self.send_keymap(&keymap);
+        true
+    }
+
+    /// Handles a key event of a keyboard whose keymap and modifiers are described by
+    /// `kb_state`.
+    ///
+    /// `client` is the client that created the keyboard if it is a virtual keyboard.
+    pub fn key_event_with_state(
+        &self,
+        time_usec: u64,
+        key: u32,
+        key_state: KeyState,
+        kb_state: &Rc<RefCell<XkbState>>,
+        client: Option<ClientId>,
+    ) {
         let (state, xkb_dir) = {
             let mut pk = self.pressed_keys.borrow_mut();
             match key_state {
@@ -335,15 +865,23 @@ impl WlSeatGlobal {
                 }
             }
         };
+        let kb_state_changed = self.set_effective_kb_state(kb_state);
         let mut shortcuts = SmallVec::<[_; 1]>::new();
-        let new_mods;
+        let mut escaped = false;
+        let mut new_mods;
         {
-            let mut kb_state = self.kb_state.borrow_mut();
+            let mut kb_state = kb_state.borrow_mut();
             if !self.state.lock.locked.get() && state == wl_keyboard::PRESSED {
                 let old_mods = kb_state.mods();
                 let keysyms = kb_state.unmodified_keysyms(key);
+                let inhibited = self.shortcuts_inhibitor.get().is_some();
+                let escape = self.shortcuts_inhibit_escape.get();
                 for &sym in keysyms {
                     let mods = old_mods.mods_effective & !(CAPS.0 | NUM.0);
+                    if inhibited {
+                        escaped |= (mods, sym) == (escape.mods.0, escape.sym.0);
+                        continue;
+                    }
                     if let Some(mods) = self.shortcuts.get(&(mods, sym)) {
                         shortcuts.push(ModifiedKeySym {
                             mods,
@@ -353,13 +891,29 @@ impl WlSeatGlobal {
                 }
             }
             new_mods = kb_state.update(key, xkb_dir);
+            if new_mods.is_none() && kb_state_changed {
+                new_mods = Some(kb_state.mods());
+            }
         }
         self.state.for_each_seat_tester(|t| {
             t.send_key(self.id, time_usec, key, key_state);
         });
         let node = self.keyboard_node.get();
-        if shortcuts.is_empty() {
-            node.node_on_key(self, time_usec, key, state);
+        let im_grab = self
+            .active_input_method_grab()
+            .filter(|grab| Some(grab.client.id) != client);
+        if escaped {
+            if let Some(inhibitor) = self.shortcuts_inhibitor.get() {
+                inhibitor.deactivate();
+            }
+        } else if shortcuts.is_empty() {
+            let grabbed = match &im_grab {
+                Some(grab) => grab.on_key(time_usec, key, state),
+                _ => false,
+            };
+            if !grabbed {
+                node.node_on_key(self, time_usec, key, state);
+            }
         } else if let Some(config) = self.state.config.get() {
             for shortcut in shortcuts {
                 config.invoke_shortcut(self.id(), &shortcut);
@@ -369,9 +923,38 @@ impl WlSeatGlobal {
             self.state.for_each_seat_tester(|t| {
                 t.send_modifiers(self.id, &mods);
             });
+            if let Some(grab) = &im_grab {
+                grab.send_modifiers(&mods);
+            }
             node.node_on_mods(self, mods);
         }
     }
+
+    /// Handles a modifier change of a virtual keyboard.
+    pub fn mods_event_with_state(&self, kb_state: &Rc<RefCell<XkbState>>, client: ClientId) {
+        self.set_effective_kb_state(kb_state);
+        let mods = kb_state.borrow().mods();
+        self.state.for_each_seat_tester(|t| {
+            t.send_modifiers(self.id, &mods);
+        });
+        if let Some(grab) = self.active_input_method_grab() {
+            if grab.client.id != client {
+                grab.send_modifiers(&mods);
+            }
+        }
+        self.keyboard_node.get().node_on_mods(self, mods);
+    }
+
+    /// Makes the state of the physical keyboards effective again if `kb_state` is
+    /// effective.
+    pub fn remove_kb_state(&self, kb_state: &Rc<RefCell<XkbState>>) {
+        if rc_eq(&self.effective_kb_state.get(), kb_state) {
+            let physical = self.kb_state.clone();
+            self.set_effective_kb_state(&physical);
+            let mods = physical.borrow().mods();
+            self.keyboard_node.get().node_on_mods(self, mods);
+        }
+    }
 }
 
 impl WlSeatGlobal {
@@ -394,6 +977,18 @@ impl WlSeatGlobal {
         self.focus_node(node);
     }
 
+    pub fn activate_toplevel(self: &Rc<Self>, tl: Rc<dyn ToplevelNode>) {
+        if let Some(ws) = tl.tl_data().workspace.get() {
+            let output = ws.output.get();
+            if output.show_workspace(&ws) {
+                ws.flush_jay_workspaces();
+                output.schedule_update_render_data();
+                self.state.tree_changed();
+            }
+        }
+        self.focus_toplevel(tl);
+    }
+
     fn ungrab_kb(self: &Rc<Self>) {
         self.kb_owner.ungrab(self);
     }
@@ -522,6 +1117,50 @@ impl WlSeatGlobal {
         });
     }
 
+    fn surface_touch_event<F>(&self, surface: &WlSurface, mut f: F)
+    where
+        F: FnMut(&Rc<WlTouch>),
+    {
+        self.for_each_seat(0, surface.client.id, |seat| {
+            for touch in seat.touches.lock().values() {
+                f(touch);
+            }
+        })
+    }
+
+    fn surface_swipe_gesture_event<F>(&self, surface: &WlSurface, mut f: F)
+    where
+        F: FnMut(&Rc<ZwpPointerGestureSwipeV1>),
+    {
+        self.for_each_seat(0, surface.client.id, |seat| {
+            for gesture in seat.swipe_gestures.lock().values() {
+                f(gesture);
+            }
+        })
+    }
+
+    fn surface_pinch_gesture_event<F>(&self, surface: &WlSurface, mut f: F)
+    where
+        F: FnMut(&Rc<ZwpPointerGesturePinchV1>),
+    {
+        self.for_each_seat(0, surface.client.id, |seat| {
+            for gesture in seat.pinch_gestures.lock().values() {
+                f(gesture);
+            }
+        })
+    }
+
+    fn surface_hold_gesture_event<F>(&self, surface: &WlSurface, mut f: F)
+    where
+        F: FnMut(&Rc<ZwpPointerGestureHoldV1>),
+    {
+        self.for_each_seat(0, surface.client.id, |seat| {
+            for gesture in seat.hold_gestures.lock().values() {
+                f(gesture);
+            }
+        })
+    }
+
     fn surface_kb_event<F>(&self, ver: u32, surface: &WlSurface, mut f: F)
     where
         F: FnMut(&Rc<WlKeyboard>),
@@ -533,7 +1172,7 @@ impl WlSeatGlobal {
         // client.flush();
     }
 
-    fn set_new_position(self: &Rc<Self>, time_usec: u64, x: Fixed, y: Fixed) {
+    pub(super) fn set_new_position(self: &Rc<Self>, time_usec: u64, x: Fixed, y: Fixed) {
         self.pos_time_usec.set(time_usec);
         self.pos.set((x, y));
         self.update_hardware_cursor_position();
@@ -543,6 +1182,9 @@ impl WlSeatGlobal {
 
     pub fn clear_shortcuts(&self) {
         self.shortcuts.clear();
+        self.swipe_bindings.clear();
+        self.shortcuts_inhibit_escape
+            .set(DEFAULT_SHORTCUTS_INHIBIT_ESCAPE);
     }
 
     pub fn add_shortcut(&self, mods: Modifiers, keysym: KeySym) {
@@ -553,13 +1195,32 @@ impl WlSeatGlobal {
         self.shortcuts.remove(&(mods.0, keysym.0));
     }
 
+    pub fn add_swipe_binding(&self, fingers: u32, direction: JayDirection) {
+        self.swipe_bindings.set((fingers, direction), ());
+    }
+
+    pub fn remove_swipe_binding(&self, fingers: u32, direction: JayDirection) {
+        self.swipe_bindings.remove(&(fingers, direction));
+    }
+
+    /// Sets the shortcut that deactivates an active shortcuts inhibitor.
+    ///
+    /// This shortcut is processed even while shortcuts are inhibited.
+    pub fn set_shortcuts_inhibit_escape(&self, mods: Modifiers, keysym: KeySym) {
+        self.shortcuts_inhibit_escape
+            .set(ModifiedKeySym { mods, sym: keysym });
+    }
+
     pub fn trigger_tree_changed(&self) {
         // log::info!("trigger_tree_changed");
         self.tree_changed.trigger();
     }
 
     pub(super) fn apply_changes(self: &Rc<Self>) {
-        self.state.damage();
+        self.damage_cursor();
+        if self.changes.get().contains(CHANGE_TREE) || self.dnd_icon().is_some() {
+            self.state.damage();
+        }
         self.pointer_owner.apply_changes(self);
         self.changes.set(0);
     }
@@ -702,13 +1363,17 @@ impl WlSeatGlobal {
 impl WlSeatGlobal {
     pub fn unfocus_surface(&self, surface: &WlSurface) {
         let serial = surface.client.next_serial();
-        self.surface_kb_event(0, surface, |k| k.send_leave(serial, surface.id))
+        self.surface_kb_event(0, surface, |k| k.send_leave(serial, surface.id));
+        self.text_input_unfocus_surface(surface);
+        if let Some(inhibitor) = surface.shortcuts_inhibitors.get(&self.id) {
+            inhibitor.deactivate();
+        }
     }
 }
 
 // Focus callbacks
 impl WlSeatGlobal {
-    pub fn focus_surface(&self, surface: &WlSurface) {
+    pub fn focus_surface(&self, surface: &Rc<WlSurface>) {
         let pressed_keys: Vec<_> = self.pressed_keys.borrow().iter().copied().collect();
         let serial = surface.client.next_serial();
         self.surface_kb_event(0, surface, |k| {
@@ -720,7 +1385,7 @@ impl WlSeatGlobal {
             mods_locked,
             group,
             ..
-        } = self.kb_state.borrow().mods();
+        } = self.effective_kb_state.get().borrow().mods();
         let serial = surface.client.next_serial();
         self.surface_kb_event(0, surface, |k| {
             k.send_modifiers(serial, mods_depressed, mods_latched, mods_locked, group)
@@ -730,6 +1395,11 @@ impl WlSeatGlobal {
             self.offer_selection::<ClipboardIpc>(&self.selection, &surface.client);
             self.offer_selection::<PrimarySelectionIpc>(&self.primary_selection, &surface.client);
         }
+
+        self.text_input_focus_surface(surface);
+        if let Some(inhibitor) = surface.shortcuts_inhibitors.get(&self.id) {
+            inhibitor.activate();
+        }
     }
 }
 
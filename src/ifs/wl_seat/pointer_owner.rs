@@ -8,7 +8,6 @@ use {
             wl_seat::{wl_pointer::PendingScroll, Dnd, DroppedDnd, WlSeatError, WlSeatGlobal},
             wl_surface::WlSurface,
         },
-        state::DeviceHandlerData,
         tree::{FoundNode, Node},
         utils::{clonecell::CloneCell, smallmap::SmallMap},
     },
@@ -52,12 +51,12 @@ impl PointerOwnerHolder {
         self.pending_scroll.stop[axis as usize].set(true);
     }
 
-    pub fn frame(&self, dev: &DeviceHandlerData, seat: &Rc<WlSeatGlobal>, time_usec: u64) {
+    pub fn frame(&self, px_per_scroll_wheel: f64, seat: &Rc<WlSeatGlobal>, time_usec: u64) {
         self.pending_scroll.time_usec.set(time_usec);
         let pending = self.pending_scroll.take();
         for axis in 0..2 {
             if let Some(dist) = pending.v120[axis].get() {
-                let px = (dist as f64 / AXIS_120 as f64) * dev.px_per_scroll_wheel.get();
+                let px = (dist as f64 / AXIS_120 as f64) * px_per_scroll_wheel;
                 pending.px[axis].set(Some(Fixed::from_f64(px)));
             }
         }
@@ -90,6 +89,10 @@ impl PointerOwnerHolder {
         }
     }
 
+    pub fn gesture_surface(&self, seat: &Rc<WlSeatGlobal>) -> Option<Rc<WlSurface>> {
+        self.owner.get().axis_node(seat)?.node_into_surface()
+    }
+
     pub fn apply_changes(&self, seat: &Rc<WlSeatGlobal>) {
         self.owner.get().apply_changes(seat)
     }
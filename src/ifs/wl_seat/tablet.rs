@@ -0,0 +1,626 @@
+pub mod zwp_tablet_manager_v2;
+pub mod zwp_tablet_pad_group_v2;
+pub mod zwp_tablet_pad_ring_v2;
+pub mod zwp_tablet_pad_strip_v2;
+pub mod zwp_tablet_pad_v2;
+pub mod zwp_tablet_seat_v2;
+pub mod zwp_tablet_tool_v2;
+pub mod zwp_tablet_v2;
+
+use {
+    crate::{
+        backend::{
+            InputDeviceCapability, InputDeviceId, KeyState, TabletPadGroupInit, TabletPadInit,
+            TabletToolChanges, TabletToolId, TabletToolInit,
+        },
+        client::{ClientError, ClientId},
+        fixed::Fixed,
+        ifs::{
+            wl_seat::{
+                tablet::{
+                    zwp_tablet_pad_v2::ZwpTabletPadV2, zwp_tablet_seat_v2::ZwpTabletSeatV2,
+                    zwp_tablet_tool_v2::ZwpTabletToolV2, zwp_tablet_v2::ZwpTabletV2,
+                },
+                WlSeatGlobal, BTN_LEFT,
+            },
+            wl_surface::WlSurface,
+        },
+        state::DeviceHandlerData,
+        time::now_usec,
+        tree::{FoundNode, Node},
+        utils::{clonecell::CloneCell, copyhashmap::CopyHashMap, rc_eq::rc_eq},
+        wire::ZwpTabletSeatV2Id,
+    },
+    std::{cell::Cell, rc::Rc},
+};
+
+type TabletSeatKey = (ClientId, ZwpTabletSeatV2Id);
+
+pub struct Tablet {
+    pub id: InputDeviceId,
+    pub name: Rc<String>,
+    /// The device group of the tablet. See [`InputDevice::device_group`].
+    ///
+    /// [`InputDevice::device_group`]: crate::backend::InputDevice::device_group
+    group: Option<usize>,
+    pub bindings: CopyHashMap<TabletSeatKey, Rc<ZwpTabletV2>>,
+}
+
+pub struct TabletTool {
+    pub init: TabletToolInit,
+    pub bindings: CopyHashMap<TabletSeatKey, Rc<ZwpTabletToolV2>>,
+    /// The tablet that the tool is currently in proximity of.
+    tablet: CloneCell<Option<Rc<Tablet>>>,
+    /// The surface that receives tablet events. If this is `None` while the tool is in
+    /// proximity, the tool emulates a pointer.
+    surface: CloneCell<Option<Rc<WlSurface>>>,
+    down: Cell<bool>,
+}
+
+pub struct TabletPad {
+    /// The layout of the pad. This always contains at least one group.
+    pub init: TabletPadInit,
+    group: Option<usize>,
+    /// The current mode of each group.
+    modes: Vec<Cell<u32>>,
+    /// The tablet that the pad belongs to.
+    tablet: CloneCell<Option<Rc<Tablet>>>,
+    pub bindings: CopyHashMap<TabletSeatKey, Rc<ZwpTabletPadV2>>,
+    /// The surface that has the keyboard focus. The pad has entered this surface if
+    /// `tablet` is `Some`.
+    surface: CloneCell<Option<Rc<WlSurface>>>,
+}
+
+impl WlSeatGlobal {
+    pub fn add_tablet_seat(&self, ts: &Rc<ZwpTabletSeatV2>) -> Result<(), ClientError> {
+        self.tablet_seats.set((ts.client.id, ts.id), ts.clone());
+        for tablet in self.tablets.lock().values() {
+            ts.announce_tablet(tablet)?;
+        }
+        for tool in self.tablet_tools.lock().values() {
+            ts.announce_tool(tool)?;
+        }
+        for pad in self.tablet_pads.lock().values() {
+            ts.announce_pad(pad)?;
+        }
+        Ok(())
+    }
+
+    pub fn remove_tablet_seat(&self, ts: &ZwpTabletSeatV2) {
+        let key = (ts.client.id, ts.id);
+        self.tablet_seats.remove(&key);
+        for tablet in self.tablets.lock().values() {
+            tablet.bindings.remove(&key);
+        }
+        for tool in self.tablet_tools.lock().values() {
+            tool.bindings.remove(&key);
+        }
+        for pad in self.tablet_pads.lock().values() {
+            pad.bindings.remove(&key);
+        }
+    }
+
+    fn for_each_tablet_seat<F>(&self, mut f: F)
+    where
+        F: FnMut(&Rc<ZwpTabletSeatV2>) -> Result<(), ClientError>,
+    {
+        let seats: Vec<_> = self.tablet_seats.lock().values().cloned().collect();
+        for ts in seats {
+            if let Err(e) = f(&ts) {
+                ts.client.error(e);
+            }
+        }
+    }
+
+    /// Makes the tablet or pad available to clients if the device is one.
+    pub fn add_tablet_device(&self, dev: &DeviceHandlerData) {
+        let id = dev.device.id();
+        let group = dev.device.device_group();
+        if dev.device.has_capability(InputDeviceCapability::TabletTool) {
+            let tablet = Rc::new(Tablet {
+                id,
+                name: dev.device.name(),
+                group,
+                bindings: Default::default(),
+            });
+            self.tablets.set(id, tablet.clone());
+            self.for_each_tablet_seat(|ts| ts.announce_tablet(&tablet));
+            let pads: Vec<_> = self.tablet_pads.lock().values().cloned().collect();
+            for pad in pads {
+                if group.is_some() && pad.group == group && pad.tablet.get().is_none() {
+                    pad.tablet.set(Some(tablet.clone()));
+                    if let Some(surface) = pad.surface.get() {
+                        self.tablet_pad_send_enter(&pad, &surface);
+                    }
+                }
+            }
+        }
+        if dev.device.has_capability(InputDeviceCapability::TabletPad) {
+            let mut init = dev.device.tablet_pad_init();
+            if init.groups.is_empty() {
+                init.groups.push(TabletPadGroupInit {
+                    buttons: (0..init.buttons).collect(),
+                    rings: (0..init.rings).collect(),
+                    strips: (0..init.strips).collect(),
+                    modes: 0,
+                    mode: 0,
+                });
+            }
+            let tablet = match group {
+                Some(_) => self
+                    .tablets
+                    .lock()
+                    .values()
+                    .find(|t| t.group == group)
+                    .cloned(),
+                _ => None,
+            };
+            let pad = Rc::new(TabletPad {
+                modes: init.groups.iter().map(|g| Cell::new(g.mode)).collect(),
+                init,
+                group,
+                tablet: CloneCell::new(tablet),
+                bindings: Default::default(),
+                surface: CloneCell::new(self.keyboard_node.get().node_into_surface()),
+            });
+            self.tablet_pads.set(id, pad.clone());
+            self.for_each_tablet_seat(|ts| ts.announce_pad(&pad));
+        }
+    }
+
+    pub fn remove_tablet_device(self: &Rc<Self>, id: InputDeviceId) {
+        if let Some(tablet) = self.tablets.remove(&id) {
+            let tools: Vec<_> = self.tablet_tools.lock().values().cloned().collect();
+            for tool in tools {
+                if let Some(t) = tool.tablet.get() {
+                    if t.id == id {
+                        self.tablet_tool_proximity_out(0, &tool);
+                    }
+                }
+            }
+            for pad in self.tablet_pads.lock().values() {
+                if let Some(t) = pad.tablet.get() {
+                    if t.id == id {
+                        if let Some(surface) = pad.surface.get() {
+                            self.tablet_pad_send_leave(pad, &surface);
+                        }
+                        pad.tablet.take();
+                    }
+                }
+            }
+            for (_, binding) in tablet.bindings.lock().drain() {
+                binding.send_removed();
+            }
+            if self.tablets.is_empty() {
+                for (_, tool) in self.tablet_tools.lock().drain() {
+                    for (_, binding) in tool.bindings.lock().drain() {
+                        binding.send_removed();
+                    }
+                }
+            }
+        }
+        if let Some(pad) = self.tablet_pads.remove(&id) {
+            for (_, binding) in pad.bindings.lock().drain() {
+                binding.send_removed();
+            }
+        }
+    }
+
+    pub(super) fn clear_tablets(&self) {
+        self.tablet_seats.clear();
+        for (_, tablet) in self.tablets.lock().drain() {
+            tablet.bindings.clear();
+        }
+        for (_, tool) in self.tablet_tools.lock().drain() {
+            tool.bindings.clear();
+            tool.tablet.take();
+            tool.surface.take();
+        }
+        for (_, pad) in self.tablet_pads.lock().drain() {
+            pad.bindings.clear();
+            pad.tablet.take();
+            pad.surface.take();
+        }
+    }
+
+    /// Calls `f` for each tool object of the client that has a matching tablet object.
+    fn tablet_tool_event<F>(&self, tool: &TabletTool, client: ClientId, mut f: F)
+    where
+        F: FnMut(&ZwpTabletToolV2, &ZwpTabletV2),
+    {
+        let tablet = match tool.tablet.get() {
+            Some(t) => t,
+            _ => return,
+        };
+        for (key, binding) in tool.bindings.lock().iter() {
+            if key.0 == client {
+                if let Some(tablet) = tablet.bindings.get(key) {
+                    f(binding, &tablet);
+                }
+            }
+        }
+    }
+
+    pub(super) fn tablet_tool_proximity_in(&self, dev: &DeviceHandlerData, init: TabletToolInit) {
+        let tablet = match self.tablets.get(&dev.device.id()) {
+            Some(t) => t,
+            _ => return,
+        };
+        let tool = match self.tablet_tools.get(&init.id) {
+            Some(t) => t,
+            _ => {
+                let tool = Rc::new(TabletTool {
+                    init,
+                    bindings: Default::default(),
+                    tablet: Default::default(),
+                    surface: Default::default(),
+                    down: Cell::new(false),
+                });
+                self.tablet_tools.set(init.id, tool.clone());
+                self.for_each_tablet_seat(|ts| ts.announce_tool(&tool));
+                tool
+            }
+        };
+        tool.tablet.set(Some(tablet));
+    }
+
+    pub(super) fn tablet_tool_proximity_out_(self: &Rc<Self>, time_usec: u64, tool: TabletToolId) {
+        if let Some(tool) = self.tablet_tools.get(&tool) {
+            self.tablet_tool_proximity_out(time_usec, &tool);
+        }
+    }
+
+    fn tablet_tool_proximity_out(self: &Rc<Self>, time_usec: u64, tool: &TabletTool) {
+        if tool.down.replace(false) && tool.surface.get().is_none() {
+            self.button_event(time_usec, BTN_LEFT, KeyState::Released);
+        }
+        self.tablet_tool_set_surface(time_usec, tool, None);
+        tool.tablet.take();
+    }
+
+    fn tablet_tool_set_surface(
+        &self,
+        time_usec: u64,
+        tool: &TabletTool,
+        surface: Option<Rc<WlSurface>>,
+    ) {
+        let old = tool.surface.get();
+        if let (Some(old), Some(new)) = (&old, &surface) {
+            if rc_eq(old, new) {
+                return;
+            }
+        }
+        if let Some(old) = old {
+            self.tablet_tool_event(tool, old.client.id, |t, _| {
+                t.send_proximity_out();
+                t.send_frame(time_usec);
+            });
+        }
+        if let Some(new) = &surface {
+            let serial = self.state.next_serial(Some(&new.client));
+            self.tablet_tool_event(tool, new.client.id, |t, tablet| {
+                t.send_proximity_in(serial, tablet, new);
+            });
+        }
+        tool.surface.set(surface);
+    }
+
+    /// Returns the surface at the position if its client has bound the tool.
+    fn tablet_tool_surface_at(
+        &self,
+        tool: &TabletTool,
+        x: Fixed,
+        y: Fixed,
+    ) -> Option<Rc<WlSurface>> {
+        let (x, y) = (x.round_down(), y.round_down());
+        let mut tree = vec![FoundNode {
+            node: self.state.root.clone(),
+            x,
+            y,
+        }];
+        self.state.root.node_find_tree_at(x, y, &mut tree);
+        let surface = tree.pop().unwrap().node.node_into_surface()?;
+        let mut bound = false;
+        self.tablet_tool_event(tool, surface.client.id, |_, _| bound = true);
+        bound.then_some(surface)
+    }
+
+    pub(super) fn tablet_tool_axes(
+        self: &Rc<Self>,
+        dev: &DeviceHandlerData,
+        time_usec: u64,
+        tool: TabletToolId,
+        changes: &TabletToolChanges,
+    ) {
+        let tool = match self.tablet_tools.get(&tool) {
+            Some(t) if t.tablet.get().is_some() => t,
+            _ => return,
+        };
+        let (x, y) = self.mapped_position(Some(dev), false, changes.x_normed, changes.y_normed);
+        if !tool.down.get() {
+            let surface = self.tablet_tool_surface_at(&tool, x, y);
+            self.tablet_tool_set_surface(time_usec, &tool, surface);
+        }
+        let surface = match tool.surface.get() {
+            Some(s) => s,
+            _ => {
+                self.set_new_position(time_usec, x, y);
+                return;
+            }
+        };
+        // The cursor follows the tool but the pointer focus stays where it is.
+        self.pos.set((x, y));
+        self.update_hardware_cursor_position();
+        self.damage_cursor();
+        let pos = surface.buffer_abs_pos.get();
+        let (x, y) = (x - pos.x1(), y - pos.y1());
+        self.tablet_tool_event(&tool, surface.client.id, |t, _| {
+            t.send_motion(x, y);
+            if let Some(pressure) = changes.pressure {
+                t.send_pressure((pressure * 65535.0) as u32);
+            }
+            if let Some(distance) = changes.distance {
+                t.send_distance((distance * 65535.0) as u32);
+            }
+            if let Some((tilt_x, tilt_y)) = changes.tilt {
+                t.send_tilt(Fixed::from_f64(tilt_x), Fixed::from_f64(tilt_y));
+            }
+            if let Some(rotation) = changes.rotation {
+                t.send_rotation(Fixed::from_f64(rotation));
+            }
+            if let Some(slider) = changes.slider {
+                t.send_slider((slider * 65535.0) as i32);
+            }
+            if let Some((degrees, clicks)) = changes.wheel {
+                t.send_wheel(Fixed::from_f64(degrees), clicks);
+            }
+            t.send_frame(time_usec);
+        });
+    }
+
+    pub(super) fn tablet_tool_tip(self: &Rc<Self>, time_usec: u64, tool: TabletToolId, down: bool) {
+        let tool = match self.tablet_tools.get(&tool) {
+            Some(t) if t.tablet.get().is_some() => t,
+            _ => return,
+        };
+        if tool.down.replace(down) == down {
+            return;
+        }
+        let surface = match tool.surface.get() {
+            Some(s) => s,
+            _ => {
+                let state = match down {
+                    true => KeyState::Pressed,
+                    false => KeyState::Released,
+                };
+                self.button_event(time_usec, BTN_LEFT, state);
+                return;
+            }
+        };
+        let serial = self.state.next_serial(Some(&surface.client));
+        self.tablet_tool_event(&tool, surface.client.id, |t, _| {
+            match down {
+                true => t.send_down(serial),
+                false => t.send_up(),
+            }
+            t.send_frame(time_usec);
+        });
+        if down {
+            if let Some(node) = surface.get_focus_node(self.id) {
+                self.focus_node(node);
+            }
+        }
+    }
+
+    pub(super) fn tablet_tool_button(
+        &self,
+        time_usec: u64,
+        tool: TabletToolId,
+        button: u32,
+        state: KeyState,
+    ) {
+        let tool = match self.tablet_tools.get(&tool) {
+            Some(t) => t,
+            _ => return,
+        };
+        if let Some(surface) = tool.surface.get() {
+            let serial = self.state.next_serial(Some(&surface.client));
+            self.tablet_tool_event(&tool, surface.client.id, |t, _| {
+                t.send_button(serial, button, state);
+                t.send_frame(time_usec);
+            });
+        }
+    }
+
+    pub(super) fn tablet_pad_button(
+        &self,
+        dev: &DeviceHandlerData,
+        time_usec: u64,
+        button: u32,
+        state: KeyState,
+    ) {
+        if dev.pad_button_bindings.get(&button).is_some() {
+            if state == KeyState::Pressed {
+                if let Some(config) = self.state.config.get() {
+                    config.invoke_pad_button(dev.device.id(), button);
+                }
+            }
+            return;
+        }
+        self.tablet_pad_event(dev, |p| p.send_button(time_usec, button, state));
+    }
+
+    pub(super) fn tablet_pad_mode_switch(
+        &self,
+        dev: &DeviceHandlerData,
+        time_usec: u64,
+        group: u32,
+        mode: u32,
+    ) {
+        let pad = match self.tablet_pads.get(&dev.device.id()) {
+            Some(p) => p,
+            _ => return,
+        };
+        match pad.modes.get(group as usize) {
+            Some(m) if m.replace(mode) != mode => {}
+            _ => return,
+        }
+        if let (Some(surface), Some(_)) = (pad.surface.get(), pad.tablet.get()) {
+            let serial = self.state.next_serial(Some(&surface.client));
+            for (key, binding) in pad.bindings.lock().iter() {
+                if key.0 == surface.client.id {
+                    if let Some(group) = binding.groups.get(&group) {
+                        group.send_mode_switch(time_usec, serial, mode);
+                    }
+                }
+            }
+        }
+    }
+
+    pub(super) fn tablet_pad_ring(
+        &self,
+        dev: &DeviceHandlerData,
+        time_usec: u64,
+        ring: u32,
+        finger: bool,
+        degrees: Option<f64>,
+    ) {
+        self.tablet_pad_event(dev, |p| {
+            if let Some(ring) = p.rings.get(&ring) {
+                if finger {
+                    ring.send_source_finger();
+                }
+                match degrees {
+                    Some(degrees) => ring.send_angle(degrees),
+                    _ => ring.send_stop(),
+                }
+                ring.send_frame(time_usec);
+            }
+        });
+    }
+
+    pub(super) fn tablet_pad_strip(
+        &self,
+        dev: &DeviceHandlerData,
+        time_usec: u64,
+        strip: u32,
+        finger: bool,
+        position: Option<f64>,
+    ) {
+        self.tablet_pad_event(dev, |p| {
+            if let Some(strip) = p.strips.get(&strip) {
+                if finger {
+                    strip.send_source_finger();
+                }
+                match position {
+                    Some(position) => strip.send_position(position),
+                    _ => strip.send_stop(),
+                }
+                strip.send_frame(time_usec);
+            }
+        });
+    }
+
+    /// Calls `f` for each pad object of the client that the pad has entered.
+    fn tablet_pad_event<F>(&self, dev: &DeviceHandlerData, mut f: F)
+    where
+        F: FnMut(&ZwpTabletPadV2),
+    {
+        let pad = match self.tablet_pads.get(&dev.device.id()) {
+            Some(p) => p,
+            _ => return,
+        };
+        if let (Some(surface), Some(_)) = (pad.surface.get(), pad.tablet.get()) {
+            for (key, binding) in pad.bindings.lock().iter() {
+                if key.0 == surface.client.id {
+                    f(binding);
+                }
+            }
+        }
+    }
+
+    /// Moves the focus of all pads to the surface that has the keyboard focus.
+    pub(super) fn tablet_pad_focus_changed(&self) {
+        if self.tablet_pads.is_empty() {
+            return;
+        }
+        let surface = self.keyboard_node.get().node_into_surface();
+        let pads: Vec<_> = self.tablet_pads.lock().values().cloned().collect();
+        for pad in pads {
+            self.tablet_pad_set_surface(&pad, surface.clone());
+        }
+    }
+
+    /// Sends the enter event to a pad object that was created while the pad was focused.
+    pub(super) fn tablet_pad_binding_added(&self, pad: &TabletPad, binding: &ZwpTabletPadV2) {
+        if let (Some(surface), Some(tablet)) = (pad.surface.get(), pad.tablet.get()) {
+            if surface.client.id == binding.client.id {
+                let key = (binding.client.id, binding.tablet_seat);
+                if let Some(tablet) = tablet.bindings.get(&key) {
+                    let serial = self.state.next_serial(Some(&surface.client));
+                    self.tablet_pad_send_enter_(pad, binding, serial, &tablet, &surface);
+                }
+            }
+        }
+    }
+
+    fn tablet_pad_set_surface(&self, pad: &TabletPad, surface: Option<Rc<WlSurface>>) {
+        let old = pad.surface.get();
+        if let (Some(old), Some(new)) = (&old, &surface) {
+            if rc_eq(old, new) {
+                return;
+            }
+        }
+        if let Some(old) = old {
+            self.tablet_pad_send_leave(pad, &old);
+        }
+        if let Some(new) = &surface {
+            self.tablet_pad_send_enter(pad, new);
+        }
+        pad.surface.set(surface);
+    }
+
+    fn tablet_pad_send_leave(&self, pad: &TabletPad, surface: &WlSurface) {
+        if pad.tablet.get().is_none() {
+            return;
+        }
+        let serial = self.state.next_serial(Some(&surface.client));
+        for (key, binding) in pad.bindings.lock().iter() {
+            if key.0 == surface.client.id {
+                binding.send_leave(serial, surface);
+            }
+        }
+    }
+
+    fn tablet_pad_send_enter(&self, pad: &TabletPad, surface: &WlSurface) {
+        let tablet = match pad.tablet.get() {
+            Some(t) => t,
+            _ => return,
+        };
+        let serial = self.state.next_serial(Some(&surface.client));
+        for (key, binding) in pad.bindings.lock().iter() {
+            if key.0 == surface.client.id {
+                if let Some(tablet) = tablet.bindings.get(key) {
+                    self.tablet_pad_send_enter_(pad, binding, serial, &tablet, surface);
+                }
+            }
+        }
+    }
+
+    fn tablet_pad_send_enter_(
+        &self,
+        pad: &TabletPad,
+        binding: &ZwpTabletPadV2,
+        serial: u32,
+        tablet: &ZwpTabletV2,
+        surface: &WlSurface,
+    ) {
+        binding.send_enter(serial, tablet, surface);
+        let now = now_usec();
+        for (idx, mode) in pad.modes.iter().enumerate() {
+            if let Some(group) = binding.groups.get(&(idx as u32)) {
+                group.send_mode_switch(now, serial, mode.get());
+            }
+        }
+    }
+}
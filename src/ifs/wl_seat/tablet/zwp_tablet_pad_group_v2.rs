@@ -0,0 +1,97 @@
+use {
+    crate::{
+        client::{Client, ClientError},
+        ifs::wl_seat::tablet::{
+            zwp_tablet_pad_ring_v2::ZwpTabletPadRingV2,
+            zwp_tablet_pad_strip_v2::ZwpTabletPadStripV2, zwp_tablet_pad_v2::ZwpTabletPadV2,
+        },
+        leaks::Tracker,
+        object::Object,
+        utils::buffd::{MsgParser, MsgParserError},
+        wire::{zwp_tablet_pad_group_v2::*, ZwpTabletPadGroupV2Id},
+    },
+    std::rc::Rc,
+    thiserror::Error,
+};
+
+pub struct ZwpTabletPadGroupV2 {
+    pub id: ZwpTabletPadGroupV2Id,
+    pub client: Rc<Client>,
+    pub pad: Rc<ZwpTabletPadV2>,
+    pub index: u32,
+    pub tracker: Tracker<Self>,
+}
+
+impl ZwpTabletPadGroupV2 {
+    pub fn send_buttons(&self, buttons: &[u32]) {
+        self.client.event(Buttons {
+            self_id: self.id,
+            buttons,
+        });
+    }
+
+    pub fn send_ring(&self, ring: &ZwpTabletPadRingV2) {
+        self.client.event(Ring {
+            self_id: self.id,
+            ring: ring.id,
+        });
+    }
+
+    pub fn send_strip(&self, strip: &ZwpTabletPadStripV2) {
+        self.client.event(Strip {
+            self_id: self.id,
+            strip: strip.id,
+        });
+    }
+
+    pub fn send_modes(&self, modes: u32) {
+        self.client.event(Modes {
+            self_id: self.id,
+            modes,
+        });
+    }
+
+    pub fn send_done(&self) {
+        self.client.event(Done { self_id: self.id });
+    }
+
+    pub fn send_mode_switch(&self, time_usec: u64, serial: u32, mode: u32) {
+        self.client.event(ModeSwitch {
+            self_id: self.id,
+            time: (time_usec / 1000) as u32,
+            serial,
+            mode,
+        });
+    }
+
+    fn destroy(&self, parser: MsgParser<'_, '_>) -> Result<(), ZwpTabletPadGroupV2Error> {
+        let _req: Destroy = self.client.parse(self, parser)?;
+        self.pad.groups.remove(&self.index);
+        self.client.remove_obj(self)?;
+        Ok(())
+    }
+}
+
+object_base! {
+    ZwpTabletPadGroupV2;
+
+    DESTROY => destroy,
+}
+
+impl Object for ZwpTabletPadGroupV2 {
+    fn num_requests(&self) -> u32 {
+        DESTROY + 1
+    }
+}
+
+simple_add_obj!(ZwpTabletPadGroupV2);
+
+#[derive(Debug, Error)]
+pub enum ZwpTabletPadGroupV2Error {
+    #[error(transparent)]
+    ClientError(Box<ClientError>),
+    #[error("Parsing failed")]
+    MsgParserError(#[source] Box<MsgParserError>),
+}
+efrom!(ZwpTabletPadGroupV2Error, ClientError);
+efrom!(ZwpTabletPadGroupV2Error, MsgParserError);
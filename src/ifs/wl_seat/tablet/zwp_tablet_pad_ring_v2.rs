@@ -0,0 +1,88 @@
+use {
+    crate::{
+        client::{Client, ClientError},
+        fixed::Fixed,
+        ifs::wl_seat::tablet::zwp_tablet_pad_v2::ZwpTabletPadV2,
+        leaks::Tracker,
+        object::Object,
+        utils::buffd::{MsgParser, MsgParserError},
+        wire::{zwp_tablet_pad_ring_v2::*, ZwpTabletPadRingV2Id},
+    },
+    std::rc::Rc,
+    thiserror::Error,
+};
+
+const SOURCE_FINGER: u32 = 1;
+
+pub struct ZwpTabletPadRingV2 {
+    pub id: ZwpTabletPadRingV2Id,
+    pub client: Rc<Client>,
+    pub pad: Rc<ZwpTabletPadV2>,
+    pub index: u32,
+    pub tracker: Tracker<Self>,
+}
+
+impl ZwpTabletPadRingV2 {
+    pub fn send_source_finger(&self) {
+        self.client.event(Source {
+            self_id: self.id,
+            source: SOURCE_FINGER,
+        });
+    }
+
+    pub fn send_angle(&self, degrees: f64) {
+        self.client.event(Angle {
+            self_id: self.id,
+            degrees: Fixed::from_f64(degrees),
+        });
+    }
+
+    pub fn send_stop(&self) {
+        self.client.event(Stop { self_id: self.id });
+    }
+
+    pub fn send_frame(&self, time_usec: u64) {
+        self.client.event(Frame {
+            self_id: self.id,
+            time: (time_usec / 1000) as u32,
+        });
+    }
+
+    fn set_feedback(&self, parser: MsgParser<'_, '_>) -> Result<(), ZwpTabletPadRingV2Error> {
+        // We don't have an on-screen display for descriptions.
+        let _req: SetFeedback = self.client.parse(self, parser)?;
+        Ok(())
+    }
+
+    fn destroy(&self, parser: MsgParser<'_, '_>) -> Result<(), ZwpTabletPadRingV2Error> {
+        let _req: Destroy = self.client.parse(self, parser)?;
+        self.pad.rings.remove(&self.index);
+        self.client.remove_obj(self)?;
+        Ok(())
+    }
+}
+
+object_base! {
+    ZwpTabletPadRingV2;
+
+    SET_FEEDBACK => set_feedback,
+    DESTROY => destroy,
+}
+
+impl Object for ZwpTabletPadRingV2 {
+    fn num_requests(&self) -> u32 {
+        DESTROY + 1
+    }
+}
+
+simple_add_obj!(ZwpTabletPadRingV2);
+
+#[derive(Debug, Error)]
+pub enum ZwpTabletPadRingV2Error {
+    #[error(transparent)]
+    ClientError(Box<ClientError>),
+    #[error("Parsing failed")]
+    MsgParserError(#[source] Box<MsgParserError>),
+}
+efrom!(ZwpTabletPadRingV2Error, ClientError);
+efrom!(ZwpTabletPadRingV2Error, MsgParserError);
@@ -0,0 +1,143 @@
+use {
+    crate::{
+        backend::KeyState,
+        client::{Client, ClientError},
+        ifs::{
+            wl_seat::tablet::{
+                zwp_tablet_pad_group_v2::ZwpTabletPadGroupV2,
+                zwp_tablet_pad_ring_v2::ZwpTabletPadRingV2,
+                zwp_tablet_pad_strip_v2::ZwpTabletPadStripV2, zwp_tablet_v2::ZwpTabletV2,
+                TabletPad,
+            },
+            wl_surface::WlSurface,
+        },
+        leaks::Tracker,
+        object::Object,
+        utils::{
+            buffd::{MsgParser, MsgParserError},
+            copyhashmap::CopyHashMap,
+        },
+        wire::{zwp_tablet_pad_v2::*, ZwpTabletPadV2Id, ZwpTabletSeatV2Id},
+    },
+    std::rc::Rc,
+    thiserror::Error,
+};
+
+const RELEASED: u32 = 0;
+const PRESSED: u32 = 1;
+
+pub struct ZwpTabletPadV2 {
+    pub id: ZwpTabletPadV2Id,
+    pub client: Rc<Client>,
+    pub tablet_seat: ZwpTabletSeatV2Id,
+    pub pad: Rc<TabletPad>,
+    /// The mode groups, rings, and strips of the pad by their index.
+    pub groups: CopyHashMap<u32, Rc<ZwpTabletPadGroupV2>>,
+    pub rings: CopyHashMap<u32, Rc<ZwpTabletPadRingV2>>,
+    pub strips: CopyHashMap<u32, Rc<ZwpTabletPadStripV2>>,
+    pub tracker: Tracker<Self>,
+}
+
+impl ZwpTabletPadV2 {
+    pub fn send_group(&self, group: &ZwpTabletPadGroupV2) {
+        self.client.event(Group {
+            self_id: self.id,
+            pad_group: group.id,
+        });
+    }
+
+    pub fn send_buttons(&self, buttons: u32) {
+        self.client.event(Buttons {
+            self_id: self.id,
+            buttons,
+        });
+    }
+
+    pub fn send_done(&self) {
+        self.client.event(Done { self_id: self.id });
+    }
+
+    pub fn send_button(&self, time_usec: u64, button: u32, state: KeyState) {
+        self.client.event(Button {
+            self_id: self.id,
+            time: (time_usec / 1000) as u32,
+            button,
+            state: match state {
+                KeyState::Released => RELEASED,
+                KeyState::Pressed => PRESSED,
+            },
+        });
+    }
+
+    pub fn send_enter(&self, serial: u32, tablet: &ZwpTabletV2, surface: &WlSurface) {
+        self.client.event(Enter {
+            self_id: self.id,
+            serial,
+            tablet: tablet.id,
+            surface: surface.id,
+        });
+    }
+
+    pub fn send_leave(&self, serial: u32, surface: &WlSurface) {
+        self.client.event(Leave {
+            self_id: self.id,
+            serial,
+            surface: surface.id,
+        });
+    }
+
+    pub fn send_removed(&self) {
+        self.client.event(Removed { self_id: self.id });
+    }
+
+    fn set_feedback(&self, parser: MsgParser<'_, '_>) -> Result<(), ZwpTabletPadV2Error> {
+        // We don't have an on-screen display for button descriptions.
+        let _req: SetFeedback = self.client.parse(self, parser)?;
+        Ok(())
+    }
+
+    fn destroy(&self, parser: MsgParser<'_, '_>) -> Result<(), ZwpTabletPadV2Error> {
+        let _req: Destroy = self.client.parse(self, parser)?;
+        self.detach();
+        self.client.remove_obj(self)?;
+        Ok(())
+    }
+
+    fn detach(&self) {
+        self.pad
+            .bindings
+            .remove(&(self.client.id, self.tablet_seat));
+        self.groups.clear();
+        self.rings.clear();
+        self.strips.clear();
+    }
+}
+
+object_base! {
+    ZwpTabletPadV2;
+
+    SET_FEEDBACK => set_feedback,
+    DESTROY => destroy,
+}
+
+impl Object for ZwpTabletPadV2 {
+    fn num_requests(&self) -> u32 {
+        DESTROY + 1
+    }
+
+    fn break_loops(&self) {
+        self.detach();
+    }
+}
+
+simple_add_obj!(ZwpTabletPadV2);
+
+#[derive(Debug, Error)]
+pub enum ZwpTabletPadV2Error {
+    #[error(transparent)]
+    ClientError(Box<ClientError>),
+    #[error("Parsing failed")]
+    MsgParserError(#[source] Box<MsgParserError>),
+}
+efrom!(ZwpTabletPadV2Error, ClientError);
+efrom!(ZwpTabletPadV2Error, MsgParserError);
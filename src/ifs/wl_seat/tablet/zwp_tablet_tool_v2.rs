@@ -0,0 +1,260 @@
+use {
+    crate::{
+        backend::{KeyState, TabletToolInit, TabletToolType},
+        client::{Client, ClientError},
+        cursor::Cursor,
+        fixed::Fixed,
+        ifs::{
+            wl_seat::{
+                tablet::{zwp_tablet_v2::ZwpTabletV2, TabletTool},
+                WlSeatGlobal,
+            },
+            wl_surface::{WlSurface, WlSurfaceError},
+        },
+        leaks::Tracker,
+        object::Object,
+        utils::buffd::{MsgParser, MsgParserError},
+        wire::{zwp_tablet_tool_v2::*, ZwpTabletSeatV2Id, ZwpTabletToolV2Id},
+    },
+    std::rc::Rc,
+    thiserror::Error,
+};
+
+const TYPE_PEN: u32 = 0x140;
+const TYPE_ERASER: u32 = 0x141;
+const TYPE_BRUSH: u32 = 0x142;
+const TYPE_PENCIL: u32 = 0x143;
+const TYPE_AIRBRUSH: u32 = 0x144;
+const TYPE_MOUSE: u32 = 0x146;
+const TYPE_LENS: u32 = 0x147;
+
+const CAPABILITY_TILT: u32 = 1;
+const CAPABILITY_PRESSURE: u32 = 2;
+const CAPABILITY_DISTANCE: u32 = 3;
+const CAPABILITY_ROTATION: u32 = 4;
+const CAPABILITY_SLIDER: u32 = 5;
+const CAPABILITY_WHEEL: u32 = 6;
+
+const RELEASED: u32 = 0;
+const PRESSED: u32 = 1;
+
+pub struct ZwpTabletToolV2 {
+    pub id: ZwpTabletToolV2Id,
+    pub client: Rc<Client>,
+    pub seat: Rc<WlSeatGlobal>,
+    pub tablet_seat: ZwpTabletSeatV2Id,
+    pub tool: Rc<TabletTool>,
+    pub tracker: Tracker<Self>,
+}
+
+impl ZwpTabletToolV2 {
+    pub fn send_description(&self, init: &TabletToolInit) {
+        let tool_type = match init.ty {
+            TabletToolType::Pen => TYPE_PEN,
+            TabletToolType::Eraser => TYPE_ERASER,
+            TabletToolType::Brush => TYPE_BRUSH,
+            TabletToolType::Pencil => TYPE_PENCIL,
+            TabletToolType::Airbrush => TYPE_AIRBRUSH,
+            TabletToolType::Mouse => TYPE_MOUSE,
+            TabletToolType::Lens => TYPE_LENS,
+        };
+        self.client.event(Type {
+            self_id: self.id,
+            tool_type,
+        });
+        if init.serial != 0 {
+            self.client.event(HardwareSerial {
+                self_id: self.id,
+                hardware_serial_hi: (init.serial >> 32) as u32,
+                hardware_serial_lo: init.serial as u32,
+            });
+        }
+        if init.hardware_id != 0 {
+            self.client.event(HardwareIdWacom {
+                self_id: self.id,
+                hardware_id_hi: (init.hardware_id >> 32) as u32,
+                hardware_id_lo: init.hardware_id as u32,
+            });
+        }
+        let caps = [
+            (init.caps.tilt, CAPABILITY_TILT),
+            (init.caps.pressure, CAPABILITY_PRESSURE),
+            (init.caps.distance, CAPABILITY_DISTANCE),
+            (init.caps.rotation, CAPABILITY_ROTATION),
+            (init.caps.slider, CAPABILITY_SLIDER),
+            (init.caps.wheel, CAPABILITY_WHEEL),
+        ];
+        for (supported, capability) in caps {
+            if supported {
+                self.client.event(Capability {
+                    self_id: self.id,
+                    capability,
+                });
+            }
+        }
+        self.client.event(Done { self_id: self.id });
+    }
+
+    pub fn send_removed(&self) {
+        self.client.event(Removed { self_id: self.id });
+    }
+
+    pub fn send_proximity_in(&self, serial: u32, tablet: &ZwpTabletV2, surface: &WlSurface) {
+        self.client.event(ProximityIn {
+            self_id: self.id,
+            serial,
+            tablet: tablet.id,
+            surface: surface.id,
+        });
+    }
+
+    pub fn send_proximity_out(&self) {
+        self.client.event(ProximityOut { self_id: self.id });
+    }
+
+    pub fn send_down(&self, serial: u32) {
+        self.client.event(Down {
+            self_id: self.id,
+            serial,
+        });
+    }
+
+    pub fn send_up(&self) {
+        self.client.event(Up { self_id: self.id });
+    }
+
+    pub fn send_motion(&self, x: Fixed, y: Fixed) {
+        self.client.event(Motion {
+            self_id: self.id,
+            x,
+            y,
+        });
+    }
+
+    pub fn send_pressure(&self, pressure: u32) {
+        self.client.event(Pressure {
+            self_id: self.id,
+            pressure,
+        });
+    }
+
+    pub fn send_distance(&self, distance: u32) {
+        self.client.event(Distance {
+            self_id: self.id,
+            distance,
+        });
+    }
+
+    pub fn send_tilt(&self, tilt_x: Fixed, tilt_y: Fixed) {
+        self.client.event(Tilt {
+            self_id: self.id,
+            tilt_x,
+            tilt_y,
+        });
+    }
+
+    pub fn send_rotation(&self, degrees: Fixed) {
+        self.client.event(Rotation {
+            self_id: self.id,
+            degrees,
+        });
+    }
+
+    pub fn send_slider(&self, position: i32) {
+        self.client.event(Slider {
+            self_id: self.id,
+            position,
+        });
+    }
+
+    pub fn send_wheel(&self, degrees: Fixed, clicks: i32) {
+        self.client.event(Wheel {
+            self_id: self.id,
+            degrees,
+            clicks,
+        });
+    }
+
+    pub fn send_button(&self, serial: u32, button: u32, state: KeyState) {
+        self.client.event(Button {
+            self_id: self.id,
+            serial,
+            button,
+            state: match state {
+                KeyState::Released => RELEASED,
+                KeyState::Pressed => PRESSED,
+            },
+        });
+    }
+
+    pub fn send_frame(&self, time_usec: u64) {
+        self.client.event(Frame {
+            self_id: self.id,
+            time: (time_usec / 1000) as u32,
+        });
+    }
+
+    fn set_cursor(&self, parser: MsgParser<'_, '_>) -> Result<(), ZwpTabletToolV2Error> {
+        let req: SetCursor = self.client.parse(self, parser)?;
+        if !self.client.valid_serial(req.serial) {
+            log::warn!("Client tried to set_cursor with an invalid serial");
+            return Ok(());
+        }
+        let mut cursor_opt = None;
+        if req.surface.is_some() {
+            let surface = self.client.lookup(req.surface)?;
+            let cursor = surface.get_cursor(&self.seat)?;
+            cursor.set_hotspot(req.hotspot_x, req.hotspot_y);
+            cursor_opt = Some(cursor as Rc<dyn Cursor>);
+        }
+        match self.tool.surface.get() {
+            Some(surface) if surface.client.id == self.client.id => {}
+            _ => return Ok(()),
+        }
+        self.seat.set_app_cursor(cursor_opt);
+        Ok(())
+    }
+
+    fn destroy(&self, parser: MsgParser<'_, '_>) -> Result<(), ZwpTabletToolV2Error> {
+        let _req: Destroy = self.client.parse(self, parser)?;
+        self.tool
+            .bindings
+            .remove(&(self.client.id, self.tablet_seat));
+        self.client.remove_obj(self)?;
+        Ok(())
+    }
+}
+
+object_base! {
+    ZwpTabletToolV2;
+
+    SET_CURSOR => set_cursor,
+    DESTROY => destroy,
+}
+
+impl Object for ZwpTabletToolV2 {
+    fn num_requests(&self) -> u32 {
+        DESTROY + 1
+    }
+
+    fn break_loops(&self) {
+        self.tool
+            .bindings
+            .remove(&(self.client.id, self.tablet_seat));
+    }
+}
+
+simple_add_obj!(ZwpTabletToolV2);
+
+#[derive(Debug, Error)]
+pub enum ZwpTabletToolV2Error {
+    #[error(transparent)]
+    ClientError(Box<ClientError>),
+    #[error("Parsing failed")]
+    MsgParserError(#[source] Box<MsgParserError>),
+    #[error(transparent)]
+    WlSurfaceError(Box<WlSurfaceError>),
+}
+efrom!(ZwpTabletToolV2Error, ClientError);
+efrom!(ZwpTabletToolV2Error, MsgParserError);
+efrom!(ZwpTabletToolV2Error, WlSurfaceError);
@@ -0,0 +1,185 @@
+pub mod zwp_input_method_keyboard_grab_v2;
+pub mod zwp_input_method_manager_v2;
+pub mod zwp_input_method_v2;
+pub mod zwp_text_input_manager_v3;
+pub mod zwp_text_input_v3;
+
+use {
+    crate::{
+        client::ClientId,
+        ifs::{
+            wl_seat::{
+                text_input::{
+                    zwp_input_method_keyboard_grab_v2::ZwpInputMethodKeyboardGrabV2,
+                    zwp_input_method_v2::ZwpInputMethodV2, zwp_text_input_v3::ZwpTextInputV3,
+                },
+                WlSeatGlobal,
+            },
+            wl_surface::{zwp_input_popup_surface_v2::ZwpInputPopupSurfaceV2, WlSurface},
+        },
+        rect::Rect,
+        utils::rc_eq::rc_eq,
+    },
+    std::{collections::hash_map::Entry, rc::Rc},
+};
+
+impl WlSeatGlobal {
+    pub fn add_text_input(&self, ti: &Rc<ZwpTextInputV3>) {
+        self.text_inputs
+            .borrow_mut()
+            .entry(ti.client.id)
+            .or_default()
+            .insert(ti.id, ti.clone());
+        if let Some(surface) = self.keyboard_node.get().node_into_surface() {
+            if surface.client.id == ti.client.id {
+                ti.send_enter(&surface);
+            }
+        }
+    }
+
+    pub fn remove_text_input(&self, ti: &ZwpTextInputV3) {
+        {
+            let mut text_inputs = self.text_inputs.borrow_mut();
+            if let Entry::Occupied(mut hm) = text_inputs.entry(ti.client.id) {
+                hm.get_mut().remove(&ti.id);
+                if hm.get().is_empty() {
+                    hm.remove();
+                }
+            }
+        }
+        if let Some(active) = self.text_input.get() {
+            if active.client.id == ti.client.id && active.id == ti.id {
+                self.deactivate_text_input();
+            }
+        }
+    }
+
+    fn for_each_text_input<C>(&self, client: ClientId, mut f: C)
+    where
+        C: FnMut(&Rc<ZwpTextInputV3>),
+    {
+        let text_inputs: Vec<_> = match self.text_inputs.borrow().get(&client) {
+            Some(tis) => tis.values().cloned().collect(),
+            _ => return,
+        };
+        for ti in &text_inputs {
+            f(ti);
+        }
+    }
+
+    pub(super) fn text_input_focus_surface(&self, surface: &Rc<WlSurface>) {
+        self.for_each_text_input(surface.client.id, |ti| ti.send_enter(surface));
+    }
+
+    pub(super) fn text_input_unfocus_surface(&self, surface: &WlSurface) {
+        self.for_each_text_input(surface.client.id, |ti| {
+            if let Some(s) = ti.surface.get() {
+                if s.id == surface.id {
+                    ti.send_leave(surface);
+                }
+            }
+        });
+        if let Some(active) = self.text_input.get() {
+            if active.surface.get().is_none() {
+                self.deactivate_text_input();
+            }
+        }
+    }
+
+    /// Updates the input method after the state of a text input has been committed.
+    pub fn text_input_committed(&self, ti: &Rc<ZwpTextInputV3>) {
+        let active = self.text_input.get();
+        let is_active = matches!(&active, Some(active) if rc_eq(active, ti));
+        if !ti.current.borrow().enabled || ti.surface.get().is_none() {
+            if is_active {
+                self.deactivate_text_input();
+            }
+            return;
+        }
+        if !is_active {
+            if active.is_some() {
+                self.deactivate_text_input();
+            }
+            self.text_input.set(Some(ti.clone()));
+            if let Some(im) = self.input_method.get() {
+                im.send_activate();
+            }
+        }
+        if let Some(im) = self.input_method.get() {
+            im.send_state(&ti.current.borrow());
+            im.send_done();
+            im.update_popups();
+        }
+    }
+
+    fn deactivate_text_input(&self) {
+        if self.text_input.take().is_none() {
+            return;
+        }
+        if let Some(im) = self.input_method.get() {
+            im.send_deactivate();
+            im.send_done();
+        }
+        self.state.damage();
+    }
+
+    pub fn text_input(&self) -> Option<Rc<ZwpTextInputV3>> {
+        self.text_input.get()
+    }
+
+    pub fn input_method(&self) -> Option<Rc<ZwpInputMethodV2>> {
+        self.input_method.get()
+    }
+
+    pub fn set_input_method(&self, im: &Rc<ZwpInputMethodV2>) {
+        self.input_method.set(Some(im.clone()));
+        if let Some(ti) = self.text_input.get() {
+            im.send_activate();
+            im.send_state(&ti.current.borrow());
+            im.send_done();
+        }
+    }
+
+    pub fn unset_input_method(&self, im: &ZwpInputMethodV2) {
+        if let Some(current) = self.input_method.get() {
+            if current.client.id == im.client.id && current.id == im.id {
+                self.input_method.take();
+                self.input_method_grab.take();
+                self.state.damage();
+            }
+        }
+    }
+
+    pub fn set_input_method_grab(&self, grab: Option<Rc<ZwpInputMethodKeyboardGrabV2>>) {
+        self.input_method_grab.set(grab);
+    }
+
+    /// Returns the keyboard grab of the input method if key events should be sent to it.
+    pub(super) fn active_input_method_grab(&self) -> Option<Rc<ZwpInputMethodKeyboardGrabV2>> {
+        self.text_input.get()?;
+        self.input_method_grab.get()
+    }
+
+    /// Returns the surface of the active text input and its cursor rectangle in
+    /// surface-local coordinates.
+    pub fn text_input_cursor_rect(&self) -> Option<(Rc<WlSurface>, Rect)> {
+        let ti = self.text_input.get()?;
+        let surface = ti.surface.get()?;
+        let rect = ti.current.borrow().cursor_rect;
+        Some((surface, rect))
+    }
+
+    pub fn for_each_input_popup<C>(&self, mut f: C)
+    where
+        C: FnMut(&Rc<ZwpInputPopupSurfaceV2>),
+    {
+        if self.text_input.get().is_none() {
+            return;
+        }
+        if let Some(im) = self.input_method.get() {
+            for popup in im.popups.lock().values() {
+                f(popup);
+            }
+        }
+    }
+}
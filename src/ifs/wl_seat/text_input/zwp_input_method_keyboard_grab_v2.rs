@@ -0,0 +1,145 @@
+use {
+    crate::{
+        client::{Client, ClientError},
+        ifs::wl_seat::{
+            copy_keymap_fd,
+            text_input::zwp_input_method_v2::ZwpInputMethodV2,
+            wl_keyboard::{self, WlKeyboardError},
+            WlSeatGlobal,
+        },
+        leaks::Tracker,
+        object::Object,
+        utils::buffd::{MsgParser, MsgParserError},
+        wire::{zwp_input_method_keyboard_grab_v2::*, ZwpInputMethodKeyboardGrabV2Id},
+        xkbcommon::{ModifierState, XkbKeymap},
+    },
+    ahash::AHashSet,
+    std::{cell::RefCell, rc::Rc},
+    thiserror::Error,
+};
+
+pub struct ZwpInputMethodKeyboardGrabV2 {
+    pub id: ZwpInputMethodKeyboardGrabV2Id,
+    pub client: Rc<Client>,
+    pub input_method: Rc<ZwpInputMethodV2>,
+    pub tracker: Tracker<Self>,
+    pressed_keys: RefCell<AHashSet<u32>>,
+}
+
+impl ZwpInputMethodKeyboardGrabV2 {
+    pub fn new(
+        id: ZwpInputMethodKeyboardGrabV2Id,
+        client: &Rc<Client>,
+        input_method: &Rc<ZwpInputMethodV2>,
+    ) -> Self {
+        Self {
+            id,
+            client: client.clone(),
+            input_method: input_method.clone(),
+            tracker: Default::default(),
+            pressed_keys: Default::default(),
+        }
+    }
+
+    pub fn send_initial_state(
+        &self,
+        seat: &WlSeatGlobal,
+    ) -> Result<(), ZwpInputMethodKeyboardGrabV2Error> {
+        let kb_state = seat.effective_kb_state.get();
+        let kb_state = kb_state.borrow();
+        self.send_keymap(kb_state.keymap())?;
+        let (rate, delay) = seat.repeat_rate.get();
+        self.send_repeat_info(rate, delay);
+        self.send_modifiers(&kb_state.mods());
+        Ok(())
+    }
+
+    pub fn send_keymap(&self, keymap: &XkbKeymap) -> Result<(), ZwpInputMethodKeyboardGrabV2Error> {
+        let fd = copy_keymap_fd(keymap)?;
+        self.client.event(Keymap {
+            self_id: self.id,
+            format: wl_keyboard::XKB_V1,
+            fd,
+            size: keymap.map_len as _,
+        });
+        Ok(())
+    }
+
+    /// Forwards a key event to the input method.
+    ///
+    /// Releases of keys that were pressed before the grab became active are not
+    /// forwarded. Returns whether the event was forwarded.
+    pub fn on_key(&self, time_usec: u64, key: u32, state: u32) -> bool {
+        let forward = match state {
+            wl_keyboard::PRESSED => self.pressed_keys.borrow_mut().insert(key),
+            _ => self.pressed_keys.borrow_mut().remove(&key),
+        };
+        if forward {
+            self.client.event(Key {
+                self_id: self.id,
+                serial: self.client.next_serial(),
+                time: (time_usec / 1000) as _,
+                key,
+                state,
+            });
+        }
+        forward
+    }
+
+    pub fn send_modifiers(&self, mods: &ModifierState) {
+        self.client.event(Modifiers {
+            self_id: self.id,
+            serial: self.client.next_serial(),
+            mods_depressed: mods.mods_depressed,
+            mods_latched: mods.mods_latched,
+            mods_locked: mods.mods_locked,
+            group: mods.group,
+        });
+    }
+
+    pub fn send_repeat_info(&self, rate: i32, delay: i32) {
+        self.client.event(RepeatInfo {
+            self_id: self.id,
+            rate,
+            delay,
+        });
+    }
+
+    fn release(&self, parser: MsgParser<'_, '_>) -> Result<(), ZwpInputMethodKeyboardGrabV2Error> {
+        let _req: Release = self.client.parse(self, parser)?;
+        self.input_method.remove_grab(self);
+        self.client.remove_obj(self)?;
+        Ok(())
+    }
+}
+
+object_base! {
+    ZwpInputMethodKeyboardGrabV2;
+
+    RELEASE => release,
+}
+
+impl Object for ZwpInputMethodKeyboardGrabV2 {
+    fn num_requests(&self) -> u32 {
+        RELEASE + 1
+    }
+
+    fn break_loops(&self) {
+        self.input_method.remove_grab(self);
+    }
+}
+
+simple_add_obj!(ZwpInputMethodKeyboardGrabV2);
+
+#[derive(Debug, Error)]
+pub enum ZwpInputMethodKeyboardGrabV2Error {
+    #[error(transparent)]
+    ClientError(Box<ClientError>),
+    #[error("Parsing failed")]
+    MsgParserError(#[source] Box<MsgParserError>),
+    #[error(transparent)]
+    WlKeyboardError(Box<WlKeyboardError>),
+}
+efrom!(ZwpInputMethodKeyboardGrabV2Error, ClientError);
+efrom!(ZwpInputMethodKeyboardGrabV2Error, MsgParserError);
+efrom!(ZwpInputMethodKeyboardGrabV2Error, WlKeyboardError);
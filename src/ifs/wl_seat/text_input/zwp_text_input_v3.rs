@@ -0,0 +1,242 @@
+use {
+    crate::{
+        client::{Client, ClientError},
+        ifs::{wl_seat::WlSeatGlobal, wl_surface::WlSurface},
+        leaks::Tracker,
+        object::Object,
+        rect::Rect,
+        utils::{
+            buffd::{MsgParser, MsgParserError},
+            clonecell::CloneCell,
+            numcell::NumCell,
+        },
+        wire::{zwp_text_input_v3::*, ZwpTextInputV3Id},
+    },
+    std::{cell::RefCell, rc::Rc},
+    thiserror::Error,
+};
+
+pub const CHANGE_CAUSE_INPUT_METHOD: u32 = 0;
+
+#[derive(Clone)]
+pub struct SurroundingText {
+    pub text: String,
+    pub cursor: u32,
+    pub anchor: u32,
+}
+
+#[derive(Clone)]
+pub struct TextInputState {
+    pub enabled: bool,
+    pub surrounding_text: Option<SurroundingText>,
+    pub change_cause: u32,
+    pub hint: u32,
+    pub purpose: u32,
+    pub cursor_rect: Rect,
+}
+
+impl Default for TextInputState {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            surrounding_text: None,
+            change_cause: CHANGE_CAUSE_INPUT_METHOD,
+            hint: 0,
+            purpose: 0,
+            cursor_rect: Default::default(),
+        }
+    }
+}
+
+pub struct ZwpTextInputV3 {
+    pub id: ZwpTextInputV3Id,
+    pub client: Rc<Client>,
+    pub seat: Rc<WlSeatGlobal>,
+    pub tracker: Tracker<Self>,
+    pub surface: CloneCell<Option<Rc<WlSurface>>>,
+    pending: RefCell<TextInputState>,
+    pub current: RefCell<TextInputState>,
+    num_commits: NumCell<u32>,
+}
+
+impl ZwpTextInputV3 {
+    pub fn new(id: ZwpTextInputV3Id, client: &Rc<Client>, seat: &Rc<WlSeatGlobal>) -> Self {
+        Self {
+            id,
+            client: client.clone(),
+            seat: seat.clone(),
+            tracker: Default::default(),
+            surface: Default::default(),
+            pending: Default::default(),
+            current: Default::default(),
+            num_commits: NumCell::new(0),
+        }
+    }
+
+    pub fn send_enter(&self, surface: &Rc<WlSurface>) {
+        self.surface.set(Some(surface.clone()));
+        self.client.event(Enter {
+            self_id: self.id,
+            surface: surface.id,
+        });
+    }
+
+    pub fn send_leave(&self, surface: &WlSurface) {
+        self.surface.take();
+        self.client.event(Leave {
+            self_id: self.id,
+            surface: surface.id,
+        });
+    }
+
+    pub fn send_preedit_string(&self, text: Option<&str>, cursor_begin: i32, cursor_end: i32) {
+        self.client.event(PreeditString {
+            self_id: self.id,
+            text,
+            cursor_begin,
+            cursor_end,
+        });
+    }
+
+    pub fn send_commit_string(&self, text: Option<&str>) {
+        self.client.event(CommitString {
+            self_id: self.id,
+            text,
+        });
+    }
+
+    pub fn send_delete_surrounding_text(&self, before_length: u32, after_length: u32) {
+        self.client.event(DeleteSurroundingText {
+            self_id: self.id,
+            before_length,
+            after_length,
+        });
+    }
+
+    pub fn send_done(&self) {
+        self.client.event(Done {
+            self_id: self.id,
+            serial: self.num_commits.get(),
+        });
+    }
+
+    fn destroy(&self, parser: MsgParser<'_, '_>) -> Result<(), ZwpTextInputV3Error> {
+        let _req: Destroy = self.client.parse(self, parser)?;
+        self.detach();
+        self.client.remove_obj(self)?;
+        Ok(())
+    }
+
+    fn enable(&self, parser: MsgParser<'_, '_>) -> Result<(), ZwpTextInputV3Error> {
+        let _req: Enable = self.client.parse(self, parser)?;
+        *self.pending.borrow_mut() = TextInputState {
+            enabled: true,
+            ..Default::default()
+        };
+        Ok(())
+    }
+
+    fn disable(&self, parser: MsgParser<'_, '_>) -> Result<(), ZwpTextInputV3Error> {
+        let _req: Disable = self.client.parse(self, parser)?;
+        self.pending.borrow_mut().enabled = false;
+        Ok(())
+    }
+
+    fn set_surrounding_text(&self, parser: MsgParser<'_, '_>) -> Result<(), ZwpTextInputV3Error> {
+        let req: SetSurroundingText = self.client.parse(self, parser)?;
+        let len = req.text.len();
+        if req.cursor < 0
+            || req.cursor as usize > len
+            || req.anchor < 0
+            || req.anchor as usize > len
+        {
+            return Err(ZwpTextInputV3Error::InvalidCursor);
+        }
+        self.pending.borrow_mut().surrounding_text = Some(SurroundingText {
+            text: req.text.to_string(),
+            cursor: req.cursor as _,
+            anchor: req.anchor as _,
+        });
+        Ok(())
+    }
+
+    fn set_text_change_cause(&self, parser: MsgParser<'_, '_>) -> Result<(), ZwpTextInputV3Error> {
+        let req: SetTextChangeCause = self.client.parse(self, parser)?;
+        self.pending.borrow_mut().change_cause = req.cause;
+        Ok(())
+    }
+
+    fn set_content_type(&self, parser: MsgParser<'_, '_>) -> Result<(), ZwpTextInputV3Error> {
+        let req: SetContentType = self.client.parse(self, parser)?;
+        let mut pending = self.pending.borrow_mut();
+        pending.hint = req.hint;
+        pending.purpose = req.purpose;
+        Ok(())
+    }
+
+    fn set_cursor_rectangle(&self, parser: MsgParser<'_, '_>) -> Result<(), ZwpTextInputV3Error> {
+        let req: SetCursorRectangle = self.client.parse(self, parser)?;
+        let rect = match Rect::new_sized(req.x, req.y, req.width, req.height) {
+            Some(r) => r,
+            _ => return Err(ZwpTextInputV3Error::InvalidRectangle),
+        };
+        self.pending.borrow_mut().cursor_rect = rect;
+        Ok(())
+    }
+
+    fn commit(self: &Rc<Self>, parser: MsgParser<'_, '_>) -> Result<(), ZwpTextInputV3Error> {
+        let _req: Commit = self.client.parse(&**self, parser)?;
+        self.num_commits.fetch_add(1);
+        {
+            let mut pending = self.pending.borrow_mut();
+            *self.current.borrow_mut() = pending.clone();
+            pending.change_cause = CHANGE_CAUSE_INPUT_METHOD;
+        }
+        self.seat.text_input_committed(self);
+        Ok(())
+    }
+
+    fn detach(&self) {
+        self.seat.remove_text_input(self);
+        self.surface.take();
+    }
+}
+
+object_base! {
+    ZwpTextInputV3;
+
+    DESTROY => destroy,
+    ENABLE => enable,
+    DISABLE => disable,
+    SET_SURROUNDING_TEXT => set_surrounding_text,
+    SET_TEXT_CHANGE_CAUSE => set_text_change_cause,
+    SET_CONTENT_TYPE => set_content_type,
+    SET_CURSOR_RECTANGLE => set_cursor_rectangle,
+    COMMIT => commit,
+}
+
+impl Object for ZwpTextInputV3 {
+    fn num_requests(&self) -> u32 {
+        COMMIT + 1
+    }
+
+    fn break_loops(&self) {
+        self.detach();
+    }
+}
+
+simple_add_obj!(ZwpTextInputV3);
+
+#[derive(Debug, Error)]
+pub enum ZwpTextInputV3Error {
+    #[error(transparent)]
+    ClientError(Box<ClientError>),
+    #[error("Parsing failed")]
+    MsgParserError(#[source] Box<MsgParserError>),
+    #[error("The cursor or anchor lies outside the surrounding text")]
+    InvalidCursor,
+    #[error("The cursor rectangle has a negative size")]
+    InvalidRectangle,
+}
+efrom!(ZwpTextInputV3Error, ClientError);
+efrom!(ZwpTextInputV3Error, MsgParserError);
@@ -25,7 +25,6 @@ pub const HORIZONTAL_SCROLL: u32 = 1;
 pub const WHEEL: u32 = 0;
 pub const FINGER: u32 = 1;
 pub const CONTINUOUS: u32 = 2;
-#[allow(dead_code)]
 pub const WHEEL_TILT: u32 = 3;
 
 pub const POINTER_FRAME_SINCE_VERSION: u32 = 5;
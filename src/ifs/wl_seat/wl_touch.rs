@@ -1,7 +1,8 @@
 use {
     crate::{
         client::ClientError,
-        ifs::wl_seat::WlSeat,
+        fixed::Fixed,
+        ifs::{wl_seat::WlSeat, wl_surface::WlSurface},
         leaks::Tracker,
         object::Object,
         utils::buffd::{MsgParser, MsgParserError},
@@ -41,8 +42,56 @@ impl WlTouch {
         }
     }
 
+    pub fn send_down(
+        &self,
+        serial: u32,
+        time_usec: u64,
+        surface: &WlSurface,
+        id: i32,
+        x: Fixed,
+        y: Fixed,
+    ) {
+        self.seat.client.event(Down {
+            self_id: self.id,
+            serial,
+            time: (time_usec / 1000) as u32,
+            surface: surface.id,
+            id,
+            x,
+            y,
+        })
+    }
+
+    pub fn send_up(&self, serial: u32, time_usec: u64, id: i32) {
+        self.seat.client.event(Up {
+            self_id: self.id,
+            serial,
+            time: (time_usec / 1000) as u32,
+            id,
+        })
+    }
+
+    pub fn send_motion(&self, time_usec: u64, id: i32, x: Fixed, y: Fixed) {
+        self.seat.client.event(Motion {
+            self_id: self.id,
+            time: (time_usec / 1000) as u32,
+            id: id as u32,
+            x,
+            y,
+        })
+    }
+
+    pub fn send_frame(&self) {
+        self.seat.client.event(Frame { self_id: self.id })
+    }
+
+    pub fn send_cancel(&self) {
+        self.seat.client.event(Cancel { self_id: self.id })
+    }
+
     fn release(&self, parser: MsgParser<'_, '_>) -> Result<(), WlTouchError> {
         let _req: Release = self.seat.client.parse(self, parser)?;
+        self.seat.touches.remove(&self.id);
         self.seat.client.remove_obj(self)?;
         Ok(())
     }
@@ -0,0 +1,227 @@
+use {
+    crate::{
+        backend::{AxisSource as BackendAxisSource, InputEvent, KeyState, ScrollAxis, AXIS_120},
+        client::{Client, ClientError},
+        fixed::Fixed,
+        ifs::{
+            wl_output::WlOutputGlobal,
+            wl_seat::{wl_pointer, WlSeatGlobal},
+        },
+        leaks::Tracker,
+        object::Object,
+        utils::buffd::{MsgParser, MsgParserError},
+        wire::{zwlr_virtual_pointer_v1::*, ZwlrVirtualPointerV1Id},
+    },
+    std::{cell::Cell, rc::Rc},
+    thiserror::Error,
+};
+
+pub struct ZwlrVirtualPointerV1 {
+    pub id: ZwlrVirtualPointerV1Id,
+    pub client: Rc<Client>,
+    pub seat: Rc<WlSeatGlobal>,
+    pub tracker: Tracker<Self>,
+    /// The output that absolute motion events are mapped to.
+    pub output: Option<Rc<WlOutputGlobal>>,
+    last_time_usec: Cell<u64>,
+}
+
+impl ZwlrVirtualPointerV1 {
+    pub fn new(
+        id: ZwlrVirtualPointerV1Id,
+        client: &Rc<Client>,
+        seat: &Rc<WlSeatGlobal>,
+        output: Option<Rc<WlOutputGlobal>>,
+    ) -> Self {
+        Self {
+            id,
+            client: client.clone(),
+            seat: seat.clone(),
+            tracker: Default::default(),
+            output,
+            last_time_usec: Cell::new(0),
+        }
+    }
+
+    fn time_usec(&self, time: u32) -> u64 {
+        let time_usec = time as u64 * 1000;
+        self.last_time_usec.set(time_usec);
+        time_usec
+    }
+
+    fn motion(&self, parser: MsgParser<'_, '_>) -> Result<(), ZwlrVirtualPointerV1Error> {
+        let req: Motion = self.client.parse(self, parser)?;
+        self.seat.virtual_event(InputEvent::Motion {
+            time_usec: self.time_usec(req.time),
+            dx: req.dx,
+            dy: req.dy,
+            dx_unaccelerated: req.dx,
+            dy_unaccelerated: req.dy,
+        });
+        Ok(())
+    }
+
+    fn motion_absolute(&self, parser: MsgParser<'_, '_>) -> Result<(), ZwlrVirtualPointerV1Error> {
+        let req: MotionAbsolute = self.client.parse(self, parser)?;
+        let time_usec = self.time_usec(req.time);
+        if req.x_extent == 0 || req.y_extent == 0 {
+            return Err(ZwlrVirtualPointerV1Error::EmptyExtents);
+        }
+        let area = match &self.output {
+            Some(output) if !output.destroyed.get() => output.pos.get(),
+            Some(_) => return Ok(()),
+            _ => self.client.state.root.extents.get(),
+        };
+        let x = area.x1() as f64 + req.x as f64 * area.width() as f64 / req.x_extent as f64;
+        let y = area.y1() as f64 + req.y as f64 * area.height() as f64 / req.y_extent as f64;
+        let (xi, yi) = (x.round() as i32, y.round() as i32);
+        let output = match &self.output {
+            Some(output) => output.clone(),
+            _ => {
+                let outputs = self.client.state.outputs.lock();
+                let output = outputs
+                    .values()
+                    .find(|o| o.node.global.pos.get().contains(xi, yi));
+                match output {
+                    Some(o) => o.node.global.clone(),
+                    _ => return Ok(()),
+                }
+            }
+        };
+        let pos = output.pos.get();
+        self.seat.virtual_event(InputEvent::ConnectorPosition {
+            time_usec,
+            connector: output.connector.connector.id(),
+            x: Fixed::from_f64(x - pos.x1() as f64),
+            y: Fixed::from_f64(y - pos.y1() as f64),
+        });
+        Ok(())
+    }
+
+    fn button(&self, parser: MsgParser<'_, '_>) -> Result<(), ZwlrVirtualPointerV1Error> {
+        let req: Button = self.client.parse(self, parser)?;
+        let state = match req.state {
+            wl_pointer::RELEASED => KeyState::Released,
+            wl_pointer::PRESSED => KeyState::Pressed,
+            _ => return Err(ZwlrVirtualPointerV1Error::UnknownButtonState(req.state)),
+        };
+        self.seat.virtual_event(InputEvent::Button {
+            time_usec: self.time_usec(req.time),
+            button: req.button,
+            state,
+        });
+        Ok(())
+    }
+
+    fn axis(&self, parser: MsgParser<'_, '_>) -> Result<(), ZwlrVirtualPointerV1Error> {
+        let req: Axis = self.client.parse(self, parser)?;
+        self.time_usec(req.time);
+        self.seat.virtual_event(InputEvent::AxisPx {
+            dist: req.value,
+            axis: parse_axis(req.axis)?,
+        });
+        Ok(())
+    }
+
+    fn frame(&self, parser: MsgParser<'_, '_>) -> Result<(), ZwlrVirtualPointerV1Error> {
+        let _req: Frame = self.client.parse(self, parser)?;
+        self.seat.virtual_event(InputEvent::AxisFrame {
+            time_usec: self.last_time_usec.get(),
+        });
+        Ok(())
+    }
+
+    fn axis_source(&self, parser: MsgParser<'_, '_>) -> Result<(), ZwlrVirtualPointerV1Error> {
+        let req: AxisSource = self.client.parse(self, parser)?;
+        let source = match req.axis_source {
+            wl_pointer::WHEEL | wl_pointer::WHEEL_TILT => BackendAxisSource::Wheel,
+            wl_pointer::FINGER => BackendAxisSource::Finger,
+            wl_pointer::CONTINUOUS => BackendAxisSource::Continuous,
+            _ => {
+                return Err(ZwlrVirtualPointerV1Error::UnknownAxisSource(
+                    req.axis_source,
+                ))
+            }
+        };
+        self.seat.virtual_event(InputEvent::AxisSource { source });
+        Ok(())
+    }
+
+    fn axis_stop(&self, parser: MsgParser<'_, '_>) -> Result<(), ZwlrVirtualPointerV1Error> {
+        let req: AxisStop = self.client.parse(self, parser)?;
+        self.time_usec(req.time);
+        self.seat.virtual_event(InputEvent::AxisStop {
+            axis: parse_axis(req.axis)?,
+        });
+        Ok(())
+    }
+
+    fn axis_discrete(&self, parser: MsgParser<'_, '_>) -> Result<(), ZwlrVirtualPointerV1Error> {
+        let req: AxisDiscrete = self.client.parse(self, parser)?;
+        self.time_usec(req.time);
+        let axis = parse_axis(req.axis)?;
+        self.seat.virtual_event(InputEvent::AxisPx {
+            dist: req.value,
+            axis,
+        });
+        self.seat.virtual_event(InputEvent::Axis120 {
+            dist: req.discrete.saturating_mul(AXIS_120),
+            axis,
+        });
+        Ok(())
+    }
+
+    fn destroy(&self, parser: MsgParser<'_, '_>) -> Result<(), ZwlrVirtualPointerV1Error> {
+        let _req: Destroy = self.client.parse(self, parser)?;
+        self.client.remove_obj(self)?;
+        Ok(())
+    }
+}
+
+fn parse_axis(axis: u32) -> Result<ScrollAxis, ZwlrVirtualPointerV1Error> {
+    match axis {
+        wl_pointer::VERTICAL_SCROLL => Ok(ScrollAxis::Vertical),
+        wl_pointer::HORIZONTAL_SCROLL => Ok(ScrollAxis::Horizontal),
+        _ => Err(ZwlrVirtualPointerV1Error::UnknownAxis(axis)),
+    }
+}
+
+object_base! {
+    ZwlrVirtualPointerV1;
+
+    MOTION => motion,
+    MOTION_ABSOLUTE => motion_absolute,
+    BUTTON => button,
+    AXIS => axis,
+    FRAME => frame,
+    AXIS_SOURCE => axis_source,
+    AXIS_STOP => axis_stop,
+    AXIS_DISCRETE => axis_discrete,
+    DESTROY => destroy,
+}
+
+impl Object for ZwlrVirtualPointerV1 {
+    fn num_requests(&self) -> u32 {
+        DESTROY + 1
+    }
+}
+
+simple_add_obj!(ZwlrVirtualPointerV1);
+
+#[derive(Debug, Error)]
+pub enum ZwlrVirtualPointerV1Error {
+    #[error(transparent)]
+    ClientError(Box<ClientError>),
+    #[error("Parsing failed")]
+    MsgParserError(#[source] Box<MsgParserError>),
+    #[error("The extents of an absolute motion event are empty")]
+    EmptyExtents,
+    #[error("Unknown button state {0}")]
+    UnknownButtonState(u32),
+    #[error("Unknown axis {0}")]
+    UnknownAxis(u32),
+    #[error("Unknown axis source {0}")]
+    UnknownAxisSource(u32),
+}
+efrom!(ZwlrVirtualPointerV1Error, ClientError);
+efrom!(ZwlrVirtualPointerV1Error, MsgParserError);
@@ -0,0 +1,112 @@
+use {
+    crate::{
+        client::{Client, ClientError},
+        ifs::{wl_seat::WlSeatGlobal, wl_surface::WlSurface},
+        leaks::Tracker,
+        object::Object,
+        utils::buffd::{MsgParser, MsgParserError},
+        wire::{zwp_keyboard_shortcuts_inhibitor_v1::*, ZwpKeyboardShortcutsInhibitorV1Id},
+    },
+    std::{cell::Cell, rc::Rc},
+    thiserror::Error,
+};
+
+pub struct ZwpKeyboardShortcutsInhibitorV1 {
+    pub id: ZwpKeyboardShortcutsInhibitorV1Id,
+    pub client: Rc<Client>,
+    pub seat: Rc<WlSeatGlobal>,
+    pub surface: Rc<WlSurface>,
+    pub tracker: Tracker<Self>,
+    /// The decision of the config. The config is asked the first time the inhibitor
+    /// would become active.
+    pub allowed: Cell<Option<bool>>,
+    pub active: Cell<bool>,
+}
+
+impl ZwpKeyboardShortcutsInhibitorV1 {
+    pub fn activate(self: &Rc<Self>) {
+        if self.active.get() {
+            return;
+        }
+        let allowed = match self.allowed.get() {
+            Some(allowed) => allowed,
+            _ => {
+                let allowed = match self.client.state.config.get() {
+                    Some(config) => {
+                        let app_id = match self.surface.get_toplevel() {
+                            Some(tl) => tl.tl_data().app_id.borrow().clone(),
+                            _ => String::new(),
+                        };
+                        config.shortcuts_inhibit_request(self.seat.id(), app_id)
+                    }
+                    _ => true,
+                };
+                self.allowed.set(Some(allowed));
+                allowed
+            }
+        };
+        if !allowed {
+            return;
+        }
+        if let Some(old) = self.seat.shortcuts_inhibitor.set(Some(self.clone())) {
+            old.deactivate();
+        }
+        self.active.set(true);
+        self.client.event(Active { self_id: self.id });
+    }
+
+    pub fn deactivate(&self) {
+        if !self.active.replace(false) {
+            return;
+        }
+        if let Some(current) = self.seat.shortcuts_inhibitor.get() {
+            if current.id == self.id && current.client.id == self.client.id {
+                self.seat.shortcuts_inhibitor.take();
+            }
+        }
+        self.client.event(Inactive { self_id: self.id });
+    }
+
+    fn detach(&self) {
+        self.deactivate();
+        self.surface.shortcuts_inhibitors.remove(&self.seat.id());
+    }
+
+    fn destroy(
+        &self,
+        parser: MsgParser<'_, '_>,
+    ) -> Result<(), ZwpKeyboardShortcutsInhibitorV1Error> {
+        let _req: Destroy = self.client.parse(self, parser)?;
+        self.detach();
+        self.client.remove_obj(self)?;
+        Ok(())
+    }
+}
+
+object_base! {
+    ZwpKeyboardShortcutsInhibitorV1;
+
+    DESTROY => destroy,
+}
+
+impl Object for ZwpKeyboardShortcutsInhibitorV1 {
+    fn num_requests(&self) -> u32 {
+        DESTROY + 1
+    }
+
+    fn break_loops(&self) {
+        self.detach();
+    }
+}
+
+simple_add_obj!(ZwpKeyboardShortcutsInhibitorV1);
+
+#[derive(Debug, Error)]
+pub enum ZwpKeyboardShortcutsInhibitorV1Error {
+    #[error(transparent)]
+    ClientError(Box<ClientError>),
+    #[error("Parsing failed")]
+    MsgParserError(#[source] Box<MsgParserError>),
+}
+efrom!(ZwpKeyboardShortcutsInhibitorV1Error, ClientError);
+efrom!(ZwpKeyboardShortcutsInhibitorV1Error, MsgParserError);
@@ -0,0 +1,109 @@
+use {
+    crate::{
+        client::{Client, ClientError},
+        globals::{Global, GlobalName},
+        ifs::wl_seat::zwp_virtual_keyboard_v1::ZwpVirtualKeyboardV1,
+        leaks::Tracker,
+        object::Object,
+        utils::buffd::{MsgParser, MsgParserError},
+        wire::{zwp_virtual_keyboard_manager_v1::*, ZwpVirtualKeyboardManagerV1Id},
+    },
+    std::rc::Rc,
+    thiserror::Error,
+};
+
+pub struct ZwpVirtualKeyboardManagerV1Global {
+    pub name: GlobalName,
+}
+
+pub struct ZwpVirtualKeyboardManagerV1 {
+    pub id: ZwpVirtualKeyboardManagerV1Id,
+    pub client: Rc<Client>,
+    pub tracker: Tracker<Self>,
+}
+
+impl ZwpVirtualKeyboardManagerV1Global {
+    pub fn new(name: GlobalName) -> Self {
+        Self { name }
+    }
+
+    fn bind_(
+        self: Rc<Self>,
+        id: ZwpVirtualKeyboardManagerV1Id,
+        client: &Rc<Client>,
+        _version: u32,
+    ) -> Result<(), ZwpVirtualKeyboardManagerV1Error> {
+        let obj = Rc::new(ZwpVirtualKeyboardManagerV1 {
+            id,
+            client: client.clone(),
+            tracker: Default::default(),
+        });
+        track!(client, obj);
+        client.add_client_obj(&obj)?;
+        Ok(())
+    }
+}
+
+global_base!(
+    ZwpVirtualKeyboardManagerV1Global,
+    ZwpVirtualKeyboardManagerV1,
+    ZwpVirtualKeyboardManagerV1Error
+);
+
+impl Global for ZwpVirtualKeyboardManagerV1Global {
+    fn singleton(&self) -> bool {
+        true
+    }
+
+    fn version(&self) -> u32 {
+        1
+    }
+
+    fn secure(&self) -> bool {
+        true
+    }
+}
+
+simple_add_global!(ZwpVirtualKeyboardManagerV1Global);
+
+impl ZwpVirtualKeyboardManagerV1 {
+    fn create_virtual_keyboard(
+        &self,
+        parser: MsgParser<'_, '_>,
+    ) -> Result<(), ZwpVirtualKeyboardManagerV1Error> {
+        let req: CreateVirtualKeyboard = self.client.parse(self, parser)?;
+        let seat = self.client.lookup(req.seat)?;
+        let kb = Rc::new(ZwpVirtualKeyboardV1::new(
+            req.id,
+            &self.client,
+            &seat.global,
+        ));
+        track!(self.client, kb);
+        self.client.add_client_obj(&kb)?;
+        Ok(())
+    }
+}
+
+object_base! {
+    ZwpVirtualKeyboardManagerV1;
+
+    CREATE_VIRTUAL_KEYBOARD => create_virtual_keyboard,
+}
+
+impl Object for ZwpVirtualKeyboardManagerV1 {
+    fn num_requests(&self) -> u32 {
+        CREATE_VIRTUAL_KEYBOARD + 1
+    }
+}
+
+simple_add_obj!(ZwpVirtualKeyboardManagerV1);
+
+#[derive(Debug, Error)]
+pub enum ZwpVirtualKeyboardManagerV1Error {
+    #[error(transparent)]
+    ClientError(Box<ClientError>),
+    #[error("Parsing failed")]
+    MsgParserError(#[source] Box<MsgParserError>),
+}
+efrom!(ZwpVirtualKeyboardManagerV1Error, ClientError);
+efrom!(ZwpVirtualKeyboardManagerV1Error, MsgParserError);
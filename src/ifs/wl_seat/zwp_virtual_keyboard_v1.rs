@@ -0,0 +1,204 @@
+use {
+    crate::{
+        backend::KeyState,
+        client::{Client, ClientError},
+        ifs::wl_seat::{wl_keyboard, WlSeatGlobal},
+        leaks::Tracker,
+        object::Object,
+        time::now_usec,
+        utils::{
+            buffd::{MsgParser, MsgParserError},
+            clonecell::CloneCell,
+            oserror::OsError,
+            pread::pread_all,
+        },
+        wire::{zwp_virtual_keyboard_v1::*, ZwpVirtualKeyboardV1Id},
+        xkbcommon::{XkbCommonError, XkbState},
+    },
+    ahash::AHashSet,
+    std::{cell::RefCell, mem, rc::Rc},
+    thiserror::Error,
+    uapi::OwnedFd,
+};
+
+/// The largest keymap that clients can upload.
+const MAX_KEYMAP_SIZE: u32 = 1024 * 1024;
+
+pub struct ZwpVirtualKeyboardV1 {
+    pub id: ZwpVirtualKeyboardV1Id,
+    pub client: Rc<Client>,
+    pub seat: Rc<WlSeatGlobal>,
+    pub tracker: Tracker<Self>,
+    kb_state: CloneCell<Option<Rc<RefCell<XkbState>>>>,
+    pressed_keys: RefCell<AHashSet<u32>>,
+}
+
+impl ZwpVirtualKeyboardV1 {
+    pub fn new(id: ZwpVirtualKeyboardV1Id, client: &Rc<Client>, seat: &Rc<WlSeatGlobal>) -> Self {
+        Self {
+            id,
+            client: client.clone(),
+            seat: seat.clone(),
+            tracker: Default::default(),
+            kb_state: Default::default(),
+            pressed_keys: Default::default(),
+        }
+    }
+
+    fn read_keymap(fd: &OwnedFd, size: u32) -> Result<Vec<u8>, ZwpVirtualKeyboardV1Error> {
+        if size > MAX_KEYMAP_SIZE {
+            return Err(ZwpVirtualKeyboardV1Error::KeymapTooLarge(size));
+        }
+        let mut buf = vec![0; size as usize];
+        let len = pread_all(fd.raw(), &mut buf).map_err(ZwpVirtualKeyboardV1Error::ReadKeymap)?;
+        buf.truncate(len);
+        while buf.last() == Some(&0) {
+            buf.pop();
+        }
+        Ok(buf)
+    }
+
+    fn keymap(&self, parser: MsgParser<'_, '_>) -> Result<(), ZwpVirtualKeyboardV1Error> {
+        let req: Keymap = self.client.parse(self, parser)?;
+        if req.format != wl_keyboard::XKB_V1 {
+            return Err(ZwpVirtualKeyboardV1Error::UnsupportedFormat(req.format));
+        }
+        let buf = Self::read_keymap(&req.fd, req.size)?;
+        let map = match std::str::from_utf8(&buf) {
+            Ok(m) => m,
+            Err(_) => return Err(ZwpVirtualKeyboardV1Error::NonUtf8Keymap),
+        };
+        let keymap = self.client.state.xkb_ctx.keymap_from_str(map)?;
+        let kb_state = Rc::new(RefCell::new(keymap.state()?));
+        self.release_keys();
+        if let Some(old) = self.kb_state.set(Some(kb_state)) {
+            self.seat.remove_kb_state(&old);
+        }
+        Ok(())
+    }
+
+    fn key(&self, parser: MsgParser<'_, '_>) -> Result<(), ZwpVirtualKeyboardV1Error> {
+        let req: Key = self.client.parse(self, parser)?;
+        let kb_state = match self.kb_state.get() {
+            Some(s) => s,
+            _ => return Err(ZwpVirtualKeyboardV1Error::NoKeymap),
+        };
+        let state = match req.state {
+            wl_keyboard::RELEASED => {
+                self.pressed_keys.borrow_mut().remove(&req.key);
+                KeyState::Released
+            }
+            wl_keyboard::PRESSED => {
+                self.pressed_keys.borrow_mut().insert(req.key);
+                KeyState::Pressed
+            }
+            _ => return Err(ZwpVirtualKeyboardV1Error::UnknownKeyState(req.state)),
+        };
+        self.seat.key_event_with_state(
+            req.time as u64 * 1000,
+            req.key,
+            state,
+            &kb_state,
+            Some(self.client.id),
+        );
+        self.seat.mark_last_active();
+        self.client.state.input_occurred();
+        Ok(())
+    }
+
+    fn modifiers(&self, parser: MsgParser<'_, '_>) -> Result<(), ZwpVirtualKeyboardV1Error> {
+        let req: Modifiers = self.client.parse(self, parser)?;
+        let kb_state = match self.kb_state.get() {
+            Some(s) => s,
+            _ => return Err(ZwpVirtualKeyboardV1Error::NoKeymap),
+        };
+        let changed = kb_state.borrow_mut().set_mods(
+            req.mods_depressed,
+            req.mods_latched,
+            req.mods_locked,
+            req.group,
+        );
+        if changed.is_some() {
+            self.seat.mods_event_with_state(&kb_state, self.client.id);
+        }
+        Ok(())
+    }
+
+    fn destroy(&self, parser: MsgParser<'_, '_>) -> Result<(), ZwpVirtualKeyboardV1Error> {
+        let _req: Destroy = self.client.parse(self, parser)?;
+        self.detach();
+        self.client.remove_obj(self)?;
+        Ok(())
+    }
+
+    fn release_keys(&self) {
+        let kb_state = match self.kb_state.get() {
+            Some(s) => s,
+            _ => return,
+        };
+        let keys = mem::take(&mut *self.pressed_keys.borrow_mut());
+        let time_usec = now_usec();
+        for key in keys {
+            self.seat.key_event_with_state(
+                time_usec,
+                key,
+                KeyState::Released,
+                &kb_state,
+                Some(self.client.id),
+            );
+        }
+    }
+
+    fn detach(&self) {
+        self.release_keys();
+        if let Some(kb_state) = self.kb_state.take() {
+            self.seat.remove_kb_state(&kb_state);
+        }
+    }
+}
+
+object_base! {
+    ZwpVirtualKeyboardV1;
+
+    KEYMAP => keymap,
+    KEY => key,
+    MODIFIERS => modifiers,
+    DESTROY => destroy,
+}
+
+impl Object for ZwpVirtualKeyboardV1 {
+    fn num_requests(&self) -> u32 {
+        DESTROY + 1
+    }
+
+    fn break_loops(&self) {
+        self.detach();
+    }
+}
+
+simple_add_obj!(ZwpVirtualKeyboardV1);
+
+#[derive(Debug, Error)]
+pub enum ZwpVirtualKeyboardV1Error {
+    #[error(transparent)]
+    ClientError(Box<ClientError>),
+    #[error("Parsing failed")]
+    MsgParserError(#[source] Box<MsgParserError>),
+    #[error(transparent)]
+    XkbCommonError(Box<XkbCommonError>),
+    #[error("Unsupported keymap format {0}")]
+    UnsupportedFormat(u32),
+    #[error("Could not read the keymap")]
+    ReadKeymap(#[source] OsError),
+    #[error("The keymap size {0} is larger than {MAX_KEYMAP_SIZE}")]
+    KeymapTooLarge(u32),
+    #[error("The keymap is not valid UTF-8")]
+    NonUtf8Keymap,
+    #[error("The client has not yet set a keymap")]
+    NoKeymap,
+    #[error("Unknown key state {0}")]
+    UnknownKeyState(u32),
+}
+efrom!(ZwpVirtualKeyboardV1Error, ClientError);
+efrom!(ZwpVirtualKeyboardV1Error, MsgParserError);
+efrom!(ZwpVirtualKeyboardV1Error, XkbCommonError);
@@ -2,12 +2,17 @@ pub mod cursor;
 pub mod ext_session_lock_surface_v1;
 pub mod wl_subsurface;
 pub mod wp_fractional_scale_v1;
+pub mod wp_tearing_control_v1;
 pub mod wp_viewport;
 pub mod x_surface;
 pub mod xdg_surface;
 pub mod xwayland_shell_v1;
 pub mod zwlr_layer_surface_v1;
 pub mod zwp_idle_inhibitor_v1;
+pub mod zwp_input_popup_surface_v2;
+
+#[cfg(test)]
+mod tests;
 
 use {
     crate::{
@@ -17,26 +22,27 @@ use {
         ifs::{
             wl_buffer::WlBuffer,
             wl_callback::WlCallback,
-            wl_output::{
-                TF_180, TF_270, TF_90, TF_FLIPPED, TF_FLIPPED_180, TF_FLIPPED_270, TF_FLIPPED_90,
-                TF_NORMAL,
-            },
             wl_seat::{
-                wl_pointer::PendingScroll, zwp_pointer_constraints_v1::SeatConstraint, Dnd,
-                NodeSeatState, SeatId, WlSeatGlobal,
+                wl_pointer::PendingScroll,
+                zwp_keyboard_shortcuts_inhibitor_v1::ZwpKeyboardShortcutsInhibitorV1,
+                zwp_pointer_constraints_v1::SeatConstraint, Dnd, NodeSeatState, SeatId,
+                WlSeatGlobal,
             },
             wl_surface::{
                 cursor::CursorSurface, wl_subsurface::WlSubsurface,
-                wp_fractional_scale_v1::WpFractionalScaleV1, wp_viewport::WpViewport,
+                wp_fractional_scale_v1::WpFractionalScaleV1,
+                wp_tearing_control_v1::WpTearingControlV1, wp_viewport::WpViewport,
                 x_surface::XSurface, xdg_surface::XdgSurfaceError,
                 zwlr_layer_surface_v1::ZwlrLayerSurfaceV1Error,
             },
             wp_presentation_feedback::WpPresentationFeedback,
+            zwp_linux_dmabuf_feedback_v1::{ScanoutFeedback, ZwpLinuxDmabufFeedbackV1},
         },
         leaks::Tracker,
         object::Object,
         rect::{Rect, Region},
         render::Renderer,
+        transform::Transform,
         tree::{
             FindTreeResult, FoundNode, Node, NodeId, NodeVisitor, NodeVisitorBase, OutputNode,
             ToplevelNode,
@@ -49,7 +55,10 @@ use {
             numcell::NumCell,
             smallmap::SmallMap,
         },
-        wire::{wl_surface::*, WlOutputId, WlSurfaceId, ZwpIdleInhibitorV1Id},
+        wire::{
+            wl_surface::*, WlOutputId, WlSurfaceId, ZwpIdleInhibitorV1Id,
+            ZwpLinuxDmabufFeedbackV1Id,
+        },
         xkbcommon::ModifierState,
         xwayland::XWaylandEvent,
     },
@@ -72,33 +81,6 @@ const INVALID_TRANSFORM: u32 = 1;
 #[allow(dead_code)]
 const INVALID_SIZE: u32 = 2;
 
-#[derive(Copy, Clone, Debug, PartialEq)]
-enum Transform {
-    Normal,
-    Rotate90,
-    Rotate180,
-    Rotate270,
-    Flipped,
-    Flipped90,
-    Flipped180,
-    Flipped270,
-}
-
-impl Transform {
-    fn swaps_dimensions(self) -> bool {
-        match self {
-            Transform::Normal => false,
-            Transform::Rotate90 => true,
-            Transform::Rotate180 => false,
-            Transform::Rotate270 => true,
-            Transform::Flipped => false,
-            Transform::Flipped90 => true,
-            Transform::Flipped180 => false,
-            Transform::Flipped270 => true,
-        }
-    }
-}
-
 #[derive(Default, Debug)]
 struct BufferPoint {
     x: f32,
@@ -200,6 +182,7 @@ pub enum SurfaceRole {
     ZwlrLayerSurface,
     XSurface,
     ExtSessionLockSurface,
+    InputPopup,
 }
 
 impl SurfaceRole {
@@ -213,6 +196,7 @@ impl SurfaceRole {
             SurfaceRole::ZwlrLayerSurface => "zwlr_layer_surface",
             SurfaceRole::XSurface => "xwayland surface",
             SurfaceRole::ExtSessionLockSurface => "ext_session_lock_surface",
+            SurfaceRole::InputPopup => "input_popup_surface",
         }
     }
 }
@@ -259,7 +243,14 @@ pub struct WlSurface {
     viewporter: CloneCell<Option<Rc<WpViewport>>>,
     output: CloneCell<Rc<OutputNode>>,
     fractional_scale: CloneCell<Option<Rc<WpFractionalScaleV1>>>,
+    tearing_control: CloneCell<Option<Rc<WpTearingControlV1>>>,
+    /// Whether the client prefers tearing page flips over vsync for this surface.
+    pub tearing: Cell<bool>,
+    pub dmabuf_feedback: CopyHashMap<ZwpLinuxDmabufFeedbackV1Id, Rc<ZwpLinuxDmabufFeedbackV1>>,
+    /// The formats of the device that could scan out this surface directly.
+    scanout_feedback: CloneCell<Option<Rc<ScanoutFeedback>>>,
     pub constraints: SmallMap<SeatId, Rc<SeatConstraint>, 1>,
+    pub shortcuts_inhibitors: SmallMap<SeatId, Rc<ZwpKeyboardShortcutsInhibitorV1>, 1>,
     xwayland_serial: Cell<Option<u64>>,
 }
 
@@ -340,19 +331,36 @@ impl SurfaceExt for NoneSurfaceExt {
     }
 }
 
+fn damage_rect(x: i32, y: i32, width: i32, height: i32) -> Option<Rect> {
+    Rect::new(x, y, x.saturating_add(width), y.saturating_add(height))
+}
+
+/// Divides a damage rectangle in buffer coordinates by the buffer scale, rounding outward.
+fn scale_buffer_damage(rect: Rect, scale: i32) -> Rect {
+    let rect = Rect::new(
+        rect.x1().div_euclid(scale),
+        rect.y1().div_euclid(scale),
+        rect.x2().saturating_add(scale - 1).div_euclid(scale),
+        rect.y2().saturating_add(scale - 1).div_euclid(scale),
+    );
+    rect.unwrap_or_default()
+}
+
 #[derive(Default)]
 struct PendingState {
     buffer: Cell<Option<Option<(i32, i32, Rc<WlBuffer>)>>>,
     opaque_region: Cell<Option<Option<Rc<Region>>>>,
     input_region: Cell<Option<Option<Rc<Region>>>>,
     frame_request: RefCell<Vec<Rc<WlCallback>>>,
-    damage: Cell<bool>,
+    damage: RefCell<Vec<Rect>>,
+    buffer_damage: RefCell<Vec<Rect>>,
     presentation_feedback: RefCell<Vec<Rc<WpPresentationFeedback>>>,
     src_rect: Cell<Option<Option<[Fixed; 4]>>>,
     dst_size: Cell<Option<Option<(i32, i32)>>>,
     scale: Cell<Option<i32>>,
     transform: Cell<Option<Transform>>,
     xwayland_serial: Cell<Option<u64>>,
+    tearing: Cell<Option<bool>>,
 }
 
 #[derive(Default)]
@@ -403,7 +411,12 @@ impl WlSurface {
             viewporter: Default::default(),
             output: CloneCell::new(client.state.dummy_output.get().unwrap()),
             fractional_scale: Default::default(),
+            tearing_control: Default::default(),
+            tearing: Cell::new(false),
+            dmabuf_feedback: Default::default(),
+            scanout_feedback: Default::default(),
             constraints: Default::default(),
+            shortcuts_inhibitors: Default::default(),
             xwayland_serial: Default::default(),
         }
     }
@@ -425,6 +438,20 @@ impl WlSurface {
         Ok(ext.into_xsurface().unwrap())
     }
 
+    pub fn scanout_feedback(&self) -> Option<Rc<ScanoutFeedback>> {
+        self.scanout_feedback.get()
+    }
+
+    pub fn set_scanout_feedback(&self, feedback: Option<Rc<ScanoutFeedback>>) {
+        let was_some = self.scanout_feedback.set(feedback.clone()).is_some();
+        if !was_some && feedback.is_none() {
+            return;
+        }
+        for fb in self.dmabuf_feedback.lock().values() {
+            fb.send_feedback();
+        }
+    }
+
     pub fn set_output(&self, output: &Rc<OutputNode>) {
         let old = self.output.set(output.clone());
         if old.id == output.id {
@@ -641,6 +668,7 @@ impl WlSurface {
         self.client.remove_obj(self)?;
         self.idle_inhibitors.clear();
         self.constraints.take();
+        self.shortcuts_inhibitors.take();
         Ok(())
     }
 
@@ -656,8 +684,10 @@ impl WlSurface {
     }
 
     fn damage(&self, parser: MsgParser<'_, '_>) -> Result<(), WlSurfaceError> {
-        let _req: Damage = self.parse(parser)?;
-        self.pending.damage.set(true);
+        let req: Damage = self.parse(parser)?;
+        if let Some(rect) = damage_rect(req.x, req.y, req.width, req.height) {
+            self.pending.damage.borrow_mut().push(rect);
+        }
         Ok(())
     }
 
@@ -724,6 +754,9 @@ impl WlSurface {
             viewport_changed = true;
             self.src_rect.set(src_rect);
         }
+        if let Some(tearing) = self.pending.tearing.take() {
+            self.tearing.set(tearing);
+        }
         if viewport_changed {
             if let Some(rect) = self.src_rect.get() {
                 if self.dst_size.get().is_none() {
@@ -744,6 +777,7 @@ impl WlSurface {
                 }
             }
             if let Some((dx, dy, buffer)) = buffer_change {
+                buffer.cancel_release();
                 let _ = buffer.update_texture();
                 self.buffer.set(Some(buffer));
                 self.buf_x.fetch_add(dx);
@@ -882,7 +916,8 @@ impl WlSurface {
                 .queue
                 .push(XWaylandEvent::SurfaceSerialAssigned(self.id));
         }
-        if self.need_extents_update.get() {
+        let extents_changed = self.need_extents_update.get();
+        if extents_changed {
             self.calculate_extents();
         }
         if buffer_changed || transform_changed {
@@ -892,10 +927,117 @@ impl WlSurface {
             }
         }
         ext.post_commit();
-        self.client.state.damage();
+        self.apply_damage(extents_changed);
         Ok(())
     }
 
+    fn apply_damage(&self, damage_all: bool) {
+        let damage = mem::take(self.pending.damage.borrow_mut().deref_mut());
+        let buffer_damage = mem::take(self.pending.buffer_damage.borrow_mut().deref_mut());
+        let state = &self.client.state;
+        if !self.cursors.is_empty() {
+            for (_, cursor) in &self.cursors {
+                cursor.damage();
+            }
+        } else if damage_all || !self.dnd_icons.is_empty() {
+            state.damage();
+            return;
+        } else {
+            let pos = self.buffer_abs_pos.get();
+            let bounds = pos.at_point(0, 0);
+            let buffer_damage = buffer_damage
+                .into_iter()
+                .map(|rect| self.buffer_rect_to_surface(rect));
+            for rect in damage.into_iter().chain(buffer_damage) {
+                let rect = rect.intersect(bounds);
+                state.damage_rect(rect.move_(pos.x1(), pos.y1()));
+            }
+        }
+        if !self.frame_requests.borrow().is_empty() {
+            self.output.get().global.connector.connector.damage();
+        }
+    }
+
+    /// Returns the attached buffer if it can be displayed on a hardware plane.
+    ///
+    /// This requires a dmabuf that is not transformed.
+    pub fn plane_buffer(&self) -> Option<Rc<WlBuffer>> {
+        let buffer = self.buffer.get()?;
+        buffer.dmabuf()?;
+        if self.buffer_transform.get() != Transform::Normal {
+            return None;
+        }
+        Some(buffer)
+    }
+
+    /// Returns the part of `buffer` that is displayed as `[x, y, width, height]` in 16.16
+    /// fixed-point buffer coordinates.
+    pub fn plane_src_rect(&self, buffer: &WlBuffer) -> [u64; 4] {
+        match self.src_rect.get() {
+            Some(src_rect) => {
+                let scale = self.buffer_scale.get() as f64;
+                src_rect.map(|v| (v.to_f64() * scale * 65536.0) as u64)
+            }
+            None => {
+                let (width, height) = buffer.rect.size();
+                [0, 0, (width as u64) << 16, (height as u64) << 16]
+            }
+        }
+    }
+
+    /// Returns the attached buffer if it can be displayed as-is without compositing.
+    ///
+    /// In addition to the requirements of `plane_buffer`, the buffer must be neither cropped
+    /// nor scaled and the surface must not have subsurfaces.
+    pub fn direct_scanout_buffer(&self) -> Option<Rc<WlBuffer>> {
+        if self.src_rect.get().is_some() || self.dst_size.get().is_some() {
+            return None;
+        }
+        if let Some(children) = &*self.children.borrow() {
+            if !children.subsurfaces.is_empty() {
+                return None;
+            }
+        }
+        self.plane_buffer()
+    }
+
+    /// Appends this surface and its subsurfaces to `dst` in the order in which they are
+    /// rendered.
+    pub fn collect_surfaces(self: &Rc<Self>, dst: &mut Vec<Rc<WlSurface>>) {
+        let children = self.children.borrow();
+        let collect = |list: &LinkedList<StackElement>, dst: &mut Vec<Rc<WlSurface>>| {
+            for child in list.rev_iter() {
+                if !child.pending.get() {
+                    child.sub_surface.surface.collect_surfaces(dst);
+                }
+            }
+        };
+        if let Some(children) = &*children {
+            collect(&children.below, dst);
+        }
+        dst.push(self.clone());
+        if let Some(children) = &*children {
+            collect(&children.above, dst);
+        }
+    }
+
+    /// Maps a rectangle in buffer coordinates to surface coordinates.
+    ///
+    /// If the buffer is transformed or cropped, this returns the whole surface.
+    fn buffer_rect_to_surface(&self, rect: Rect) -> Rect {
+        if self.buffer_transform.get() != Transform::Normal
+            || self.src_rect.get().is_some()
+            || self.dst_size.get().is_some()
+        {
+            return self.buffer_abs_pos.get().at_point(0, 0);
+        }
+        let scale = self.buffer_scale.get();
+        if scale == 1 {
+            return rect;
+        }
+        scale_buffer_damage(rect, scale)
+    }
+
     fn commit(self: &Rc<Self>, parser: MsgParser<'_, '_>) -> Result<(), WlSurfaceError> {
         let _req: Commit = self.parse(parser)?;
         self.do_commit(CommitContext::RootCommit)?;
@@ -904,16 +1046,8 @@ impl WlSurface {
 
     fn set_buffer_transform(&self, parser: MsgParser<'_, '_>) -> Result<(), WlSurfaceError> {
         let req: SetBufferTransform = self.parse(parser)?;
-        use Transform::*;
-        let tf = match req.transform {
-            TF_NORMAL => Normal,
-            TF_90 => Rotate90,
-            TF_180 => Rotate180,
-            TF_270 => Rotate270,
-            TF_FLIPPED => Flipped,
-            TF_FLIPPED_90 => Flipped90,
-            TF_FLIPPED_180 => Flipped180,
-            TF_FLIPPED_270 => Flipped270,
+        let tf = match Transform::from_wl(req.transform) {
+            Some(tf) => tf,
             _ => return Err(WlSurfaceError::UnknownBufferTransform(req.transform)),
         };
         self.pending.transform.set(Some(tf));
@@ -930,8 +1064,10 @@ impl WlSurface {
     }
 
     fn damage_buffer(&self, parser: MsgParser<'_, '_>) -> Result<(), WlSurfaceError> {
-        let _req: DamageBuffer = self.parse(parser)?;
-        self.pending.damage.set(true);
+        let req: DamageBuffer = self.parse(parser)?;
+        if let Some(rect) = damage_rect(req.x, req.y, req.width, req.height) {
+            self.pending.buffer_damage.borrow_mut().push(rect);
+        }
         Ok(())
     }
 
@@ -1017,6 +1153,9 @@ impl WlSurface {
         for (_, constraint) in &self.constraints {
             constraint.deactivate();
         }
+        for (_, inhibitor) in &self.shortcuts_inhibitors {
+            inhibitor.deactivate();
+        }
         for (_, inhibitor) in self.idle_inhibitors.lock().drain() {
             inhibitor.deactivate();
         }
@@ -1080,7 +1219,11 @@ impl Object for WlSurface {
         self.presentation_feedback.borrow_mut().clear();
         self.viewporter.take();
         self.fractional_scale.take();
+        self.tearing_control.take();
+        self.dmabuf_feedback.clear();
+        self.scanout_feedback.take();
         self.constraints.clear();
+        self.shortcuts_inhibitors.clear();
     }
 }
 
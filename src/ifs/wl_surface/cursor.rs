@@ -61,6 +61,10 @@ impl CursorSurface {
             self.seat.update_hardware_cursor();
         }
     }
+
+    pub fn damage(&self) {
+        self.seat.damage_cursor();
+    }
 }
 
 impl Cursor for CursorSurface {
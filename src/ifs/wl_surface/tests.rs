@@ -0,0 +1,22 @@
+use crate::{
+    ifs::wl_surface::{damage_rect, scale_buffer_damage},
+    rect::Rect,
+};
+
+#[test]
+fn full_buffer_damage_at_scale_2() {
+    let rect = damage_rect(0, 0, i32::MAX, i32::MAX).unwrap();
+    let scaled = scale_buffer_damage(rect, 2);
+    assert_eq!(scaled, Rect::new(0, 0, i32::MAX / 2, i32::MAX / 2).unwrap());
+}
+
+#[test]
+fn partial_damage_rounds_outward() {
+    let rect = damage_rect(3, 5, 4, 4).unwrap();
+    assert_eq!(scale_buffer_damage(rect, 2), Rect::new(1, 2, 4, 5).unwrap());
+    let rect = damage_rect(-3, -1, 2, 2).unwrap();
+    assert_eq!(
+        scale_buffer_damage(rect, 2),
+        Rect::new(-2, -1, 0, 1).unwrap()
+    );
+}
@@ -82,6 +82,13 @@ fn update_children_attach(
 }
 
 impl WlSubsurface {
+    /// Damages the area covered by the subsurface and its children if the subsurface
+    /// buffer is positioned at `pos`.
+    fn damage_extents(&self, pos: Rect) {
+        let rect = self.surface.extents.get().move_(pos.x1(), pos.y1());
+        self.surface.client.state.damage_rect(rect);
+    }
+
     pub fn new(id: WlSubsurfaceId, surface: &Rc<WlSurface>, parent: &Rc<WlSurface>) -> Self {
         Self {
             id,
@@ -157,6 +164,7 @@ impl WlSubsurface {
                 parent.calculate_extents();
                 parent_opt = parent.ext.get().subsurface_parent();
             }
+            self.damage_extents(self.surface.buffer_abs_pos.get());
         }
         self.surface.client.remove_obj(self)?;
         Ok(())
@@ -277,14 +285,18 @@ impl SurfaceExt for WlSubsurface {
     }
 
     fn post_commit(self: Rc<Self>) {
+        let pos = self.surface.buffer_abs_pos.get();
         if let Some(v) = self.pending.node.take() {
             v.pending.set(false);
             self.node.borrow_mut().replace(v);
+            self.damage_extents(pos);
         }
         if let Some((x, y)) = self.pending.position.take() {
-            self.position
-                .set(self.surface.buffer_abs_pos.get().at_point(x, y));
+            self.position.set(pos.at_point(x, y));
             self.parent.need_extents_update.set(true);
+            let parent_pos = self.parent.buffer_abs_pos.get();
+            self.damage_extents(pos);
+            self.damage_extents(pos.at_point(parent_pos.x1() + x, parent_pos.y1() + y));
         }
     }
 
@@ -0,0 +1,89 @@
+use {
+    crate::{
+        client::{Client, ClientError},
+        ifs::wl_surface::WlSurface,
+        leaks::Tracker,
+        object::Object,
+        utils::buffd::{MsgParser, MsgParserError},
+        wire::{wp_tearing_control_v1::*, WpTearingControlV1Id},
+    },
+    std::rc::Rc,
+    thiserror::Error,
+};
+
+const VSYNC: u32 = 0;
+const ASYNC: u32 = 1;
+
+pub struct WpTearingControlV1 {
+    pub id: WpTearingControlV1Id,
+    pub client: Rc<Client>,
+    pub surface: Rc<WlSurface>,
+    pub tracker: Tracker<Self>,
+}
+
+impl WpTearingControlV1 {
+    pub fn new(id: WpTearingControlV1Id, surface: &Rc<WlSurface>) -> Self {
+        Self {
+            id,
+            client: surface.client.clone(),
+            surface: surface.clone(),
+            tracker: Default::default(),
+        }
+    }
+
+    pub fn install(self: &Rc<Self>) -> Result<(), WpTearingControlV1Error> {
+        if self.surface.tearing_control.get().is_some() {
+            return Err(WpTearingControlV1Error::TearingControlExists);
+        }
+        self.surface.tearing_control.set(Some(self.clone()));
+        Ok(())
+    }
+
+    fn set_presentation_hint(&self, msg: MsgParser<'_, '_>) -> Result<(), WpTearingControlV1Error> {
+        let req: SetPresentationHint = self.client.parse(self, msg)?;
+        let tearing = match req.hint {
+            VSYNC => false,
+            ASYNC => true,
+            _ => return Err(WpTearingControlV1Error::UnknownHint(req.hint)),
+        };
+        self.surface.pending.tearing.set(Some(tearing));
+        Ok(())
+    }
+
+    fn destroy(&self, msg: MsgParser<'_, '_>) -> Result<(), WpTearingControlV1Error> {
+        let _req: Destroy = self.client.parse(self, msg)?;
+        self.surface.pending.tearing.set(Some(false));
+        self.surface.tearing_control.take();
+        self.client.remove_obj(self)?;
+        Ok(())
+    }
+}
+
+object_base! {
+    WpTearingControlV1;
+
+    SET_PRESENTATION_HINT => set_presentation_hint,
+    DESTROY => destroy,
+}
+
+impl Object for WpTearingControlV1 {
+    fn num_requests(&self) -> u32 {
+        DESTROY + 1
+    }
+}
+
+simple_add_obj!(WpTearingControlV1);
+
+#[derive(Debug, Error)]
+pub enum WpTearingControlV1Error {
+    #[error("Parsing failed")]
+    MsgParserError(#[source] Box<MsgParserError>),
+    #[error(transparent)]
+    ClientError(Box<ClientError>),
+    #[error("The surface already has a tearing control object")]
+    TearingControlExists,
+    #[error("Unknown presentation hint {0}")]
+    UnknownHint(u32),
+}
+efrom!(WpTearingControlV1Error, MsgParserError);
+efrom!(WpTearingControlV1Error, ClientError);
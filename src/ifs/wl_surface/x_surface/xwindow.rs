@@ -95,6 +95,8 @@ pub struct XwindowInfo {
     pub role: RefCell<Option<BString>>,
     pub protocols: CopyHashMap<u32, ()>,
     pub window_types: CopyHashMap<u32, ()>,
+    /// The window types as a mask of the `X_WINDOW_TYPE_*` constants of the config.
+    pub config_window_types: Cell<u32>,
     pub never_focus: Cell<bool>,
     pub utf8_title: Cell<bool>,
     pub icccm_hints: IcccmHints,
@@ -212,6 +214,10 @@ impl Xwindow {
             Some(surface.client.clone()),
         );
         tld.pos.set(surface.extents.get());
+        tld.is_minimized.set(data.info.minimized.get());
+        if let Some(class) = data.info.class.borrow_mut().deref() {
+            *tld.app_id.borrow_mut() = class.to_string();
+        }
         let slf = Rc::new(Self {
             id: data.state.node_ids.next(),
             seat_state: Default::default(),
@@ -252,6 +258,15 @@ impl Xwindow {
         }
     }
 
+    fn handle_startup_id(self: &Rc<Self>) {
+        let startup_id = self.data.info.startup_id.borrow_mut().take();
+        if let Some(id) = startup_id {
+            if let Ok(id) = std::str::from_utf8(&id) {
+                self.data.state.activate_toplevel(id, self.clone());
+            }
+        }
+    }
+
     pub fn map_status_changed(self: &Rc<Self>) {
         let map_change = self.map_change();
         match map_change {
@@ -270,19 +285,21 @@ impl Xwindow {
                     Some(self.data.state.root.stacked.add_last(self.clone()));
                 self.data.state.tree_changed();
             }
-            Change::Map if self.data.info.wants_floating.get() => {
-                let ws = self.data.state.float_map_ws();
-                let ext = self.data.info.pending_extents.get();
-                self.data
-                    .state
-                    .map_floating(self.clone(), ext.width(), ext.height(), &ws);
-                self.data.title_changed();
-            }
             Change::Map => {
-                self.data.state.map_tiled(self.clone());
+                let mut floating = None;
+                if self.data.info.wants_floating.get() {
+                    let ws = self.data.state.float_map_ws();
+                    let ext = self.data.info.pending_extents.get();
+                    floating = Some((ws, ext.width(), ext.height()));
+                }
+                self.data.state.map_new_window(self.clone(), floating);
                 self.data.title_changed();
+                self.toplevel_data.broadcast(self.clone());
             }
         }
+        if matches!(map_change, Change::Map) && !self.data.info.override_redirect.get() {
+            self.handle_startup_id();
+        }
         match map_change {
             Change::Unmap => self.tl_set_visible(false),
             Change::Map => self.tl_set_visible(true),
@@ -363,6 +380,10 @@ impl ToplevelNode for Xwindow {
         &self.toplevel_data
     }
 
+    fn tl_x_data(&self) -> Option<&XwindowData> {
+        Some(&self.data)
+    }
+
     fn tl_accepts_keyboard_focus(&self) -> bool {
         self.data.info.never_focus.get().not()
             && self.data.info.input_model.get() != XInputModel::None
@@ -413,6 +434,14 @@ impl ToplevelNode for Xwindow {
             .push(XWaylandEvent::Close(self.data.clone()));
     }
 
+    fn tl_set_minimized(&self, minimized: bool) {
+        self.data
+            .state
+            .xwayland
+            .queue
+            .push(XWaylandEvent::SetMinimized(self.data.clone(), minimized));
+    }
+
     fn tl_set_visible(&self, visible: bool) {
         self.x.surface.set_visible(visible);
         self.seat_state.set_visible(self, visible);
@@ -423,6 +452,10 @@ impl ToplevelNode for Xwindow {
         self.display_link.borrow_mut().take();
         self.x.surface.destroy_node();
     }
+
+    fn tl_scanout_surface(&self) -> Option<Rc<WlSurface>> {
+        Some(self.x.surface.clone())
+    }
 }
 
 impl StackedNode for Xwindow {
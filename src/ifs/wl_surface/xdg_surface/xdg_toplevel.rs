@@ -7,7 +7,10 @@ use {
         fixed::Fixed,
         ifs::{
             wl_seat::{NodeSeatState, SeatId, WlSeatGlobal},
-            wl_surface::xdg_surface::{XdgSurface, XdgSurfaceError, XdgSurfaceExt},
+            wl_surface::{
+                xdg_surface::{XdgSurface, XdgSurfaceError, XdgSurfaceExt},
+                WlSurface,
+            },
         },
         leaks::Tracker,
         object::Object,
@@ -229,6 +232,7 @@ impl XdgToplevel {
     fn set_app_id(&self, parser: MsgParser<'_, '_>) -> Result<(), XdgToplevelError> {
         let req: SetAppId = self.xdg.surface.client.parse(self, parser)?;
         self.bugs.set(bugs::get(req.app_id));
+        self.toplevel_data.set_app_id(req.app_id);
         Ok(())
     }
 
@@ -340,23 +344,6 @@ impl XdgToplevel {
         let _req: SetMinimized = self.xdg.surface.client.parse(self, parser)?;
         Ok(())
     }
-
-    fn map_floating(self: &Rc<Self>, workspace: &Rc<WorkspaceNode>) {
-        let (width, height) = self.toplevel_data.float_size(workspace);
-        self.state
-            .map_floating(self.clone(), width, height, workspace);
-    }
-
-    fn map_child(self: &Rc<Self>, parent: &XdgToplevel) {
-        match parent.xdg.workspace.get() {
-            Some(w) => self.map_floating(&w),
-            _ => self.map_tiled(),
-        }
-    }
-
-    fn map_tiled(self: &Rc<Self>) {
-        self.state.map_tiled(self.clone());
-    }
 }
 
 object_base! {
@@ -473,6 +460,25 @@ impl ToplevelNode for XdgToplevel {
         Some(self.xdg.surface.clone())
     }
 
+    fn tl_set_fullscreen(self: Rc<Self>, fullscreen: bool) {
+        {
+            let mut states = self.states.borrow_mut();
+            match fullscreen {
+                true => states.insert(STATE_FULLSCREEN),
+                false => states.remove(&STATE_FULLSCREEN),
+            };
+        }
+        let data = &self.toplevel_data;
+        if fullscreen {
+            if let Some(ws) = data.workspace.get() {
+                data.set_fullscreen2(&self.state, self.clone(), &ws);
+            }
+        } else {
+            data.unset_fullscreen(&self.state, self.clone());
+        }
+        self.send_current_configure();
+    }
+
     fn tl_set_workspace_ext(self: Rc<Self>, ws: &Rc<WorkspaceNode>) {
         self.xdg.set_workspace(ws);
     }
@@ -511,6 +517,10 @@ impl ToplevelNode for XdgToplevel {
         self.xdg.destroy_node();
     }
 
+    fn tl_scanout_surface(&self) -> Option<Rc<WlSurface>> {
+        Some(self.xdg.surface.clone())
+    }
+
     // fn move_to_workspace(self: &Rc<Self>, workspace: &Rc<WorkspaceNode>) {
     //     let parent = match self.parent_node.get() {
     //         Some(p) => p,
@@ -537,7 +547,22 @@ impl ToplevelNode for XdgToplevel {
 
 impl XdgSurfaceExt for XdgToplevel {
     fn initial_configure(self: Rc<Self>) -> Result<(), XdgSurfaceError> {
-        self.send_configure(0, 0);
+        let (mut width, mut height) = (0, 0);
+        let data = &self.toplevel_data;
+        if !data.window_rules_applied.get() {
+            let actions = self.state.window_rules.evaluate(&*self);
+            if actions.fullscreen == Some(true) {
+                let output = self.state.window_rule_output(&actions).global.pos.get();
+                self.states.borrow_mut().insert(STATE_FULLSCREEN);
+                (width, height) = (output.width(), output.height());
+            } else if actions.floating.unwrap_or(self.parent.get().is_some()) {
+                if let Some(size) = actions.size {
+                    (width, height) = size;
+                }
+            }
+            *data.window_rule_actions.borrow_mut() = Some(actions);
+        }
+        self.send_configure(width, height);
         Ok(())
     }
 
@@ -556,16 +581,21 @@ impl XdgSurfaceExt for XdgToplevel {
                 self.state.tree_changed();
             }
         } else if surface.buffer.get().is_some() {
-            if let Some(parent) = self.parent.get() {
-                self.map_child(&parent);
-            } else {
-                self.map_tiled();
-            }
+            let floating = self
+                .parent
+                .get()
+                .and_then(|p| p.xdg.workspace.get())
+                .map(|ws| {
+                    let (width, height) = self.toplevel_data.float_size(&ws);
+                    (ws, width, height)
+                });
+            self.state.map_new_window(self.clone(), floating);
             self.extents_changed();
             if let Some(workspace) = self.xdg.workspace.get() {
                 let output = workspace.output.get();
                 surface.set_output(&output);
             }
+            self.toplevel_data.broadcast(self.clone());
             // {
             //     let seats = surface.client.state.globals.lock_seats();
             //     for seat in seats.values() {
@@ -0,0 +1,122 @@
+use {
+    crate::{
+        client::{Client, ClientError},
+        ifs::{
+            wl_seat::text_input::zwp_input_method_v2::ZwpInputMethodV2,
+            wl_surface::{SurfaceExt, SurfaceRole, WlSurface, WlSurfaceError},
+        },
+        leaks::Tracker,
+        object::Object,
+        rect::Rect,
+        utils::buffd::{MsgParser, MsgParserError},
+        wire::{zwp_input_popup_surface_v2::*, WlSurfaceId, ZwpInputPopupSurfaceV2Id},
+    },
+    std::{cell::Cell, rc::Rc},
+    thiserror::Error,
+};
+
+pub struct ZwpInputPopupSurfaceV2 {
+    pub id: ZwpInputPopupSurfaceV2Id,
+    pub client: Rc<Client>,
+    pub surface: Rc<WlSurface>,
+    pub input_method: Rc<ZwpInputMethodV2>,
+    pub tracker: Tracker<Self>,
+    pub text_input_rectangle: Cell<Option<Rect>>,
+}
+
+impl ZwpInputPopupSurfaceV2 {
+    pub fn install(self: &Rc<Self>) -> Result<(), ZwpInputPopupSurfaceV2Error> {
+        self.surface.set_role(SurfaceRole::InputPopup)?;
+        if self.surface.ext.get().is_some() {
+            return Err(ZwpInputPopupSurfaceV2Error::AlreadyAttached(
+                self.surface.id,
+            ));
+        }
+        self.surface.ext.set(self.clone());
+        Ok(())
+    }
+
+    /// Places the popup below the cursor rectangle of the active text input.
+    ///
+    /// If the popup does not fit below the cursor, it is placed above it instead.
+    pub fn update_position(&self) {
+        let (ti_surface, cursor) = match self.input_method.seat.text_input_cursor_rect() {
+            Some(r) => r,
+            _ => return,
+        };
+        let pos = ti_surface.buffer_abs_pos.get();
+        let cursor = cursor.move_(pos.x1(), pos.y1());
+        let (width, height) = self.surface.extents.get().size();
+        let output = ti_surface.output.get();
+        let opos = output.global.pos.get();
+        let mut x = cursor.x1();
+        let mut y = cursor.y2();
+        if x + width > opos.x2() {
+            x = (opos.x2() - width).max(opos.x1());
+        }
+        if y + height > opos.y2() && cursor.y1() - height >= opos.y1() {
+            y = cursor.y1() - height;
+        }
+        self.surface.set_absolute_position(x, y);
+        self.surface.set_output(&output);
+        let rect = cursor.move_(-x, -y);
+        if self.text_input_rectangle.replace(Some(rect)) != Some(rect) {
+            self.client.event(TextInputRectangle {
+                self_id: self.id,
+                x: rect.x1(),
+                y: rect.y1(),
+                width: rect.width(),
+                height: rect.height(),
+            });
+        }
+    }
+
+    fn destroy(&self, parser: MsgParser<'_, '_>) -> Result<(), ZwpInputPopupSurfaceV2Error> {
+        let _req: Destroy = self.client.parse(self, parser)?;
+        self.input_method.popups.remove(&self.id);
+        self.surface.unset_ext();
+        let pos = self.surface.buffer_abs_pos.get();
+        let extents = self.surface.extents.get().move_(pos.x1(), pos.y1());
+        self.client.state.damage_rect(extents);
+        self.client.remove_obj(self)?;
+        Ok(())
+    }
+}
+
+impl SurfaceExt for ZwpInputPopupSurfaceV2 {
+    fn post_commit(self: Rc<Self>) {
+        self.update_position();
+    }
+}
+
+object_base! {
+    ZwpInputPopupSurfaceV2;
+
+    DESTROY => destroy,
+}
+
+impl Object for ZwpInputPopupSurfaceV2 {
+    fn num_requests(&self) -> u32 {
+        DESTROY + 1
+    }
+
+    fn break_loops(&self) {
+        self.input_method.popups.remove(&self.id);
+    }
+}
+
+simple_add_obj!(ZwpInputPopupSurfaceV2);
+
+#[derive(Debug, Error)]
+pub enum ZwpInputPopupSurfaceV2Error {
+    #[error(transparent)]
+    ClientError(Box<ClientError>),
+    #[error("Parsing failed")]
+    MsgParserError(#[source] Box<MsgParserError>),
+    #[error(transparent)]
+    WlSurfaceError(#[from] WlSurfaceError),
+    #[error("Surface {0} cannot be turned into an input popup surface because it already has an attached role object")]
+    AlreadyAttached(WlSurfaceId),
+}
+efrom!(ZwpInputPopupSurfaceV2Error, ClientError);
+efrom!(ZwpInputPopupSurfaceV2Error, MsgParserError);
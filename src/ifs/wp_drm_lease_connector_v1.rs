@@ -0,0 +1,89 @@
+use {
+    crate::{
+        client::{Client, ClientError},
+        ifs::wp_drm_lease_device_v1::{LeasableConnector, WpDrmLeaseDeviceV1},
+        leaks::Tracker,
+        object::Object,
+        utils::buffd::{MsgParser, MsgParserError},
+        wire::{wp_drm_lease_connector_v1::*, WpDrmLeaseConnectorV1Id},
+    },
+    std::rc::Rc,
+    thiserror::Error,
+};
+
+pub struct WpDrmLeaseConnectorV1 {
+    pub id: WpDrmLeaseConnectorV1Id,
+    pub client: Rc<Client>,
+    pub tracker: Tracker<Self>,
+    pub device: Rc<WpDrmLeaseDeviceV1>,
+    pub connector: Rc<LeasableConnector>,
+}
+
+impl WpDrmLeaseConnectorV1 {
+    pub fn send_static(&self) {
+        self.client.event(Name {
+            self_id: self.id,
+            name: &self.connector.data.name,
+        });
+        let info = &self.connector.info;
+        let description = format!(
+            "{} {} {}",
+            info.manufacturer, info.product, info.serial_number
+        );
+        self.client.event(Description {
+            self_id: self.id,
+            description: description.trim(),
+        });
+        if let Some(id) = self.connector.data.connector.drm_object_id() {
+            self.client.event(ConnectorId {
+                self_id: self.id,
+                connector_id: id.0,
+            });
+        }
+        self.client.event(Done { self_id: self.id });
+    }
+
+    pub fn send_withdrawn(&self) {
+        self.client.event(Withdrawn { self_id: self.id });
+    }
+
+    fn destroy(&self, parser: MsgParser<'_, '_>) -> Result<(), WpDrmLeaseConnectorV1Error> {
+        let _req: Destroy = self.client.parse(self, parser)?;
+        let id = self.connector.data.connector.id();
+        if let Some(obj) = self.device.connectors.get(&id) {
+            if obj.id == self.id {
+                self.device.connectors.remove(&id);
+            }
+        }
+        self.client.remove_obj(self)?;
+        Ok(())
+    }
+}
+
+object_base! {
+    WpDrmLeaseConnectorV1;
+
+    DESTROY => destroy,
+}
+
+impl Object for WpDrmLeaseConnectorV1 {
+    fn num_requests(&self) -> u32 {
+        DESTROY + 1
+    }
+}
+
+dedicated_add_obj!(
+    WpDrmLeaseConnectorV1,
+    WpDrmLeaseConnectorV1Id,
+    drm_lease_connectors
+);
+
+#[derive(Debug, Error)]
+pub enum WpDrmLeaseConnectorV1Error {
+    #[error(transparent)]
+    ClientError(Box<ClientError>),
+    #[error("Parsing failed")]
+    MsgParserError(#[source] Box<MsgParserError>),
+}
+efrom!(WpDrmLeaseConnectorV1Error, ClientError);
+efrom!(WpDrmLeaseConnectorV1Error, MsgParserError);
@@ -0,0 +1,248 @@
+use {
+    crate::{
+        backend::{BackendDrmDevice, ConnectorId, MonitorInfo},
+        client::{Client, ClientError, ClientId},
+        globals::{Global, GlobalName},
+        ifs::{
+            wp_drm_lease_connector_v1::WpDrmLeaseConnectorV1,
+            wp_drm_lease_request_v1::WpDrmLeaseRequestV1,
+        },
+        leaks::Tracker,
+        object::Object,
+        state::ConnectorData,
+        utils::{
+            buffd::{MsgParser, MsgParserError},
+            copyhashmap::CopyHashMap,
+        },
+        wire::{wp_drm_lease_device_v1::*, WpDrmLeaseDeviceV1Id},
+    },
+    std::{cell::Cell, rc::Rc},
+    thiserror::Error,
+    uapi::OwnedFd,
+};
+
+/// A connected non-desktop connector of a DRM device.
+pub struct LeasableConnector {
+    pub data: Rc<ConnectorData>,
+    pub info: MonitorInfo,
+    /// Whether the connector is currently leased to a client.
+    pub leased: Cell<bool>,
+    /// Whether the display has been disconnected.
+    pub withdrawn: Cell<bool>,
+}
+
+pub struct WpDrmLeaseDeviceV1Global {
+    pub name: GlobalName,
+    pub dev: Rc<dyn BackendDrmDevice>,
+    /// The non-master fd that is sent to clients if a new one cannot be opened.
+    pub drm_fd: Rc<OwnedFd>,
+    pub connectors: CopyHashMap<ConnectorId, Rc<LeasableConnector>>,
+    pub bindings: CopyHashMap<(ClientId, WpDrmLeaseDeviceV1Id), Rc<WpDrmLeaseDeviceV1>>,
+}
+
+impl WpDrmLeaseDeviceV1Global {
+    pub fn new(name: GlobalName, dev: &Rc<dyn BackendDrmDevice>, drm_fd: Rc<OwnedFd>) -> Self {
+        Self {
+            name,
+            dev: dev.clone(),
+            drm_fd,
+            connectors: Default::default(),
+            bindings: Default::default(),
+        }
+    }
+
+    fn bind_(
+        self: Rc<Self>,
+        id: WpDrmLeaseDeviceV1Id,
+        client: &Rc<Client>,
+        _version: u32,
+    ) -> Result<(), WpDrmLeaseDeviceV1Error> {
+        let obj = Rc::new(WpDrmLeaseDeviceV1 {
+            id,
+            client: client.clone(),
+            tracker: Default::default(),
+            global: self.clone(),
+            connectors: Default::default(),
+        });
+        track!(client, obj);
+        client.add_client_obj(&obj)?;
+        self.bindings.set((client.id, id), obj.clone());
+        let fd = self
+            .dev
+            .non_master_fd()
+            .unwrap_or_else(|| self.drm_fd.clone());
+        client.event(DrmFd { self_id: id, fd });
+        if !self.dev.paused() {
+            for connector in self.connectors.lock().values() {
+                if !connector.leased.get() {
+                    obj.offer(connector)?;
+                }
+            }
+        }
+        obj.send_done();
+        Ok(())
+    }
+
+    /// Adds a newly connected connector and offers it to all clients.
+    pub fn add_connector(&self, connector: &Rc<LeasableConnector>) {
+        self.connectors
+            .set(connector.data.connector.id(), connector.clone());
+        self.offer(connector);
+    }
+
+    /// Removes a disconnected connector and withdraws it from all clients.
+    pub fn remove_connector(&self, id: ConnectorId) {
+        if let Some(connector) = self.connectors.remove(&id) {
+            connector.withdrawn.set(true);
+            self.withdraw(id);
+        }
+    }
+
+    pub fn offer(&self, connector: &Rc<LeasableConnector>) {
+        if self.dev.paused() {
+            return;
+        }
+        for binding in self.bindings.lock().values() {
+            match binding.offer(connector) {
+                Ok(_) => binding.send_done(),
+                Err(e) => binding.client.error(e),
+            }
+        }
+    }
+
+    /// Withdraws all connectors while the device is paused.
+    pub fn pause(&self) {
+        for id in self.connectors.lock().keys() {
+            self.withdraw(*id);
+        }
+    }
+
+    /// Offers the connectors that are not leased again after the device was resumed.
+    pub fn resume(&self) {
+        let connectors: Vec<_> = self.connectors.lock().values().cloned().collect();
+        for connector in connectors {
+            if !connector.leased.get() {
+                self.offer(&connector);
+            }
+        }
+    }
+
+    pub fn withdraw(&self, id: ConnectorId) {
+        for binding in self.bindings.lock().values() {
+            if let Some(connector) = binding.connectors.remove(&id) {
+                connector.send_withdrawn();
+                binding.send_done();
+            }
+        }
+    }
+}
+
+global_base!(
+    WpDrmLeaseDeviceV1Global,
+    WpDrmLeaseDeviceV1,
+    WpDrmLeaseDeviceV1Error
+);
+
+impl Global for WpDrmLeaseDeviceV1Global {
+    fn singleton(&self) -> bool {
+        false
+    }
+
+    fn version(&self) -> u32 {
+        1
+    }
+}
+
+simple_add_global!(WpDrmLeaseDeviceV1Global);
+
+pub struct WpDrmLeaseDeviceV1 {
+    pub id: WpDrmLeaseDeviceV1Id,
+    pub client: Rc<Client>,
+    pub tracker: Tracker<Self>,
+    pub global: Rc<WpDrmLeaseDeviceV1Global>,
+    pub connectors: CopyHashMap<ConnectorId, Rc<WpDrmLeaseConnectorV1>>,
+}
+
+impl WpDrmLeaseDeviceV1 {
+    fn offer(self: &Rc<Self>, connector: &Rc<LeasableConnector>) -> Result<(), ClientError> {
+        let obj = Rc::new(WpDrmLeaseConnectorV1 {
+            id: self.client.new_id()?,
+            client: self.client.clone(),
+            tracker: Default::default(),
+            device: self.clone(),
+            connector: connector.clone(),
+        });
+        track!(self.client, obj);
+        self.client.add_server_obj(&obj);
+        self.client.event(Connector {
+            self_id: self.id,
+            id: obj.id,
+        });
+        obj.send_static();
+        self.connectors.set(connector.data.connector.id(), obj);
+        Ok(())
+    }
+
+    fn send_done(&self) {
+        self.client.event(Done { self_id: self.id });
+    }
+
+    fn create_lease_request(
+        self: &Rc<Self>,
+        parser: MsgParser<'_, '_>,
+    ) -> Result<(), WpDrmLeaseDeviceV1Error> {
+        let req: CreateLeaseRequest = self.client.parse(&**self, parser)?;
+        let obj = Rc::new(WpDrmLeaseRequestV1 {
+            id: req.id,
+            client: self.client.clone(),
+            tracker: Default::default(),
+            device: self.clone(),
+            connectors: Default::default(),
+        });
+        track!(self.client, obj);
+        self.client.add_client_obj(&obj)?;
+        Ok(())
+    }
+
+    fn release(&self, parser: MsgParser<'_, '_>) -> Result<(), WpDrmLeaseDeviceV1Error> {
+        let _req: Release = self.client.parse(self, parser)?;
+        self.remove_from_global();
+        self.client.event(Released { self_id: self.id });
+        self.client.remove_obj(self)?;
+        Ok(())
+    }
+
+    fn remove_from_global(&self) {
+        self.global.bindings.remove(&(self.client.id, self.id));
+        self.connectors.clear();
+    }
+}
+
+object_base! {
+    WpDrmLeaseDeviceV1;
+
+    CREATE_LEASE_REQUEST => create_lease_request,
+    RELEASE => release,
+}
+
+impl Object for WpDrmLeaseDeviceV1 {
+    fn num_requests(&self) -> u32 {
+        RELEASE + 1
+    }
+
+    fn break_loops(&self) {
+        self.remove_from_global();
+    }
+}
+
+simple_add_obj!(WpDrmLeaseDeviceV1);
+
+#[derive(Debug, Error)]
+pub enum WpDrmLeaseDeviceV1Error {
+    #[error(transparent)]
+    ClientError(Box<ClientError>),
+    #[error("Parsing failed")]
+    MsgParserError(#[source] Box<MsgParserError>),
+}
+efrom!(WpDrmLeaseDeviceV1Error, ClientError);
+efrom!(WpDrmLeaseDeviceV1Error, MsgParserError);
@@ -0,0 +1,98 @@
+use {
+    crate::{
+        client::{Client, ClientError},
+        ifs::{
+            wp_drm_lease_device_v1::{LeasableConnector, WpDrmLeaseDeviceV1},
+            wp_drm_lease_v1::WpDrmLeaseV1,
+        },
+        leaks::Tracker,
+        object::Object,
+        utils::buffd::{MsgParser, MsgParserError},
+        wire::{wp_drm_lease_request_v1::*, WpDrmLeaseRequestV1Id},
+    },
+    std::{cell::RefCell, mem, rc::Rc},
+    thiserror::Error,
+};
+
+pub struct WpDrmLeaseRequestV1 {
+    pub id: WpDrmLeaseRequestV1Id,
+    pub client: Rc<Client>,
+    pub tracker: Tracker<Self>,
+    pub device: Rc<WpDrmLeaseDeviceV1>,
+    pub connectors: RefCell<Vec<Rc<LeasableConnector>>>,
+}
+
+impl WpDrmLeaseRequestV1 {
+    fn request_connector(&self, parser: MsgParser<'_, '_>) -> Result<(), WpDrmLeaseRequestV1Error> {
+        let req: RequestConnector = self.client.parse(self, parser)?;
+        let connector = self.client.lookup(req.connector)?;
+        if !Rc::ptr_eq(&connector.device.global, &self.device.global) {
+            return Err(WpDrmLeaseRequestV1Error::WrongDevice);
+        }
+        let mut connectors = self.connectors.borrow_mut();
+        if connectors
+            .iter()
+            .any(|c| Rc::ptr_eq(c, &connector.connector))
+        {
+            return Err(WpDrmLeaseRequestV1Error::DuplicateConnector);
+        }
+        connectors.push(connector.connector.clone());
+        Ok(())
+    }
+
+    fn submit(&self, parser: MsgParser<'_, '_>) -> Result<(), WpDrmLeaseRequestV1Error> {
+        let req: Submit = self.client.parse(self, parser)?;
+        let connectors = mem::take(&mut *self.connectors.borrow_mut());
+        if connectors.is_empty() {
+            return Err(WpDrmLeaseRequestV1Error::EmptyLease);
+        }
+        let lease = Rc::new(WpDrmLeaseV1 {
+            id: req.id,
+            client: self.client.clone(),
+            tracker: Default::default(),
+            global: self.device.global.clone(),
+            connectors,
+            lease: Default::default(),
+        });
+        track!(self.client, lease);
+        self.client.add_client_obj(&lease)?;
+        self.client.remove_obj(self)?;
+        lease.create();
+        Ok(())
+    }
+}
+
+object_base! {
+    WpDrmLeaseRequestV1;
+
+    REQUEST_CONNECTOR => request_connector,
+    SUBMIT => submit,
+}
+
+impl Object for WpDrmLeaseRequestV1 {
+    fn num_requests(&self) -> u32 {
+        SUBMIT + 1
+    }
+
+    fn break_loops(&self) {
+        self.connectors.borrow_mut().clear();
+    }
+}
+
+simple_add_obj!(WpDrmLeaseRequestV1);
+
+#[derive(Debug, Error)]
+pub enum WpDrmLeaseRequestV1Error {
+    #[error(transparent)]
+    ClientError(Box<ClientError>),
+    #[error("Parsing failed")]
+    MsgParserError(#[source] Box<MsgParserError>),
+    #[error("The connector belongs to a different lease device")]
+    WrongDevice,
+    #[error("The connector has already been requested")]
+    DuplicateConnector,
+    #[error("The lease request does not contain any connectors")]
+    EmptyLease,
+}
+efrom!(WpDrmLeaseRequestV1Error, ClientError);
+efrom!(WpDrmLeaseRequestV1Error, MsgParserError);
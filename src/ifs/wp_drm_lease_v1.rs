@@ -0,0 +1,119 @@
+use {
+    crate::{
+        backend::{BackendDrmLease, BackendDrmLessee},
+        client::{Client, ClientError},
+        ifs::wp_drm_lease_device_v1::{LeasableConnector, WpDrmLeaseDeviceV1Global},
+        leaks::Tracker,
+        object::Object,
+        utils::{
+            buffd::{MsgParser, MsgParserError},
+            clonecell::CloneCell,
+        },
+        wire::{wp_drm_lease_v1::*, WpDrmLeaseV1Id},
+    },
+    std::rc::Rc,
+    thiserror::Error,
+};
+
+pub struct WpDrmLeaseV1 {
+    pub id: WpDrmLeaseV1Id,
+    pub client: Rc<Client>,
+    pub tracker: Tracker<Self>,
+    pub global: Rc<WpDrmLeaseDeviceV1Global>,
+    pub connectors: Vec<Rc<LeasableConnector>>,
+    pub lease: CloneCell<Option<Rc<dyn BackendDrmLease>>>,
+}
+
+impl WpDrmLeaseV1 {
+    /// Asks the backend to lease the requested connectors.
+    pub fn create(self: &Rc<Self>) {
+        if self
+            .connectors
+            .iter()
+            .any(|c| c.leased.get() || c.withdrawn.get())
+        {
+            self.send_finished();
+            return;
+        }
+        let ids: Vec<_> = self
+            .connectors
+            .iter()
+            .map(|c| c.data.connector.id())
+            .collect();
+        let lease = match self.global.dev.clone().create_lease(self.clone(), &ids) {
+            Some(l) => l,
+            _ => {
+                self.send_finished();
+                return;
+            }
+        };
+        for connector in &self.connectors {
+            connector.leased.set(true);
+            self.global.withdraw(connector.data.connector.id());
+        }
+        self.client.event(LeaseFd {
+            self_id: self.id,
+            leased_fd: lease.fd().clone(),
+        });
+        self.lease.set(Some(lease));
+    }
+
+    fn send_finished(&self) {
+        self.client.event(Finished { self_id: self.id });
+    }
+
+    /// Revokes the lease and offers the connectors to clients again.
+    fn end(&self) {
+        if self.lease.take().is_none() {
+            return;
+        }
+        for connector in &self.connectors {
+            connector.leased.set(false);
+            if !connector.withdrawn.get() {
+                self.global.offer(connector);
+            }
+        }
+    }
+
+    fn destroy(&self, parser: MsgParser<'_, '_>) -> Result<(), WpDrmLeaseV1Error> {
+        let _req: Destroy = self.client.parse(self, parser)?;
+        self.end();
+        self.client.remove_obj(self)?;
+        Ok(())
+    }
+}
+
+impl BackendDrmLessee for WpDrmLeaseV1 {
+    fn revoked(&self) {
+        self.end();
+        self.send_finished();
+    }
+}
+
+object_base! {
+    WpDrmLeaseV1;
+
+    DESTROY => destroy,
+}
+
+impl Object for WpDrmLeaseV1 {
+    fn num_requests(&self) -> u32 {
+        DESTROY + 1
+    }
+
+    fn break_loops(&self) {
+        self.end();
+    }
+}
+
+simple_add_obj!(WpDrmLeaseV1);
+
+#[derive(Debug, Error)]
+pub enum WpDrmLeaseV1Error {
+    #[error(transparent)]
+    ClientError(Box<ClientError>),
+    #[error("Parsing failed")]
+    MsgParserError(#[source] Box<MsgParserError>),
+}
+efrom!(WpDrmLeaseV1Error, ClientError);
+efrom!(WpDrmLeaseV1Error, MsgParserError);
@@ -0,0 +1,117 @@
+use {
+    crate::{
+        client::{Client, ClientError},
+        ifs::wl_seat::WlSeatGlobal,
+        leaks::Tracker,
+        object::Object,
+        utils::{
+            activation_token::ActivationToken,
+            buffd::{MsgParser, MsgParserError},
+            clonecell::CloneCell,
+        },
+        wire::{xdg_activation_token_v1::*, XdgActivationTokenV1Id},
+    },
+    std::{cell::Cell, rc::Rc},
+    thiserror::Error,
+};
+
+pub struct XdgActivationTokenV1 {
+    pub id: XdgActivationTokenV1Id,
+    pub client: Rc<Client>,
+    pub tracker: Tracker<Self>,
+    pub seat: CloneCell<Option<Rc<WlSeatGlobal>>>,
+    pub committed: Cell<bool>,
+}
+
+impl XdgActivationTokenV1 {
+    fn set_serial(&self, parser: MsgParser<'_, '_>) -> Result<(), XdgActivationTokenV1Error> {
+        let req: SetSerial = self.client.parse(self, parser)?;
+        let seat = self.client.lookup(req.seat)?;
+        self.seat.set(Some(seat.global.clone()));
+        Ok(())
+    }
+
+    fn set_app_id(&self, parser: MsgParser<'_, '_>) -> Result<(), XdgActivationTokenV1Error> {
+        let _req: SetAppId = self.client.parse(self, parser)?;
+        Ok(())
+    }
+
+    fn set_surface(&self, parser: MsgParser<'_, '_>) -> Result<(), XdgActivationTokenV1Error> {
+        let req: SetSurface = self.client.parse(self, parser)?;
+        let _surface = self.client.lookup(req.surface)?;
+        Ok(())
+    }
+
+    /// Tokens are only honored if the requesting client has the keyboard focus. The client
+    /// still receives a token otherwise so that it cannot probe for the focus.
+    fn has_focus(&self) -> bool {
+        let client_id = Some(self.client.id);
+        match self.seat.get() {
+            Some(seat) => seat.keyboard_focus_client() == client_id,
+            _ => {
+                let seats = self.client.state.globals.seats.lock();
+                seats
+                    .values()
+                    .any(|s| s.keyboard_focus_client() == client_id)
+            }
+        }
+    }
+
+    fn commit(&self, parser: MsgParser<'_, '_>) -> Result<(), XdgActivationTokenV1Error> {
+        let _req: Commit = self.client.parse(self, parser)?;
+        if self.committed.replace(true) {
+            return Err(XdgActivationTokenV1Error::AlreadyUsed);
+        }
+        let token = ActivationToken::new();
+        if self.has_focus() {
+            let seat = self.seat.get().map(|s| s.id());
+            self.client.state.add_activation_token(token, seat);
+        }
+        self.client.event(Done {
+            self_id: self.id,
+            token: &token.to_string(),
+        });
+        Ok(())
+    }
+
+    fn destroy(&self, parser: MsgParser<'_, '_>) -> Result<(), XdgActivationTokenV1Error> {
+        let _req: Destroy = self.client.parse(self, parser)?;
+        self.seat.take();
+        self.client.remove_obj(self)?;
+        Ok(())
+    }
+}
+
+object_base! {
+    XdgActivationTokenV1;
+
+    SET_SERIAL => set_serial,
+    SET_APP_ID => set_app_id,
+    SET_SURFACE => set_surface,
+    COMMIT => commit,
+    DESTROY => destroy,
+}
+
+impl Object for XdgActivationTokenV1 {
+    fn num_requests(&self) -> u32 {
+        DESTROY + 1
+    }
+
+    fn break_loops(&self) {
+        self.seat.take();
+    }
+}
+
+simple_add_obj!(XdgActivationTokenV1);
+
+#[derive(Debug, Error)]
+pub enum XdgActivationTokenV1Error {
+    #[error(transparent)]
+    ClientError(Box<ClientError>),
+    #[error("Parsing failed")]
+    MsgParserError(#[source] Box<MsgParserError>),
+    #[error("The token has already been committed")]
+    AlreadyUsed,
+}
+efrom!(XdgActivationTokenV1Error, ClientError);
+efrom!(XdgActivationTokenV1Error, MsgParserError);
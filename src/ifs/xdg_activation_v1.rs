@@ -0,0 +1,116 @@
+use {
+    crate::{
+        client::{Client, ClientError},
+        globals::{Global, GlobalName},
+        ifs::xdg_activation_token_v1::XdgActivationTokenV1,
+        leaks::Tracker,
+        object::Object,
+        utils::buffd::{MsgParser, MsgParserError},
+        wire::{xdg_activation_v1::*, XdgActivationV1Id},
+    },
+    std::{cell::Cell, rc::Rc},
+    thiserror::Error,
+};
+
+pub struct XdgActivationV1Global {
+    name: GlobalName,
+}
+
+impl XdgActivationV1Global {
+    pub fn new(name: GlobalName) -> Self {
+        Self { name }
+    }
+
+    fn bind_(
+        self: Rc<Self>,
+        id: XdgActivationV1Id,
+        client: &Rc<Client>,
+        _version: u32,
+    ) -> Result<(), XdgActivationV1Error> {
+        let obj = Rc::new(XdgActivationV1 {
+            id,
+            client: client.clone(),
+            tracker: Default::default(),
+        });
+        track!(client, obj);
+        client.add_client_obj(&obj)?;
+        Ok(())
+    }
+}
+
+global_base!(XdgActivationV1Global, XdgActivationV1, XdgActivationV1Error);
+
+impl Global for XdgActivationV1Global {
+    fn singleton(&self) -> bool {
+        true
+    }
+
+    fn version(&self) -> u32 {
+        1
+    }
+}
+
+simple_add_global!(XdgActivationV1Global);
+
+pub struct XdgActivationV1 {
+    pub id: XdgActivationV1Id,
+    pub client: Rc<Client>,
+    pub tracker: Tracker<Self>,
+}
+
+impl XdgActivationV1 {
+    fn destroy(&self, parser: MsgParser<'_, '_>) -> Result<(), XdgActivationV1Error> {
+        let _req: Destroy = self.client.parse(self, parser)?;
+        self.client.remove_obj(self)?;
+        Ok(())
+    }
+
+    fn get_activation_token(&self, parser: MsgParser<'_, '_>) -> Result<(), XdgActivationV1Error> {
+        let req: GetActivationToken = self.client.parse(self, parser)?;
+        let obj = Rc::new(XdgActivationTokenV1 {
+            id: req.id,
+            client: self.client.clone(),
+            tracker: Default::default(),
+            seat: Default::default(),
+            committed: Cell::new(false),
+        });
+        track!(self.client, obj);
+        self.client.add_client_obj(&obj)?;
+        Ok(())
+    }
+
+    fn activate(&self, parser: MsgParser<'_, '_>) -> Result<(), XdgActivationV1Error> {
+        let req: Activate = self.client.parse(self, parser)?;
+        let surface = self.client.lookup(req.surface)?;
+        if let Some(tl) = surface.get_toplevel() {
+            self.client.state.activate_toplevel(req.token, tl);
+        }
+        Ok(())
+    }
+}
+
+object_base! {
+    XdgActivationV1;
+
+    DESTROY => destroy,
+    GET_ACTIVATION_TOKEN => get_activation_token,
+    ACTIVATE => activate,
+}
+
+impl Object for XdgActivationV1 {
+    fn num_requests(&self) -> u32 {
+        ACTIVATE + 1
+    }
+}
+
+simple_add_obj!(XdgActivationV1);
+
+#[derive(Debug, Error)]
+pub enum XdgActivationV1Error {
+    #[error(transparent)]
+    ClientError(Box<ClientError>),
+    #[error("Parsing failed")]
+    MsgParserError(#[source] Box<MsgParserError>),
+}
+efrom!(XdgActivationV1Error, ClientError);
+efrom!(XdgActivationV1Error, MsgParserError);
@@ -0,0 +1,263 @@
+use {
+    crate::{
+        client::{Client, ClientError},
+        leaks::Tracker,
+        object::Object,
+        tree::{OutputNode, ToplevelData, ToplevelNode},
+        utils::{
+            buffd::{MsgParser, MsgParserError},
+            clonecell::CloneCell,
+        },
+        wire::{zwlr_foreign_toplevel_handle_v1::*, ZwlrForeignToplevelHandleV1Id},
+    },
+    std::rc::Rc,
+    thiserror::Error,
+};
+
+const STATE_MINIMIZED: u32 = 1;
+const STATE_ACTIVATED: u32 = 2;
+const STATE_FULLSCREEN: u32 = 3;
+
+pub struct ZwlrForeignToplevelHandleV1 {
+    pub id: ZwlrForeignToplevelHandleV1Id,
+    pub client: Rc<Client>,
+    pub tracker: Tracker<Self>,
+    pub version: u32,
+    pub toplevel: CloneCell<Option<Rc<dyn ToplevelNode>>>,
+    pub output: CloneCell<Option<Rc<OutputNode>>>,
+}
+
+impl ZwlrForeignToplevelHandleV1 {
+    pub fn send_title(&self, title: &str) {
+        self.client.event(Title {
+            self_id: self.id,
+            title,
+        });
+    }
+
+    pub fn send_app_id(&self, app_id: &str) {
+        self.client.event(AppId {
+            self_id: self.id,
+            app_id,
+        });
+    }
+
+    /// Sends `output_leave`/`output_enter` events if the output of the toplevel has changed.
+    ///
+    /// Returns whether any events were sent.
+    pub fn send_output(&self, data: &ToplevelData) -> bool {
+        let new = data.workspace.get().map(|ws| ws.output.get());
+        let old = self.output.get();
+        if old.as_ref().map(|o| o.id) == new.as_ref().map(|o| o.id) {
+            return false;
+        }
+        if let Some(old) = old {
+            old.global.for_each_binding(self.client.id, |b| {
+                self.client.event(OutputLeave {
+                    self_id: self.id,
+                    output: b.id,
+                });
+            });
+        }
+        if let Some(new) = &new {
+            new.global.for_each_binding(self.client.id, |b| {
+                self.client.event(OutputEnter {
+                    self_id: self.id,
+                    output: b.id,
+                });
+            });
+        }
+        self.output.set(new);
+        true
+    }
+
+    pub fn send_state(&self, data: &ToplevelData) {
+        let mut state = vec![];
+        if data.is_minimized.get() {
+            state.push(STATE_MINIMIZED);
+        }
+        if data.active_surfaces.get() > 0 {
+            state.push(STATE_ACTIVATED);
+        }
+        if data.is_fullscreen.get() {
+            state.push(STATE_FULLSCREEN);
+        }
+        self.client.event(State {
+            self_id: self.id,
+            state: &state,
+        });
+    }
+
+    pub fn send_done(&self) {
+        self.client.event(Done { self_id: self.id });
+    }
+
+    pub fn send_closed(&self) {
+        self.toplevel.take();
+        self.output.take();
+        self.client.event(Closed { self_id: self.id });
+    }
+
+    fn set_maximized(
+        &self,
+        parser: MsgParser<'_, '_>,
+    ) -> Result<(), ZwlrForeignToplevelHandleV1Error> {
+        let _req: SetMaximized = self.client.parse(self, parser)?;
+        Ok(())
+    }
+
+    fn unset_maximized(
+        &self,
+        parser: MsgParser<'_, '_>,
+    ) -> Result<(), ZwlrForeignToplevelHandleV1Error> {
+        let _req: UnsetMaximized = self.client.parse(self, parser)?;
+        Ok(())
+    }
+
+    fn set_minimized(
+        &self,
+        parser: MsgParser<'_, '_>,
+    ) -> Result<(), ZwlrForeignToplevelHandleV1Error> {
+        let _req: SetMinimized = self.client.parse(self, parser)?;
+        if let Some(toplevel) = self.toplevel.get() {
+            toplevel.tl_set_minimized(true);
+        }
+        Ok(())
+    }
+
+    fn unset_minimized(
+        &self,
+        parser: MsgParser<'_, '_>,
+    ) -> Result<(), ZwlrForeignToplevelHandleV1Error> {
+        let _req: UnsetMinimized = self.client.parse(self, parser)?;
+        if let Some(toplevel) = self.toplevel.get() {
+            toplevel.tl_set_minimized(false);
+        }
+        Ok(())
+    }
+
+    fn activate(&self, parser: MsgParser<'_, '_>) -> Result<(), ZwlrForeignToplevelHandleV1Error> {
+        let req: Activate = self.client.parse(self, parser)?;
+        let seat = self.client.lookup(req.seat)?;
+        let toplevel = match self.toplevel.get() {
+            Some(tl) => tl,
+            _ => return Ok(()),
+        };
+        seat.global.activate_toplevel(toplevel);
+        Ok(())
+    }
+
+    fn close(&self, parser: MsgParser<'_, '_>) -> Result<(), ZwlrForeignToplevelHandleV1Error> {
+        let _req: Close = self.client.parse(self, parser)?;
+        if let Some(toplevel) = self.toplevel.get() {
+            toplevel.tl_close();
+        }
+        Ok(())
+    }
+
+    fn set_rectangle(
+        &self,
+        parser: MsgParser<'_, '_>,
+    ) -> Result<(), ZwlrForeignToplevelHandleV1Error> {
+        let req: SetRectangle = self.client.parse(self, parser)?;
+        if req.width < 0 || req.height < 0 {
+            return Err(ZwlrForeignToplevelHandleV1Error::InvalidRectangle);
+        }
+        Ok(())
+    }
+
+    fn destroy(&self, parser: MsgParser<'_, '_>) -> Result<(), ZwlrForeignToplevelHandleV1Error> {
+        let _req: Destroy = self.client.parse(self, parser)?;
+        self.detach();
+        self.client.remove_obj(self)?;
+        Ok(())
+    }
+
+    fn set_fullscreen(
+        &self,
+        parser: MsgParser<'_, '_>,
+    ) -> Result<(), ZwlrForeignToplevelHandleV1Error> {
+        let req: SetFullscreen = self.client.parse(self, parser)?;
+        let output = if req.output.is_some() {
+            self.client.lookup(req.output)?.global.node.get()
+        } else {
+            None
+        };
+        let toplevel = match self.toplevel.get() {
+            Some(tl) => tl,
+            _ => return Ok(()),
+        };
+        match output {
+            Some(output) => {
+                let data = toplevel.tl_data();
+                data.set_fullscreen(&self.client.state, toplevel.clone(), &output);
+            }
+            _ => toplevel.tl_set_fullscreen(true),
+        }
+        Ok(())
+    }
+
+    fn unset_fullscreen(
+        &self,
+        parser: MsgParser<'_, '_>,
+    ) -> Result<(), ZwlrForeignToplevelHandleV1Error> {
+        let _req: UnsetFullscreen = self.client.parse(self, parser)?;
+        if let Some(toplevel) = self.toplevel.get() {
+            toplevel.tl_set_fullscreen(false);
+        }
+        Ok(())
+    }
+
+    fn detach(&self) {
+        if let Some(toplevel) = self.toplevel.take() {
+            toplevel
+                .tl_data()
+                .handles
+                .remove(&(self.client.id, self.id));
+        }
+        self.output.take();
+    }
+}
+
+object_base! {
+    ZwlrForeignToplevelHandleV1;
+
+    SET_MAXIMIZED => set_maximized,
+    UNSET_MAXIMIZED => unset_maximized,
+    SET_MINIMIZED => set_minimized,
+    UNSET_MINIMIZED => unset_minimized,
+    ACTIVATE => activate,
+    CLOSE => close,
+    SET_RECTANGLE => set_rectangle,
+    DESTROY => destroy,
+    SET_FULLSCREEN => set_fullscreen,
+    UNSET_FULLSCREEN => unset_fullscreen,
+}
+
+impl Object for ZwlrForeignToplevelHandleV1 {
+    fn num_requests(&self) -> u32 {
+        if self.version < 2 {
+            DESTROY + 1
+        } else {
+            UNSET_FULLSCREEN + 1
+        }
+    }
+
+    fn break_loops(&self) {
+        self.detach();
+    }
+}
+
+simple_add_obj!(ZwlrForeignToplevelHandleV1);
+
+#[derive(Debug, Error)]
+pub enum ZwlrForeignToplevelHandleV1Error {
+    #[error(transparent)]
+    ClientError(Box<ClientError>),
+    #[error("Parsing failed")]
+    MsgParserError(#[source] Box<MsgParserError>),
+    #[error("The rectangle has a negative size")]
+    InvalidRectangle,
+}
+efrom!(ZwlrForeignToplevelHandleV1Error, ClientError);
+efrom!(ZwlrForeignToplevelHandleV1Error, MsgParserError);
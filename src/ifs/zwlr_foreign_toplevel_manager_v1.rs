@@ -0,0 +1,150 @@
+use {
+    crate::{
+        client::{Client, ClientError},
+        globals::{Global, GlobalName},
+        ifs::zwlr_foreign_toplevel_handle_v1::ZwlrForeignToplevelHandleV1,
+        leaks::Tracker,
+        object::Object,
+        tree::ToplevelNode,
+        utils::buffd::{MsgParser, MsgParserError},
+        wire::{zwlr_foreign_toplevel_manager_v1::*, ZwlrForeignToplevelManagerV1Id},
+    },
+    std::rc::Rc,
+    thiserror::Error,
+};
+
+pub struct ZwlrForeignToplevelManagerV1Global {
+    pub name: GlobalName,
+}
+
+impl ZwlrForeignToplevelManagerV1Global {
+    pub fn new(name: GlobalName) -> Self {
+        Self { name }
+    }
+
+    fn bind_(
+        self: Rc<Self>,
+        id: ZwlrForeignToplevelManagerV1Id,
+        client: &Rc<Client>,
+        version: u32,
+    ) -> Result<(), ZwlrForeignToplevelManagerV1Error> {
+        let mgr = Rc::new(ZwlrForeignToplevelManagerV1 {
+            id,
+            client: client.clone(),
+            tracker: Default::default(),
+            version,
+        });
+        track!(client, mgr);
+        client.add_client_obj(&mgr)?;
+        client
+            .state
+            .toplevel_managers
+            .set((client.id, id), mgr.clone());
+        let toplevels: Vec<_> = client.state.toplevels.lock().values().cloned().collect();
+        for toplevel in toplevels {
+            mgr.announce(&toplevel)?;
+        }
+        Ok(())
+    }
+}
+
+global_base!(
+    ZwlrForeignToplevelManagerV1Global,
+    ZwlrForeignToplevelManagerV1,
+    ZwlrForeignToplevelManagerV1Error
+);
+
+simple_add_global!(ZwlrForeignToplevelManagerV1Global);
+
+impl Global for ZwlrForeignToplevelManagerV1Global {
+    fn singleton(&self) -> bool {
+        true
+    }
+
+    fn version(&self) -> u32 {
+        3
+    }
+
+    fn secure(&self) -> bool {
+        true
+    }
+}
+
+pub struct ZwlrForeignToplevelManagerV1 {
+    pub id: ZwlrForeignToplevelManagerV1Id,
+    pub client: Rc<Client>,
+    pub tracker: Tracker<Self>,
+    pub version: u32,
+}
+
+impl ZwlrForeignToplevelManagerV1 {
+    /// Creates a handle for the toplevel and sends its current state.
+    pub fn announce(&self, toplevel: &Rc<dyn ToplevelNode>) -> Result<(), ClientError> {
+        let handle = Rc::new(ZwlrForeignToplevelHandleV1 {
+            id: self.client.new_id()?,
+            client: self.client.clone(),
+            tracker: Default::default(),
+            version: self.version,
+            toplevel: Default::default(),
+            output: Default::default(),
+        });
+        track!(self.client, handle);
+        self.client.add_server_obj(&handle);
+        self.client.event(Toplevel {
+            self_id: self.id,
+            toplevel: handle.id,
+        });
+        let data = toplevel.tl_data();
+        handle.send_title(&data.title.borrow_mut());
+        handle.send_app_id(&data.app_id.borrow_mut());
+        handle.send_output(data);
+        handle.send_state(data);
+        handle.send_done();
+        handle.toplevel.set(Some(toplevel.clone()));
+        data.handles.set((self.client.id, handle.id), handle);
+        Ok(())
+    }
+
+    fn stop(&self, parser: MsgParser<'_, '_>) -> Result<(), ZwlrForeignToplevelManagerV1Error> {
+        let _req: Stop = self.client.parse(self, parser)?;
+        self.remove_from_state();
+        self.client.event(Finished { self_id: self.id });
+        self.client.remove_obj(self)?;
+        Ok(())
+    }
+
+    fn remove_from_state(&self) {
+        self.client
+            .state
+            .toplevel_managers
+            .remove(&(self.client.id, self.id));
+    }
+}
+
+object_base! {
+    ZwlrForeignToplevelManagerV1;
+
+    STOP => stop,
+}
+
+impl Object for ZwlrForeignToplevelManagerV1 {
+    fn num_requests(&self) -> u32 {
+        STOP + 1
+    }
+
+    fn break_loops(&self) {
+        self.remove_from_state();
+    }
+}
+
+simple_add_obj!(ZwlrForeignToplevelManagerV1);
+
+#[derive(Debug, Error)]
+pub enum ZwlrForeignToplevelManagerV1Error {
+    #[error(transparent)]
+    ClientError(Box<ClientError>),
+    #[error("Parsing failed")]
+    MsgParserError(#[source] Box<MsgParserError>),
+}
+efrom!(ZwlrForeignToplevelManagerV1Error, ClientError);
+efrom!(ZwlrForeignToplevelManagerV1Error, MsgParserError);
@@ -0,0 +1,129 @@
+use {
+    crate::{
+        client::{Client, ClientError},
+        globals::{Global, GlobalName},
+        ifs::zwlr_gamma_control_v1::ZwlrGammaControlV1,
+        leaks::Tracker,
+        object::Object,
+        utils::buffd::{MsgParser, MsgParserError},
+        wire::{zwlr_gamma_control_manager_v1::*, ZwlrGammaControlManagerV1Id},
+    },
+    std::rc::Rc,
+    thiserror::Error,
+};
+
+pub struct ZwlrGammaControlManagerV1Global {
+    pub name: GlobalName,
+}
+
+pub struct ZwlrGammaControlManagerV1 {
+    pub id: ZwlrGammaControlManagerV1Id,
+    pub client: Rc<Client>,
+    pub tracker: Tracker<Self>,
+}
+
+impl ZwlrGammaControlManagerV1Global {
+    pub fn new(name: GlobalName) -> Self {
+        Self { name }
+    }
+
+    fn bind_(
+        self: Rc<Self>,
+        id: ZwlrGammaControlManagerV1Id,
+        client: &Rc<Client>,
+        _version: u32,
+    ) -> Result<(), ZwlrGammaControlManagerV1Error> {
+        let obj = Rc::new(ZwlrGammaControlManagerV1 {
+            id,
+            client: client.clone(),
+            tracker: Default::default(),
+        });
+        track!(client, obj);
+        client.add_client_obj(&obj)?;
+        Ok(())
+    }
+}
+
+global_base!(
+    ZwlrGammaControlManagerV1Global,
+    ZwlrGammaControlManagerV1,
+    ZwlrGammaControlManagerV1Error
+);
+
+impl Global for ZwlrGammaControlManagerV1Global {
+    fn singleton(&self) -> bool {
+        true
+    }
+
+    fn version(&self) -> u32 {
+        1
+    }
+
+    fn secure(&self) -> bool {
+        true
+    }
+}
+
+simple_add_global!(ZwlrGammaControlManagerV1Global);
+
+impl ZwlrGammaControlManagerV1 {
+    fn get_gamma_control(
+        &self,
+        parser: MsgParser<'_, '_>,
+    ) -> Result<(), ZwlrGammaControlManagerV1Error> {
+        let req: GetGammaControl = self.client.parse(self, parser)?;
+        let output = self.client.lookup(req.output)?;
+        let connector = &output.global.connector;
+        let control = Rc::new(ZwlrGammaControlV1 {
+            id: req.id,
+            client: self.client.clone(),
+            tracker: Default::default(),
+            connector: connector.clone(),
+            size: connector.connector.gamma_size(),
+            ramps: Default::default(),
+        });
+        track!(self.client, control);
+        self.client.add_client_obj(&control)?;
+        if output.global.destroyed.get()
+            || control.size == 0
+            || connector.gamma_control.get().is_some()
+        {
+            control.send_failed();
+        } else {
+            connector.gamma_control.set(Some(control.clone()));
+            control.send_gamma_size();
+        }
+        Ok(())
+    }
+
+    fn destroy(&self, parser: MsgParser<'_, '_>) -> Result<(), ZwlrGammaControlManagerV1Error> {
+        let _req: Destroy = self.client.parse(self, parser)?;
+        self.client.remove_obj(self)?;
+        Ok(())
+    }
+}
+
+object_base! {
+    ZwlrGammaControlManagerV1;
+
+    GET_GAMMA_CONTROL => get_gamma_control,
+    DESTROY => destroy,
+}
+
+impl Object for ZwlrGammaControlManagerV1 {
+    fn num_requests(&self) -> u32 {
+        DESTROY + 1
+    }
+}
+
+simple_add_obj!(ZwlrGammaControlManagerV1);
+
+#[derive(Debug, Error)]
+pub enum ZwlrGammaControlManagerV1Error {
+    #[error(transparent)]
+    ClientError(Box<ClientError>),
+    #[error("Parsing failed")]
+    MsgParserError(#[source] Box<MsgParserError>),
+}
+efrom!(ZwlrGammaControlManagerV1Error, ClientError);
+efrom!(ZwlrGammaControlManagerV1Error, MsgParserError);
@@ -0,0 +1,118 @@
+use {
+    crate::{
+        client::{Client, ClientError},
+        leaks::Tracker,
+        object::Object,
+        state::ConnectorData,
+        utils::{
+            buffd::{MsgParser, MsgParserError},
+            clonecell::CloneCell,
+            oserror::OsError,
+            pread::pread_all,
+        },
+        wire::{zwlr_gamma_control_v1::*, ZwlrGammaControlV1Id},
+    },
+    std::rc::Rc,
+    thiserror::Error,
+    uapi::OwnedFd,
+};
+
+pub struct ZwlrGammaControlV1 {
+    pub id: ZwlrGammaControlV1Id,
+    pub client: Rc<Client>,
+    pub tracker: Tracker<Self>,
+    pub connector: Rc<ConnectorData>,
+    pub size: u32,
+    /// The gamma ramps set by the client.
+    pub ramps: CloneCell<Option<Rc<[u16]>>>,
+}
+
+impl ZwlrGammaControlV1 {
+    pub fn send_gamma_size(&self) {
+        self.client.event(GammaSize {
+            self_id: self.id,
+            size: self.size,
+        });
+    }
+
+    pub fn send_failed(&self) {
+        self.client.event(Failed { self_id: self.id });
+    }
+
+    fn is_active(&self) -> bool {
+        match self.connector.gamma_control.get() {
+            Some(gc) => gc.id == self.id && gc.client.id == self.client.id,
+            _ => false,
+        }
+    }
+
+    fn read_ramps(&self, fd: &OwnedFd) -> Result<Rc<[u16]>, ZwlrGammaControlV1Error> {
+        let mut ramps = vec![0u16; 3 * self.size as usize];
+        let buf = uapi::as_bytes_mut(&mut ramps[..]);
+        let len = pread_all(fd.raw(), buf).map_err(ZwlrGammaControlV1Error::ReadGamma)?;
+        if len != buf.len() {
+            return Err(ZwlrGammaControlV1Error::InvalidGamma);
+        }
+        Ok(ramps.into())
+    }
+
+    fn set_gamma(&self, parser: MsgParser<'_, '_>) -> Result<(), ZwlrGammaControlV1Error> {
+        let req: SetGamma = self.client.parse(self, parser)?;
+        if !self.is_active() {
+            return Ok(());
+        }
+        let ramps = self.read_ramps(&req.fd)?;
+        self.ramps.set(Some(ramps));
+        self.connector.update_gamma();
+        Ok(())
+    }
+
+    fn detach(&self) {
+        if self.is_active() {
+            self.connector.gamma_control.take();
+            if self.ramps.get().is_some() {
+                self.connector.update_gamma();
+            }
+        }
+    }
+
+    fn destroy(&self, parser: MsgParser<'_, '_>) -> Result<(), ZwlrGammaControlV1Error> {
+        let _req: Destroy = self.client.parse(self, parser)?;
+        self.detach();
+        self.client.remove_obj(self)?;
+        Ok(())
+    }
+}
+
+object_base! {
+    ZwlrGammaControlV1;
+
+    SET_GAMMA => set_gamma,
+    DESTROY => destroy,
+}
+
+impl Object for ZwlrGammaControlV1 {
+    fn num_requests(&self) -> u32 {
+        DESTROY + 1
+    }
+
+    fn break_loops(&self) {
+        self.detach();
+    }
+}
+
+simple_add_obj!(ZwlrGammaControlV1);
+
+#[derive(Debug, Error)]
+pub enum ZwlrGammaControlV1Error {
+    #[error(transparent)]
+    ClientError(Box<ClientError>),
+    #[error("Parsing failed")]
+    MsgParserError(#[source] Box<MsgParserError>),
+    #[error("Could not read the gamma ramps")]
+    ReadGamma(#[source] OsError),
+    #[error("The gamma ramps have the wrong size")]
+    InvalidGamma,
+}
+efrom!(ZwlrGammaControlV1Error, ClientError);
+efrom!(ZwlrGammaControlV1Error, MsgParserError);
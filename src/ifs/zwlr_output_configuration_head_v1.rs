@@ -0,0 +1,145 @@
+use {
+    crate::{
+        backend,
+        client::{Client, ClientError},
+        fixed::Fixed,
+        ifs::zwlr_output_head_v1::ZwlrOutputHeadV1,
+        leaks::Tracker,
+        object::Object,
+        utils::buffd::{MsgParser, MsgParserError},
+        wire::{zwlr_output_configuration_head_v1::*, ZwlrOutputConfigurationHeadV1Id},
+    },
+    std::{cell::Cell, rc::Rc},
+    thiserror::Error,
+};
+
+pub struct ZwlrOutputConfigurationHeadV1 {
+    pub id: ZwlrOutputConfigurationHeadV1Id,
+    pub client: Rc<Client>,
+    pub tracker: Tracker<Self>,
+    pub head: Rc<ZwlrOutputHeadV1>,
+    pub mode: Cell<Option<backend::Mode>>,
+    pub position: Cell<Option<(i32, i32)>>,
+    pub transform: Cell<Option<crate::transform::Transform>>,
+    pub scale: Cell<Option<Fixed>>,
+}
+
+impl ZwlrOutputConfigurationHeadV1 {
+    fn set_mode(
+        &self,
+        parser: MsgParser<'_, '_>,
+    ) -> Result<(), ZwlrOutputConfigurationHeadV1Error> {
+        let req: SetMode = self.client.parse(self, parser)?;
+        let mode = self.client.lookup(req.mode)?;
+        if mode.connector != self.head.connector.connector.id() {
+            return Err(ZwlrOutputConfigurationHeadV1Error::ForeignMode);
+        }
+        if self.mode.replace(Some(mode.mode)).is_some() {
+            return Err(ZwlrOutputConfigurationHeadV1Error::AlreadySet("mode"));
+        }
+        Ok(())
+    }
+
+    fn set_custom_mode(
+        &self,
+        parser: MsgParser<'_, '_>,
+    ) -> Result<(), ZwlrOutputConfigurationHeadV1Error> {
+        let req: SetCustomMode = self.client.parse(self, parser)?;
+        if req.width <= 0 || req.height <= 0 || req.refresh < 0 {
+            return Err(ZwlrOutputConfigurationHeadV1Error::InvalidCustomMode);
+        }
+        let mode = backend::Mode {
+            width: req.width,
+            height: req.height,
+            refresh_rate_millihz: req.refresh as _,
+        };
+        if self.mode.replace(Some(mode)).is_some() {
+            return Err(ZwlrOutputConfigurationHeadV1Error::AlreadySet("mode"));
+        }
+        Ok(())
+    }
+
+    fn set_position(
+        &self,
+        parser: MsgParser<'_, '_>,
+    ) -> Result<(), ZwlrOutputConfigurationHeadV1Error> {
+        let req: SetPosition = self.client.parse(self, parser)?;
+        if self.position.replace(Some((req.x, req.y))).is_some() {
+            return Err(ZwlrOutputConfigurationHeadV1Error::AlreadySet("position"));
+        }
+        Ok(())
+    }
+
+    fn set_transform(
+        &self,
+        parser: MsgParser<'_, '_>,
+    ) -> Result<(), ZwlrOutputConfigurationHeadV1Error> {
+        let req: SetTransform = self.client.parse(self, parser)?;
+        let transform = match crate::transform::Transform::from_wl(req.transform) {
+            Some(tf) => tf,
+            _ => {
+                return Err(ZwlrOutputConfigurationHeadV1Error::InvalidTransform(
+                    req.transform,
+                ))
+            }
+        };
+        if self.transform.replace(Some(transform)).is_some() {
+            return Err(ZwlrOutputConfigurationHeadV1Error::AlreadySet("transform"));
+        }
+        Ok(())
+    }
+
+    fn set_scale(
+        &self,
+        parser: MsgParser<'_, '_>,
+    ) -> Result<(), ZwlrOutputConfigurationHeadV1Error> {
+        let req: SetScale = self.client.parse(self, parser)?;
+        if req.scale.to_f64() <= 0.0 {
+            return Err(ZwlrOutputConfigurationHeadV1Error::InvalidScale(
+                req.scale.to_f64(),
+            ));
+        }
+        if self.scale.replace(Some(req.scale)).is_some() {
+            return Err(ZwlrOutputConfigurationHeadV1Error::AlreadySet("scale"));
+        }
+        Ok(())
+    }
+}
+
+object_base! {
+    ZwlrOutputConfigurationHeadV1;
+
+    SET_MODE => set_mode,
+    SET_CUSTOM_MODE => set_custom_mode,
+    SET_POSITION => set_position,
+    SET_TRANSFORM => set_transform,
+    SET_SCALE => set_scale,
+}
+
+impl Object for ZwlrOutputConfigurationHeadV1 {
+    fn num_requests(&self) -> u32 {
+        SET_SCALE + 1
+    }
+}
+
+simple_add_obj!(ZwlrOutputConfigurationHeadV1);
+
+#[derive(Debug, Error)]
+pub enum ZwlrOutputConfigurationHeadV1Error {
+    #[error(transparent)]
+    ClientError(Box<ClientError>),
+    #[error("Parsing failed")]
+    MsgParserError(#[source] Box<MsgParserError>),
+    #[error("The {0} has already been set")]
+    AlreadySet(&'static str),
+    #[error("The mode does not belong to the head")]
+    ForeignMode,
+    #[error("The custom mode is invalid")]
+    InvalidCustomMode,
+    #[error("Transform {0} is invalid")]
+    InvalidTransform(i32),
+    #[error("Scale {0} is invalid")]
+    InvalidScale(f64),
+}
+efrom!(ZwlrOutputConfigurationHeadV1Error, ClientError);
+efrom!(ZwlrOutputConfigurationHeadV1Error, MsgParserError);
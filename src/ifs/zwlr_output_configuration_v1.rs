@@ -0,0 +1,275 @@
+use {
+    crate::{
+        backend::{self, ConnectorId},
+        client::{Client, ClientError},
+        compositor::MAX_EXTENTS,
+        ifs::{
+            zwlr_output_configuration_head_v1::ZwlrOutputConfigurationHeadV1,
+            zwlr_output_head_v1::ZwlrOutputHeadV1, zwlr_output_manager_v1::ZwlrOutputManagerV1,
+        },
+        leaks::Tracker,
+        object::Object,
+        scale::Scale,
+        utils::{
+            buffd::{MsgParser, MsgParserError},
+            errorfmt::ErrorFmt,
+        },
+        wire::{zwlr_output_configuration_v1::*, ZwlrOutputConfigurationV1Id},
+    },
+    ahash::AHashMap,
+    std::{
+        cell::{Cell, RefCell},
+        rc::Rc,
+    },
+    thiserror::Error,
+};
+
+pub struct ZwlrOutputConfigurationV1 {
+    pub id: ZwlrOutputConfigurationV1Id,
+    pub client: Rc<Client>,
+    pub tracker: Tracker<Self>,
+    pub manager: Rc<ZwlrOutputManagerV1>,
+    pub serial: u32,
+    pub heads: RefCell<AHashMap<ConnectorId, ConfiguredHead>>,
+    pub used: Cell<bool>,
+}
+
+pub enum ConfiguredHead {
+    Enabled(Rc<ZwlrOutputConfigurationHeadV1>),
+    Disabled(Rc<ZwlrOutputHeadV1>),
+}
+
+impl ConfiguredHead {
+    fn head(&self) -> &Rc<ZwlrOutputHeadV1> {
+        match self {
+            ConfiguredHead::Enabled(c) => &c.head,
+            ConfiguredHead::Disabled(h) => h,
+        }
+    }
+}
+
+/// Returns the mode of the head that matches the requested mode.
+///
+/// A refresh rate of 0 matches any refresh rate.
+fn find_mode(head: &ZwlrOutputHeadV1, mode: backend::Mode) -> Option<backend::Mode> {
+    head.info.modes.iter().copied().find(|m| {
+        (m.width, m.height) == (mode.width, mode.height)
+            && (mode.refresh_rate_millihz == 0
+                || m.refresh_rate_millihz == mode.refresh_rate_millihz)
+    })
+}
+
+impl ZwlrOutputConfigurationV1 {
+    fn configure_head(
+        &self,
+        head: &Rc<ZwlrOutputHeadV1>,
+        configured: ConfiguredHead,
+    ) -> Result<(), ZwlrOutputConfigurationV1Error> {
+        if self.used.get() {
+            return Err(ZwlrOutputConfigurationV1Error::AlreadyUsed);
+        }
+        let mut heads = self.heads.borrow_mut();
+        if heads
+            .insert(head.connector.connector.id(), configured)
+            .is_some()
+        {
+            return Err(ZwlrOutputConfigurationV1Error::AlreadyConfigured);
+        }
+        Ok(())
+    }
+
+    fn enable_head(&self, parser: MsgParser<'_, '_>) -> Result<(), ZwlrOutputConfigurationV1Error> {
+        let req: EnableHead = self.client.parse(self, parser)?;
+        let head = self.client.lookup(req.head)?;
+        let obj = Rc::new(ZwlrOutputConfigurationHeadV1 {
+            id: req.id,
+            client: self.client.clone(),
+            tracker: Default::default(),
+            head: head.clone(),
+            mode: Cell::new(None),
+            position: Cell::new(None),
+            transform: Cell::new(None),
+            scale: Cell::new(None),
+        });
+        track!(self.client, obj);
+        self.client.add_client_obj(&obj)?;
+        self.configure_head(&head, ConfiguredHead::Enabled(obj))
+    }
+
+    fn disable_head(
+        &self,
+        parser: MsgParser<'_, '_>,
+    ) -> Result<(), ZwlrOutputConfigurationV1Error> {
+        let req: DisableHead = self.client.parse(self, parser)?;
+        let head = self.client.lookup(req.head)?;
+        self.configure_head(&head, ConfiguredHead::Disabled(head.clone()))
+    }
+
+    fn apply(&self, parser: MsgParser<'_, '_>) -> Result<(), ZwlrOutputConfigurationV1Error> {
+        let _req: Apply = self.client.parse(self, parser)?;
+        self.handle(false)
+    }
+
+    fn test(&self, parser: MsgParser<'_, '_>) -> Result<(), ZwlrOutputConfigurationV1Error> {
+        let _req: Test = self.client.parse(self, parser)?;
+        self.handle(true)
+    }
+
+    fn handle(&self, test_only: bool) -> Result<(), ZwlrOutputConfigurationV1Error> {
+        if self.used.replace(true) {
+            return Err(ZwlrOutputConfigurationV1Error::AlreadyUsed);
+        }
+        if self.serial != self.manager.serial.get() {
+            self.client.event(Cancelled { self_id: self.id });
+            return Ok(());
+        }
+        if let Err(e) = self.validate() {
+            log::warn!("Rejecting output configuration: {}", ErrorFmt(e));
+            self.client.event(Failed { self_id: self.id });
+            return Ok(());
+        }
+        if !test_only {
+            self.apply_heads();
+        }
+        self.client.event(Succeeded { self_id: self.id });
+        Ok(())
+    }
+
+    fn validate(&self) -> Result<(), ZwlrOutputConfigurationV1Error> {
+        let state = &self.client.state;
+        for (id, configured) in self.heads.borrow_mut().iter() {
+            let head = configured.head();
+            let available = match state.connectors.get(id) {
+                Some(c) => c.monitor_info.get().map(|i| Rc::ptr_eq(&i, &head.info)),
+                _ => None,
+            };
+            if available != Some(true) {
+                return Err(ZwlrOutputConfigurationV1Error::HeadUnavailable(
+                    head.connector.name.clone(),
+                ));
+            }
+            let config = match configured {
+                ConfiguredHead::Enabled(c) => c,
+                ConfiguredHead::Disabled(_) => continue,
+            };
+            if let Some(mode) = config.mode.get() {
+                if find_mode(head, mode).is_none() {
+                    return Err(ZwlrOutputConfigurationV1Error::UnsupportedMode(mode));
+                }
+            }
+            if let Some((x, y)) = config.position.get() {
+                if x < 0 || y < 0 || x > MAX_EXTENTS || y > MAX_EXTENTS {
+                    return Err(ZwlrOutputConfigurationV1Error::InvalidPosition(x, y));
+                }
+            }
+            if let Some(scale) = config.scale.get() {
+                let scale = scale.to_f64();
+                if !(0.1..=1000.0).contains(&scale) {
+                    return Err(ZwlrOutputConfigurationV1Error::ScaleOutOfRange(scale));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn apply_heads(&self) {
+        let state = &self.client.state;
+        let mut scale_changed = false;
+        for (id, configured) in self.heads.borrow_mut().iter() {
+            let connector = &configured.head().connector;
+            let config = match configured {
+                ConfiguredHead::Enabled(c) => c,
+                ConfiguredHead::Disabled(_) => {
+                    connector.set_enabled(false);
+                    continue;
+                }
+            };
+            let scale = config.scale.get().map(|s| Scale::from_f64(s.to_f64()));
+            let position = config.position.get();
+            let transform = config.transform.get();
+            let mode = config.mode.get().and_then(|m| find_mode(&config.head, m));
+            match state.outputs.get(id) {
+                Some(output) => {
+                    if let Some(mode) = mode {
+                        if mode != output.node.global.mode.get() {
+                            connector.connector.set_mode(mode);
+                        }
+                    }
+                    if let Some(scale) = scale {
+                        output.node.set_preferred_scale(scale);
+                        scale_changed = true;
+                    }
+                    if let Some((x, y)) = position {
+                        output.node.set_position(x, y);
+                    }
+                    if let Some(transform) = transform {
+                        output.node.set_transform(transform);
+                    }
+                }
+                _ => {
+                    if let Some(mode) = mode {
+                        connector.connector.set_mode(mode);
+                    }
+                    connector.pending_scale.set(scale);
+                    connector.pending_position.set(position);
+                    connector.pending_transform.set(transform);
+                    connector.set_enabled(true);
+                }
+            }
+        }
+        if scale_changed {
+            state.damage();
+        }
+    }
+
+    fn destroy(&self, parser: MsgParser<'_, '_>) -> Result<(), ZwlrOutputConfigurationV1Error> {
+        let _req: Destroy = self.client.parse(self, parser)?;
+        self.heads.borrow_mut().clear();
+        self.client.remove_obj(self)?;
+        Ok(())
+    }
+}
+
+object_base! {
+    ZwlrOutputConfigurationV1;
+
+    ENABLE_HEAD => enable_head,
+    DISABLE_HEAD => disable_head,
+    APPLY => apply,
+    TEST => test,
+    DESTROY => destroy,
+}
+
+impl Object for ZwlrOutputConfigurationV1 {
+    fn num_requests(&self) -> u32 {
+        DESTROY + 1
+    }
+
+    fn break_loops(&self) {
+        self.heads.borrow_mut().clear();
+    }
+}
+
+simple_add_obj!(ZwlrOutputConfigurationV1);
+
+#[derive(Debug, Error)]
+pub enum ZwlrOutputConfigurationV1Error {
+    #[error(transparent)]
+    ClientError(Box<ClientError>),
+    #[error("Parsing failed")]
+    MsgParserError(#[source] Box<MsgParserError>),
+    #[error("The configuration has already been applied or tested")]
+    AlreadyUsed,
+    #[error("The head has already been configured")]
+    AlreadyConfigured,
+    #[error("Head {0} is no longer available")]
+    HeadUnavailable(String),
+    #[error("The head does not support mode {0:?}")]
+    UnsupportedMode(backend::Mode),
+    #[error("Position {0}x{1} is outside the allowed range")]
+    InvalidPosition(i32, i32),
+    #[error("Scale {0} is outside the allowed range")]
+    ScaleOutOfRange(f64),
+}
+efrom!(ZwlrOutputConfigurationV1Error, ClientError);
+efrom!(ZwlrOutputConfigurationV1Error, MsgParserError);
@@ -0,0 +1,212 @@
+use {
+    crate::{
+        backend::{self, MonitorInfo},
+        client::{Client, ClientError},
+        fixed::Fixed,
+        ifs::zwlr_output_mode_v1::ZwlrOutputModeV1,
+        leaks::Tracker,
+        object::Object,
+        state::{ConnectorData, State},
+        utils::buffd::{MsgParser, MsgParserError},
+        wire::{zwlr_output_head_v1::*, ZwlrOutputHeadV1Id, ZwlrOutputModeV1Id},
+    },
+    std::{
+        cell::{Cell, RefCell},
+        rc::Rc,
+    },
+    thiserror::Error,
+};
+
+pub const MAKE_SINCE: u32 = 2;
+pub const RELEASE_SINCE: u32 = 3;
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct HeadState {
+    pub enabled: bool,
+    pub mode: backend::Mode,
+    pub position: (i32, i32),
+    pub scale: crate::scale::Scale,
+    pub transform: crate::transform::Transform,
+}
+
+impl HeadState {
+    pub fn of(state: &State, connector: &ConnectorData, info: &MonitorInfo) -> Self {
+        match state.outputs.get(&connector.connector.id()) {
+            Some(output) => {
+                let pos = output.node.global.pos.get();
+                Self {
+                    enabled: true,
+                    mode: output.node.global.mode.get(),
+                    position: (pos.x1(), pos.y1()),
+                    scale: output.node.preferred_scale.get(),
+                    transform: output.node.global.transform.get(),
+                }
+            }
+            _ => Self {
+                enabled: false,
+                mode: info.initial_mode,
+                position: (0, 0),
+                scale: crate::scale::Scale::from_int(1),
+                transform: Default::default(),
+            },
+        }
+    }
+}
+
+pub struct ZwlrOutputHeadV1 {
+    pub id: ZwlrOutputHeadV1Id,
+    pub client: Rc<Client>,
+    pub tracker: Tracker<Self>,
+    pub version: u32,
+    pub connector: Rc<ConnectorData>,
+    pub info: Rc<MonitorInfo>,
+    pub modes: RefCell<Vec<Rc<ZwlrOutputModeV1>>>,
+    pub sent: Cell<Option<HeadState>>,
+    pub released: Cell<bool>,
+}
+
+impl ZwlrOutputHeadV1 {
+    pub fn send_static(&self) {
+        self.client.event(Name {
+            self_id: self.id,
+            name: &self.connector.name,
+        });
+        let description = format!(
+            "{} {} {}",
+            self.info.manufacturer, self.info.product, self.info.serial_number
+        );
+        self.client.event(Description {
+            self_id: self.id,
+            description: description.trim(),
+        });
+        if self.info.width_mm > 0 && self.info.height_mm > 0 {
+            self.client.event(PhysicalSize {
+                self_id: self.id,
+                width: self.info.width_mm,
+                height: self.info.height_mm,
+            });
+        }
+        if self.version >= MAKE_SINCE {
+            self.client.event(Make {
+                self_id: self.id,
+                make: &self.info.manufacturer,
+            });
+            self.client.event(Model {
+                self_id: self.id,
+                model: &self.info.product,
+            });
+            self.client.event(SerialNumber {
+                self_id: self.id,
+                serial_number: &self.info.serial_number,
+            });
+        }
+    }
+
+    pub fn send_mode(&self, mode: ZwlrOutputModeV1Id) {
+        self.client.event(Mode {
+            self_id: self.id,
+            mode,
+        });
+    }
+
+    /// Sends the parts of `new` that differ from the last state sent to the client.
+    ///
+    /// Returns whether any events were sent.
+    pub fn send_state(&self, new: &HeadState) -> bool {
+        if self.released.get() {
+            return false;
+        }
+        let old = self.sent.replace(Some(*new));
+        if old == Some(*new) {
+            return false;
+        }
+        if old.map(|o| o.enabled) != Some(new.enabled) {
+            self.client.event(Enabled {
+                self_id: self.id,
+                enabled: new.enabled as _,
+            });
+        }
+        if !new.enabled {
+            return true;
+        }
+        let old = old.filter(|o| o.enabled);
+        if old.map(|o| o.mode) != Some(new.mode) {
+            let modes = self.modes.borrow_mut();
+            if let Some(mode) = modes.iter().find(|m| m.mode == new.mode) {
+                self.client.event(CurrentMode {
+                    self_id: self.id,
+                    mode: mode.id,
+                });
+            }
+        }
+        if old.map(|o| o.position) != Some(new.position) {
+            self.client.event(Position {
+                self_id: self.id,
+                x: new.position.0,
+                y: new.position.1,
+            });
+        }
+        if old.map(|o| o.transform) != Some(new.transform) {
+            self.client.event(Transform {
+                self_id: self.id,
+                transform: new.transform.to_wl(),
+            });
+        }
+        if old.map(|o| o.scale) != Some(new.scale) {
+            self.client.event(Scale {
+                self_id: self.id,
+                scale: Fixed::from_f64(new.scale.to_f64()),
+            });
+        }
+        true
+    }
+
+    pub fn send_finished(&self) {
+        if self.released.get() {
+            return;
+        }
+        for mode in self.modes.borrow_mut().iter() {
+            mode.send_finished();
+        }
+        self.client.event(Finished { self_id: self.id });
+    }
+
+    fn release(&self, parser: MsgParser<'_, '_>) -> Result<(), ZwlrOutputHeadV1Error> {
+        let _req: Release = self.client.parse(self, parser)?;
+        self.released.set(true);
+        self.client.remove_obj(self)?;
+        Ok(())
+    }
+}
+
+object_base! {
+    ZwlrOutputHeadV1;
+
+    RELEASE => release,
+}
+
+impl Object for ZwlrOutputHeadV1 {
+    fn num_requests(&self) -> u32 {
+        if self.version < RELEASE_SINCE {
+            0
+        } else {
+            RELEASE + 1
+        }
+    }
+
+    fn break_loops(&self) {
+        self.modes.borrow_mut().clear();
+    }
+}
+
+dedicated_add_obj!(ZwlrOutputHeadV1, ZwlrOutputHeadV1Id, output_heads);
+
+#[derive(Debug, Error)]
+pub enum ZwlrOutputHeadV1Error {
+    #[error(transparent)]
+    ClientError(Box<ClientError>),
+    #[error("Parsing failed")]
+    MsgParserError(#[source] Box<MsgParserError>),
+}
+efrom!(ZwlrOutputHeadV1Error, ClientError);
+efrom!(ZwlrOutputHeadV1Error, MsgParserError);
@@ -0,0 +1,259 @@
+use {
+    crate::{
+        backend::{ConnectorId, MonitorInfo},
+        client::{Client, ClientError},
+        globals::{Global, GlobalName},
+        ifs::{
+            zwlr_output_configuration_v1::ZwlrOutputConfigurationV1,
+            zwlr_output_head_v1::{HeadState, ZwlrOutputHeadV1},
+            zwlr_output_mode_v1::ZwlrOutputModeV1,
+        },
+        leaks::Tracker,
+        object::Object,
+        state::{ConnectorData, State},
+        utils::{
+            buffd::{MsgParser, MsgParserError},
+            copyhashmap::CopyHashMap,
+            numcell::NumCell,
+        },
+        wire::{zwlr_output_manager_v1::*, ZwlrOutputManagerV1Id},
+    },
+    std::{
+        cell::{Cell, RefCell},
+        rc::Rc,
+    },
+    thiserror::Error,
+};
+
+pub struct ZwlrOutputManagerV1Global {
+    pub name: GlobalName,
+}
+
+impl ZwlrOutputManagerV1Global {
+    pub fn new(name: GlobalName) -> Self {
+        Self { name }
+    }
+
+    fn bind_(
+        self: Rc<Self>,
+        id: ZwlrOutputManagerV1Id,
+        client: &Rc<Client>,
+        version: u32,
+    ) -> Result<(), ZwlrOutputManagerV1Error> {
+        let mgr = Rc::new(ZwlrOutputManagerV1 {
+            id,
+            client: client.clone(),
+            tracker: Default::default(),
+            version,
+            serial: NumCell::new(0),
+            heads: Default::default(),
+        });
+        track!(client, mgr);
+        client.add_client_obj(&mgr)?;
+        client
+            .state
+            .output_managers
+            .set((client.id, id), mgr.clone());
+        mgr.update(true)?;
+        Ok(())
+    }
+}
+
+global_base!(
+    ZwlrOutputManagerV1Global,
+    ZwlrOutputManagerV1,
+    ZwlrOutputManagerV1Error
+);
+
+simple_add_global!(ZwlrOutputManagerV1Global);
+
+impl Global for ZwlrOutputManagerV1Global {
+    fn singleton(&self) -> bool {
+        true
+    }
+
+    fn version(&self) -> u32 {
+        3
+    }
+
+    fn secure(&self) -> bool {
+        true
+    }
+}
+
+pub async fn output_manager_updates(state: Rc<State>) {
+    loop {
+        state.output_managers_changed.triggered().await;
+        let managers: Vec<_> = state.output_managers.lock().values().cloned().collect();
+        for manager in managers {
+            if let Err(e) = manager.update(false) {
+                manager.client.error(e);
+            }
+        }
+    }
+}
+
+pub struct ZwlrOutputManagerV1 {
+    pub id: ZwlrOutputManagerV1Id,
+    pub client: Rc<Client>,
+    pub tracker: Tracker<Self>,
+    pub version: u32,
+    pub serial: NumCell<u32>,
+    pub heads: CopyHashMap<ConnectorId, Rc<ZwlrOutputHeadV1>>,
+}
+
+impl ZwlrOutputManagerV1 {
+    /// Brings the heads of this manager up to date with the connectors of the compositor.
+    ///
+    /// Sends a `done` event with a new serial if anything changed or if `force_done` is set.
+    pub fn update(&self, force_done: bool) -> Result<(), ClientError> {
+        let state = &self.client.state;
+        let mut changed = force_done;
+        let mut removed = vec![];
+        for (id, head) in self.heads.lock().iter() {
+            let current = state.connectors.get(id).and_then(|c| c.monitor_info.get());
+            match current {
+                Some(info) if Rc::ptr_eq(&info, &head.info) => {}
+                _ => removed.push(*id),
+            }
+        }
+        for id in removed {
+            if let Some(head) = self.heads.remove(&id) {
+                head.send_finished();
+                changed = true;
+            }
+        }
+        let connectors: Vec<_> = state.connectors.lock().values().cloned().collect();
+        for connector in connectors {
+            let info = match connector.monitor_info.get() {
+                Some(info) => info,
+                _ => continue,
+            };
+            let head = match self.heads.get(&connector.connector.id()) {
+                Some(head) => head,
+                _ => {
+                    changed = true;
+                    self.create_head(&connector, &info)?
+                }
+            };
+            changed |= head.send_state(&HeadState::of(state, &connector, &info));
+        }
+        if changed {
+            self.client.event(Done {
+                self_id: self.id,
+                serial: self.serial.fetch_add(1) + 1,
+            });
+        }
+        Ok(())
+    }
+
+    fn create_head(
+        &self,
+        connector: &Rc<ConnectorData>,
+        info: &Rc<MonitorInfo>,
+    ) -> Result<Rc<ZwlrOutputHeadV1>, ClientError> {
+        let head = Rc::new(ZwlrOutputHeadV1 {
+            id: self.client.new_id()?,
+            client: self.client.clone(),
+            tracker: Default::default(),
+            version: self.version,
+            connector: connector.clone(),
+            info: info.clone(),
+            modes: RefCell::new(vec![]),
+            sent: Cell::new(None),
+            released: Cell::new(false),
+        });
+        track!(self.client, head);
+        self.client.add_server_obj(&head);
+        self.client.event(Head {
+            self_id: self.id,
+            head: head.id,
+        });
+        head.send_static();
+        for mode in &info.modes {
+            let mode = Rc::new(ZwlrOutputModeV1 {
+                id: self.client.new_id()?,
+                client: self.client.clone(),
+                tracker: Default::default(),
+                version: self.version,
+                connector: connector.connector.id(),
+                mode: *mode,
+                released: Cell::new(false),
+            });
+            track!(self.client, mode);
+            self.client.add_server_obj(&mode);
+            head.send_mode(mode.id);
+            mode.send();
+            if info.preferred_mode == Some(mode.mode) {
+                mode.send_preferred();
+            }
+            head.modes.borrow_mut().push(mode);
+        }
+        self.heads.set(connector.connector.id(), head.clone());
+        Ok(head)
+    }
+
+    fn create_configuration(
+        self: &Rc<Self>,
+        parser: MsgParser<'_, '_>,
+    ) -> Result<(), ZwlrOutputManagerV1Error> {
+        let req: CreateConfiguration = self.client.parse(&**self, parser)?;
+        let config = Rc::new(ZwlrOutputConfigurationV1 {
+            id: req.id,
+            client: self.client.clone(),
+            tracker: Default::default(),
+            manager: self.clone(),
+            serial: req.serial,
+            heads: Default::default(),
+            used: Cell::new(false),
+        });
+        track!(self.client, config);
+        self.client.add_client_obj(&config)?;
+        Ok(())
+    }
+
+    fn stop(&self, parser: MsgParser<'_, '_>) -> Result<(), ZwlrOutputManagerV1Error> {
+        let _req: Stop = self.client.parse(self, parser)?;
+        self.remove_from_state();
+        self.client.event(Finished { self_id: self.id });
+        self.client.remove_obj(self)?;
+        Ok(())
+    }
+
+    fn remove_from_state(&self) {
+        self.client
+            .state
+            .output_managers
+            .remove(&(self.client.id, self.id));
+        self.heads.clear();
+    }
+}
+
+object_base! {
+    ZwlrOutputManagerV1;
+
+    CREATE_CONFIGURATION => create_configuration,
+    STOP => stop,
+}
+
+impl Object for ZwlrOutputManagerV1 {
+    fn num_requests(&self) -> u32 {
+        STOP + 1
+    }
+
+    fn break_loops(&self) {
+        self.remove_from_state();
+    }
+}
+
+simple_add_obj!(ZwlrOutputManagerV1);
+
+#[derive(Debug, Error)]
+pub enum ZwlrOutputManagerV1Error {
+    #[error(transparent)]
+    ClientError(Box<ClientError>),
+    #[error("Parsing failed")]
+    MsgParserError(#[source] Box<MsgParserError>),
+}
+efrom!(ZwlrOutputManagerV1Error, ClientError);
+efrom!(ZwlrOutputManagerV1Error, MsgParserError);
@@ -0,0 +1,82 @@
+use {
+    crate::{
+        backend::{self, ConnectorId},
+        client::{Client, ClientError},
+        ifs::zwlr_output_head_v1::RELEASE_SINCE,
+        leaks::Tracker,
+        object::Object,
+        utils::buffd::{MsgParser, MsgParserError},
+        wire::{zwlr_output_mode_v1::*, ZwlrOutputModeV1Id},
+    },
+    std::{cell::Cell, rc::Rc},
+    thiserror::Error,
+};
+
+pub struct ZwlrOutputModeV1 {
+    pub id: ZwlrOutputModeV1Id,
+    pub client: Rc<Client>,
+    pub tracker: Tracker<Self>,
+    pub version: u32,
+    pub connector: ConnectorId,
+    pub mode: backend::Mode,
+    pub released: Cell<bool>,
+}
+
+impl ZwlrOutputModeV1 {
+    pub fn send(&self) {
+        self.client.event(Size {
+            self_id: self.id,
+            width: self.mode.width,
+            height: self.mode.height,
+        });
+        self.client.event(Refresh {
+            self_id: self.id,
+            refresh: self.mode.refresh_rate_millihz as _,
+        });
+    }
+
+    pub fn send_preferred(&self) {
+        self.client.event(Preferred { self_id: self.id });
+    }
+
+    pub fn send_finished(&self) {
+        if !self.released.get() {
+            self.client.event(Finished { self_id: self.id });
+        }
+    }
+
+    fn release(&self, parser: MsgParser<'_, '_>) -> Result<(), ZwlrOutputModeV1Error> {
+        let _req: Release = self.client.parse(self, parser)?;
+        self.released.set(true);
+        self.client.remove_obj(self)?;
+        Ok(())
+    }
+}
+
+object_base! {
+    ZwlrOutputModeV1;
+
+    RELEASE => release,
+}
+
+impl Object for ZwlrOutputModeV1 {
+    fn num_requests(&self) -> u32 {
+        if self.version < RELEASE_SINCE {
+            0
+        } else {
+            RELEASE + 1
+        }
+    }
+}
+
+dedicated_add_obj!(ZwlrOutputModeV1, ZwlrOutputModeV1Id, output_modes);
+
+#[derive(Debug, Error)]
+pub enum ZwlrOutputModeV1Error {
+    #[error(transparent)]
+    ClientError(Box<ClientError>),
+    #[error("Parsing failed")]
+    MsgParserError(#[source] Box<MsgParserError>),
+}
+efrom!(ZwlrOutputModeV1Error, ClientError);
+efrom!(ZwlrOutputModeV1Error, MsgParserError);
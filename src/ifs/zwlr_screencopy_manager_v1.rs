@@ -97,10 +97,16 @@ impl ZwlrScreencopyManagerV1 {
         region: Option<Rect>,
     ) -> Result<(), ZwlrScreencopyManagerV1Error> {
         let output = self.client.lookup(output)?;
-        let mut rect = output.global.position().at_point(0, 0);
+        let pos = output.global.position();
+        let mut rect = pos.at_point(0, 0);
         if let Some(region) = region {
             rect = rect.intersect(region);
         }
+        let rect = output
+            .global
+            .transform
+            .get()
+            .apply_rect(rect, pos.width(), pos.height());
         let frame = Rc::new(ZwlrScreencopyFrameV1 {
             id: frame,
             client: self.client.clone(),
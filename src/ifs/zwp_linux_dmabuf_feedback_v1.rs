@@ -0,0 +1,239 @@
+use {
+    crate::{
+        client::{Client, ClientError},
+        ifs::wl_surface::WlSurface,
+        leaks::Tracker,
+        object::Object,
+        render::RenderContext,
+        state::State,
+        utils::{
+            buffd::{MsgParser, MsgParserError},
+            errorfmt::ErrorFmt,
+            oserror::OsError,
+        },
+        video::INVALID_MODIFIER,
+        wire::{zwp_linux_dmabuf_feedback_v1::*, ZwpLinuxDmabufFeedbackV1Id},
+    },
+    ahash::AHashSet,
+    std::{io::Write, rc::Rc},
+    thiserror::Error,
+    uapi::{c, OwnedFd},
+};
+
+#[cfg(test)]
+mod tests;
+
+pub const TRANCHE_FLAG_SCANOUT: u32 = 1;
+
+/// The format table of the render device that is shared by all feedback objects.
+pub struct DmabufFeedbackTable {
+    pub fd: Rc<OwnedFd>,
+    pub size: u32,
+    pub main_device: c::dev_t,
+    /// The format and modifier of each entry in the table.
+    pub entries: Vec<(u32, u64)>,
+    pub all_indices: Vec<u16>,
+}
+
+impl DmabufFeedbackTable {
+    pub fn new(ctx: &RenderContext) -> Result<Self, DmabufFeedbackTableError> {
+        let main_device = match uapi::stat(&**ctx.render_node()) {
+            Ok(stat) => stat.st_rdev,
+            Err(e) => return Err(DmabufFeedbackTableError::Stat(e.into())),
+        };
+        let mut entries = vec![];
+        for format in ctx.formats().values() {
+            if format.implicit_external_only && !ctx.supports_external_texture() {
+                continue;
+            }
+            entries.push((format.format.drm, INVALID_MODIFIER));
+            for modifier in format.modifiers.values() {
+                if modifier.external_only && !ctx.supports_external_texture() {
+                    continue;
+                }
+                entries.push((format.format.drm, modifier.modifier));
+            }
+        }
+        entries.sort_unstable();
+        entries.truncate(u16::MAX as usize + 1);
+        let data = format_table_data(&entries);
+        let mut memfd = match uapi::memfd_create(
+            "dmabuf-feedback-format-table",
+            c::MFD_CLOEXEC | c::MFD_ALLOW_SEALING,
+        ) {
+            Ok(fd) => fd,
+            Err(e) => return Err(DmabufFeedbackTableError::Memfd(e.into())),
+        };
+        if let Err(e) = memfd.write_all(&data) {
+            return Err(DmabufFeedbackTableError::Write(e));
+        }
+        let seals = c::F_SEAL_SEAL | c::F_SEAL_GROW | c::F_SEAL_SHRINK | c::F_SEAL_WRITE;
+        if let Err(e) = uapi::fcntl_add_seals(memfd.raw(), seals) {
+            return Err(DmabufFeedbackTableError::Seal(e.into()));
+        }
+        Ok(Self {
+            fd: Rc::new(memfd),
+            size: data.len() as _,
+            main_device,
+            all_indices: (0..entries.len()).map(|i| i as u16).collect(),
+            entries,
+        })
+    }
+}
+
+/// Serializes the entries as an array of `{ format: u32, padding: u32, modifier: u64 }`.
+fn format_table_data(entries: &[(u32, u64)]) -> Vec<u8> {
+    let mut data = Vec::with_capacity(entries.len() * 16);
+    for &(format, modifier) in entries {
+        data.extend_from_slice(&format.to_ne_bytes());
+        data.extend_from_slice(&[0; 4]);
+        data.extend_from_slice(&modifier.to_ne_bytes());
+    }
+    data
+}
+
+/// The formats that a device can scan out directly.
+pub struct ScanoutFeedback {
+    pub device: c::dev_t,
+    /// The supported format and modifier pairs.
+    pub formats: AHashSet<(u32, u64)>,
+}
+
+impl ScanoutFeedback {
+    /// Returns the indices of the table entries that can be scanned out.
+    fn indices(&self, entries: &[(u32, u64)]) -> Vec<u16> {
+        entries
+            .iter()
+            .enumerate()
+            .filter(|(_, entry)| self.formats.contains(entry))
+            .map(|(idx, _)| idx as u16)
+            .collect()
+    }
+}
+
+pub struct ZwpLinuxDmabufFeedbackV1 {
+    pub id: ZwpLinuxDmabufFeedbackV1Id,
+    pub client: Rc<Client>,
+    pub tracker: Tracker<Self>,
+    /// The surface of a surface feedback object or `None` for the default feedback.
+    pub surface: Option<Rc<WlSurface>>,
+}
+
+impl ZwpLinuxDmabufFeedbackV1 {
+    pub fn send_feedback(&self) {
+        let table = match self.client.state.dmabuf_feedback_table.get() {
+            Some(t) => t,
+            _ => return,
+        };
+        self.client.event(FormatTable {
+            self_id: self.id,
+            fd: table.fd.clone(),
+            size: table.size,
+        });
+        self.client.event(MainDevice {
+            self_id: self.id,
+            device: table.main_device,
+        });
+        if let Some(scanout) = self.surface.as_ref().and_then(|s| s.scanout_feedback()) {
+            let indices = scanout.indices(&table.entries);
+            if !indices.is_empty() {
+                self.send_tranche(scanout.device, &indices, TRANCHE_FLAG_SCANOUT);
+            }
+        }
+        self.send_tranche(table.main_device, &table.all_indices, 0);
+        self.client.event(Done { self_id: self.id });
+    }
+
+    fn send_tranche(&self, device: c::dev_t, indices: &[u16], flags: u32) {
+        self.client.event(TrancheTargetDevice {
+            self_id: self.id,
+            device,
+        });
+        self.client.event(TrancheFormats {
+            self_id: self.id,
+            indices,
+        });
+        self.client.event(TrancheFlags {
+            self_id: self.id,
+            flags,
+        });
+        self.client.event(TrancheDone { self_id: self.id });
+    }
+
+    fn detach(&self) {
+        self.client
+            .state
+            .dmabuf_feedback
+            .remove(&(self.client.id, self.id));
+        if let Some(surface) = &self.surface {
+            surface.dmabuf_feedback.remove(&self.id);
+        }
+    }
+
+    fn destroy(&self, parser: MsgParser<'_, '_>) -> Result<(), ZwpLinuxDmabufFeedbackV1Error> {
+        let _req: Destroy = self.client.parse(self, parser)?;
+        self.detach();
+        self.client.remove_obj(self)?;
+        Ok(())
+    }
+}
+
+/// Sends the current feedback to all feedback objects, e.g. after the render device
+/// changed.
+pub fn resend_dmabuf_feedback(state: &State) {
+    let feedback: Vec<_> = state.dmabuf_feedback.lock().values().cloned().collect();
+    for feedback in feedback {
+        feedback.send_feedback();
+    }
+}
+
+/// Creates the format table of the render device or logs why it could not be created.
+pub fn create_dmabuf_feedback_table(ctx: &RenderContext) -> Option<Rc<DmabufFeedbackTable>> {
+    match DmabufFeedbackTable::new(ctx) {
+        Ok(t) => Some(Rc::new(t)),
+        Err(e) => {
+            log::error!("Could not create the dmabuf format table: {}", ErrorFmt(e));
+            None
+        }
+    }
+}
+
+object_base! {
+    ZwpLinuxDmabufFeedbackV1;
+
+    DESTROY => destroy,
+}
+
+impl Object for ZwpLinuxDmabufFeedbackV1 {
+    fn num_requests(&self) -> u32 {
+        DESTROY + 1
+    }
+
+    fn break_loops(&self) {
+        self.detach();
+    }
+}
+
+simple_add_obj!(ZwpLinuxDmabufFeedbackV1);
+
+#[derive(Debug, Error)]
+pub enum DmabufFeedbackTableError {
+    #[error("Could not stat the render node")]
+    Stat(#[source] OsError),
+    #[error("Could not create a memfd")]
+    Memfd(#[source] OsError),
+    #[error("Could not write the format table")]
+    Write(#[source] std::io::Error),
+    #[error("Could not seal the memfd")]
+    Seal(#[source] OsError),
+}
+
+#[derive(Debug, Error)]
+pub enum ZwpLinuxDmabufFeedbackV1Error {
+    #[error(transparent)]
+    ClientError(Box<ClientError>),
+    #[error("Parsing failed")]
+    MsgParserError(#[source] Box<MsgParserError>),
+}
+efrom!(ZwpLinuxDmabufFeedbackV1Error, ClientError);
+efrom!(ZwpLinuxDmabufFeedbackV1Error, MsgParserError);
@@ -0,0 +1,54 @@
+use {
+    crate::{
+        ifs::zwp_linux_dmabuf_feedback_v1::{format_table_data, ScanoutFeedback},
+        video::{INVALID_MODIFIER, LINEAR_MODIFIER},
+    },
+    ahash::AHashSet,
+};
+
+const XRGB8888: u32 = u32::from_le_bytes(*b"XR24");
+const ARGB8888: u32 = u32::from_le_bytes(*b"AR24");
+const TILED: u64 = 0x0100000000000001;
+
+#[test]
+fn table_layout() {
+    let entries = [(XRGB8888, LINEAR_MODIFIER), (ARGB8888, TILED)];
+    let data = format_table_data(&entries);
+    assert_eq!(data.len(), 32);
+    for (chunk, &(format, modifier)) in data.chunks(16).zip(entries.iter()) {
+        assert_eq!(chunk[..4], format.to_ne_bytes());
+        assert_eq!(chunk[4..8], [0; 4]);
+        assert_eq!(chunk[8..], modifier.to_ne_bytes());
+    }
+}
+
+#[test]
+fn empty_table() {
+    assert!(format_table_data(&[]).is_empty());
+}
+
+#[test]
+fn scanout_indices_match_format_and_modifier() {
+    let entries = [
+        (ARGB8888, INVALID_MODIFIER),
+        (ARGB8888, LINEAR_MODIFIER),
+        (XRGB8888, INVALID_MODIFIER),
+        (XRGB8888, LINEAR_MODIFIER),
+        (XRGB8888, TILED),
+    ];
+    let feedback = ScanoutFeedback {
+        device: 0,
+        formats: AHashSet::from_iter([(XRGB8888, LINEAR_MODIFIER), (ARGB8888, TILED)]),
+    };
+    assert_eq!(feedback.indices(&entries), [3]);
+}
+
+#[test]
+fn scanout_indices_empty() {
+    let entries = [(XRGB8888, LINEAR_MODIFIER)];
+    let feedback = ScanoutFeedback {
+        device: 0,
+        formats: Default::default(),
+    };
+    assert!(feedback.indices(&entries).is_empty());
+}
@@ -2,11 +2,14 @@ use {
     crate::{
         client::{Client, ClientError},
         globals::{Global, GlobalName},
-        ifs::zwp_linux_buffer_params_v1::ZwpLinuxBufferParamsV1,
+        ifs::{
+            wl_surface::WlSurface, zwp_linux_buffer_params_v1::ZwpLinuxBufferParamsV1,
+            zwp_linux_dmabuf_feedback_v1::ZwpLinuxDmabufFeedbackV1,
+        },
         leaks::Tracker,
         object::Object,
         utils::buffd::{MsgParser, MsgParserError},
-        wire::{zwp_linux_dmabuf_v1::*, ZwpLinuxDmabufV1Id},
+        wire::{zwp_linux_dmabuf_v1::*, ZwpLinuxDmabufFeedbackV1Id, ZwpLinuxDmabufV1Id},
     },
     std::rc::Rc,
     thiserror::Error,
@@ -35,6 +38,9 @@ impl ZwpLinuxDmabufV1Global {
         });
         track!(client, obj);
         client.add_client_obj(&obj)?;
+        if version >= FEEDBACK_SINCE_VERSION {
+            return Ok(());
+        }
         if let Some(ctx) = client.state.render_ctx.get() {
             let formats = ctx.formats();
             for format in formats.values() {
@@ -57,6 +63,7 @@ impl ZwpLinuxDmabufV1Global {
 }
 
 const MODIFIERS_SINCE_VERSION: u32 = 3;
+const FEEDBACK_SINCE_VERSION: u32 = 4;
 
 global_base!(
     ZwpLinuxDmabufV1Global,
@@ -70,7 +77,7 @@ impl Global for ZwpLinuxDmabufV1Global {
     }
 
     fn version(&self) -> u32 {
-        3
+        4
     }
 }
 
@@ -116,6 +123,47 @@ impl ZwpLinuxDmabufV1 {
         self.client.add_client_obj(&params)?;
         Ok(())
     }
+
+    fn get_default_feedback(
+        self: &Rc<Self>,
+        parser: MsgParser<'_, '_>,
+    ) -> Result<(), ZwpLinuxDmabufV1Error> {
+        let req: GetDefaultFeedback = self.client.parse(&**self, parser)?;
+        self.create_feedback(req.id, None)
+    }
+
+    fn get_surface_feedback(
+        self: &Rc<Self>,
+        parser: MsgParser<'_, '_>,
+    ) -> Result<(), ZwpLinuxDmabufV1Error> {
+        let req: GetSurfaceFeedback = self.client.parse(&**self, parser)?;
+        let surface = self.client.lookup(req.surface)?;
+        self.create_feedback(req.id, Some(surface))
+    }
+
+    fn create_feedback(
+        &self,
+        id: ZwpLinuxDmabufFeedbackV1Id,
+        surface: Option<Rc<WlSurface>>,
+    ) -> Result<(), ZwpLinuxDmabufV1Error> {
+        let fb = Rc::new(ZwpLinuxDmabufFeedbackV1 {
+            id,
+            client: self.client.clone(),
+            tracker: Default::default(),
+            surface: surface.clone(),
+        });
+        track!(self.client, fb);
+        self.client.add_client_obj(&fb)?;
+        self.client
+            .state
+            .dmabuf_feedback
+            .set((self.client.id, id), fb.clone());
+        if let Some(surface) = &surface {
+            surface.dmabuf_feedback.set(id, fb.clone());
+        }
+        fb.send_feedback();
+        Ok(())
+    }
 }
 
 object_base! {
@@ -123,11 +171,13 @@ object_base! {
 
     DESTROY => destroy,
     CREATE_PARAMS => create_params,
+    GET_DEFAULT_FEEDBACK => get_default_feedback,
+    GET_SURFACE_FEEDBACK => get_surface_feedback,
 }
 
 impl Object for ZwpLinuxDmabufV1 {
     fn num_requests(&self) -> u32 {
-        CREATE_PARAMS + 1
+        GET_SURFACE_FEEDBACK + 1
     }
 }
 
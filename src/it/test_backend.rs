@@ -125,8 +125,10 @@ impl TestBackend {
                 product: "TestConnector".to_string(),
                 serial_number: self.default_connector.id.to_string(),
                 initial_mode: mode,
+                preferred_mode: Some(mode),
                 width_mm: 80,
                 height_mm: 60,
+                non_desktop: false,
             }));
         self.state
             .backend_events
@@ -250,6 +252,10 @@ impl Connector for TestConnector {
     fn set_enabled(&self, _enabled: bool) {
         // todo
     }
+
+    fn set_mode(&self, _mode: Mode) {
+        // todo
+    }
 }
 
 pub struct TestMouseClick {
@@ -93,6 +93,9 @@ unsafe extern "C" fn handle_msg(data: *const u8, msg: *const u8, size: usize) {
             tc.invoked_shortcuts
                 .set((SeatId::from_raw(seat.0 as _), mods | sym), ());
         }
+        ServerMessage::InvokeSwipe { .. } => {}
+        ServerMessage::InvokePadButton { .. } => {}
+        ServerMessage::SwitchEvent { .. } => {}
         ServerMessage::NewInputDevice { .. } => {}
         ServerMessage::DelInputDevice { .. } => {}
         ServerMessage::ConnectorConnect { .. } => {}
@@ -106,6 +109,8 @@ unsafe extern "C" fn handle_msg(data: *const u8, msg: *const u8, size: usize) {
         ServerMessage::DelDrmDev { .. } => {}
         ServerMessage::Idle => {}
         ServerMessage::DevicesEnumerated => {}
+        ServerMessage::ShortcutsInhibitRequest { .. } => {}
+        ServerMessage::NewWindow { .. } => {}
     }
 }
 
@@ -13,9 +13,17 @@ use {
             libinput_device_config_tap_get_drag_lock_enabled,
             libinput_device_config_tap_get_enabled, libinput_device_config_tap_set_drag_enabled,
             libinput_device_config_tap_set_drag_lock_enabled,
-            libinput_device_config_tap_set_enabled, libinput_device_get_name,
-            libinput_device_get_user_data, libinput_device_has_capability,
-            libinput_device_set_user_data, libinput_device_unref, libinput_path_remove_device,
+            libinput_device_config_tap_set_enabled, libinput_device_get_device_group,
+            libinput_device_get_name, libinput_device_get_user_data,
+            libinput_device_has_capability, libinput_device_set_user_data,
+            libinput_device_tablet_pad_get_mode_group, libinput_device_tablet_pad_get_num_buttons,
+            libinput_device_tablet_pad_get_num_mode_groups,
+            libinput_device_tablet_pad_get_num_rings, libinput_device_tablet_pad_get_num_strips,
+            libinput_device_unref, libinput_path_remove_device, libinput_tablet_pad_mode_group,
+            libinput_tablet_pad_mode_group_get_index, libinput_tablet_pad_mode_group_get_mode,
+            libinput_tablet_pad_mode_group_get_num_modes,
+            libinput_tablet_pad_mode_group_has_button, libinput_tablet_pad_mode_group_has_ring,
+            libinput_tablet_pad_mode_group_has_strip,
         },
         LibInput,
     },
@@ -23,6 +31,37 @@ use {
     std::{ffi::CStr, marker::PhantomData, rc::Rc},
 };
 
+pub struct LibInputTabletPadModeGroup<'a> {
+    pub(super) group: *mut libinput_tablet_pad_mode_group,
+    pub(super) _phantom: PhantomData<&'a ()>,
+}
+
+impl<'a> LibInputTabletPadModeGroup<'a> {
+    pub fn index(&self) -> u32 {
+        unsafe { libinput_tablet_pad_mode_group_get_index(self.group) }
+    }
+
+    pub fn num_modes(&self) -> u32 {
+        unsafe { libinput_tablet_pad_mode_group_get_num_modes(self.group) }
+    }
+
+    pub fn mode(&self) -> u32 {
+        unsafe { libinput_tablet_pad_mode_group_get_mode(self.group) }
+    }
+
+    pub fn has_button(&self, button: u32) -> bool {
+        unsafe { libinput_tablet_pad_mode_group_has_button(self.group, button) != 0 }
+    }
+
+    pub fn has_ring(&self, ring: u32) -> bool {
+        unsafe { libinput_tablet_pad_mode_group_has_ring(self.group, ring) != 0 }
+    }
+
+    pub fn has_strip(&self, strip: u32) -> bool {
+        unsafe { libinput_tablet_pad_mode_group_has_strip(self.group, strip) != 0 }
+    }
+}
+
 pub struct LibInputDevice<'a> {
     pub(super) dev: *mut libinput_device,
     pub(super) _phantom: PhantomData<&'a ()>,
@@ -80,6 +119,44 @@ impl<'a> LibInputDevice<'a> {
         }
     }
 
+    pub fn tablet_pad_num_buttons(&self) -> u32 {
+        let res = unsafe { libinput_device_tablet_pad_get_num_buttons(self.dev) };
+        res.max(0) as u32
+    }
+
+    pub fn tablet_pad_num_rings(&self) -> u32 {
+        let res = unsafe { libinput_device_tablet_pad_get_num_rings(self.dev) };
+        res.max(0) as u32
+    }
+
+    pub fn tablet_pad_num_strips(&self) -> u32 {
+        let res = unsafe { libinput_device_tablet_pad_get_num_strips(self.dev) };
+        res.max(0) as u32
+    }
+
+    pub fn tablet_pad_num_mode_groups(&self) -> u32 {
+        let res = unsafe { libinput_device_tablet_pad_get_num_mode_groups(self.dev) };
+        res.max(0) as u32
+    }
+
+    pub fn tablet_pad_mode_group(&self, idx: u32) -> Option<LibInputTabletPadModeGroup<'_>> {
+        let res = unsafe { libinput_device_tablet_pad_get_mode_group(self.dev, idx) };
+        if res.is_null() {
+            return None;
+        }
+        Some(LibInputTabletPadModeGroup {
+            group: res,
+            _phantom: Default::default(),
+        })
+    }
+
+    /// Returns an identifier of the physical device that this device belongs to.
+    ///
+    /// For example, a tablet and its pad have the same device group.
+    pub fn device_group(&self) -> usize {
+        unsafe { libinput_device_get_device_group(self.dev) as usize }
+    }
+
     pub fn name(&self) -> String {
         unsafe {
             let name = libinput_device_get_name(self.dev);
@@ -58,6 +58,7 @@ mod edid;
 mod fixed;
 mod forker;
 mod format;
+mod gamma;
 mod globals;
 mod ifs;
 mod io_uring;
@@ -81,12 +82,14 @@ mod text;
 mod theme;
 mod time;
 mod tools;
+mod transform;
 mod tree;
 mod udev;
 mod user_session;
 mod utils;
 mod video;
 mod wheel;
+mod window_rules;
 mod wire;
 mod wire_dbus;
 mod wire_xcon;
@@ -74,7 +74,6 @@ impl Region {
         })
     }
 
-    #[allow(dead_code)]
     pub fn extents(&self) -> Rect {
         self.extents
     }
@@ -145,7 +144,6 @@ impl RegionBuilder {
         self.base.clone()
     }
 
-    #[allow(dead_code)]
     pub fn clear(&mut self) {
         self.pending.clear();
         self.base = Region::empty();
@@ -35,14 +35,19 @@ impl Drop for GlFrameBuffer {
     }
 }
 
+/// Restricts rendering to the intersection of `scissor` and the current scissor.
 pub unsafe fn with_scissor<T, F: FnOnce() -> T>(scissor: &Rect, f: F) -> T {
     #[thread_local]
     static mut SCISSOR: *const Rect = ptr::null();
 
     let prev = SCISSOR;
-    if prev.is_null() {
+    let scissor = if prev.is_null() {
         glEnable(GL_SCISSOR_TEST);
-    }
+        *scissor
+    } else {
+        prev.deref().intersect(*scissor)
+    };
+    let scissor = &scissor;
     glScissor(
         scissor.x1(),
         scissor.y1(),
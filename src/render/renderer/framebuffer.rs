@@ -3,10 +3,11 @@ use {
         cursor::Cursor,
         fixed::Fixed,
         format::{Format, ARGB8888, XRGB8888},
-        rect::Rect,
+        ifs::wl_surface::SurfaceNodeId,
+        rect::{Rect, Region},
         render::{
             gl::{
-                frame_buffer::GlFrameBuffer,
+                frame_buffer::{with_scissor, GlFrameBuffer},
                 sys::{
                     glBindFramebuffer, glClear, glClearColor, glViewport, GL_COLOR_BUFFER_BIT,
                     GL_FRAMEBUFFER,
@@ -18,6 +19,7 @@ use {
         },
         scale::Scale,
         state::State,
+        transform::Transform,
         tree::Node,
     },
     std::{
@@ -27,6 +29,10 @@ use {
     },
 };
 
+/// The maximum number of damage rectangles that are rendered separately. Damage with more
+/// rectangles is rendered once, clipped to its extents.
+const MAX_SCISSOR_RECTS: usize = 4;
+
 pub struct Framebuffer {
     pub(super) ctx: Rc<RenderContext>,
     pub(super) gl: GlFrameBuffer,
@@ -55,7 +61,19 @@ impl Framebuffer {
         });
     }
 
-    pub fn copy_texture(&self, state: &State, texture: &Texture, x: i32, y: i32, alpha: bool) {
+    /// Copies the texture into the framebuffer.
+    ///
+    /// `transform` is the transform that has been applied to the contents of the texture.
+    /// It is undone while copying.
+    pub fn copy_texture(
+        &self,
+        state: &State,
+        texture: &Texture,
+        x: i32,
+        y: i32,
+        alpha: bool,
+        transform: Transform,
+    ) {
         let _ = self.ctx.ctx.with_current(|| {
             unsafe {
                 glBindFramebuffer(GL_FRAMEBUFFER, self.gl.fbo);
@@ -74,9 +92,11 @@ impl Framebuffer {
                     scaled: false,
                     scale,
                     scalef: 1.0,
+                    transform: Transform::Normal,
                 },
                 state,
                 on_output: false,
+                overlays: &[],
                 result: &mut RenderResult::default(),
                 logical_extents: Rect::new_sized(0, 0, self.gl.width, self.gl.height).unwrap(),
             };
@@ -84,9 +104,16 @@ impl Framebuffer {
                 true => ARGB8888,
                 false => XRGB8888,
             };
+            let (tpoints, tsize) = match transform {
+                Transform::Normal => (None, None),
+                _ => (
+                    Some(transform.texcoords()),
+                    Some(transform.maybe_swap((texture.width(), texture.height()))),
+                ),
+            };
             renderer
                 .base
-                .render_texture(texture, x, y, format, None, None, scale);
+                .render_texture(texture, x, y, format, tpoints.as_ref(), tsize, scale);
             unsafe {
                 glFlush();
             }
@@ -136,6 +163,7 @@ impl Framebuffer {
                 scaled: scale != 1,
                 scale,
                 scalef: scale.to_f64(),
+                transform: Transform::Normal,
             };
             f(&mut renderer);
             unsafe {
@@ -145,6 +173,14 @@ impl Framebuffer {
         });
     }
 
+    /// Renders the node into the framebuffer.
+    ///
+    /// If `damage` is given, it must be in the logical coordinate space of the node and only
+    /// the pixels within its extents are updated. The remainder of the framebuffer is left
+    /// untouched.
+    ///
+    /// The buffers of the surfaces in `overlays` are displayed on hardware planes and are
+    /// not rendered.
     pub fn render(
         &self,
         node: &dyn Node,
@@ -154,14 +190,15 @@ impl Framebuffer {
         result: &mut RenderResult,
         scale: Scale,
         render_hardware_cursor: bool,
+        transform: Transform,
+        damage: Option<&Region>,
+        overlays: &[SurfaceNodeId],
     ) {
         let _ = self.ctx.ctx.with_current(|| {
             let c = state.theme.colors.background.get();
             unsafe {
                 glBindFramebuffer(GL_FRAMEBUFFER, self.gl.fbo);
                 glViewport(0, 0, self.gl.width, self.gl.height);
-                glClearColor(c.r, c.g, c.b, 1.0);
-                glClear(GL_COLOR_BUFFER_BIT);
                 glBlendFunc(GL_ONE, GL_ONE_MINUS_SRC_ALPHA);
             }
             let mut renderer = Renderer {
@@ -171,37 +208,51 @@ impl Framebuffer {
                     scaled: scale != 1,
                     scale,
                     scalef: scale.to_f64(),
+                    transform,
                 },
                 state,
                 on_output,
+                overlays,
                 result,
                 logical_extents: node.node_absolute_position().at_point(0, 0),
             };
-            node.node_render(&mut renderer, 0, 0);
-            if let Some(rect) = cursor_rect {
-                let seats = state.globals.lock_seats();
-                for seat in seats.values() {
-                    if !render_hardware_cursor && seat.hardware_cursor() {
-                        continue;
-                    }
-                    if let Some(cursor) = seat.get_cursor() {
-                        let (mut x, mut y) = seat.get_position();
-                        if let Some(dnd_icon) = seat.dnd_icon() {
-                            let extents = dnd_icon.extents.get().move_(
-                                x.round_down() + dnd_icon.buf_x.get(),
-                                y.round_down() + dnd_icon.buf_y.get(),
-                            );
-                            if extents.intersects(&rect) {
-                                let (x, y) = rect.translate(extents.x1(), extents.y1());
-                                renderer.render_surface(&dnd_icon, x, y);
-                            }
-                        }
-                        cursor.tick();
-                        x -= Fixed::from_int(rect.x1());
-                        y -= Fixed::from_int(rect.y1());
-                        cursor.render(&mut renderer, x, y);
+            let scissors = damage.map(|damage| {
+                let extents = [damage.extents()];
+                let rects = match damage.len() > MAX_SCISSOR_RECTS {
+                    true => &extents[..],
+                    false => &damage[..],
+                };
+                rects
+                    .iter()
+                    .map(|rect| {
+                        let rect = renderer.base.scale_rect_outward(*rect);
+                        renderer.base.transform_rect(rect)
+                    })
+                    .collect::<Vec<_>>()
+            });
+            if cursor_rect.is_some() {
+                Self::tick_cursors(state, render_hardware_cursor);
+            }
+            let mut render = || {
+                unsafe {
+                    glClearColor(c.r, c.g, c.b, 1.0);
+                    glClear(GL_COLOR_BUFFER_BIT);
+                }
+                Self::render_node(
+                    &mut renderer,
+                    node,
+                    state,
+                    cursor_rect,
+                    render_hardware_cursor,
+                );
+            };
+            match scissors {
+                Some(scissors) => {
+                    for scissor in &scissors {
+                        unsafe { with_scissor(scissor, &mut render) }
                     }
                 }
+                _ => render(),
             }
             unsafe {
                 glFlush();
@@ -210,7 +261,62 @@ impl Framebuffer {
         });
     }
 
-    pub fn render_hardware_cursor(&self, cursor: &dyn Cursor, state: &State, scale: Scale) {
+    fn render_node(
+        renderer: &mut Renderer,
+        node: &dyn Node,
+        state: &State,
+        cursor_rect: Option<Rect>,
+        render_hardware_cursor: bool,
+    ) {
+        node.node_render(renderer, 0, 0);
+        if let Some(rect) = cursor_rect {
+            let seats = state.globals.lock_seats();
+            for seat in seats.values() {
+                if !render_hardware_cursor && seat.hardware_cursor() {
+                    continue;
+                }
+                if let Some(cursor) = seat.get_cursor() {
+                    let (mut x, mut y) = seat.get_position();
+                    if let Some(dnd_icon) = seat.dnd_icon() {
+                        let extents = dnd_icon.extents.get().move_(
+                            x.round_down() + dnd_icon.buf_x.get(),
+                            y.round_down() + dnd_icon.buf_y.get(),
+                        );
+                        if extents.intersects(&rect) {
+                            let (x, y) = rect.translate(extents.x1(), extents.y1());
+                            renderer.render_surface(&dnd_icon, x, y);
+                        }
+                    }
+                    x -= Fixed::from_int(rect.x1());
+                    y -= Fixed::from_int(rect.y1());
+                    cursor.render(renderer, x, y);
+                }
+            }
+        }
+    }
+
+    /// Advances the animations of the cursors rendered by [`Self::render_node`].
+    ///
+    /// This must happen once per frame and not once per damage rect.
+    fn tick_cursors(state: &State, render_hardware_cursor: bool) {
+        let seats = state.globals.lock_seats();
+        for seat in seats.values() {
+            if !render_hardware_cursor && seat.hardware_cursor() {
+                continue;
+            }
+            if let Some(cursor) = seat.get_cursor() {
+                cursor.tick();
+            }
+        }
+    }
+
+    pub fn render_hardware_cursor(
+        &self,
+        cursor: &dyn Cursor,
+        state: &State,
+        scale: Scale,
+        transform: Transform,
+    ) {
         let _ = self.ctx.ctx.with_current(|| {
             unsafe {
                 glBindFramebuffer(GL_FRAMEBUFFER, self.gl.fbo);
@@ -227,9 +333,11 @@ impl Framebuffer {
                     scaled: scale != 1,
                     scale,
                     scalef: scale.to_f64(),
+                    transform,
                 },
                 state,
                 on_output: false,
+                overlays: &[],
                 result: &mut res,
                 logical_extents: Rect::new_empty(0, 0),
             };
@@ -5,7 +5,8 @@ use {
             wl_buffer::WlBuffer,
             wl_callback::WlCallback,
             wl_surface::{
-                xdg_surface::XdgSurface, zwlr_layer_surface_v1::ZwlrLayerSurfaceV1, WlSurface,
+                xdg_surface::XdgSurface, zwlr_layer_surface_v1::ZwlrLayerSurfaceV1, SurfaceNodeId,
+                WlSurface,
             },
             wp_presentation_feedback::WpPresentationFeedback,
         },
@@ -43,6 +44,8 @@ pub struct Renderer<'a> {
     pub base: RendererBase<'a>,
     pub(super) state: &'a State,
     pub(super) on_output: bool,
+    /// Surfaces whose buffers are displayed on hardware planes.
+    pub(super) overlays: &'a [SurfaceNodeId],
     pub(super) result: &'a mut RenderResult,
     pub(super) logical_extents: Rect,
 }
@@ -97,6 +100,7 @@ impl Renderer<'_> {
                 fs.tl_as_node().node_render(self, x, y);
                 render_layer!(output.layers[2]);
                 render_layer!(output.layers[3]);
+                self.render_input_popups(output, x, y);
                 return;
             }
         }
@@ -148,6 +152,27 @@ impl Renderer<'_> {
         }
         render_layer!(output.layers[2]);
         render_layer!(output.layers[3]);
+        self.render_input_popups(output, x, y);
+    }
+
+    fn render_input_popups(&mut self, output: &OutputNode, x: i32, y: i32) {
+        let opos = output.global.pos.get();
+        for seat in self.state.globals.lock_seats().values() {
+            seat.for_each_input_popup(|popup| {
+                let surface = &popup.surface;
+                if surface.buffer.get().is_none() {
+                    return;
+                }
+                let pos = surface.buffer_abs_pos.get();
+                if pos.intersects(&opos) {
+                    self.render_surface(
+                        surface,
+                        x + pos.x1() - opos.x1(),
+                        y + pos.y1() - opos.y1(),
+                    );
+                }
+            });
+        }
     }
 
     pub fn render_workspace(&mut self, workspace: &WorkspaceNode, x: i32, y: i32) {
@@ -177,6 +202,9 @@ impl Renderer<'_> {
             self.base.fill_boxes2(&rd.title_rects, &c, x, y);
             let c = self.state.theme.colors.focused_title_background.get();
             self.base.fill_boxes2(&rd.active_title_rects, &c, x, y);
+            let c = self.state.theme.colors.attention_requested_background.get();
+            self.base
+                .fill_boxes2(&rd.attention_requested_rects, &c, x, y);
             let c = self.state.theme.colors.separator.get();
             self.base.fill_boxes2(&rd.underline_rects, &c, x, y);
             let c = self.state.theme.colors.border.get();
@@ -209,6 +237,7 @@ impl Renderer<'_> {
             unsafe {
                 let body = container.mono_body.get().move_(x, y);
                 let body = self.base.scale_rect(body);
+                let body = self.base.transform_rect(body);
                 with_scissor(&body, || {
                     let content = container.mono_content.get();
                     child
@@ -224,6 +253,7 @@ impl Renderer<'_> {
                 }
                 let body = body.move_(x, y);
                 let body = self.base.scale_rect(body);
+                let body = self.base.transform_rect(body);
                 unsafe {
                     with_scissor(&body, || {
                         let content = child.content.get();
@@ -296,9 +326,11 @@ impl Renderer<'_> {
                 };
             }
             render!(&children.below);
-            self.render_buffer(&buffer, x, y, &tpoints, size);
+            if !self.overlays.contains(&surface.node_id) {
+                self.render_buffer(&buffer, x, y, &tpoints, size);
+            }
             render!(&children.above);
-        } else {
+        } else if !self.overlays.contains(&surface.node_id) {
             self.render_buffer(&buffer, x, y, &tpoints, size);
         }
         if self.on_output {
@@ -344,9 +376,12 @@ impl Renderer<'_> {
         let th = theme.sizes.title_height.get();
         let bw = theme.sizes.border_width.get();
         let bc = theme.colors.border.get();
-        let tc = match floating.active.get() {
-            true => theme.colors.focused_title_background.get(),
-            false => theme.colors.unfocused_title_background.get(),
+        let tc = if floating.active.get() {
+            theme.colors.focused_title_background.get()
+        } else if child.tl_data().wants_attention.get() {
+            theme.colors.attention_requested_background.get()
+        } else {
+            theme.colors.unfocused_title_background.get()
         };
         let uc = theme.colors.separator.get();
         let borders = [
@@ -373,7 +408,7 @@ impl Renderer<'_> {
             pos.height() - 2 * bw - th - 1,
         )
         .unwrap();
-        let scissor_body = self.base.scale_rect(body);
+        let scissor_body = self.base.transform_rect(self.base.scale_rect(body));
         unsafe {
             with_scissor(&scissor_body, || {
                 child.node_render(self, body.x1(), body.y1());
@@ -385,6 +420,7 @@ impl Renderer<'_> {
         unsafe {
             let body = surface.position().at_point(x, y);
             let body = self.base.scale_rect(body);
+            let body = self.base.transform_rect(body);
             with_scissor(&body, || {
                 self.render_surface(&surface.surface, x, y);
             });
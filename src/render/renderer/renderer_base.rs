@@ -19,6 +19,7 @@ use {
         },
         scale::Scale,
         theme::Color,
+        transform::Transform,
         utils::rc_eq::rc_eq,
     },
     std::rc::Rc,
@@ -30,6 +31,7 @@ pub struct RendererBase<'a> {
     pub(super) scaled: bool,
     pub(super) scale: Scale,
     pub(super) scalef: f64,
+    pub(super) transform: Transform,
 }
 
 impl RendererBase<'_> {
@@ -38,7 +40,27 @@ impl RendererBase<'_> {
     }
 
     pub fn physical_extents(&self) -> Rect {
-        Rect::new_sized(0, 0, self.fb.width, self.fb.height).unwrap()
+        let (width, height) = self.fb_size();
+        Rect::new_sized(0, 0, width, height).unwrap()
+    }
+
+    /// Returns the size of the framebuffer before the transform is applied.
+    fn fb_size(&self) -> (i32, i32) {
+        self.transform.maybe_swap((self.fb.width, self.fb.height))
+    }
+
+    /// Maps a rectangle to the framebuffer by applying the transform.
+    pub fn transform_rect(&self, rect: Rect) -> Rect {
+        let (width, height) = self.fb_size();
+        self.transform.apply_rect(rect, width, height)
+    }
+
+    fn transform_ndc(&self, pos: &mut [f32]) {
+        if self.transform != Transform::Normal {
+            for p in pos.chunks_exact_mut(2) {
+                (p[0], p[1]) = self.transform.apply_ndc(p[0], p[1]);
+            }
+        }
     }
 
     pub fn scale_point(&self, mut x: i32, mut y: i32) -> (i32, i32) {
@@ -68,6 +90,18 @@ impl RendererBase<'_> {
         rect
     }
 
+    /// Scales a rectangle such that the result contains every pixel touched by it.
+    pub fn scale_rect_outward(&self, mut rect: Rect) -> Rect {
+        if self.scaled {
+            let x1 = (rect.x1() as f64 * self.scalef).floor() as _;
+            let y1 = (rect.y1() as f64 * self.scalef).floor() as _;
+            let x2 = (rect.x2() as f64 * self.scalef).ceil() as _;
+            let y2 = (rect.y2() as f64 * self.scalef).ceil() as _;
+            rect = Rect::new(x1, y1, x2, y2).unwrap();
+        }
+        rect
+    }
+
     pub fn scale_rect_f(&self, mut rect: (f32, f32, f32, f32)) -> (f32, f32, f32, f32) {
         if self.scaled {
             let x1 = (rect.0 as f64 * self.scalef).round() as _;
@@ -80,19 +114,19 @@ impl RendererBase<'_> {
     }
 
     fn xf_to_f(&self, x: f32) -> f32 {
-        2.0 * (x / self.fb.width as f32) - 1.0
+        2.0 * (x / self.fb_size().0 as f32) - 1.0
     }
 
     fn yf_to_f(&self, y: f32) -> f32 {
-        2.0 * (y / self.fb.height as f32) - 1.0
+        2.0 * (y / self.fb_size().1 as f32) - 1.0
     }
 
     fn x_to_f(&self, x: i32) -> f32 {
-        2.0 * (x as f32 / self.fb.width as f32) - 1.0
+        2.0 * (x as f32 / self.fb_size().0 as f32) - 1.0
     }
 
     fn y_to_f(&self, y: i32) -> f32 {
-        2.0 * (y as f32 / self.fb.height as f32) - 1.0
+        2.0 * (y as f32 / self.fb_size().1 as f32) - 1.0
     }
 
     pub fn clear(&self, c: &Color) {
@@ -129,7 +163,7 @@ impl RendererBase<'_> {
                 x2, y2, // bottom right
             ]);
         }
-        self.fill_boxes3(&pos, color)
+        self.fill_boxes3(&mut pos, color)
     }
 
     pub fn fill_boxes_f(&self, boxes: &[(f32, f32, f32, f32)], color: &Color) {
@@ -159,10 +193,11 @@ impl RendererBase<'_> {
                 x2, y2, // bottom right
             ]);
         }
-        self.fill_boxes3(&pos, color)
+        self.fill_boxes3(&mut pos, color)
     }
 
-    fn fill_boxes3(&self, boxes: &[f32], color: &Color) {
+    fn fill_boxes3(&self, boxes: &mut [f32], color: &Color) {
+        self.transform_ndc(boxes);
         unsafe {
             glUseProgram(self.ctx.fill_prog.prog);
             glUniform4f(self.ctx.fill_prog_color, color.r, color.g, color.b, color.a);
@@ -231,8 +266,9 @@ impl RendererBase<'_> {
                 Some(tp) => tp,
             };
 
-            let f_width = self.fb.width as f32;
-            let f_height = self.fb.height as f32;
+            let (f_width, f_height) = self.fb_size();
+            let f_width = f_width as f32;
+            let f_height = f_height as f32;
 
             let (twidth, theight) = if let Some(size) = tsize {
                 size
@@ -251,12 +287,13 @@ impl RendererBase<'_> {
             let x2 = 2.0 * ((x + twidth) as f32 / f_width) - 1.0;
             let y2 = 2.0 * ((y + theight) as f32 / f_height) - 1.0;
 
-            let pos: [f32; 8] = [
+            let mut pos: [f32; 8] = [
                 x2, y1, // top right
                 x1, y1, // top left
                 x2, y2, // bottom right
                 x1, y2, // bottom left
             ];
+            self.transform_ndc(&mut pos);
 
             glVertexAttribPointer(
                 prog.texcoord as _,
@@ -4,6 +4,7 @@ use {
         render::RenderError,
         scale::Scale,
         state::State,
+        transform::Transform,
         video::{
             drm::DrmError,
             gbm::{GbmBo, GbmError, GBM_BO_USE_LINEAR, GBM_BO_USE_RENDERING},
@@ -62,6 +63,9 @@ pub fn take_screenshot(state: &State) -> Result<Screenshot, ScreenshooterError>
         &mut Default::default(),
         Scale::from_int(1),
         true,
+        Transform::Normal,
+        None,
+        &[],
     );
     let drm = ctx.gbm.drm.dup_render()?.fd().clone();
     Ok(Screenshot { drm, bo })
@@ -5,6 +5,7 @@ use {
         backend::{
             Backend, BackendDrmDevice, BackendEvent, Connector, ConnectorId, ConnectorIds,
             DrmDeviceId, DrmDeviceIds, InputDevice, InputDeviceId, InputDeviceIds, MonitorInfo,
+            TabletToolIds,
         },
         backends::dummy::DummyBackend,
         cli::RunArgs,
@@ -13,18 +14,28 @@ use {
         cursor::{Cursor, ServerCursors},
         dbus::Dbus,
         forker::ForkerProxy,
+        gamma::{temperature_ramps, NEUTRAL_TEMPERATURE},
         globals::{Globals, GlobalsError, WaylandGlobal},
         ifs::{
+            ext_foreign_toplevel_list_v1::ExtForeignToplevelListV1,
             ext_session_lock_v1::ExtSessionLockV1,
             jay_render_ctx::JayRenderCtx,
             jay_seat_events::JaySeatEvents,
             jay_workspace_watcher::JayWorkspaceWatcher,
             wl_drm::WlDrmGlobal,
-            wl_seat::{SeatIds, WlSeatGlobal},
+            wl_seat::{collect_kb_foci, SeatId, SeatIds, WlSeatGlobal},
             wl_surface::{
                 zwp_idle_inhibitor_v1::{IdleInhibitorId, IdleInhibitorIds, ZwpIdleInhibitorV1},
                 NoneSurfaceExt, WlSurface,
             },
+            wp_drm_lease_device_v1::WpDrmLeaseDeviceV1Global,
+            zwlr_foreign_toplevel_manager_v1::ZwlrForeignToplevelManagerV1,
+            zwlr_gamma_control_v1::ZwlrGammaControlV1,
+            zwlr_output_manager_v1::ZwlrOutputManagerV1,
+            zwp_linux_dmabuf_feedback_v1::{
+                create_dmabuf_feedback_table, resend_dmabuf_feedback, DmabufFeedbackTable,
+                ZwpLinuxDmabufFeedbackV1,
+            },
             zwp_linux_dmabuf_v1::ZwpLinuxDmabufV1Global,
         },
         io_uring::IoUring,
@@ -34,25 +45,32 @@ use {
         render::RenderContext,
         scale::Scale,
         theme::Theme,
+        transform::Transform,
         tree::{
-            ContainerNode, ContainerSplit, Direction, DisplayNode, FloatNode, Node, NodeIds,
-            NodeVisitorBase, OutputNode, PlaceholderNode, ToplevelNode, WorkspaceNode,
+            ContainerNode, ContainerSplit, Direction, DisplayNode, FloatNode, Node, NodeId,
+            NodeIds, NodeVisitorBase, OutputNode, PlaceholderNode, ToplevelNode, WorkspaceNode,
         },
         utils::{
-            asyncevent::AsyncEvent, clonecell::CloneCell, copyhashmap::CopyHashMap,
-            errorfmt::ErrorFmt, fdcloser::FdCloser, linkedlist::LinkedList, numcell::NumCell,
-            queue::AsyncQueue, refcounted::RefCounted, run_toplevel::RunToplevel,
+            activation_token::ActivationToken, asyncevent::AsyncEvent, clonecell::CloneCell,
+            copyhashmap::CopyHashMap, errorfmt::ErrorFmt, fdcloser::FdCloser,
+            linkedlist::LinkedList, numcell::NumCell, queue::AsyncQueue, refcounted::RefCounted,
+            run_toplevel::RunToplevel,
         },
         wheel::Wheel,
-        wire::{JayRenderCtxId, JaySeatEventsId, JayWorkspaceWatcherId},
+        window_rules::{WindowRuleActions, WindowRules},
+        wire::{
+            ExtForeignToplevelListV1Id, JayRenderCtxId, JaySeatEventsId, JayWorkspaceWatcherId,
+            ZwlrForeignToplevelManagerV1Id, ZwlrOutputManagerV1Id, ZwpLinuxDmabufFeedbackV1Id,
+        },
         xkbcommon::{XkbContext, XkbKeymap},
         xwayland::{self, XWaylandEvent},
     },
     ahash::AHashMap,
     bstr::ByteSlice,
-    jay_config::PciId,
+    jay_config::{ActivationBehavior, PciId},
     std::{
         cell::{Cell, RefCell},
+        collections::VecDeque,
         fmt::{Debug, Formatter},
         mem,
         num::Wrapping,
@@ -63,6 +81,8 @@ use {
     },
 };
 
+const MAX_ACTIVATION_TOKENS: usize = 64;
+
 pub struct State {
     pub xkb_ctx: XkbContext,
     pub backend: CloneCell<Rc<dyn Backend>>,
@@ -81,6 +101,7 @@ pub struct State {
     pub seat_ids: SeatIds,
     pub idle_inhibitor_ids: IdleInhibitorIds,
     pub input_device_ids: InputDeviceIds,
+    pub tablet_tool_ids: TabletToolIds,
     pub node_ids: NodeIds,
     pub root: Rc<DisplayNode>,
     pub workspaces: CopyHashMap<String, Rc<WorkspaceNode>>,
@@ -122,7 +143,21 @@ pub struct State {
     pub hardware_tick_cursor: AsyncQueue<Option<Rc<dyn Cursor>>>,
     pub testers: RefCell<AHashMap<(ClientId, JaySeatEventsId), Rc<JaySeatEvents>>>,
     pub render_ctx_watchers: CopyHashMap<(ClientId, JayRenderCtxId), Rc<JayRenderCtx>>,
+    pub window_rules: WindowRules,
+    pub dmabuf_feedback_table: CloneCell<Option<Rc<DmabufFeedbackTable>>>,
+    pub dmabuf_feedback:
+        CopyHashMap<(ClientId, ZwpLinuxDmabufFeedbackV1Id), Rc<ZwpLinuxDmabufFeedbackV1>>,
     pub workspace_watchers: CopyHashMap<(ClientId, JayWorkspaceWatcherId), Rc<JayWorkspaceWatcher>>,
+    pub output_managers: CopyHashMap<(ClientId, ZwlrOutputManagerV1Id), Rc<ZwlrOutputManagerV1>>,
+    pub output_managers_changed: AsyncEvent,
+    pub toplevels: CopyHashMap<NodeId, Rc<dyn ToplevelNode>>,
+    pub toplevel_managers:
+        CopyHashMap<(ClientId, ZwlrForeignToplevelManagerV1Id), Rc<ZwlrForeignToplevelManagerV1>>,
+    pub toplevel_lists:
+        CopyHashMap<(ClientId, ExtForeignToplevelListV1Id), Rc<ExtForeignToplevelListV1>>,
+    pub activation_tokens: RefCell<VecDeque<(ActivationToken, Option<SeatId>)>>,
+    pub activation_behavior: Cell<ActivationBehavior>,
+    pub tearing_allowed: Cell<bool>,
 }
 
 // impl Drop for State {
@@ -184,12 +219,37 @@ pub struct InputDeviceData {
     pub async_event: Rc<AsyncEvent>,
 }
 
+/// The area that absolute positions reported by an input device are mapped to.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum InputMapping {
+    /// Absolute pointers use the whole layout. Touchscreens and tablets use the output
+    /// that contains the cursor.
+    Default,
+    Connector(ConnectorId),
+    Layout,
+}
+
 pub struct DeviceHandlerData {
     pub seat: CloneCell<Option<Rc<WlSeatGlobal>>>,
     pub px_per_scroll_wheel: Cell<f64>,
+    pub mapping: Cell<InputMapping>,
+    /// Pad buttons that are handled by the config instead of applications.
+    pub pad_button_bindings: CopyHashMap<u32, ()>,
+    pub lid_closed: Cell<bool>,
     pub device: Rc<dyn InputDevice>,
 }
 
+impl DeviceHandlerData {
+    pub fn set_seat(&self, seat: Option<Rc<WlSeatGlobal>>) {
+        if let Some(old) = self.seat.set(seat.clone()) {
+            old.remove_tablet_device(self.device.id());
+        }
+        if let Some(seat) = seat {
+            seat.add_tablet_device(self);
+        }
+    }
+}
+
 pub struct ConnectorData {
     pub connector: Rc<dyn Connector>,
     pub handler: Cell<Option<SpawnedFuture<()>>>,
@@ -197,6 +257,37 @@ pub struct ConnectorData {
     pub name: String,
     pub drm_dev: Option<Rc<DrmDevData>>,
     pub async_event: Rc<AsyncEvent>,
+    pub enabled: Cell<bool>,
+    pub monitor_info: CloneCell<Option<Rc<MonitorInfo>>>,
+    pub pending_position: Cell<Option<(i32, i32)>>,
+    pub pending_scale: Cell<Option<Scale>>,
+    pub pending_transform: Cell<Option<Transform>>,
+    pub gamma_control: CloneCell<Option<Rc<ZwlrGammaControlV1>>>,
+    pub color_temperature: Cell<u32>,
+}
+
+impl ConnectorData {
+    pub fn set_enabled(&self, enabled: bool) {
+        self.enabled.set(enabled);
+        self.connector.set_enabled(enabled);
+    }
+
+    /// Applies the gamma ramps of the active gamma control or, if there is none, the
+    /// configured color temperature.
+    pub fn update_gamma(&self) {
+        let mut ramps = None;
+        if let Some(gc) = self.gamma_control.get() {
+            ramps = gc.ramps.get();
+        }
+        let temperature = self.color_temperature.get();
+        if ramps.is_none() && temperature != NEUTRAL_TEMPERATURE {
+            let size = self.connector.gamma_size();
+            if size > 0 {
+                ramps = Some(temperature_ramps(temperature, size));
+            }
+        }
+        self.connector.set_gamma(ramps);
+    }
 }
 
 pub struct OutputData {
@@ -214,6 +305,7 @@ pub struct DrmDevData {
     pub vendor: Option<String>,
     pub model: Option<String>,
     pub pci_id: Option<PciId>,
+    pub lease_global: Option<Rc<WpDrmLeaseDeviceV1Global>>,
 }
 
 impl DrmDevData {
@@ -366,6 +458,10 @@ impl State {
             watcher.send_render_ctx(ctx);
         }
 
+        self.dmabuf_feedback_table
+            .set(ctx.and_then(|ctx| create_dmabuf_feedback_table(ctx)));
+        resend_dmabuf_feedback(self);
+
         let mut scs = vec![];
         for client in self.clients.clients.borrow_mut().values() {
             for sc in client.data.objects.screencasts.lock().values() {
@@ -412,6 +508,196 @@ impl State {
         }
     }
 
+    pub fn add_activation_token(&self, token: ActivationToken, seat: Option<SeatId>) {
+        let tokens = &mut *self.activation_tokens.borrow_mut();
+        if tokens.len() >= MAX_ACTIVATION_TOKENS {
+            tokens.pop_front();
+        }
+        tokens.push_back((token, seat));
+    }
+
+    pub fn activate_toplevel(&self, token: &str, tl: Rc<dyn ToplevelNode>) {
+        let Ok(token) = token.parse::<ActivationToken>() else {
+            return;
+        };
+        let seat = {
+            let tokens = &mut *self.activation_tokens.borrow_mut();
+            let Some(idx) = tokens.iter().position(|(t, _)| *t == token) else {
+                return;
+            };
+            tokens.remove(idx).unwrap().1
+        };
+        if tl.tl_data().active_surfaces.get() > 0 {
+            return;
+        }
+        match self.activation_behavior.get() {
+            ActivationBehavior::Focus => {
+                let seat = seat
+                    .and_then(|id| {
+                        let seats = self.globals.seats.lock();
+                        seats.values().find(|s| s.id() == id).cloned()
+                    })
+                    .or_else(|| self.seat_queue.last().map(|s| (*s).clone()));
+                if let Some(seat) = seat {
+                    seat.activate_toplevel(tl);
+                }
+            }
+            ActivationBehavior::RequestAttention => tl.tl_set_wants_attention(true),
+        }
+    }
+
+    /// Moves the toplevel to the workspace unless it is fullscreen.
+    pub fn move_toplevel_to_workspace(
+        self: &Rc<Self>,
+        tl: Rc<dyn ToplevelNode>,
+        ws: &Rc<WorkspaceNode>,
+    ) {
+        if tl.tl_data().is_fullscreen.get() {
+            return;
+        }
+        let old_ws = match tl.tl_data().workspace.get() {
+            Some(ws) => ws,
+            _ => return,
+        };
+        if old_ws.id == ws.id {
+            return;
+        }
+        let cn = match tl
+            .tl_data()
+            .parent
+            .get()
+            .and_then(|p| p.node_into_containing_node())
+        {
+            Some(cn) => cn,
+            _ => return,
+        };
+        let kb_foci = collect_kb_foci(tl.clone().tl_into_node());
+        cn.cnode_remove_child2(tl.tl_as_node(), true);
+        if !ws.visible.get() {
+            for focus in kb_foci {
+                old_ws.clone().node_do_focus(&focus, Direction::Unspecified);
+            }
+        }
+        if tl.tl_data().is_floating.get() {
+            self.map_floating(
+                tl.clone(),
+                tl.tl_data().float_width.get(),
+                tl.tl_data().float_height.get(),
+                ws,
+            );
+        } else {
+            self.map_tiled_on(tl, ws);
+        }
+    }
+
+    /// Moves the toplevel between the tiling layout and the floating layer unless it is
+    /// fullscreen.
+    pub fn set_toplevel_floating(self: &Rc<Self>, tl: Rc<dyn ToplevelNode>, floating: bool) {
+        let data = tl.tl_data();
+        if data.is_fullscreen.get() {
+            return;
+        }
+        if data.is_floating.get() == floating {
+            return;
+        }
+        let parent = match data.parent.get() {
+            Some(p) => p,
+            _ => return,
+        };
+        if let Some(cn) = parent.node_into_containing_node() {
+            if !floating {
+                cn.cnode_remove_child2(tl.tl_as_node(), true);
+                self.map_tiled(tl);
+            } else if let Some(ws) = data.workspace.get() {
+                cn.cnode_remove_child2(tl.tl_as_node(), true);
+                let (width, height) = data.float_size(&ws);
+                self.map_floating(tl, width, height, &ws);
+            }
+        }
+    }
+
+    /// Maps a toplevel that is shown for the first time according to the window rules.
+    ///
+    /// If `floating` is set, the toplevel floats by default on the given workspace and with
+    /// the given size. Otherwise it is tiled by default.
+    pub fn map_new_window(
+        self: &Rc<Self>,
+        tl: Rc<dyn ToplevelNode>,
+        floating: Option<(Rc<WorkspaceNode>, i32, i32)>,
+    ) {
+        let data = tl.tl_data();
+        let actions = match data.window_rules_applied.replace(true) {
+            true => WindowRuleActions::default(),
+            false => match data.window_rule_actions.take() {
+                Some(actions) => actions,
+                _ => self.window_rules.evaluate(&*tl),
+            },
+        };
+        let ws = actions
+            .workspace
+            .as_ref()
+            .map(|name| self.window_rule_workspace(name));
+        let is_floating = actions.floating.unwrap_or(floating.is_some());
+        if is_floating {
+            let (default_ws, width, height) = match floating {
+                Some(f) => f,
+                _ => {
+                    let ws = self.float_map_ws();
+                    let (width, height) = tl.tl_data().float_size(&ws);
+                    (ws, width, height)
+                }
+            };
+            let ws = ws.unwrap_or(default_ws);
+            let (width, height) = actions.size.unwrap_or((width, height));
+            self.map_floating_at(tl.clone(), width, height, &ws, actions.position);
+        } else if let Some(ws) = &ws {
+            self.map_tiled_on(tl.clone(), ws);
+        } else {
+            let seat = self.seat_queue.last();
+            self.do_map_tiled(seat.as_deref(), tl.clone());
+        }
+        if let Some(fullscreen) = actions.fullscreen {
+            tl.clone().tl_set_fullscreen(fullscreen);
+        }
+        let seat = self.seat_queue.last();
+        if let Some(seat) = seat.as_deref() {
+            match actions.focus {
+                Some(true) => seat.activate_toplevel(tl),
+                None if !is_floating && tl.node_visible() => {
+                    tl.node_do_focus(seat, Direction::Unspecified);
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn window_rule_workspace(&self, name: &str) -> Rc<WorkspaceNode> {
+        if let Some(ws) = self.workspaces.get(name) {
+            return ws;
+        }
+        self.window_rule_default_output().create_workspace(name)
+    }
+
+    fn window_rule_default_output(&self) -> Rc<OutputNode> {
+        self.seat_queue
+            .last()
+            .map(|s| s.get_output())
+            .filter(|o| !o.is_dummy)
+            .or_else(|| self.root.outputs.lock().values().next().cloned())
+            .or_else(|| self.dummy_output.get())
+            .unwrap()
+    }
+
+    /// Returns the output that a window with these actions will be mapped on.
+    pub fn window_rule_output(&self, actions: &WindowRuleActions) -> Rc<OutputNode> {
+        if let Some(name) = &actions.workspace {
+            if let Some(ws) = self.workspaces.get(name.as_str()) {
+                return ws.output.get();
+            }
+        }
+        self.window_rule_default_output()
+    }
+
     pub fn map_tiled(self: &Rc<Self>, node: Rc<dyn ToplevelNode>) {
         let seat = self.seat_queue.last();
         self.do_map_tiled(seat.as_deref(), node.clone());
@@ -453,11 +739,26 @@ impl State {
     }
 
     pub fn map_floating(
+        self: &Rc<Self>,
+        node: Rc<dyn ToplevelNode>,
+        width: i32,
+        height: i32,
+        workspace: &Rc<WorkspaceNode>,
+    ) {
+        self.map_floating_at(node, width, height, workspace, None);
+    }
+
+    /// Maps the toplevel as a floating window.
+    ///
+    /// `position` is relative to the output of the workspace. If it is not set, the window
+    /// is centered.
+    pub fn map_floating_at(
         self: &Rc<Self>,
         node: Rc<dyn ToplevelNode>,
         mut width: i32,
         mut height: i32,
         workspace: &Rc<WorkspaceNode>,
+        position: Option<(i32, i32)>,
     ) {
         node.clone().tl_set_workspace(workspace);
         width += 2 * self.theme.sizes.border_width.get();
@@ -477,6 +778,10 @@ impl State {
             } else {
                 height = output_rect.height();
             }
+            if let Some((x, y)) = position {
+                x1 = output_rect.x1() + x;
+                y1 = output_rect.y1() + y;
+            }
             Rect::new_sized(x1, y1, width, height).unwrap()
         };
         FloatNode::new(self, workspace, position, node);
@@ -575,7 +880,14 @@ impl State {
         serial as _
     }
 
+    /// Damages all outputs.
+    ///
+    /// This is used for changes whose area is not tracked, such as layout changes of the
+    /// tree, drag-and-drop icons, theme and status changes, and the session lock.
     pub fn damage(&self) {
+        for output in self.root.outputs.lock().values() {
+            output.damage_all();
+        }
         for connector in self.connectors.lock().values() {
             if connector.connected.get() {
                 connector.connector.damage();
@@ -583,6 +895,35 @@ impl State {
         }
     }
 
+    /// Damages an area in global coordinates.
+    pub fn damage_rect(&self, rect: Rect) {
+        if rect.is_empty() {
+            return;
+        }
+        for output in self.root.outputs.lock().values() {
+            if output.global.pos.get().intersects(&rect) {
+                output.add_damage(rect);
+                output.global.connector.connector.damage();
+            }
+        }
+    }
+
+    /// Damages an area in global coordinates on the outputs that render the cursor in
+    /// software.
+    pub fn damage_software_cursor_rect(&self, rect: Rect) {
+        if rect.is_empty() {
+            return;
+        }
+        for output in self.root.outputs.lock().values() {
+            let software_cursor =
+                output.hardware_cursor.get().is_none() || output.hardware_cursor_too_small.get();
+            if software_cursor && output.global.pos.get().intersects(&rect) {
+                output.add_damage(rect);
+                output.global.connector.connector.damage();
+            }
+        }
+    }
+
     pub fn clear(&self) {
         self.lock.lock.take();
         self.xwayland.handler.borrow_mut().take();
@@ -617,7 +958,13 @@ impl State {
         self.pending_float_layout.clear();
         self.pending_float_titles.clear();
         self.render_ctx_watchers.clear();
+        self.dmabuf_feedback.clear();
         self.workspace_watchers.clear();
+        self.output_managers.clear();
+        self.output_managers_changed.clear();
+        self.toplevels.clear();
+        self.toplevel_managers.clear();
+        self.toplevel_lists.clear();
         self.slow_clients.clear();
         for (_, h) in self.input_device_handlers.borrow_mut().drain() {
             h.async_event.clear();
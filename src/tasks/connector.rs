@@ -1,7 +1,8 @@
 use {
     crate::{
         backend::{Connector, ConnectorEvent, ConnectorId, MonitorInfo},
-        ifs::wl_output::WlOutputGlobal,
+        gamma::NEUTRAL_TEMPERATURE,
+        ifs::{wl_output::WlOutputGlobal, wp_drm_lease_device_v1::LeasableConnector},
         scale::Scale,
         state::{ConnectorData, OutputData, State},
         tree::{OutputNode, OutputRenderData},
@@ -29,6 +30,13 @@ pub fn handle(state: &Rc<State>, connector: &Rc<dyn Connector>) {
         name: connector.kernel_id().to_string(),
         drm_dev: drm_dev.clone(),
         async_event: Rc::new(AsyncEvent::default()),
+        enabled: Cell::new(true),
+        monitor_info: Default::default(),
+        pending_position: Default::default(),
+        pending_scale: Default::default(),
+        pending_transform: Default::default(),
+        gamma_control: Default::default(),
+        color_temperature: Cell::new(NEUTRAL_TEMPERATURE),
     });
     if let Some(dev) = drm_dev {
         dev.connectors.set(id, data.clone());
@@ -64,6 +72,9 @@ impl ConnectorHandler {
             while let Some(event) = self.data.connector.event() {
                 match event {
                     ConnectorEvent::Removed => break 'outer,
+                    ConnectorEvent::Connected(mi) if mi.non_desktop => {
+                        self.handle_non_desktop_connected(mi).await
+                    }
                     ConnectorEvent::Connected(mi) => self.handle_connected(mi).await,
                     _ => unreachable!(),
                 }
@@ -75,11 +86,49 @@ impl ConnectorHandler {
         }
         self.data.handler.set(None);
         self.state.connectors.remove(&self.id);
+        self.state.output_managers_changed.trigger();
+    }
+
+    /// Offers a connected non-desktop display for leasing instead of adding it to the
+    /// output layout.
+    async fn handle_non_desktop_connected(&self, info: MonitorInfo) {
+        log::info!(
+            "Connector {} connected to a non-desktop display",
+            self.data.connector.kernel_id()
+        );
+        let global = self
+            .data
+            .drm_dev
+            .as_ref()
+            .and_then(|d| d.lease_global.clone());
+        if let Some(global) = &global {
+            global.add_connector(&Rc::new(LeasableConnector {
+                data: self.data.clone(),
+                info,
+                leased: Cell::new(false),
+                withdrawn: Cell::new(false),
+            }));
+        }
+        'outer: loop {
+            while let Some(event) = self.data.connector.event() {
+                match event {
+                    ConnectorEvent::Disconnected => break 'outer,
+                    ConnectorEvent::HardwareCursor(_) | ConnectorEvent::ModeChanged(_) => {}
+                    ev => unreachable!("received unexpected event {:?}", ev),
+                }
+            }
+            self.data.async_event.triggered().await;
+        }
+        log::info!("Connector {} disconnected", self.data.connector.kernel_id());
+        if let Some(global) = &global {
+            global.remove_connector(self.id);
+        }
     }
 
     async fn handle_connected(&self, info: MonitorInfo) {
         log::info!("Connector {} connected", self.data.connector.kernel_id());
         self.data.connected.set(true);
+        self.data.monitor_info.set(Some(Rc::new(info.clone())));
         let name = self.state.globals.name();
         let x1 = self
             .state
@@ -124,8 +173,10 @@ impl ConnectorHandler {
             lock_surface: Default::default(),
             preferred_scale: Cell::new(Scale::from_int(1)),
             hardware_cursor: Default::default(),
+            hardware_cursor_too_small: Cell::new(false),
             jay_outputs: Default::default(),
             screencasts: Default::default(),
+            damage: Default::default(),
             update_render_data_scheduled: Cell::new(false),
         });
         self.state.add_output_scale(on.preferred_scale.get());
@@ -193,7 +244,18 @@ impl ConnectorHandler {
         on.schedule_update_render_data();
         self.state.root.outputs.set(self.id, on.clone());
         self.state.root.update_extents();
+        if let Some(scale) = self.data.pending_scale.take() {
+            on.set_preferred_scale(scale);
+        }
+        if let Some(transform) = self.data.pending_transform.take() {
+            on.set_transform(transform);
+        }
+        if let Some((x, y)) = self.data.pending_position.take() {
+            on.set_position(x, y);
+        }
+        self.data.update_gamma();
         self.state.add_global(&global);
+        self.state.output_managers_changed.trigger();
         'outer: loop {
             while let Some(event) = self.data.connector.event() {
                 match event {
@@ -224,9 +286,17 @@ impl ConnectorHandler {
             sc.do_destroy();
         }
         global.destroyed.set(true);
+        if let Some(gc) = self.data.gamma_control.take() {
+            gc.send_failed();
+            self.data.update_gamma();
+        }
         self.state.root.outputs.remove(&self.id);
         self.data.connected.set(false);
         self.state.outputs.remove(&self.id);
+        if self.data.enabled.get() {
+            self.data.monitor_info.take();
+        }
+        self.state.output_managers_changed.trigger();
         on.lock_surface.take();
         {
             let mut surfaces = vec![];
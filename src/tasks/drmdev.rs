@@ -1,6 +1,7 @@
 use {
     crate::{
         backend::{BackendDrmDevice, DrmDeviceId, DrmEvent},
+        ifs::wp_drm_lease_device_v1::WpDrmLeaseDeviceV1Global,
         state::{DrmDevData, State},
         udev::{Udev, UdevDeviceType},
         utils::{asyncevent::AsyncEvent, errorfmt::ErrorFmt},
@@ -65,6 +66,19 @@ pub fn handle(state: &Rc<State>, dev: Rc<dyn BackendDrmDevice>) {
             pci_id = Some(PciId { vendor, model });
         }
     }
+    let mut lease_global = None;
+    if dev.supports_leases() {
+        // Clients cannot use the global without a non-master fd.
+        if let Some(fd) = dev.non_master_fd() {
+            let global = Rc::new(WpDrmLeaseDeviceV1Global::new(
+                state.globals.name(),
+                &dev,
+                fd,
+            ));
+            state.add_global(&global);
+            lease_global = Some(global);
+        }
+    }
     let data = Rc::new(DrmDevData {
         dev: dev.clone(),
         handler: Cell::new(None),
@@ -74,6 +88,7 @@ pub fn handle(state: &Rc<State>, dev: Rc<dyn BackendDrmDevice>) {
         vendor,
         model,
         pci_id,
+        lease_global,
     });
     let oh = DrvDevHandler {
         id,
@@ -104,10 +119,19 @@ impl DrvDevHandler {
             config.new_drm_dev(self.id);
         }
         'outer: loop {
-            #[allow(clippy::never_loop)]
             while let Some(event) = self.data.dev.event() {
                 match event {
                     DrmEvent::Removed => break 'outer,
+                    DrmEvent::Paused => {
+                        if let Some(global) = &self.data.lease_global {
+                            global.pause();
+                        }
+                    }
+                    DrmEvent::Resumed => {
+                        if let Some(global) = &self.data.lease_global {
+                            global.resume();
+                        }
+                    }
                 }
             }
             ae.triggered().await;
@@ -118,6 +142,9 @@ impl DrvDevHandler {
         if let Some(config) = self.state.config.get() {
             config.del_drm_dev(self.id);
         }
+        if let Some(global) = &self.data.lease_global {
+            let _ = self.state.remove_global(&**global);
+        }
         self.data.handler.set(None);
         self.state.drm_devs.remove(&self.id);
     }
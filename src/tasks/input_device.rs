@@ -1,8 +1,8 @@
 use {
     crate::{
-        backend::InputDevice,
+        backend::{InputDevice, InputEvent, SwitchEvent},
         ifs::wl_seat::PX_PER_SCROLL,
-        state::{DeviceHandlerData, InputDeviceData, State},
+        state::{DeviceHandlerData, InputDeviceData, InputMapping, State},
         utils::asyncevent::AsyncEvent,
     },
     std::{cell::Cell, rc::Rc},
@@ -12,6 +12,9 @@ pub fn handle(state: &Rc<State>, dev: Rc<dyn InputDevice>) {
     let data = Rc::new(DeviceHandlerData {
         seat: Default::default(),
         px_per_scroll_wheel: Cell::new(PX_PER_SCROLL),
+        mapping: Cell::new(InputMapping::Default),
+        pad_button_bindings: Default::default(),
+        lid_closed: Cell::new(false),
         device: dev.clone(),
     });
     let ae = Rc::new(AsyncEvent::default());
@@ -53,23 +56,25 @@ impl DeviceHandler {
             if self.dev.removed() {
                 break;
             }
-            if let Some(seat) = self.data.seat.get() {
-                let mut any_events = false;
-                while let Some(event) = self.dev.event() {
+            let seat = self.data.seat.get();
+            let mut any_events = false;
+            while let Some(event) = self.dev.event() {
+                if let InputEvent::Switch { event } = event {
+                    self.handle_switch(event);
+                } else if let Some(seat) = &seat {
                     seat.event(&self.data, event);
                     any_events = true;
                 }
-                if any_events {
+            }
+            if any_events {
+                if let Some(seat) = &seat {
                     seat.mark_last_active();
-                    self.state.input_occurred();
-                }
-            } else {
-                while self.dev.event().is_some() {
-                    // nothing
                 }
+                self.state.input_occurred();
             }
             self.ae.triggered().await;
         }
+        self.data.set_seat(None);
         if let Some(config) = self.state.config.get() {
             config.del_input_device(self.dev.id());
         }
@@ -78,4 +83,15 @@ impl DeviceHandler {
             .borrow_mut()
             .remove(&self.dev.id());
     }
+
+    fn handle_switch(&self, event: SwitchEvent) {
+        match event {
+            SwitchEvent::LidOpened => self.data.lid_closed.set(false),
+            SwitchEvent::LidClosed => self.data.lid_closed.set(true),
+            _ => {}
+        }
+        if let Some(config) = self.state.config.get() {
+            config.switch_event(self.dev.id(), event);
+        }
+    }
 }
@@ -94,6 +94,7 @@ colors! {
     border = (0x3f, 0x47, 0x4a),
     bar_background = (0x00, 0x00, 0x00),
     bar_text = (0xff, 0xff, 0xff),
+    attention_requested_background = (0x23, 0x09, 0x2c),
 }
 
 macro_rules! sizes {
@@ -0,0 +1,155 @@
+use crate::{
+    ifs::wl_output::{
+        TF_180, TF_270, TF_90, TF_FLIPPED, TF_FLIPPED_180, TF_FLIPPED_270, TF_FLIPPED_90, TF_NORMAL,
+    },
+    rect::Rect,
+};
+
+#[cfg(test)]
+mod tests;
+
+/// A transform as defined by `wl_output.transform`.
+///
+/// The flipped variants describe a flip around the vertical axis followed by a rotation.
+/// Rotations are counter-clockwise.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub enum Transform {
+    #[default]
+    Normal,
+    Rotate90,
+    Rotate180,
+    Rotate270,
+    Flipped,
+    Flipped90,
+    Flipped180,
+    Flipped270,
+}
+
+impl Transform {
+    pub fn from_wl(tf: i32) -> Option<Self> {
+        use Transform::*;
+        let tf = match tf {
+            TF_NORMAL => Normal,
+            TF_90 => Rotate90,
+            TF_180 => Rotate180,
+            TF_270 => Rotate270,
+            TF_FLIPPED => Flipped,
+            TF_FLIPPED_90 => Flipped90,
+            TF_FLIPPED_180 => Flipped180,
+            TF_FLIPPED_270 => Flipped270,
+            _ => return None,
+        };
+        Some(tf)
+    }
+
+    pub fn to_wl(self) -> i32 {
+        use Transform::*;
+        match self {
+            Normal => TF_NORMAL,
+            Rotate90 => TF_90,
+            Rotate180 => TF_180,
+            Rotate270 => TF_270,
+            Flipped => TF_FLIPPED,
+            Flipped90 => TF_FLIPPED_90,
+            Flipped180 => TF_FLIPPED_180,
+            Flipped270 => TF_FLIPPED_270,
+        }
+    }
+
+    pub fn swaps_dimensions(self) -> bool {
+        use Transform::*;
+        match self {
+            Normal | Rotate180 | Flipped | Flipped180 => false,
+            Rotate90 | Rotate270 | Flipped90 | Flipped270 => true,
+        }
+    }
+
+    pub fn maybe_swap<T>(self, (width, height): (T, T)) -> (T, T) {
+        match self.swaps_dimensions() {
+            true => (height, width),
+            false => (width, height),
+        }
+    }
+
+    /// Returns the transform that undoes this transform.
+    pub fn inverse(self) -> Self {
+        use Transform::*;
+        match self {
+            Rotate90 => Rotate270,
+            Rotate270 => Rotate90,
+            _ => self,
+        }
+    }
+
+    /// Maps a point in normalized device coordinates.
+    pub fn apply_ndc(self, x: f32, y: f32) -> (f32, f32) {
+        use Transform::*;
+        match self {
+            Normal => (x, y),
+            Rotate90 => (y, -x),
+            Rotate180 => (-x, -y),
+            Rotate270 => (-y, x),
+            Flipped => (-x, y),
+            Flipped90 => (y, x),
+            Flipped180 => (x, -y),
+            Flipped270 => (-y, -x),
+        }
+    }
+
+    /// Maps a point in an untransformed area of size `width`x`height` to the transformed
+    /// area.
+    pub fn apply_point(self, x: i32, y: i32, width: i32, height: i32) -> (i32, i32) {
+        use Transform::*;
+        match self {
+            Normal => (x, y),
+            Rotate90 => (y, width - x),
+            Rotate180 => (width - x, height - y),
+            Rotate270 => (height - y, x),
+            Flipped => (width - x, y),
+            Flipped90 => (y, x),
+            Flipped180 => (x, height - y),
+            Flipped270 => (height - y, width - x),
+        }
+    }
+
+    /// Like [`Self::apply_point`] but for coordinates normalized to `0..=1`.
+    pub fn apply_normed(self, x: f64, y: f64) -> (f64, f64) {
+        use Transform::*;
+        match self {
+            Normal => (x, y),
+            Rotate90 => (y, 1.0 - x),
+            Rotate180 => (1.0 - x, 1.0 - y),
+            Rotate270 => (1.0 - y, x),
+            Flipped => (1.0 - x, y),
+            Flipped90 => (y, x),
+            Flipped180 => (x, 1.0 - y),
+            Flipped270 => (1.0 - y, 1.0 - x),
+        }
+    }
+
+    /// Maps a rectangle in an untransformed area of size `width`x`height` to the
+    /// transformed area.
+    pub fn apply_rect(self, rect: Rect, width: i32, height: i32) -> Rect {
+        if self == Transform::Normal {
+            return rect;
+        }
+        let (x1, y1) = self.apply_point(rect.x1(), rect.y1(), width, height);
+        let (x2, y2) = self.apply_point(rect.x2(), rect.y2(), width, height);
+        Rect::new(x1.min(x2), y1.min(y2), x1.max(x2), y1.max(y2)).unwrap()
+    }
+
+    /// Returns the texture coordinates to sample a buffer whose contents have been
+    /// transformed with this transform.
+    ///
+    /// The coordinates are in the order top right, top left, bottom right, bottom left.
+    pub fn texcoords(self) -> [f32; 8] {
+        let mut res = [0.0; 8];
+        let corners = [(1.0, 0.0), (0.0, 0.0), (1.0, 1.0), (0.0, 1.0)];
+        for (idx, (x, y)) in corners.into_iter().enumerate() {
+            let (x, y) = self.apply_ndc(2.0 * x - 1.0, 2.0 * y - 1.0);
+            res[2 * idx] = (x + 1.0) / 2.0;
+            res[2 * idx + 1] = (y + 1.0) / 2.0;
+        }
+        res
+    }
+}
@@ -0,0 +1,85 @@
+use crate::{rect::Rect, transform::Transform};
+
+const ALL: [Transform; 8] = [
+    Transform::Normal,
+    Transform::Rotate90,
+    Transform::Rotate180,
+    Transform::Rotate270,
+    Transform::Flipped,
+    Transform::Flipped90,
+    Transform::Flipped180,
+    Transform::Flipped270,
+];
+
+const WIDTH: i32 = 40;
+const HEIGHT: i32 = 30;
+
+#[test]
+fn wl_round_trip() {
+    for tf in ALL {
+        assert_eq!(Transform::from_wl(tf.to_wl()), Some(tf));
+    }
+    assert_eq!(Transform::from_wl(8), None);
+    assert_eq!(Transform::from_wl(-1), None);
+}
+
+#[test]
+fn inverse_is_involution() {
+    for tf in ALL {
+        assert_eq!(tf.inverse().inverse(), tf);
+        assert_eq!(tf.inverse().swaps_dimensions(), tf.swaps_dimensions());
+    }
+}
+
+#[test]
+fn point_round_trip() {
+    for tf in ALL {
+        let (tw, th) = tf.maybe_swap((WIDTH, HEIGHT));
+        for (x, y) in [(0, 0), (WIDTH, 0), (0, HEIGHT), (WIDTH, HEIGHT), (7, 23)] {
+            let (tx, ty) = tf.apply_point(x, y, WIDTH, HEIGHT);
+            assert!((0..=tw).contains(&tx) && (0..=th).contains(&ty), "{tf:?}");
+            assert_eq!(tf.inverse().apply_point(tx, ty, tw, th), (x, y), "{tf:?}");
+        }
+    }
+}
+
+#[test]
+fn normed_round_trip() {
+    for tf in ALL {
+        for (x, y) in [(0.0, 0.0), (1.0, 0.0), (0.0, 1.0), (0.25, 0.75)] {
+            let (tx, ty) = tf.apply_normed(x, y);
+            assert_eq!(tf.inverse().apply_normed(tx, ty), (x, y), "{tf:?}");
+        }
+    }
+}
+
+#[test]
+fn normed_matches_point() {
+    for tf in ALL {
+        let (tw, th) = tf.maybe_swap((WIDTH, HEIGHT));
+        let (x, y) = (8, 12);
+        let (px, py) = tf.apply_point(x, y, WIDTH, HEIGHT);
+        let (nx, ny) = tf.apply_normed(x as f64 / WIDTH as f64, y as f64 / HEIGHT as f64);
+        assert!((px as f64 / tw as f64 - nx).abs() < 1e-9, "{tf:?}");
+        assert!((py as f64 / th as f64 - ny).abs() < 1e-9, "{tf:?}");
+    }
+}
+
+#[test]
+fn rect_round_trip() {
+    let rect = Rect::new_sized(5, 7, 10, 3).unwrap();
+    for tf in ALL {
+        let (tw, th) = tf.maybe_swap((WIDTH, HEIGHT));
+        let transformed = tf.apply_rect(rect, WIDTH, HEIGHT);
+        assert_eq!(transformed.width(), tf.maybe_swap((10, 3)).0, "{tf:?}");
+        assert_eq!(tf.inverse().apply_rect(transformed, tw, th), rect, "{tf:?}");
+    }
+}
+
+#[test]
+fn texcoords_of_normal() {
+    assert_eq!(
+        Transform::Normal.texcoords(),
+        [1.0, 0.0, 0.0, 0.0, 1.0, 1.0, 0.0, 1.0],
+    );
+}
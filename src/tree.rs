@@ -137,6 +137,10 @@ pub trait Node: 'static {
         let _ = (child, active, depth);
     }
 
+    fn node_child_attention_request_changed(self: Rc<Self>, child: &dyn Node, set: bool) {
+        let _ = (child, set);
+    }
+
     fn node_render(&self, renderer: &mut Renderer, x: i32, y: i32) {
         let _ = renderer;
         let _ = x;
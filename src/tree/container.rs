@@ -83,6 +83,7 @@ pub struct ContainerTitle {
 pub struct ContainerRenderData {
     pub title_rects: Vec<Rect>,
     pub active_title_rects: Vec<Rect>,
+    pub attention_requested_rects: Vec<Rect>,
     pub last_active_rect: Option<Rect>,
     pub border_rects: Vec<Rect>,
     pub underline_rects: Vec<Rect>,
@@ -641,6 +642,7 @@ impl ContainerNode {
         }
         rd.title_rects.clear();
         rd.active_title_rects.clear();
+        rd.attention_requested_rects.clear();
         rd.border_rects.clear();
         rd.underline_rects.clear();
         rd.last_active_rect.take();
@@ -664,6 +666,9 @@ impl ContainerNode {
             let color = if child.active.get() {
                 rd.active_title_rects.push(rect);
                 theme.colors.focused_title_text.get()
+            } else if child.node.tl_data().wants_attention.get() {
+                rd.attention_requested_rects.push(rect);
+                theme.colors.unfocused_title_text.get()
             } else if !have_active && last_active == Some(child.node.node_id()) {
                 rd.last_active_rect = Some(rect);
                 theme.colors.focused_inactive_title_text.get()
@@ -946,6 +951,7 @@ pub async fn container_render_data(state: Rc<State>) {
         let container = state.pending_container_render_data.pop().await;
         if container.compute_render_data_scheduled.get() {
             container.compute_render_data();
+            state.damage_rect(container.node_absolute_position());
         }
     }
 }
@@ -1086,6 +1092,10 @@ impl Node for ContainerNode {
             .node_child_active_changed(self.deref(), active, depth + 1);
     }
 
+    fn node_child_attention_request_changed(self: Rc<Self>, _child: &dyn Node, _set: bool) {
+        self.schedule_compute_render_data();
+    }
+
     fn node_render(&self, renderer: &mut Renderer, x: i32, y: i32) {
         renderer.render_container(self, x, y);
     }
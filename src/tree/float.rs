@@ -85,6 +85,7 @@ pub async fn float_titles(state: Rc<State>) {
         let node = state.pending_float_titles.pop().await;
         if node.render_titles_scheduled.get() {
             node.render_title();
+            state.damage_rect(node.position.get());
         }
     }
 }
@@ -420,6 +421,10 @@ impl Node for FloatNode {
         }
     }
 
+    fn node_child_attention_request_changed(self: Rc<Self>, _child: &dyn Node, _set: bool) {
+        self.state.damage_rect(self.position.get());
+    }
+
     fn node_render(&self, renderer: &mut Renderer, x: i32, y: i32) {
         renderer.render_floating(self, x, y)
     }
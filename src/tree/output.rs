@@ -7,24 +7,28 @@ use {
         ifs::{
             jay_output::JayOutput,
             jay_screencast::JayScreencast,
+            wl_buffer::WlBuffer,
             wl_output::WlOutputGlobal,
             wl_seat::{
                 collect_kb_foci2, wl_pointer::PendingScroll, NodeSeatState, SeatId, WlSeatGlobal,
                 BTN_LEFT,
             },
             wl_surface::{
-                ext_session_lock_surface_v1::ExtSessionLockSurfaceV1,
-                zwlr_layer_surface_v1::ZwlrLayerSurfaceV1, SurfaceSendPreferredScaleVisitor,
+                ext_session_lock_surface_v1::ExtSessionLockSurfaceV1, x_surface::xwindow::Xwindow,
+                xdg_surface::xdg_toplevel::XdgToplevel, zwlr_layer_surface_v1::ZwlrLayerSurfaceV1,
+                SurfaceSendPreferredScaleVisitor, WlSurface,
             },
             zwlr_layer_shell_v1::{BACKGROUND, BOTTOM, OVERLAY, TOP},
         },
-        rect::Rect,
+        rect::{Rect, Region, RegionBuilder},
         render::{Framebuffer, Renderer, Texture},
         scale::Scale,
         state::State,
         text,
+        transform::Transform,
         tree::{
-            walker::NodeVisitor, Direction, FindTreeResult, FoundNode, Node, NodeId, WorkspaceNode,
+            walker::{NodeVisitor, NodeVisitorBase},
+            Direction, FindTreeResult, FoundNode, Node, NodeId, ToplevelNode, WorkspaceNode,
         },
         utils::{
             clonecell::CloneCell, copyhashmap::CopyHashMap, errorfmt::ErrorFmt,
@@ -60,8 +64,13 @@ pub struct OutputNode {
     pub lock_surface: CloneCell<Option<Rc<ExtSessionLockSurfaceV1>>>,
     pub preferred_scale: Cell<Scale>,
     pub hardware_cursor: CloneCell<Option<Rc<dyn HardwareCursor>>>,
+    /// Whether the cursor is rendered in software because it does not fit into the
+    /// hardware cursor.
+    pub hardware_cursor_too_small: Cell<bool>,
     pub update_render_data_scheduled: Cell<bool>,
     pub screencasts: CopyHashMap<(ClientId, JayScreencastId), Rc<JayScreencast>>,
+    /// The damage accumulated since the last frame in output-local coordinates.
+    pub damage: RefCell<RegionBuilder>,
 }
 
 pub async fn output_render_data(state: Rc<State>) {
@@ -72,6 +81,9 @@ pub async fn output_render_data(state: Rc<State>) {
         }
         if container.update_render_data_scheduled.get() {
             container.update_render_data();
+            let pos = container.global.pos.get();
+            let th = state.theme.sizes.title_height.get();
+            state.damage_rect(Rect::new_sized(pos.x1(), pos.y1(), pos.width(), th + 1).unwrap());
         }
     }
 }
@@ -84,6 +96,182 @@ impl OutputNode {
         }
     }
 
+    /// Adds damage in global coordinates.
+    pub fn add_damage(&self, rect: Rect) {
+        let pos = self.global.pos.get();
+        let rect = rect.intersect(pos).move_(-pos.x1(), -pos.y1());
+        if !rect.is_empty() {
+            self.damage.borrow_mut().add(rect);
+        }
+    }
+
+    pub fn damage_all(&self) {
+        self.add_damage(self.global.pos.get());
+    }
+
+    /// Returns the damage accumulated since the last call.
+    ///
+    /// Animated cursors advance to their next image whenever they are rendered. Their
+    /// area is therefore always considered damaged.
+    pub fn take_damage(&self) -> Rc<Region> {
+        for seat in self.state.globals.lock_seats().values() {
+            if let Some(cursor) = seat.get_cursor() {
+                if cursor.needs_tick() {
+                    if let Some(rect) = seat.cursor_extents() {
+                        self.add_damage(rect);
+                    }
+                }
+            }
+        }
+        let mut damage = self.damage.borrow_mut();
+        let res = damage.get();
+        damage.clear();
+        res
+    }
+
+    /// Returns whether client buffers must not be displayed on hardware planes because
+    /// the rendered output is needed or cannot be reproduced with planes.
+    fn client_planes_blocked(&self) -> bool {
+        self.state.lock.locked.get()
+            || self.global.transform.get() != Transform::Normal
+            || !self.global.pending_captures.is_empty()
+            || !self.screencasts.is_empty()
+    }
+
+    /// Collects the areas covered by cursors and input popups.
+    ///
+    /// Cursors are only taken into account if they are not displayed via a hardware cursor,
+    /// as indicated by `render_hardware_cursor`. Returns `false` if a drag-and-drop icon
+    /// is visible.
+    fn collect_seat_occluders(&self, render_hardware_cursor: bool, dst: &mut Vec<Rect>) -> bool {
+        for seat in self.state.globals.lock_seats().values() {
+            if seat.dnd_icon().is_some() {
+                return false;
+            }
+            if render_hardware_cursor || !seat.hardware_cursor() {
+                dst.extend(seat.cursor_extents());
+            }
+            seat.for_each_input_popup(|popup| {
+                if popup.surface.buffer.get().is_some() {
+                    dst.push(popup.surface.buffer_abs_pos.get());
+                }
+            });
+        }
+        true
+    }
+
+    /// Returns the fullscreen surface and its buffer if the buffer could be scanned out
+    /// directly instead of rendering the output.
+    ///
+    /// This is only possible if the buffer covers the whole output and no layer surfaces
+    /// are visible above it. Cursors and popups that can come and go at any time are
+    /// checked separately by [`Self::direct_scanout_occluded`].
+    pub fn direct_scanout_surface(&self) -> Option<(Rc<WlSurface>, Rc<WlBuffer>)> {
+        if self.client_planes_blocked() || !self.layers[2].is_empty() || !self.layers[3].is_empty()
+        {
+            return None;
+        }
+        let fs = self.workspace.get()?.fullscreen.get()?;
+        let surface = fs.tl_scanout_surface()?;
+        let buffer = surface.direct_scanout_buffer()?;
+        let pos = self.global.pos.get();
+        let mode = self.global.mode.get();
+        if surface.buffer_abs_pos.get() != pos || buffer.rect.size() != (mode.width, mode.height) {
+            return None;
+        }
+        Some((surface, buffer))
+    }
+
+    /// Returns whether a cursor, input popup, or drag-and-drop icon is visible on the
+    /// output and prevents direct scanout.
+    pub fn direct_scanout_occluded(&self, render_hardware_cursor: bool) -> bool {
+        let mut occluders = vec![];
+        if !self.collect_seat_occluders(render_hardware_cursor, &mut occluders) {
+            return true;
+        }
+        let pos = self.global.pos.get();
+        occluders.iter().any(|o| o.intersects(&pos))
+    }
+
+    /// Returns the surfaces of tiled windows whose buffers could be displayed on overlay
+    /// planes, largest first.
+    ///
+    /// A surface is a candidate if it lies completely within its window and the output and
+    /// if nothing that is rendered after it overlaps it.
+    pub fn overlay_plane_candidates(
+        &self,
+        render_hardware_cursor: bool,
+    ) -> Vec<(Rc<WlSurface>, Rc<WlBuffer>)> {
+        let mut res = vec![];
+        if self.client_planes_blocked() {
+            return res;
+        }
+        let ws = match self.workspace.get() {
+            Some(ws) if ws.fullscreen.get().is_none() => ws,
+            _ => return res,
+        };
+        let mut occluders = vec![];
+        if !self.collect_seat_occluders(render_hardware_cursor, &mut occluders) {
+            return res;
+        }
+        for layer in &self.layers[2..] {
+            occluders.extend(layer.iter().map(|ls| ls.position()));
+        }
+        for stacked in self.state.root.stacked.iter() {
+            if stacked.node_visible() {
+                occluders.push(stacked.node_absolute_position());
+            }
+        }
+        struct Toplevels(Vec<Rc<dyn ToplevelNode>>);
+        impl NodeVisitorBase for Toplevels {
+            fn visit_toplevel(&mut self, node: &Rc<XdgToplevel>) {
+                if node.node_visible() {
+                    self.0.push(node.clone());
+                }
+            }
+            fn visit_xwindow(&mut self, node: &Rc<Xwindow>) {
+                if node.node_visible() {
+                    self.0.push(node.clone());
+                }
+            }
+        }
+        let mut toplevels = Toplevels(vec![]);
+        ws.node_visit_children(&mut toplevels);
+        let pos = self.global.pos.get();
+        let mut surfaces = vec![];
+        for tl in toplevels.0 {
+            let root = match tl.tl_scanout_surface() {
+                Some(s) => s,
+                _ => continue,
+            };
+            let body = tl.node_absolute_position();
+            surfaces.clear();
+            root.collect_surfaces(&mut surfaces);
+            for (idx, surface) in surfaces.iter().enumerate() {
+                let buffer = match surface.plane_buffer() {
+                    Some(b) => b,
+                    _ => continue,
+                };
+                let rect = surface.buffer_abs_pos.get();
+                if rect.is_empty() || !body.contains_rect(&rect) || !pos.contains_rect(&rect) {
+                    continue;
+                }
+                let occluded = occluders.iter().any(|o| o.intersects(&rect))
+                    || surfaces[idx + 1..].iter().any(|s| {
+                        s.buffer.get().is_some() && s.buffer_abs_pos.get().intersects(&rect)
+                    });
+                if !occluded {
+                    res.push((surface.clone(), buffer));
+                }
+            }
+        }
+        res.sort_by_key(|(s, _)| {
+            let (width, height) = s.buffer_abs_pos.get().size();
+            std::cmp::Reverse(width as i64 * height as i64)
+        });
+        res
+    }
+
     pub fn clear(&self) {
         self.global.clear();
         self.workspace.set(None);
@@ -349,6 +537,16 @@ impl OutputNode {
         }
         let rect = pos.at_point(x, y);
         self.change_extents_(&rect);
+        let seats = self.state.globals.seats.lock();
+        for seat in seats.values() {
+            if seat.get_output().id == self.id {
+                let seat_pos = seat.position();
+                seat.set_position(
+                    seat_pos.0.round_down() + x - pos.x1(),
+                    seat_pos.1.round_down() + y - pos.y1(),
+                );
+            }
+        }
     }
 
     pub fn update_mode(self: &Rc<Self>, mode: Mode) {
@@ -361,28 +559,50 @@ impl OutputNode {
         self.change_extents_(&rect);
 
         if (old_mode.width, old_mode.height) != (mode.width, mode.height) {
-            let mut to_destroy = vec![];
-            if let Some(ctx) = self.state.render_ctx.get() {
-                for sc in self.screencasts.lock().values() {
-                    if let Err(e) = sc.realloc(&ctx) {
-                        log::error!(
-                            "Could not re-allocate buffers for screencast after mode change: {}",
-                            ErrorFmt(e)
-                        );
-                        to_destroy.push(sc.clone());
-                    }
+            self.realloc_screencasts();
+        }
+    }
+
+    pub fn set_transform(self: &Rc<Self>, transform: Transform) {
+        let old_transform = self.global.transform.replace(transform);
+        if old_transform == transform {
+            return;
+        }
+        let rect = self.calculate_extents();
+        self.change_extents_(&rect);
+        if old_transform.swaps_dimensions() != transform.swaps_dimensions() {
+            self.realloc_screencasts();
+        }
+        self.state.refresh_hardware_cursors();
+        self.damage_all();
+        self.global.connector.connector.damage();
+    }
+
+    fn realloc_screencasts(&self) {
+        let mut to_destroy = vec![];
+        if let Some(ctx) = self.state.render_ctx.get() {
+            for sc in self.screencasts.lock().values() {
+                if let Err(e) = sc.realloc(&ctx) {
+                    log::error!(
+                        "Could not re-allocate buffers for screencast after output change: {}",
+                        ErrorFmt(e)
+                    );
+                    to_destroy.push(sc.clone());
                 }
             }
-            for sc in to_destroy {
-                sc.do_destroy();
-            }
+        }
+        for sc in to_destroy {
+            sc.do_destroy();
         }
     }
 
     fn calculate_extents(&self) -> Rect {
         let mode = self.global.mode.get();
-        let mut width = mode.width;
-        let mut height = mode.height;
+        let (mut width, mut height) = self
+            .global
+            .transform
+            .get()
+            .maybe_swap((mode.width, mode.height));
         let scale = self.preferred_scale.get();
         if scale != 1 {
             let scale = scale.to_f64();
@@ -412,6 +632,7 @@ impl OutputNode {
             }
         }
         self.global.send_mode();
+        self.state.output_managers_changed.trigger();
     }
 
     pub fn find_layer_surface_at(
@@ -1,11 +1,20 @@
 use {
     crate::{
-        client::Client,
-        ifs::wl_seat::{collect_kb_foci, collect_kb_foci2, NodeSeatState, SeatId},
+        client::{Client, ClientId},
+        ifs::{
+            ext_foreign_toplevel_handle_v1::ExtForeignToplevelHandleV1,
+            wl_seat::{collect_kb_foci, collect_kb_foci2, NodeSeatState, SeatId},
+            wl_surface::{x_surface::xwindow::XwindowData, WlSurface},
+            zwlr_foreign_toplevel_handle_v1::ZwlrForeignToplevelHandleV1,
+        },
         rect::Rect,
         state::State,
         tree::{ContainingNode, Direction, Node, OutputNode, PlaceholderNode, WorkspaceNode},
-        utils::{clonecell::CloneCell, numcell::NumCell, smallmap::SmallMap},
+        utils::{
+            clonecell::CloneCell, copyhashmap::CopyHashMap, numcell::NumCell, smallmap::SmallMap,
+        },
+        window_rules::WindowRuleActions,
+        wire::{ExtForeignToplevelHandleV1Id, ZwlrForeignToplevelHandleV1Id},
     },
     std::{
         cell::{Cell, RefCell},
@@ -44,6 +53,8 @@ pub trait ToplevelNode: Node {
         if active {
             if data.active_surfaces.fetch_add(1) == 0 {
                 self.tl_set_active(true);
+                self.tl_set_wants_attention(false);
+                data.send_handle_state();
                 if let Some(parent) = data.parent.get() {
                     parent.node_child_active_changed(self.tl_as_node(), true, 1);
                 }
@@ -51,6 +62,7 @@ pub trait ToplevelNode: Node {
         } else {
             if data.active_surfaces.fetch_sub(1) == 1 {
                 self.tl_set_active(false);
+                data.send_handle_state();
                 if let Some(parent) = data.parent.get() {
                     parent.node_child_active_changed(self.tl_as_node(), false, 1);
                 }
@@ -86,6 +98,23 @@ pub trait ToplevelNode: Node {
             *data.placeholder.tl_data().title.borrow_mut() = title.clone();
             data.placeholder.tl_title_changed();
         }
+        for handle in data.handles.lock().values() {
+            handle.send_title(&title);
+            handle.send_done();
+        }
+        for handle in data.ext_handles.lock().values() {
+            handle.send_title(&title);
+            handle.send_done();
+        }
+    }
+
+    fn tl_set_wants_attention(&self, wants_attention: bool) {
+        let data = self.tl_data();
+        if data.wants_attention.replace(wants_attention) != wants_attention {
+            if let Some(parent) = data.parent.get() {
+                parent.node_child_attention_request_changed(self.tl_as_node(), wants_attention);
+            }
+        }
     }
 
     fn tl_set_parent(&self, parent: Rc<dyn ContainingNode>) {
@@ -129,6 +158,7 @@ pub trait ToplevelNode: Node {
     fn tl_set_workspace(self: Rc<Self>, ws: &Rc<WorkspaceNode>) {
         let data = self.tl_data();
         data.workspace.set(Some(ws.clone()));
+        data.send_handle_output();
         self.tl_set_workspace_ext(ws);
     }
 
@@ -144,9 +174,24 @@ pub trait ToplevelNode: Node {
         // nothing
     }
 
+    /// Asks the client to minimize or restore the toplevel.
+    fn tl_set_minimized(&self, minimized: bool) {
+        let _ = minimized;
+    }
+
     fn tl_set_visible(&self, visible: bool);
     fn tl_destroy(&self);
 
+    /// Returns the surface that makes up the entire content of this toplevel, if any.
+    fn tl_scanout_surface(&self) -> Option<Rc<WlSurface>> {
+        None
+    }
+
+    /// Returns the X11 properties of this toplevel if it is an X window.
+    fn tl_x_data(&self) -> Option<&XwindowData> {
+        None
+    }
+
     fn tl_last_active_child(self: Rc<Self>) -> Rc<dyn ToplevelNode> {
         self.tl_into_dyn()
     }
@@ -168,12 +213,22 @@ pub struct ToplevelData {
     pub float_width: Cell<i32>,
     pub float_height: Cell<i32>,
     pub is_fullscreen: Cell<bool>,
+    pub is_minimized: Cell<bool>,
     pub fullscrceen_data: RefCell<Option<FullscreenedData>>,
     pub workspace: CloneCell<Option<Rc<WorkspaceNode>>>,
     pub title: RefCell<String>,
+    pub app_id: RefCell<String>,
+    pub wants_attention: Cell<bool>,
+    pub handles:
+        CopyHashMap<(ClientId, ZwlrForeignToplevelHandleV1Id), Rc<ZwlrForeignToplevelHandleV1>>,
+    pub ext_handles:
+        CopyHashMap<(ClientId, ExtForeignToplevelHandleV1Id), Rc<ExtForeignToplevelHandleV1>>,
     pub parent: CloneCell<Option<Rc<dyn ContainingNode>>>,
     pub pos: Cell<Rect>,
     pub seat_state: NodeSeatState,
+    /// The window rule actions evaluated before the window was mapped.
+    pub window_rule_actions: RefCell<Option<WindowRuleActions>>,
+    pub window_rules_applied: Cell<bool>,
 }
 
 impl ToplevelData {
@@ -189,12 +244,19 @@ impl ToplevelData {
             float_width: Default::default(),
             float_height: Default::default(),
             is_fullscreen: Default::default(),
+            is_minimized: Default::default(),
             fullscrceen_data: Default::default(),
             workspace: Default::default(),
             title: RefCell::new(title),
+            app_id: Default::default(),
+            wants_attention: Cell::new(false),
+            handles: Default::default(),
+            ext_handles: Default::default(),
             parent: Default::default(),
             pos: Default::default(),
             seat_state: Default::default(),
+            window_rule_actions: Default::default(),
+            window_rules_applied: Cell::new(false),
         }
     }
 
@@ -211,7 +273,80 @@ impl ToplevelData {
         (width, height)
     }
 
+    /// Publishes the toplevel to all foreign toplevel managers and lists.
+    pub fn broadcast(&self, toplevel: Rc<dyn ToplevelNode>) {
+        let id = toplevel.node_id();
+        if self.state.toplevels.set(id, toplevel.clone()).is_some() {
+            return;
+        }
+        let managers: Vec<_> = self
+            .state
+            .toplevel_managers
+            .lock()
+            .values()
+            .cloned()
+            .collect();
+        for manager in managers {
+            if let Err(e) = manager.announce(&toplevel) {
+                manager.client.error(e);
+            }
+        }
+        let lists: Vec<_> = self.state.toplevel_lists.lock().values().cloned().collect();
+        for list in lists {
+            if let Err(e) = list.announce(&toplevel) {
+                list.client.error(e);
+            }
+        }
+        if let Some(config) = self.state.config.get() {
+            config.new_window(id);
+        }
+    }
+
+    pub fn set_app_id(&self, app_id: &str) {
+        let mut old = self.app_id.borrow_mut();
+        if *old == app_id {
+            return;
+        }
+        *old = app_id.to_string();
+        for handle in self.handles.lock().values() {
+            handle.send_app_id(app_id);
+            handle.send_done();
+        }
+        for handle in self.ext_handles.lock().values() {
+            handle.send_app_id(app_id);
+            handle.send_done();
+        }
+    }
+
+    pub fn send_handle_state(&self) {
+        for handle in self.handles.lock().values() {
+            handle.send_state(self);
+            handle.send_done();
+        }
+    }
+
+    pub fn set_minimized(&self, minimized: bool) {
+        if self.is_minimized.replace(minimized) != minimized {
+            self.send_handle_state();
+        }
+    }
+
+    pub fn send_handle_output(&self) {
+        for handle in self.handles.lock().values() {
+            if handle.send_output(self) {
+                handle.send_done();
+            }
+        }
+    }
+
     pub fn destroy_node(&self, node: &dyn Node) {
+        self.state.toplevels.remove(&node.node_id());
+        for (_, handle) in self.handles.lock().drain() {
+            handle.send_closed();
+        }
+        for (_, handle) in self.ext_handles.lock().drain() {
+            handle.send_closed();
+        }
         if let Some(fd) = self.fullscrceen_data.borrow_mut().take() {
             fd.placeholder.tl_destroy();
         }
@@ -277,6 +412,7 @@ impl ToplevelData {
         });
         drop(data);
         self.is_fullscreen.set(true);
+        self.send_handle_state();
         ws.fullscreen.set(Some(node.clone()));
         node.tl_set_parent(ws.clone());
         node.clone().tl_set_workspace(ws);
@@ -302,6 +438,7 @@ impl ToplevelData {
             }
         };
         self.is_fullscreen.set(false);
+        self.send_handle_state();
         match fd.workspace.fullscreen.get() {
             None => {
                 log::error!("Node is supposed to be fullscreened on a workspace but workspace has not fullscreen node.");
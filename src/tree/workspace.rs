@@ -6,7 +6,9 @@ use {
             jay_workspace::JayWorkspace,
             wl_output::OutputId,
             wl_seat::{NodeSeatState, WlSeatGlobal},
-            wl_surface::WlSurface,
+            wl_surface::{
+                x_surface::xwindow::Xwindow, xdg_surface::xdg_toplevel::XdgToplevel, WlSurface,
+            },
         },
         rect::Rect,
         render::Renderer,
@@ -62,6 +64,16 @@ impl WorkspaceNode {
             fn visit_surface(&mut self, node: &Rc<WlSurface>) {
                 node.set_output(self.0);
             }
+
+            fn visit_toplevel(&mut self, node: &Rc<XdgToplevel>) {
+                node.tl_data().send_handle_output();
+                node.node_visit_children(self);
+            }
+
+            fn visit_xwindow(&mut self, node: &Rc<Xwindow>) {
+                node.tl_data().send_handle_output();
+                node.node_visit_children(self);
+            }
         }
         let mut visitor = OutputSetter(output);
         self.node_visit_children(&mut visitor);
@@ -1,3 +1,4 @@
+pub mod activation_token;
 pub mod array;
 pub mod asyncevent;
 pub mod bitfield;
@@ -21,6 +22,7 @@ pub mod once;
 pub mod option_ext;
 pub mod oserror;
 pub mod page_size;
+pub mod pread;
 pub mod ptr_ext;
 pub mod queue;
 pub mod rc_eq;
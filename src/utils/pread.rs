@@ -0,0 +1,16 @@
+use {crate::utils::oserror::OsError, uapi::c};
+
+/// Reads the file from the start until `buf` is full or the end of the file is reached.
+///
+/// Returns the number of bytes read.
+pub fn pread_all(fd: c::c_int, buf: &mut [u8]) -> Result<usize, OsError> {
+    let mut pos = 0;
+    while pos < buf.len() {
+        match uapi::pread(fd, &mut buf[pos..], pos as _) {
+            Ok([]) => break,
+            Ok(n) => pos += n.len(),
+            Err(e) => return Err(e.into()),
+        }
+    }
+    Ok(pos)
+}
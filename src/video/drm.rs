@@ -4,15 +4,16 @@ use {
     crate::{
         utils::oserror::OsError,
         video::drm::sys::{
-            create_lease, drm_event, drm_event_vblank, gem_close, get_cap,
+            create_lease, drm_event, drm_event_vblank, drop_master, gem_close, get_cap,
             get_device_name_from_fd2, get_minor_name_from_fd, get_node_type_from_fd, get_nodes,
             mode_addfb2, mode_atomic, mode_create_blob, mode_destroy_blob, mode_get_resources,
             mode_getconnector, mode_getencoder, mode_getplane, mode_getplaneresources,
             mode_getprobblob, mode_getproperty, mode_obj_getproperties, mode_rmfb,
-            prime_fd_to_handle, set_client_cap, DRM_DISPLAY_MODE_LEN, DRM_MODE_ATOMIC_TEST_ONLY,
-            DRM_MODE_FB_MODIFIERS, DRM_MODE_OBJECT_BLOB, DRM_MODE_OBJECT_CONNECTOR,
-            DRM_MODE_OBJECT_CRTC, DRM_MODE_OBJECT_ENCODER, DRM_MODE_OBJECT_FB,
-            DRM_MODE_OBJECT_MODE, DRM_MODE_OBJECT_PLANE, DRM_MODE_OBJECT_PROPERTY,
+            prime_fd_to_handle, revoke_lease, set_client_cap, DRM_DISPLAY_MODE_LEN,
+            DRM_MODE_ATOMIC_TEST_ONLY, DRM_MODE_FB_MODIFIERS, DRM_MODE_OBJECT_BLOB,
+            DRM_MODE_OBJECT_CONNECTOR, DRM_MODE_OBJECT_CRTC, DRM_MODE_OBJECT_ENCODER,
+            DRM_MODE_OBJECT_FB, DRM_MODE_OBJECT_MODE, DRM_MODE_OBJECT_PLANE,
+            DRM_MODE_OBJECT_PROPERTY,
         },
     },
     ahash::AHashMap,
@@ -39,8 +40,9 @@ use crate::{
     },
 };
 pub use sys::{
-    drm_mode_modeinfo, DRM_CLIENT_CAP_ATOMIC, DRM_MODE_ATOMIC_ALLOW_MODESET,
-    DRM_MODE_ATOMIC_NONBLOCK, DRM_MODE_PAGE_FLIP_EVENT,
+    drm_color_lut, drm_mode_modeinfo, DRM_CLIENT_CAP_ATOMIC, DRM_MODE_ATOMIC_ALLOW_MODESET,
+    DRM_MODE_ATOMIC_NONBLOCK, DRM_MODE_PAGE_FLIP_ASYNC, DRM_MODE_PAGE_FLIP_EVENT,
+    DRM_MODE_TYPE_PREFERRED,
 };
 
 #[derive(Debug, Error)]
@@ -105,6 +107,12 @@ pub enum DrmError {
     InvalidRead,
     #[error("Could not determine the drm version")]
     Version(#[source] OsError),
+    #[error("Could not create a lease")]
+    CreateLease(#[source] OsError),
+    #[error("Could not revoke a lease")]
+    RevokeLease(#[source] OsError),
+    #[error("Could not drop DRM master")]
+    DropMaster(#[source] OsError),
 }
 
 fn render_node_name(fd: c::c_int) -> Result<Ustring, DrmError> {
@@ -274,7 +282,7 @@ impl DrmMaster {
         res
     }
 
-    pub fn create_blob<T>(self: &Rc<Self>, t: &T) -> Result<PropBlob, DrmError> {
+    pub fn create_blob<T: ?Sized>(self: &Rc<Self>, t: &T) -> Result<PropBlob, DrmError> {
         match mode_create_blob(self.raw(), t) {
             Ok(b) => Ok(PropBlob {
                 master: self.clone(),
@@ -284,6 +292,47 @@ impl DrmMaster {
         }
     }
 
+    pub fn create_lease(&self, objects: &[u32]) -> Result<(OwnedFd, u32), DrmError> {
+        create_lease(self.raw(), objects, (c::O_CLOEXEC | c::O_NONBLOCK) as _)
+            .map_err(DrmError::CreateLease)
+    }
+
+    pub fn revoke_lease(&self, lessee_id: u32) -> Result<(), DrmError> {
+        revoke_lease(self.raw(), lessee_id).map_err(DrmError::RevokeLease)
+    }
+
+    /// Opens the device without acquiring DRM master.
+    ///
+    /// This opens the primary node if possible and the render node otherwise.
+    pub fn open_non_master(&self) -> Result<Rc<OwnedFd>, DrmError> {
+        match self.open_primary_non_master() {
+            Ok(fd) => return Ok(fd),
+            Err(e) => log::warn!(
+                "Could not open the primary node, using the render node: {}",
+                ErrorFmt(e)
+            ),
+        }
+        let path = render_node_name(self.raw())?;
+        match uapi::open(&path, c::O_RDWR | c::O_CLOEXEC, 0) {
+            Ok(f) => Ok(Rc::new(f)),
+            Err(e) => Err(DrmError::ReopenNode(e.into())),
+        }
+    }
+
+    fn open_primary_non_master(&self) -> Result<Rc<OwnedFd>, DrmError> {
+        let path = device_node_name(self.raw())?;
+        let fd = match uapi::open(&path, c::O_RDWR | c::O_CLOEXEC, 0) {
+            Ok(f) => f,
+            Err(e) => return Err(DrmError::ReopenNode(e.into())),
+        };
+        // The first client to open a primary node becomes master automatically. Dropping
+        // master fails with EINVAL if the fd is not master.
+        match drop_master(fd.raw()) {
+            Ok(()) | Err(OsError(c::EINVAL)) => Ok(Rc::new(fd)),
+            Err(e) => Err(DrmError::DropMaster(e)),
+        }
+    }
+
     pub fn add_fb(self: &Rc<Self>, dma: &DmaBuf) -> Result<DrmFramebuffer, DrmError> {
         let mut modifier = 0;
         let mut flags = 0;
@@ -635,6 +684,10 @@ impl DrmModeInfo {
         }
     }
 
+    pub fn is_preferred(&self) -> bool {
+        self.ty & DRM_MODE_TYPE_PREFERRED != 0
+    }
+
     pub fn to_backend(&self) -> backend::Mode {
         backend::Mode {
             width: self.hdisplay as _,
@@ -684,7 +737,6 @@ pub struct ObjectChange<'a> {
 }
 
 impl Change {
-    #[allow(dead_code)]
     pub fn test(&self, flags: u32) -> Result<(), DrmError> {
         mode_atomic(
             self.master.raw(),
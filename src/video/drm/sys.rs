@@ -70,6 +70,30 @@ pub fn create_lease(fd: c::c_int, objects: &[u32], flags: u32) -> Result<(OwnedF
     Ok((OwnedFd::new(create.fd as _), create.lessee_id))
 }
 
+const DRM_IOCTL_MODE_REVOKE_LEASE: u64 = drm_iowr::<drm_mode_revoke_lease>(0xc9);
+
+#[repr(C)]
+struct drm_mode_revoke_lease {
+    lessee_id: u32,
+}
+
+pub fn revoke_lease(fd: c::c_int, lessee_id: u32) -> Result<(), OsError> {
+    let mut revoke = drm_mode_revoke_lease { lessee_id };
+    unsafe {
+        ioctl(fd, DRM_IOCTL_MODE_REVOKE_LEASE, &mut revoke)?;
+    }
+    Ok(())
+}
+
+const DRM_IOCTL_DROP_MASTER: u64 = uapi::_IO(DRM_IOCTL_BASE, 0x1f);
+
+pub fn drop_master(fd: c::c_int) -> Result<(), OsError> {
+    unsafe {
+        ioctl(fd, DRM_IOCTL_DROP_MASTER, &mut ())?;
+    }
+    Ok(())
+}
+
 pub fn get_node_type_from_fd(fd: c::c_int) -> Result<NodeType, OsError> {
     let (_, _, min) = drm_stat(fd)?;
     get_minor_type(min)
@@ -378,6 +402,8 @@ pub fn mode_obj_getproperties(
     Ok(props)
 }
 
+pub const DRM_MODE_TYPE_PREFERRED: u32 = 1 << 3;
+
 pub const DRM_MODE_OBJECT_CRTC: u32 = 0xcccccccc;
 pub const DRM_MODE_OBJECT_CONNECTOR: u32 = 0xc0c0c0c0;
 pub const DRM_MODE_OBJECT_ENCODER: u32 = 0xe0e0e0e0;
@@ -697,6 +723,15 @@ pub struct drm_mode_modeinfo {
 
 unsafe impl Pod for drm_mode_modeinfo {}
 
+#[repr(C)]
+#[derive(Copy, Clone, Debug)]
+pub struct drm_color_lut {
+    pub red: u16,
+    pub green: u16,
+    pub blue: u16,
+    pub reserved: u16,
+}
+
 impl Into<DrmModeInfo> for drm_mode_modeinfo {
     fn into(self) -> DrmModeInfo {
         DrmModeInfo {
@@ -846,6 +881,7 @@ struct drm_mode_atomic {
 const DRM_IOCTL_MODE_ATOMIC: u64 = drm_iowr::<drm_mode_atomic>(0xbc);
 
 pub const DRM_MODE_PAGE_FLIP_EVENT: u32 = 0x01;
+pub const DRM_MODE_PAGE_FLIP_ASYNC: u32 = 0x02;
 pub const DRM_MODE_ATOMIC_TEST_ONLY: u32 = 0x0100;
 pub const DRM_MODE_ATOMIC_NONBLOCK: u32 = 0x0200;
 pub const DRM_MODE_ATOMIC_ALLOW_MODESET: u32 = 0x0400;
@@ -898,9 +934,9 @@ struct drm_mode_create_blob {
 
 const DRM_IOCTL_MODE_CREATEPROPBLOB: u64 = drm_iowr::<drm_mode_create_blob>(0xbd);
 
-pub fn mode_create_blob<T>(fd: c::c_int, t: &T) -> Result<DrmBlob, OsError> {
+pub fn mode_create_blob<T: ?Sized>(fd: c::c_int, t: &T) -> Result<DrmBlob, OsError> {
     let mut res = drm_mode_create_blob {
-        data: t as *const T as _,
+        data: t as *const T as *const u8 as _,
         length: mem::size_of_val(t) as _,
         blob_id: 0,
     };
@@ -58,7 +58,6 @@ const GBM_BO_IMPORT_FD: u32 = 0x5503;
 const GBM_BO_IMPORT_FD_MODIFIER: u32 = 0x5504;
 
 const GBM_BO_TRANSFER_READ: u32 = 1 << 0;
-#[allow(dead_code)]
 const GBM_BO_TRANSFER_WRITE: u32 = 1 << 1;
 #[allow(dead_code)]
 const GBM_BO_TRANSFER_READ_WRITE: u32 = GBM_BO_TRANSFER_READ | GBM_BO_TRANSFER_WRITE;
@@ -140,12 +139,21 @@ pub struct GbmBoMap {
     bo: Rc<GbmBo>,
     data: *mut [u8],
     opaque: *mut u8,
+    stride: u32,
 }
 
 impl GbmBoMap {
     pub unsafe fn data(&self) -> &[u8] {
         &*self.data
     }
+
+    pub unsafe fn data_mut(&mut self) -> &mut [u8] {
+        &mut *self.data
+    }
+
+    pub fn stride(&self) -> u32 {
+        self.stride
+    }
 }
 
 unsafe fn export_bo(bo: *mut Bo) -> Result<DmaBuf, GbmError> {
@@ -275,6 +283,14 @@ impl GbmBo {
     }
 
     pub fn map(self: &Rc<Self>) -> Result<GbmBoMap, GbmError> {
+        self.map_(GBM_BO_TRANSFER_READ)
+    }
+
+    pub fn map_write(self: &Rc<Self>) -> Result<GbmBoMap, GbmError> {
+        self.map_(GBM_BO_TRANSFER_WRITE)
+    }
+
+    fn map_(self: &Rc<Self>, flags: u32) -> Result<GbmBoMap, GbmError> {
         let mut stride = 0;
         let mut map_data = ptr::null_mut();
         unsafe {
@@ -284,7 +300,7 @@ impl GbmBo {
                 0,
                 self.dmabuf.width as _,
                 self.dmabuf.height as _,
-                GBM_BO_TRANSFER_READ,
+                flags,
                 &mut stride,
                 &mut map_data,
             );
@@ -296,6 +312,7 @@ impl GbmBo {
                 bo: self.clone(),
                 data: map,
                 opaque: map_data,
+                stride,
             })
         }
     }
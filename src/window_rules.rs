@@ -0,0 +1,225 @@
+use {
+    crate::{
+        client::get_comm, ifs::wl_surface::x_surface::xwindow::XwindowData, tree::ToplevelNode,
+        utils::errorfmt::ErrorFmt,
+    },
+    bstr::BString,
+    jay_config::window::{WindowMatcher, XWindowTypes},
+    regex::Regex,
+    std::{cell::RefCell, rc::Rc},
+};
+
+#[cfg(test)]
+mod tests;
+
+/// The properties that the window rules assign to a new window.
+#[derive(Clone, Debug, Default)]
+pub struct WindowRuleActions {
+    pub workspace: Option<Rc<String>>,
+    pub floating: Option<bool>,
+    pub size: Option<(i32, i32)>,
+    pub position: Option<(i32, i32)>,
+    pub fullscreen: Option<bool>,
+    pub focus: Option<bool>,
+}
+
+impl WindowRuleActions {
+    fn merge(&mut self, other: &Self) {
+        macro_rules! merge {
+            ($($field:ident,)*) => {
+                $(
+                    if other.$field.is_some() {
+                        self.$field = other.$field.clone();
+                    }
+                )*
+            };
+        }
+        merge!(workspace, floating, size, position, fullscreen, focus,);
+    }
+}
+
+pub struct WindowRule {
+    app_id: Option<String>,
+    title: Option<Regex>,
+    x_class: Option<BString>,
+    x_instance: Option<BString>,
+    x_role: Option<BString>,
+    x_window_types: Option<XWindowTypes>,
+    pid: Option<u32>,
+    comm: Option<String>,
+    actions: WindowRuleActions,
+}
+
+impl WindowRule {
+    /// Compiles the matcher sent by the config.
+    ///
+    /// Returns `None` if the title is not a valid regular expression.
+    pub fn new(matcher: WindowMatcher, actions: WindowRuleActions) -> Option<Self> {
+        let title = match matcher.title {
+            Some(title) => match Regex::new(&title) {
+                Ok(re) => Some(re),
+                Err(e) => {
+                    log::error!(
+                        "Window rule title `{}` is not a valid regex: {}",
+                        title,
+                        ErrorFmt(e)
+                    );
+                    return None;
+                }
+            },
+            _ => None,
+        };
+        Some(Self {
+            app_id: matcher.app_id,
+            title,
+            x_class: matcher.x_class.map(BString::from),
+            x_instance: matcher.x_instance.map(BString::from),
+            x_role: matcher.x_role.map(BString::from),
+            x_window_types: matcher.x_window_types,
+            pid: matcher.pid,
+            comm: matcher.comm,
+            actions,
+        })
+    }
+
+    fn needs_process(&self) -> bool {
+        self.pid.is_some() || self.comm.is_some()
+    }
+
+    fn matches(&self, info: &WindowInfo) -> bool {
+        if let Some(app_id) = &self.app_id {
+            if info.app_id != *app_id {
+                return false;
+            }
+        }
+        if let Some(title) = &self.title {
+            if !title.is_match(&info.title) {
+                return false;
+            }
+        }
+        let x_props = [
+            (&self.x_class, info.x.as_ref().map(|x| &x.class)),
+            (&self.x_instance, info.x.as_ref().map(|x| &x.instance)),
+            (&self.x_role, info.x.as_ref().map(|x| &x.role)),
+        ];
+        for (expected, actual) in x_props {
+            if let Some(expected) = expected {
+                match actual.and_then(|a| a.as_ref()) {
+                    Some(actual) if actual == expected => {}
+                    _ => return false,
+                }
+            }
+        }
+        if let Some(types) = self.x_window_types {
+            match &info.x {
+                Some(x) if x.window_types & types.0 != 0 => {}
+                _ => return false,
+            }
+        }
+        if self.pid.is_some() && self.pid != info.pid {
+            return false;
+        }
+        if let Some(expected) = &self.comm {
+            if info.comm.as_deref() != Some(expected.as_str()) {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+/// The properties of a window that are matched by the rules.
+#[derive(Default)]
+struct WindowInfo {
+    app_id: String,
+    title: String,
+    x: Option<XWindowInfo>,
+    pid: Option<u32>,
+    comm: Option<String>,
+}
+
+#[derive(Default)]
+struct XWindowInfo {
+    class: Option<BString>,
+    instance: Option<BString>,
+    role: Option<BString>,
+    window_types: u32,
+}
+
+impl WindowInfo {
+    fn new(tl: &dyn ToplevelNode, with_process: bool) -> Self {
+        let data = tl.tl_data();
+        let x = tl.tl_x_data();
+        let mut info = Self {
+            app_id: data.app_id.borrow().clone(),
+            title: data.title.borrow().clone(),
+            ..Default::default()
+        };
+        if let Some(x) = x {
+            info.title = x.info.title.borrow().clone().unwrap_or_default();
+            info.x = Some(XWindowInfo {
+                class: x.info.class.borrow().clone(),
+                instance: x.info.instance.borrow().clone(),
+                role: x.info.role.borrow().clone(),
+                window_types: x.info.config_window_types.get(),
+            });
+        }
+        if with_process {
+            (info.pid, info.comm) = window_process(tl, x);
+        }
+        info
+    }
+}
+
+/// Returns the process ID and the name of the process that owns the window.
+fn window_process(tl: &dyn ToplevelNode, x: Option<&XwindowData>) -> (Option<u32>, Option<String>) {
+    if let Some(x) = x {
+        return match x.info.pid.get() {
+            Some(pid) => (Some(pid), Some(get_comm(pid as _))),
+            _ => (None, None),
+        };
+    }
+    match &tl.tl_data().client {
+        Some(client) if client.pid_info.pid != 0 => (
+            Some(client.pid_info.pid as u32),
+            Some(client.pid_info.comm.clone()),
+        ),
+        _ => (None, None),
+    }
+}
+
+/// The window rules registered by the config.
+#[derive(Default)]
+pub struct WindowRules {
+    rules: RefCell<Vec<WindowRule>>,
+}
+
+impl WindowRules {
+    pub fn add(&self, rule: WindowRule) {
+        self.rules.borrow_mut().push(rule);
+    }
+
+    pub fn clear(&self) {
+        self.rules.borrow_mut().clear();
+    }
+
+    /// Returns the combined actions of all rules that match the toplevel.
+    pub fn evaluate(&self, tl: &dyn ToplevelNode) -> WindowRuleActions {
+        let rules = self.rules.borrow();
+        if rules.is_empty() {
+            return WindowRuleActions::default();
+        }
+        let with_process = rules.iter().any(|r| r.needs_process());
+        evaluate(&rules, &WindowInfo::new(tl, with_process))
+    }
+}
+
+fn evaluate(rules: &[WindowRule], info: &WindowInfo) -> WindowRuleActions {
+    let mut res = WindowRuleActions::default();
+    for rule in rules {
+        if rule.matches(info) {
+            res.merge(&rule.actions);
+        }
+    }
+    res
+}
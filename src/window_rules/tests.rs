@@ -0,0 +1,205 @@
+use {
+    crate::window_rules::{evaluate, WindowInfo, WindowRule, WindowRuleActions, XWindowInfo},
+    bstr::BString,
+    jay_config::window::{
+        WindowMatcher, X_WINDOW_TYPE_DIALOG, X_WINDOW_TYPE_NORMAL, X_WINDOW_TYPE_SPLASH,
+        X_WINDOW_TYPE_UTILITY,
+    },
+};
+
+fn new_rule(matcher: WindowMatcher) -> WindowRule {
+    WindowRule::new(matcher, WindowRuleActions::default()).unwrap()
+}
+
+fn rule_title(title: &str) -> WindowRule {
+    new_rule(WindowMatcher {
+        title: Some(title.to_string()),
+        ..Default::default()
+    })
+}
+
+fn rule_instance(instance: &str) -> WindowRule {
+    new_rule(WindowMatcher {
+        x_instance: Some(instance.to_string()),
+        ..Default::default()
+    })
+}
+
+fn wayland(app_id: &str, title: &str) -> WindowInfo {
+    WindowInfo {
+        app_id: app_id.to_string(),
+        title: title.to_string(),
+        ..Default::default()
+    }
+}
+
+fn x(class: &str, instance: &str, role: Option<&str>, window_types: u32) -> WindowInfo {
+    WindowInfo {
+        title: "title".to_string(),
+        x: Some(XWindowInfo {
+            class: Some(BString::from(class)),
+            instance: Some(BString::from(instance)),
+            role: role.map(BString::from),
+            window_types,
+        }),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn empty_matcher() {
+    let rule = new_rule(Default::default());
+    assert!(rule.matches(&wayland("firefox", "title")));
+    assert!(rule.matches(&x("class", "instance", None, 0)));
+}
+
+#[test]
+fn app_id() {
+    let rule = new_rule(WindowMatcher {
+        app_id: Some("firefox".to_string()),
+        ..Default::default()
+    });
+    assert!(rule.matches(&wayland("firefox", "title")));
+    assert!(!rule.matches(&wayland("firefox-esr", "title")));
+    assert!(!rule.matches(&wayland("", "firefox")));
+}
+
+#[test]
+fn title_regex() {
+    let rule = new_rule(WindowMatcher {
+        title: Some("^Picture-in-Picture$".to_string()),
+        ..Default::default()
+    });
+    assert!(rule.matches(&wayland("firefox", "Picture-in-Picture")));
+    assert!(!rule.matches(&wayland("firefox", "Picture-in-Picture - Mozilla")));
+    let rule = rule_title("Mozilla");
+    assert!(rule.matches(&wayland("firefox", "Picture-in-Picture - Mozilla")));
+    assert!(!rule.matches(&wayland("firefox", "mozilla")));
+}
+
+#[test]
+fn invalid_title_regex() {
+    let matcher = WindowMatcher {
+        title: Some("(".to_string()),
+        ..Default::default()
+    };
+    assert!(WindowRule::new(matcher, Default::default()).is_none());
+}
+
+#[test]
+fn x_properties() {
+    let rule = new_rule(WindowMatcher {
+        x_class: Some("Gimp".to_string()),
+        x_role: Some("gimp-toolbox".to_string()),
+        ..Default::default()
+    });
+    assert!(rule.matches(&x("Gimp", "gimp", Some("gimp-toolbox"), 0)));
+    assert!(!rule.matches(&x("Gimp", "gimp", Some("gimp-image-window"), 0)));
+    assert!(!rule.matches(&x("Gimp", "gimp", None, 0)));
+    assert!(!rule.matches(&x("gimp", "gimp", Some("gimp-toolbox"), 0)));
+    let rule = rule_instance("gimp");
+    assert!(rule.matches(&x("Gimp", "gimp", None, 0)));
+    assert!(!rule.matches(&x("gimp", "Gimp", None, 0)));
+}
+
+#[test]
+fn x_properties_never_match_wayland() {
+    let rule = rule_instance("firefox");
+    assert!(!rule.matches(&wayland("firefox", "firefox")));
+    let rule = new_rule(WindowMatcher {
+        x_window_types: Some(X_WINDOW_TYPE_NORMAL),
+        ..Default::default()
+    });
+    assert!(!rule.matches(&wayland("firefox", "firefox")));
+}
+
+#[test]
+fn x_window_types() {
+    let rule = new_rule(WindowMatcher {
+        x_window_types: Some(X_WINDOW_TYPE_DIALOG | X_WINDOW_TYPE_UTILITY),
+        ..Default::default()
+    });
+    assert!(rule.matches(&x("c", "i", None, X_WINDOW_TYPE_DIALOG.0)));
+    assert!(rule.matches(&x("c", "i", None, X_WINDOW_TYPE_UTILITY.0)));
+    assert!(rule.matches(&x(
+        "c",
+        "i",
+        None,
+        (X_WINDOW_TYPE_NORMAL | X_WINDOW_TYPE_UTILITY).0
+    )));
+    assert!(!rule.matches(&x("c", "i", None, X_WINDOW_TYPE_NORMAL.0)));
+    assert!(!rule.matches(&x("c", "i", None, X_WINDOW_TYPE_SPLASH.0)));
+    assert!(!rule.matches(&x("c", "i", None, 0)));
+}
+
+#[test]
+fn process() {
+    let rule = new_rule(WindowMatcher {
+        pid: Some(10),
+        comm: Some("foot".to_string()),
+        ..Default::default()
+    });
+    let mut info = wayland("foot", "foot");
+    assert!(!rule.matches(&info));
+    info.pid = Some(10);
+    assert!(!rule.matches(&info));
+    info.comm = Some("foot".to_string());
+    assert!(rule.matches(&info));
+    info.pid = Some(11);
+    assert!(!rule.matches(&info));
+}
+
+#[test]
+fn merge() {
+    let rules = [
+        WindowRule::new(
+            WindowMatcher {
+                app_id: Some("mpv".to_string()),
+                ..Default::default()
+            },
+            WindowRuleActions {
+                floating: Some(true),
+                size: Some((640, 480)),
+                focus: Some(false),
+                ..Default::default()
+            },
+        )
+        .unwrap(),
+        WindowRule::new(
+            WindowMatcher {
+                title: Some("^video".to_string()),
+                ..Default::default()
+            },
+            WindowRuleActions {
+                floating: Some(false),
+                fullscreen: Some(true),
+                ..Default::default()
+            },
+        )
+        .unwrap(),
+        WindowRule::new(
+            WindowMatcher {
+                app_id: Some("foot".to_string()),
+                ..Default::default()
+            },
+            WindowRuleActions {
+                size: Some((100, 100)),
+                ..Default::default()
+            },
+        )
+        .unwrap(),
+    ];
+    let actions = evaluate(&rules, &wayland("mpv", "video.mkv"));
+    assert_eq!(actions.floating, Some(false));
+    assert_eq!(actions.size, Some((640, 480)));
+    assert_eq!(actions.fullscreen, Some(true));
+    assert_eq!(actions.focus, Some(false));
+    assert_eq!(actions.position, None);
+    assert_eq!(actions.workspace, None);
+    let actions = evaluate(&rules, &wayland("mpv", "audio.flac"));
+    assert_eq!(actions.floating, Some(true));
+    assert_eq!(actions.fullscreen, None);
+    let actions = evaluate(&rules, &wayland("alacritty", "alacritty"));
+    assert_eq!(actions.floating, None);
+    assert_eq!(actions.size, None);
+}
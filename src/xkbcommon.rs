@@ -106,6 +106,15 @@ extern "C" {
         key: u32,
         direction: xkb_key_direction,
     ) -> xkb_state_component;
+    fn xkb_state_update_mask(
+        state: *mut xkb_state,
+        depressed_mods: u32,
+        latched_mods: u32,
+        locked_mods: u32,
+        depressed_layout: xkb_layout_index_t,
+        latched_layout: xkb_layout_index_t,
+        locked_layout: xkb_layout_index_t,
+    ) -> xkb_state_component;
     #[allow(dead_code)]
     fn xkb_state_serialize_mods(state: *mut xkb_state, components: xkb_state_component) -> u32;
     #[allow(dead_code)]
@@ -254,26 +263,54 @@ impl XkbState {
         self.mods
     }
 
+    pub fn keymap(&self) -> &Rc<XkbKeymap> {
+        &self.map
+    }
+
     #[allow(dead_code)]
     pub fn update(&mut self, key: u32, direction: XkbKeyDirection) -> Option<ModifierState> {
+        let changes = unsafe { xkb_state_update_key(self.state, key + 8, direction.raw() as _) };
+        self.update_mods(changes)
+    }
+
+    pub fn set_mods(
+        &mut self,
+        mods_depressed: u32,
+        mods_latched: u32,
+        mods_locked: u32,
+        group: u32,
+    ) -> Option<ModifierState> {
+        let changes = unsafe {
+            xkb_state_update_mask(
+                self.state,
+                mods_depressed,
+                mods_latched,
+                mods_locked,
+                0,
+                0,
+                group,
+            )
+        };
+        self.update_mods(changes)
+    }
+
+    fn update_mods(&mut self, changes: xkb_state_component) -> Option<ModifierState> {
+        if changes == 0 {
+            return None;
+        }
         unsafe {
-            let changes = xkb_state_update_key(self.state, key + 8, direction.raw() as _);
-            if changes != 0 {
-                self.mods.mods_depressed =
-                    xkb_state_serialize_mods(self.state, XKB_STATE_MODS_DEPRESSED.raw() as _);
-                self.mods.mods_latched =
-                    xkb_state_serialize_mods(self.state, XKB_STATE_MODS_LATCHED.raw() as _);
-                self.mods.mods_locked =
-                    xkb_state_serialize_mods(self.state, XKB_STATE_MODS_LOCKED.raw() as _);
-                self.mods.mods_effective =
-                    self.mods.mods_depressed | self.mods.mods_latched | self.mods.mods_locked;
-                self.mods.group =
-                    xkb_state_serialize_layout(self.state, XKB_STATE_LAYOUT_EFFECTIVE.raw() as _);
-                Some(self.mods)
-            } else {
-                None
-            }
+            self.mods.mods_depressed =
+                xkb_state_serialize_mods(self.state, XKB_STATE_MODS_DEPRESSED.raw() as _);
+            self.mods.mods_latched =
+                xkb_state_serialize_mods(self.state, XKB_STATE_MODS_LATCHED.raw() as _);
+            self.mods.mods_locked =
+                xkb_state_serialize_mods(self.state, XKB_STATE_MODS_LOCKED.raw() as _);
+            self.mods.mods_effective =
+                self.mods.mods_depressed | self.mods.mods_latched | self.mods.mods_locked;
+            self.mods.group =
+                xkb_state_serialize_layout(self.state, XKB_STATE_LAYOUT_EFFECTIVE.raw() as _);
         }
+        Some(self.mods)
     }
 
     pub fn unmodified_keysyms(&self, key: u32) -> &[xkb_keysym_t] {
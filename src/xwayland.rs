@@ -277,6 +277,7 @@ pub enum XWaylandEvent {
     Activate(Rc<XwindowData>),
     ActivateRoot,
     Close(Rc<XwindowData>),
+    SetMinimized(Rc<XwindowData>, bool),
     #[allow(dead_code)]
     SeatChanged,
 
@@ -61,8 +61,14 @@ use {
         xwayland::{XWaylandError, XWaylandEvent},
     },
     ahash::{AHashMap, AHashSet},
-    bstr::ByteSlice,
+    bstr::{BString, ByteSlice},
     futures_util::{select, FutureExt},
+    jay_config::window::{
+        XWindowTypes, X_WINDOW_TYPE_COMBO, X_WINDOW_TYPE_DIALOG, X_WINDOW_TYPE_DND,
+        X_WINDOW_TYPE_DROPDOWN_MENU, X_WINDOW_TYPE_MENU, X_WINDOW_TYPE_NORMAL,
+        X_WINDOW_TYPE_NOTIFICATION, X_WINDOW_TYPE_POPUP_MENU, X_WINDOW_TYPE_SPLASH,
+        X_WINDOW_TYPE_TOOLBAR, X_WINDOW_TYPE_TOOLTIP, X_WINDOW_TYPE_UTILITY,
+    },
     smallvec::SmallVec,
     std::{
         borrow::Cow,
@@ -232,6 +238,7 @@ pub struct Wm {
     c: Rc<Xcon>,
     atoms: Atoms,
     never_focus: AHashSet<u32>,
+    config_window_types: AHashMap<u32, XWindowTypes>,
     root: u32,
     xwin: u32,
     client: Rc<Client>,
@@ -307,6 +314,28 @@ impl Wm {
             nf.insert(atoms._NET_WM_WINDOW_TYPE_UTILITY);
             nf
         };
+        let config_window_types = {
+            let mut cwt = AHashMap::new();
+            cwt.insert(atoms._NET_WM_WINDOW_TYPE_NORMAL, X_WINDOW_TYPE_NORMAL);
+            cwt.insert(atoms._NET_WM_WINDOW_TYPE_DIALOG, X_WINDOW_TYPE_DIALOG);
+            cwt.insert(atoms._NET_WM_WINDOW_TYPE_UTILITY, X_WINDOW_TYPE_UTILITY);
+            cwt.insert(atoms._NET_WM_WINDOW_TYPE_TOOLBAR, X_WINDOW_TYPE_TOOLBAR);
+            cwt.insert(atoms._NET_WM_WINDOW_TYPE_SPLASH, X_WINDOW_TYPE_SPLASH);
+            cwt.insert(atoms._NET_WM_WINDOW_TYPE_MENU, X_WINDOW_TYPE_MENU);
+            cwt.insert(
+                atoms._NET_WM_WINDOW_TYPE_DROPDOWN_MENU,
+                X_WINDOW_TYPE_DROPDOWN_MENU,
+            );
+            cwt.insert(atoms._NET_WM_WINDOW_TYPE_POPUP_MENU, X_WINDOW_TYPE_POPUP_MENU);
+            cwt.insert(atoms._NET_WM_WINDOW_TYPE_TOOLTIP, X_WINDOW_TYPE_TOOLTIP);
+            cwt.insert(
+                atoms._NET_WM_WINDOW_TYPE_NOTIFICATION,
+                X_WINDOW_TYPE_NOTIFICATION,
+            );
+            cwt.insert(atoms._NET_WM_WINDOW_TYPE_COMBO, X_WINDOW_TYPE_COMBO);
+            cwt.insert(atoms._NET_WM_WINDOW_TYPE_DND, X_WINDOW_TYPE_DND);
+            cwt
+        };
         let root = c.setup().screens[0].root;
         {
             let events = 0
@@ -512,6 +541,7 @@ impl Wm {
             c,
             atoms,
             never_focus,
+            config_window_types,
             root,
             xwin,
             client,
@@ -610,6 +640,9 @@ impl Wm {
             }
             XWaylandEvent::ActivateRoot => self.activate_window(None, Initiator::Wayland).await,
             XWaylandEvent::Close(window) => self.close_window(&window).await,
+            XWaylandEvent::SetMinimized(window, minimized) => {
+                self.handle_set_minimized_request(&window, minimized).await
+            }
             XWaylandEvent::SeatChanged => self.seats_changed(),
             XWaylandEvent::PrimarySelectionCancelSource(src) => {
                 self.dd_cancel_source(&self.shared.clone().primary_selection, &src)
@@ -975,6 +1008,9 @@ impl Wm {
     }
 
     async fn set_net_wm_state(&self, data: &Rc<XwindowData>) {
+        if let Some(w) = data.window.get() {
+            w.toplevel_data.set_minimized(data.info.minimized.get());
+        }
         let mut args = SmallVec::<[_; 6]>::new();
         if data.info.modal.get() {
             args.push(self.atoms._NET_WM_STATE_MODAL);
@@ -1074,7 +1110,11 @@ impl Wm {
         }
         let mut iter = buf.split(|c| *c == 0);
         *data.info.instance.borrow_mut() = Some(iter.next().unwrap_or(&[]).to_vec().into());
-        *data.info.class.borrow_mut() = Some(iter.next().unwrap_or(&[]).to_vec().into());
+        let class: BString = iter.next().unwrap_or(&[]).to_vec().into();
+        if let Some(window) = data.window.get() {
+            window.toplevel_data.set_app_id(&class.to_string());
+        }
+        *data.info.class.borrow_mut() = Some(class);
     }
 
     async fn load_window_wm_name2(&self, data: &Rc<XwindowData>, prop: u32, name: &str) {
@@ -1376,6 +1416,11 @@ impl Wm {
             .window_types
             .lock()
             .extend(buf.iter().copied().map(|v| (v, ())));
+        let config_window_types = buf
+            .iter()
+            .filter_map(|t| self.config_window_types.get(t))
+            .fold(0, |acc, t| acc | t.0);
+        data.info.config_window_types.set(config_window_types);
         self.update_wants_floating(data);
     }
 
@@ -2241,6 +2286,19 @@ impl Wm {
         Ok(())
     }
 
+    async fn handle_set_minimized_request(&self, data: &Rc<XwindowData>, minimized: bool) {
+        if data.destroyed.get() || data.info.minimized.get() == minimized {
+            return;
+        }
+        if minimized {
+            let minimized = self.handle_minimize_requested(data).await;
+            data.info.minimized.set(minimized);
+            self.set_net_wm_state(data).await;
+        } else {
+            self.set_minimized(data, false).await;
+        }
+    }
+
     async fn handle_minimize_requested(&self, data: &Rc<XwindowData>) -> bool {
         if let Some(w) = data.window.get() {
             if w.toplevel_data.active_surfaces.get() > 0 {